@@ -19,6 +19,29 @@ struct
   __uint(max_entries, 8 * 1024 * 1024);
 } rb SEC(".maps");
 
+// Events dropped because the ring buffer was full, counted per CPU and read by user space
+struct
+{
+  __uint(type, BPF_MAP_TYPE_PERCPU_ARRAY);
+  __uint(max_entries, 1);
+  __type(key, u32);
+  __type(value, u64);
+} lost_events SEC(".maps");
+
+// Filename passed to an in-flight execve, keyed by thread id, so a failure can be reported
+struct exec_attempt
+{
+  char filename[MAX_STR_LEN];
+};
+
+struct
+{
+  __uint(type, BPF_MAP_TYPE_HASH);
+  __uint(max_entries, 10240);
+  __type(key, u32);
+  __type(value, struct exec_attempt);
+} exec_attempts SEC(".maps");
+
 // Print in debug mode
 static __always_inline void debug_printk(const char *fmt)
 {
@@ -28,6 +51,14 @@ static __always_inline void debug_printk(const char *fmt)
 
 // PIDs are not sufficient to uniquely identify processes,
 // because of PID reuse, so we combine with process start time
+static __always_inline void count_lost_event(void)
+{
+  u32 key = 0;
+  u64 *count = bpf_map_lookup_elem(&lost_events, &key);
+  if (count)
+    *count += 1;
+}
+
 static __always_inline u64 make_upid(u32 pid, u64 start_ns)
 {
   const u64 PID_MASK = 0x00FFFFFFULL;       /* 24 ones */
@@ -181,7 +212,10 @@ fill_oom_mark_victim(struct event *e,
                                                                                   \
     struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);                    \
     if (!e)                                                                       \
+    {                                                                             \
+      count_lost_event();                                                         \
       return 0;                                                                   \
+    }                                                                             \
                                                                                   \
     /* Fill fields common to every event */                                       \
     struct task_struct *task = (struct task_struct *)bpf_get_current_task();      \
@@ -208,5 +242,66 @@ fill_oom_mark_victim(struct event *e,
 EVENT_LIST(HANDLER_DECL)
 #undef HANDLER_DECL
 
+/* -------------------------------------------------------------------------- */
+/* 4.  Failed exec tracking                                                   */
+/* -------------------------------------------------------------------------- */
+
+// A successful execve is reported by sched_process_exec, but a failing one never reaches it.
+// Remember the filename on entry and report an event on exit only if the call failed.
+SEC("tracepoint/syscalls/sys_enter_execve")
+int handle__sys_enter_execve(struct trace_event_raw_sys_enter *ctx)
+{
+  u32 tid = (u32)bpf_get_current_pid_tgid();
+  struct exec_attempt attempt = {};
+
+  bpf_probe_read_user_str(attempt.filename, MAX_STR_LEN, (void *)BPF_CORE_READ(ctx, args[0]));
+  bpf_map_update_elem(&exec_attempts, &tid, &attempt, BPF_ANY);
+  return 0;
+}
+
+SEC("tracepoint/syscalls/sys_exit_execve")
+int handle__sys_exit_execve(struct trace_event_raw_sys_exit *ctx)
+{
+  u64 id = bpf_get_current_pid_tgid();
+  u32 tgid = id >> 32;
+  u32 tid = (u32)id;
+
+  struct exec_attempt *attempt = bpf_map_lookup_elem(&exec_attempts, &tid);
+  if (!attempt)
+    return 0;
+
+  long ret = BPF_CORE_READ(ctx, ret);
+  if (ret >= 0)
+  {
+    bpf_map_delete_elem(&exec_attempts, &tid);
+    return 0;
+  }
+
+  struct event *e = bpf_ringbuf_reserve(&rb, sizeof(*e), 0);
+  if (!e)
+  {
+    count_lost_event();
+    bpf_map_delete_elem(&exec_attempts, &tid);
+    return 0;
+  }
+
+  struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+  struct task_struct *parent = BPF_CORE_READ(task, parent);
+
+  e->event_type = EVENT__SYSCALL__SYS_EXIT_EXECVE;
+  e->timestamp_ns = bpf_ktime_get_ns() + system_boot_ns;
+  e->pid = tgid;
+  e->ppid = BPF_CORE_READ(parent, tgid);
+  e->upid = make_upid(e->pid, BPF_CORE_READ(task, start_time));
+  e->uppid = make_upid(e->ppid, BPF_CORE_READ(parent, start_time));
+
+  __builtin_memcpy(e->syscall__sys_exit_execve__payload.filename, attempt->filename, MAX_STR_LEN);
+  e->syscall__sys_exit_execve__payload.ret = (int)ret;
+
+  bpf_ringbuf_submit(e, 0);
+  bpf_map_delete_elem(&exec_attempts, &tid);
+  return 0;
+}
+
 // Licence, required to invoke GPL-restricted BPF functions
 char LICENSE[] SEC("license") = "GPL";
\ No newline at end of file
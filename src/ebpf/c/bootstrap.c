@@ -1,6 +1,7 @@
 #define _POSIX_C_SOURCE 200809L
 #include <signal.h>
 #include <stdarg.h>
+#include <stdatomic.h>
 #include <stdio.h>
 #include <string.h>
 #include <sys/resource.h>
@@ -9,6 +10,7 @@
 #include <sys/stat.h>
 #include <errno.h>
 
+#include <bpf/bpf.h>
 #include <bpf/libbpf.h>
 
 #include "bootstrap.h"
@@ -60,6 +62,12 @@ static int libbpf_print_cb(enum libbpf_print_level lvl,
 
 static volatile bool exiting;
 
+// Descriptor of the per-CPU lost events map while the BPF program is loaded, -1 otherwise
+static _Atomic int lost_events_fd = -1;
+
+// Boot time added to the eBPF timestamps while the BPF program is loaded, 0 otherwise
+static _Atomic u64 loaded_system_boot_ns;
+
 static void sig_handler(int sig) { exiting = true; }
 
 // Ring‑buffer callback
@@ -120,6 +128,7 @@ int initialize(void *buffer, size_t byte_cnt,
 		.skel = NULL,
 		.rb = NULL,
 	};
+	u64 boot_ns;
 	int err;
 
 	libbpf_set_print(libbpf_print_cb);
@@ -137,7 +146,8 @@ int initialize(void *buffer, size_t byte_cnt,
 
 	// Propagate runtime knobs into .rodata
 	lc.skel->rodata->debug_enabled = env.debug_bpf;
-	lc.skel->rodata->system_boot_ns = get_system_boot_ns();
+	boot_ns = get_system_boot_ns();
+	lc.skel->rodata->system_boot_ns = boot_ns;
 
 	err = bootstrap_bpf__load(lc.skel);
 	if (err)
@@ -152,6 +162,9 @@ int initialize(void *buffer, size_t byte_cnt,
 		goto out;
 	}
 
+	atomic_store(&lost_events_fd, bpf_map__fd(lc.skel->maps.lost_events));
+	atomic_store(&loaded_system_boot_ns, boot_ns);
+
 	lc.rb = ring_buffer__new(
 		bpf_map__fd(lc.skel->maps.rb),
 		handle_event, &lc, NULL);
@@ -177,7 +190,37 @@ int initialize(void *buffer, size_t byte_cnt,
 	}
 
 out:
+	atomic_store(&lost_events_fd, -1);
+	atomic_store(&loaded_system_boot_ns, 0);
 	ring_buffer__free(lc.rb);
 	bootstrap_bpf__destroy(lc.skel);
 	return err < 0 ? -err : 0;
-}
\ No newline at end of file
+}
+
+int read_lost_events(unsigned long long *per_cpu, size_t cpu_count)
+{
+	__u32 key = 0;
+	int fd = atomic_load(&lost_events_fd);
+	int cpus = libbpf_num_possible_cpus();
+
+	if (fd < 0)
+		return -ENOENT;
+	if (cpus < 0)
+		return cpus;
+	if ((size_t)cpus > cpu_count)
+		return -ENOSPC;
+	if (bpf_map_lookup_elem(fd, &key, per_cpu))
+		return -errno;
+	return cpus;
+}
+
+unsigned long long read_kernel_clock_ns(void)
+{
+	struct timespec monotonic;
+	u64 boot_ns = atomic_load(&loaded_system_boot_ns);
+
+	if (!boot_ns)
+		return 0;
+	clock_gettime(CLOCK_MONOTONIC, &monotonic);
+	return boot_ns + monotonic.tv_sec * 1000000000ULL + monotonic.tv_nsec;
+}
@@ -21,6 +21,7 @@ enum event_type
     EVENT__SYSCALL__SYS_EXIT_READ = 1027,
     EVENT__SYSCALL__SYS_ENTER_WRITE = 1028,
     EVENT__SYSCALL__SYS_EXIT_WRITE = 1029,
+    EVENT__SYSCALL__SYS_EXIT_EXECVE = 1030,
 
     EVENT__VMSCAN__MM_VMSCAN_DIRECT_RECLAIM_BEGIN = 2048,
 
@@ -52,6 +53,12 @@ struct syscall__sys_exit_openat__payload
     int fd;
 };
 
+struct syscall__sys_exit_execve__payload
+{
+    char filename[MAX_STR_LEN];
+    int ret; // negative errno of the failed execve
+};
+
 struct syscall__sys_enter_read__payload
 {
     int fd;
@@ -96,6 +103,7 @@ struct event
         struct sched__sched_process_exit__payload sched__sched_process_exit__payload;
         struct syscall__sys_enter_openat__payload syscall__sys_enter_openat__payload;
         struct syscall__sys_exit_openat__payload syscall__sys_exit_openat__payload;
+        struct syscall__sys_exit_execve__payload syscall__sys_exit_execve__payload;
         struct syscall__sys_enter_read__payload syscall__sys_enter_read__payload;
         struct syscall__sys_enter_write__payload syscall__sys_enter_write__payload;
         struct vmscan__mm_vmscan_direct_reclaim_begin__payload vmscan__mm_vmscan_direct_reclaim_begin__payload;
@@ -26,4 +26,25 @@ typedef void (*event_callback_t)(void *context, size_t filled_bytes);
  */
 int initialize(void *buffer, size_t byte_count, event_callback_t callback, void *callback_ctx);
 
+/**
+ * Read the number of events dropped because the ring buffer was full, per CPU.
+ *
+ * The counts are cumulative since the BPF program was loaded by `initialize`.
+ *
+ * @param per_cpu Buffer receiving one count per possible CPU
+ * @param cpu_count Number of elements of the buffer
+ * @return the number of CPUs read, or a negative error code (-ENOENT if not running)
+ */
+int read_lost_events(unsigned long long *per_cpu, size_t cpu_count);
+
+/**
+ * Read the current time on the clock of the event timestamps.
+ *
+ * Events are timestamped with the monotonic clock shifted by the boot time read when the BPF
+ * program was loaded, so the clock isn't stepped along with the system clock.
+ *
+ * @return the time in nanoseconds since the epoch, or 0 if the BPF program is not loaded
+ */
+unsigned long long read_kernel_clock_ns(void);
+
 #endif /* __BOOTSTRAP_API_H */
\ No newline at end of file
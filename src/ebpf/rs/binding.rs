@@ -1,12 +1,14 @@
 #[cfg(target_os = "linux")]
-pub use linux::start_processing_events;
+pub use linux::{kernel_clock_now, start_processing_events};
 #[cfg(not(target_os = "linux"))]
-pub use non_linux::start_processing_events;
+pub use non_linux::{kernel_clock_now, start_processing_events};
 
 #[cfg(target_os = "linux")]
 mod linux {
     use crate::ebpf_trigger::Trigger;
+    use crate::stats::LOST_EVENTS;
     use anyhow::Result;
+    use chrono::{DateTime, Utc};
     use tokio::sync::mpsc::UnboundedSender;
 
     // Linux-specific imports
@@ -14,6 +16,7 @@ mod linux {
     use std::ffi::c_void;
     use std::sync::{mpsc as std_mpsc, Arc};
     use std::time::Duration;
+    use tracing::{error, warn};
 
     // Define the FFI interface to the C function - only on Linux
     #[link(name = "bootstrap", kind = "static")]
@@ -25,10 +28,18 @@ mod linux {
             callback: extern "C" fn(*mut c_void, usize) -> (),
             callback_ctx: *mut c_void,
         ) -> i32;
+
+        // Corresponds to the read_lost_events function in bootstrap_api.h
+        fn read_lost_events(per_cpu: *mut u64, cpu_count: usize) -> i32;
+
+        // Corresponds to the read_kernel_clock_ns function in bootstrap_api.h
+        fn read_kernel_clock_ns() -> u64;
     }
 
     // Constants - only needed on Linux
     const BUFFER_SIZE: usize = 4096;
+    const MAX_CPUS: usize = 1024;
+    const LOST_EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
     // Define a struct to hold our context - only needed on Linux
     struct ProcessingContext {
@@ -42,6 +53,19 @@ mod linux {
         shared_context: Arc<ProcessingContext>,
     }
 
+    /// Current time on the clock the eBPF timestamps are taken with: the monotonic clock,
+    /// shifted by the boot time read when the program was loaded. Unlike the system clock it is
+    /// never stepped, so the time elapsed since a trigger is exact. `None` while the program
+    /// isn't loaded.
+    pub fn kernel_clock_now() -> Option<DateTime<Utc>> {
+        const NS_PER_SEC: u64 = 1_000_000_000;
+        let now_ns = unsafe { read_kernel_clock_ns() };
+        if now_ns == 0 {
+            return None;
+        }
+        DateTime::from_timestamp((now_ns / NS_PER_SEC) as i64, (now_ns % NS_PER_SEC) as u32)
+    }
+
     pub fn start_processing_events(tx: UnboundedSender<Trigger>) -> Result<()> {
         // Channel for sending events from the C callback to our Rust thread
         let (events_tx, events_rx) = std_mpsc::channel::<Vec<Trigger>>();
@@ -74,7 +98,7 @@ mod linux {
 
                     // Check if we have enough bytes for a complete event
                     if offset + event_size > buffer_slice.len() {
-                        eprintln!("Buffer too small for event at offset {}", offset);
+                        error!("Buffer too small for event at offset {}", offset);
                         continue;
                     }
 
@@ -86,7 +110,7 @@ mod linux {
                     match c_event.try_into() {
                         Ok(trigger) => events.push(trigger),
                         Err(e) => {
-                            eprintln!("Error converting CEvent to Trigger: {:?}", e);
+                            error!("Error converting CEvent to Trigger: {:?}", e);
                             continue;
                         }
                     }
@@ -95,13 +119,13 @@ mod linux {
                 // Send the events to our channel
                 if !events.is_empty() {
                     if let Err(e) = context.shared_context.events_tx.send(events) {
-                        eprintln!("Failed to send events: {:?}", e);
+                        error!("Failed to send events: {:?}", e);
                     }
                 }
 
                 // Signal that we should call initialize again
                 if let Err(e) = context.shared_context.initialize_tx.send(()) {
-                    eprintln!("Failed to send initialize signal: {:?}", e);
+                    error!("Failed to send initialize signal: {:?}", e);
                 }
             }
         }
@@ -135,7 +159,7 @@ mod linux {
 
                     if result != 0 {
                         // If initialization failed, break the loop
-                        eprintln!("eBPF initialization failed with code: {}", result);
+                        error!("eBPF initialization failed with code: {}", result);
                         break;
                     }
                 }
@@ -145,13 +169,34 @@ mod linux {
                     Ok(_) => {}
                     Err(std_mpsc::RecvTimeoutError::Timeout) => {}
                     Err(std_mpsc::RecvTimeoutError::Disconnected) => {
-                        eprintln!("Initialize channel closed, stopping eBPF processing");
+                        warn!("Initialize channel closed, stopping eBPF processing");
                         break;
                     }
                 }
             }
         });
 
+        // Spawn a thread accumulating the events lost because the ring buffer was full
+        std::thread::spawn(|| {
+            let mut per_cpu = vec![0u64; MAX_CPUS];
+            loop {
+                std::thread::sleep(LOST_EVENTS_POLL_INTERVAL);
+                // negative results mean the program is not loaded (yet) or the read failed
+                let cpus = unsafe { read_lost_events(per_cpu.as_mut_ptr(), per_cpu.len()) };
+                if cpus <= 0 {
+                    continue;
+                }
+                let lost = LOST_EVENTS.observe_per_cpu(&per_cpu[..cpus as usize]);
+                if lost > 0 {
+                    warn!(
+                        "Lost {} eBPF events ({} in total), the ring buffer is too small",
+                        lost,
+                        LOST_EVENTS.total()
+                    );
+                }
+            }
+        });
+
         // Task to forward events from internal std channel to external Tokio channel
         // Use a separate OS thread for this to ensure it works across runtimes
         std::thread::spawn(move || {
@@ -159,7 +204,7 @@ mod linux {
                 for event in events {
                     // Use unbounded_send which doesn't require async
                     if let Err(e) = tx.send(event) {
-                        eprintln!("Failed to send event, channel likely closed: {:?}", e);
+                        error!("Failed to send event, channel likely closed: {:?}", e);
                         return;
                     }
                 }
@@ -261,10 +306,16 @@ mod linux {
 mod non_linux {
     use crate::ebpf_trigger::Trigger;
     use anyhow::Result;
+    use chrono::{DateTime, Utc};
     use tokio::sync::mpsc::UnboundedSender;
+    use tracing::warn;
+
+    pub fn kernel_clock_now() -> Option<DateTime<Utc>> {
+        None
+    }
 
     pub fn start_processing_events(_tx: UnboundedSender<Trigger>) -> Result<()> {
-        eprintln!("eBPF functionality is only supported on Linux");
+        warn!("eBPF functionality is only supported on Linux");
         Ok(())
     }
 }
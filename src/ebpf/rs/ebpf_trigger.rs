@@ -25,6 +25,11 @@ pub struct ProcessStartTrigger {
     pub command_string: String,
     /// Command start time
     pub started_at: DateTime<Utc>,
+    /// Id, name and image of the container the process runs in, if any. These are not known to
+    /// the eBPF program; they are filled in by the client before target matching.
+    pub container_id: Option<String>,
+    pub container_name: Option<String>,
+    pub container_image: Option<String>,
 }
 
 fn unquote(mut argv: Vec<String>) -> Vec<String> {
@@ -56,6 +61,9 @@ impl ProcessStartTrigger {
                 (timestamp_ns % NS_PER_SEC) as u32,
             )
             .unwrap(),
+            container_id: None,
+            container_name: None,
+            container_image: None,
         }
     }
 
@@ -73,6 +81,9 @@ impl ProcessStartTrigger {
             command_string: join_args(&argv),
             argv: unquote(argv),
             started_at: Utc::now(),
+            container_id: None,
+            container_name: None,
+            container_image: None,
         }
     }
 
@@ -86,6 +97,9 @@ impl ProcessStartTrigger {
             argv: unquote(argv),
             command_string: command_string.to_string(),
             started_at: Utc::now(),
+            container_id: None,
+            container_name: None,
+            container_image: None,
         }
     }
 }
@@ -116,6 +130,56 @@ pub struct OutOfMemoryTrigger {
     pub timestamp: DateTime<Utc>,
 }
 
+/// A trigger indicating an `execve` call failed, so the command never started. The process that
+/// attempted it keeps running its previous image (or exits, e.g. a shell reporting 127).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ExecFailedTrigger {
+    pub pid: usize,
+    pub ppid: usize,
+    /// The file that was to be executed
+    pub file_name: String,
+    pub errno: i32,
+    /// Description of `errno`, e.g. "No such file or directory"
+    pub error: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ExecFailedTrigger {
+    pub fn new(
+        pid: usize,
+        ppid: usize,
+        file_name: String,
+        errno: i32,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            pid,
+            ppid,
+            file_name,
+            errno,
+            error: errno_description(errno),
+            timestamp,
+        }
+    }
+
+    /// Command name of the attempted executable (without path)
+    pub fn command_name(&self) -> &str {
+        self.file_name
+            .rsplit('/')
+            .next()
+            .unwrap_or(self.file_name.as_str())
+    }
+}
+
+fn errno_description(errno: i32) -> String {
+    let description = std::io::Error::from_raw_os_error(errno).to_string();
+    // strip the " (os error N)" suffix added by std
+    match description.rfind(" (os error") {
+        Some(index) => description[..index].to_string(),
+        None => description,
+    }
+}
+
 /// Format duration in human-readable form
 pub fn format_duration_ns(duration_ns: u64) -> String {
     if duration_ns == 0 {
@@ -137,6 +201,7 @@ pub enum Trigger {
     ProcessEnd(ProcessEndTrigger),
     OutOfMemory(OutOfMemoryTrigger),
     FileOpen(FileOpenTrigger),
+    ExecFailed(ExecFailedTrigger),
 }
 
 impl fmt::Display for Trigger {
@@ -146,6 +211,9 @@ impl fmt::Display for Trigger {
             Trigger::ProcessEnd(t) => write!(f, "ProcessEnd(pid={})", t.pid),
             Trigger::OutOfMemory(t) => write!(f, "OOM(pid={}, comm={})", t.pid, t.comm),
             Trigger::FileOpen(t) => write!(f, "FileOpen(pid={}, file={})", t.pid, t.filename),
+            Trigger::ExecFailed(t) => {
+                write!(f, "ExecFailed(pid={}, file={})", t.pid, t.file_name)
+            }
         }
     }
 }
@@ -165,7 +233,17 @@ impl ExitReason {
     }
 
     pub fn out_of_memory_killed() -> Self {
-        Self::from(EXIT_CODE_OUT_OF_MEMORY_KILLED)
+        Self {
+            code: EXIT_CODE_OUT_OF_MEMORY_KILLED,
+            term_signal: Some(SIGKILL),
+            reason: exit_code_reason(EXIT_CODE_OUT_OF_MEMORY_KILLED),
+            explanation: exit_code_explanation(EXIT_CODE_OUT_OF_MEMORY_KILLED),
+        }
+    }
+
+    /// Whether the process exited with a non-zero code or was killed by a signal
+    pub fn is_failure(&self) -> bool {
+        self.code != EXIT_CODE_SUCCESS || self.term_signal.is_some()
     }
 }
 
@@ -181,11 +259,15 @@ impl From<i64> for ExitReason {
         let code = ((status >> 8) & 0xff) as i64;
         let signaled = (status & 0x7f) != 0;
         let term_signal = if signaled { Some(status & 0x7f) } else { None };
+        let (reason, explanation) = match term_signal {
+            Some(signal) => (signal_reason(signal), signal_explanation(signal)),
+            None => (exit_code_reason(code), exit_code_explanation(code)),
+        };
         Self {
             code,
             term_signal,
-            reason: exit_code_reason(code),
-            explanation: exit_code_explanation(code),
+            reason,
+            explanation,
         }
     }
 }
@@ -197,6 +279,47 @@ pub const EXIT_CODE_CTRL_C_KILLED: i64 = 130;
 pub const EXIT_CODE_OUT_OF_MEMORY_KILLED: i64 = 137;
 pub const EXIT_CODE_SIGNAL_TERMINATED: i64 = 143;
 
+const SIGINT: u16 = 2;
+const SIGABRT: u16 = 6;
+const SIGBUS: u16 = 7;
+const SIGKILL: u16 = 9;
+const SIGSEGV: u16 = 11;
+const SIGPIPE: u16 = 13;
+const SIGTERM: u16 = 15;
+const SIGXCPU: u16 = 24;
+
+pub fn signal_reason(signal: u16) -> String {
+    match signal {
+        SIGINT => "SIGINT".to_string(),
+        SIGABRT => "SIGABRT".to_string(),
+        SIGBUS => "SIGBUS".to_string(),
+        SIGKILL => "SIGKILL".to_string(),
+        SIGSEGV => "SIGSEGV".to_string(),
+        SIGPIPE => "SIGPIPE".to_string(),
+        SIGTERM => "SIGTERM".to_string(),
+        SIGXCPU => "SIGXCPU".to_string(),
+        signal => format!("Signal {}", signal),
+    }
+}
+
+pub fn signal_explanation(signal: u16) -> String {
+    match signal {
+        SIGINT => "SIGINT: Interrupted, e.g. by Ctrl-C.".to_string(),
+        SIGABRT => "SIGABRT: The process aborted, typically on a failed assertion or a fatal \
+            error detected by the process itself."
+            .to_string(),
+        SIGBUS => "SIGBUS: Bus error, typically a truncated memory-mapped file.".to_string(),
+        SIGKILL => "SIGKILL: The process was forcefully killed, typically by the OOM killer or \
+            a scheduler enforcing resource limits."
+            .to_string(),
+        SIGSEGV => "SIGSEGV: Segmentation fault, the process accessed invalid memory.".to_string(),
+        SIGPIPE => "SIGPIPE: The process wrote to a pipe whose reader had exited.".to_string(),
+        SIGTERM => "SIGTERM: Graceful termination requested.".to_string(),
+        SIGXCPU => "SIGXCPU: The process exceeded its CPU time limit.".to_string(),
+        signal => format!("Terminated by signal {}.", signal),
+    }
+}
+
 pub fn exit_code_reason(code: i64) -> String {
     match code {
         EXIT_CODE_SUCCESS => "Success".to_string(),
@@ -243,4 +366,45 @@ mod tests {
         assert_eq!(format_duration_ns(1_500_000), "1.50ms");
         assert_eq!(format_duration_ns(1_500_000_000), "1.500s");
     }
+
+    #[test]
+    fn test_exit_reason_from_wait_status() {
+        let success = ExitReason::from(0);
+        assert_eq!(success.code, 0);
+        assert_eq!(success.term_signal, None);
+        assert!(!success.is_failure());
+
+        let failed = ExitReason::from(1 << 8);
+        assert_eq!(failed.code, 1);
+        assert_eq!(failed.reason, "Exit code 1");
+        assert!(failed.is_failure());
+
+        let killed = ExitReason::from(9);
+        assert_eq!(killed.term_signal, Some(9));
+        assert_eq!(killed.reason, "SIGKILL");
+        assert!(killed.is_failure());
+
+        // with the core dumped flag
+        let segfault = ExitReason::from(0x80 | 11);
+        assert_eq!(segfault.term_signal, Some(11));
+        assert_eq!(segfault.reason, "SIGSEGV");
+        assert!(segfault
+            .explanation
+            .starts_with("SIGSEGV: Segmentation fault"));
+
+        let oom = ExitReason::out_of_memory_killed();
+        assert_eq!(oom.reason, "OOM Killed");
+        assert!(oom.is_failure());
+    }
+
+    #[test]
+    fn test_exec_failed_describes_errno() {
+        let trigger = ExecFailedTrigger::new(10, 1, "/opt/bin/bwa".to_string(), 2, Utc::now());
+        assert_eq!(trigger.error, "No such file or directory");
+        assert_eq!(trigger.command_name(), "bwa");
+
+        let trigger = ExecFailedTrigger::new(10, 1, "samtools".to_string(), 13, Utc::now());
+        assert_eq!(trigger.error, "Permission denied");
+        assert_eq!(trigger.command_name(), "samtools");
+    }
 }
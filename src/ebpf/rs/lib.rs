@@ -1,4 +1,6 @@
 pub mod binding;
 pub mod ebpf_trigger;
+pub mod preflight;
+pub mod stats;
 pub mod types;
 pub mod utils;
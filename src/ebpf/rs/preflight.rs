@@ -0,0 +1,119 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+
+/// Kernel type information the CO-RE relocations of the BPF program are resolved against
+const BTF_PATH: &str = "sys/kernel/btf/vmlinux";
+
+/// Mount points of tracefs, which exposes the tracepoints the BPF program attaches to
+const TRACEFS_PATHS: &[&str] = &["sys/kernel/tracing", "sys/kernel/debug/tracing"];
+
+/// Tracepoints the BPF program attaches to, see `EVENT_LIST` and the exec failure handlers in
+/// `bootstrap.bpf.c`
+pub const REQUIRED_TRACEPOINTS: &[&str] = &[
+    "sched/sched_process_exec",
+    "sched/sched_process_exit",
+    "vmscan/mm_vmscan_direct_reclaim_begin",
+    "oom/mark_victim",
+    "syscalls/sys_enter_openat",
+    "syscalls/sys_enter_execve",
+    "syscalls/sys_exit_execve",
+];
+
+/// Checks that the running kernel provides everything the BPF program needs to load and attach,
+/// so a missing feature is reported as an error rather than failing inside the loader
+pub fn check_compatibility() -> Result<()> {
+    check_compatibility_at(Path::new("/"))
+}
+
+/// Same as [`check_compatibility`], resolving the kernel interfaces relative to `root`
+pub fn check_compatibility_at(root: &Path) -> Result<()> {
+    if !root.join(BTF_PATH).exists() {
+        bail!(
+            "BTF type information is not available (/{} is missing), the kernel must be built with CONFIG_DEBUG_INFO_BTF",
+            BTF_PATH
+        );
+    }
+
+    let Some(tracefs) = TRACEFS_PATHS
+        .iter()
+        .map(|path| root.join(path))
+        .find(|path| path.join("events").is_dir())
+    else {
+        bail!("tracefs is not mounted at /{}", TRACEFS_PATHS.join(" or /"));
+    };
+
+    let missing: Vec<&str> = REQUIRED_TRACEPOINTS
+        .iter()
+        .copied()
+        .filter(|tracepoint| !tracefs.join("events").join(tracepoint).is_dir())
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "the kernel does not provide the tracepoint(s) {}",
+            missing.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn kernel_root(tracefs: &str, tracepoints: &[&str]) -> TempDir {
+        let root = TempDir::new().unwrap();
+        let btf = root.path().join(BTF_PATH);
+        std::fs::create_dir_all(btf.parent().unwrap()).unwrap();
+        std::fs::write(btf, b"").unwrap();
+        for tracepoint in tracepoints {
+            std::fs::create_dir_all(root.path().join(tracefs).join("events").join(tracepoint))
+                .unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_compatible_kernel() {
+        let root = kernel_root("sys/kernel/debug/tracing", REQUIRED_TRACEPOINTS);
+        assert!(check_compatibility_at(root.path()).is_ok());
+    }
+
+    #[test]
+    fn test_missing_btf() {
+        let root = kernel_root("sys/kernel/tracing", REQUIRED_TRACEPOINTS);
+        std::fs::remove_file(root.path().join(BTF_PATH)).unwrap();
+
+        let error = check_compatibility_at(root.path()).unwrap_err();
+        assert!(error.to_string().contains("BTF"));
+    }
+
+    #[test]
+    fn test_tracefs_not_mounted() {
+        let root = kernel_root("sys/kernel/tracing", &[]);
+
+        let error = check_compatibility_at(root.path()).unwrap_err();
+        assert!(error.to_string().contains("tracefs is not mounted"));
+    }
+
+    #[test]
+    fn test_missing_tracepoint() {
+        let root = kernel_root(
+            "sys/kernel/tracing",
+            &[
+                "sched/sched_process_exec",
+                "sched/sched_process_exit",
+                "syscalls/sys_enter_openat",
+                "syscalls/sys_enter_execve",
+                "syscalls/sys_exit_execve",
+            ],
+        );
+
+        let error = check_compatibility_at(root.path()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the kernel does not provide the tracepoint(s) vmscan/mm_vmscan_direct_reclaim_begin, oom/mark_victim"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of events the kernel dropped because the ring buffer was full, accumulated over the
+/// lifetime of the process (the kernel-side counts restart whenever the BPF program is reloaded)
+pub struct LostEventCounter {
+    total: AtomicU64,
+    last_kernel_total: AtomicU64,
+}
+
+impl Default for LostEventCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LostEventCounter {
+    pub const fn new() -> Self {
+        Self {
+            total: AtomicU64::new(0),
+            last_kernel_total: AtomicU64::new(0),
+        }
+    }
+
+    /// Accounts the kernel's cumulative per-CPU counts, returning how many events were newly lost
+    pub fn observe_per_cpu(&self, per_cpu: &[u64]) -> u64 {
+        let kernel_total: u64 = per_cpu.iter().sum();
+        let last = self.last_kernel_total.swap(kernel_total, Ordering::Relaxed);
+        // a smaller total means the program was reloaded and counts from zero again
+        let lost = if kernel_total >= last {
+            kernel_total - last
+        } else {
+            kernel_total
+        };
+        self.total.fetch_add(lost, Ordering::Relaxed);
+        lost
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) static LOST_EVENTS: LostEventCounter = LostEventCounter::new();
+
+/// Total number of eBPF events lost so far; always zero when eBPF is not in use
+pub fn events_lost() -> u64 {
+    LOST_EVENTS.total()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_loss_increments_counter() {
+        let counter = LostEventCounter::new();
+        assert_eq!(counter.observe_per_cpu(&[0, 0, 0, 0]), 0);
+
+        assert_eq!(counter.observe_per_cpu(&[3, 0, 2, 0]), 5);
+        assert_eq!(counter.observe_per_cpu(&[3, 1, 2, 0]), 1);
+        // no new loss
+        assert_eq!(counter.observe_per_cpu(&[3, 1, 2, 0]), 0);
+        assert_eq!(counter.total(), 6);
+    }
+
+    #[test]
+    fn test_reload_restarts_kernel_counts() {
+        let counter = LostEventCounter::new();
+        counter.observe_per_cpu(&[10, 10]);
+        // the program was reloaded, so these are new losses
+        assert_eq!(counter.observe_per_cpu(&[1, 2]), 3);
+        assert_eq!(counter.total(), 23);
+    }
+}
@@ -18,6 +18,7 @@ pub const EVENT__SYSCALL__SYS_ENTER_READ: u32 = 1026;
 pub const EVENT__SYSCALL__SYS_EXIT_READ: u32 = 1027;
 pub const EVENT__SYSCALL__SYS_ENTER_WRITE: u32 = 1028;
 pub const EVENT__SYSCALL__SYS_EXIT_WRITE: u32 = 1029;
+pub const EVENT__SYSCALL__SYS_EXIT_EXECVE: u32 = 1030;
 pub const EVENT__VMSCAN__MM_VMSCAN_DIRECT_RECLAIM_BEGIN: u32 = 2048;
 pub const EVENT__OOM__MARK_VICTIM: u32 = 3072;
 
@@ -44,6 +45,13 @@ pub struct SysEnterOpenAtPayload {
     pub mode: i32,
 }
 
+// struct syscall__sys_exit_execve__payload in bootstrap.h
+#[repr(C, packed)]
+pub struct SysExitExecvePayload {
+    pub filename: [u8; MAX_STR_LEN],
+    pub ret: i32,
+}
+
 // Define the CEvent struct to match the memory layout of the C struct
 #[repr(C, packed)]
 pub struct CEvent {
@@ -199,6 +207,25 @@ impl TryInto<ebpf_trigger::Trigger> for &CEvent {
                     },
                 ))
             }
+            EVENT__SYSCALL__SYS_EXIT_EXECVE => {
+                let payload_ptr = self.payload.as_ptr() as *const SysExitExecvePayload;
+                let payload = unsafe { &*payload_ptr };
+                let ret = payload.ret;
+
+                Ok(ebpf_trigger::Trigger::ExecFailed(
+                    ebpf_trigger::ExecFailedTrigger::new(
+                        self.pid as usize,
+                        self.ppid as usize,
+                        from_bpf_str(&payload.filename)?,
+                        -ret,
+                        chrono::DateTime::from_timestamp(
+                            (self.timestamp_ns / 1_000_000_000) as i64,
+                            (self.timestamp_ns % 1_000_000_000) as u32,
+                        )
+                        .unwrap(),
+                    ),
+                ))
+            }
             _ => Err(anyhow::anyhow!("Unsupported event type")),
         }
     }
@@ -1,8 +1,16 @@
 // checks if the user has root access to perform any operation
 use crate::checks::InstallCheck;
 use std::fs;
+use std::path::Path;
 use std::process::Command;
 
+/// Oldest kernel whose BTF and tracepoints the eBPF programs are built against
+pub const MIN_KERNEL_VERSION: (u32, u32) = (5, 15);
+/// Set to `1`/`true` to install on kernels without eBPF support; Tracer then polls procfs
+pub const SKIP_KERNEL_CHECK_ENV_VAR: &str = "TRACER_SKIP_KERNEL_CHECK";
+/// Type information of the running kernel, needed to load the eBPF programs
+const KERNEL_BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+
 pub struct KernelCheck;
 
 impl KernelCheck {
@@ -72,8 +80,25 @@ impl KernelCheck {
     }
 
     fn is_compatible_kernel(version: (u32, u32)) -> bool {
-        let (major, minor) = version;
-        major > 5 || (major == 5 && minor >= 15)
+        version >= MIN_KERNEL_VERSION
+    }
+
+    fn has_btf() -> bool {
+        Path::new(KERNEL_BTF_PATH).exists()
+    }
+
+    fn failure_message(version: Option<(u32, u32)>, has_btf: bool) -> String {
+        let (min_major, min_minor) = MIN_KERNEL_VERSION;
+        let detected = match version {
+            Some((major, minor)) => format!("Detected Linux v{}.{}", major, minor),
+            None => "Could not detect the Linux version".to_string(),
+        };
+        let btf = if has_btf { "present" } else { "missing" };
+        format!(
+            "Failed: {}, requires v{}.{} or newer (BTF {}). Set {}=1 to install anyway; \
+             Tracer then polls /proc instead of using eBPF.",
+            detected, min_major, min_minor, btf, SKIP_KERNEL_CHECK_ENV_VAR
+        )
     }
 
     fn get_os_name() -> Option<String> {
@@ -111,24 +136,51 @@ impl InstallCheck for KernelCheck {
         if !Self::is_supported_os() {
             let os_name = Self::get_os_name().unwrap_or_else(|| "Unknown".to_string());
             return format!(
-                "Failed: {} detected. Requires Linux kernel ≥ 5.15.",
-                os_name
+                "Failed: {} detected. Requires Linux kernel ≥ {}.{}.",
+                os_name, MIN_KERNEL_VERSION.0, MIN_KERNEL_VERSION.1
             );
         }
+        Self::failure_message(Self::get_kernel_version(), Self::has_btf())
+    }
 
-        match Self::get_kernel_version() {
-            Some((major, minor)) => {
-                format!(
-                    "Failed: Detected Linux v{}.{} (min required: v5.15)",
-                    major, minor
-                )
-            }
+    fn success_message(&self) -> String {
+        format!(
+            "Linux kernel is compatible with eBPF (>= {}.{}).",
+            MIN_KERNEL_VERSION.0, MIN_KERNEL_VERSION.1
+        )
+    }
 
-            None => "Linux version unknown. Requires kernel ≥ v5.15.".to_string(),
-        }
+    fn skippable(&self) -> bool {
+        true
     }
+}
 
-    fn success_message(&self) -> String {
-        "Linux kernel is compatible with eBPF (>= 5.15).".to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_around_the_minimum() {
+        assert!(KernelCheck::is_compatible_kernel((5, 15)));
+        assert!(KernelCheck::is_compatible_kernel((5, 19)));
+        assert!(KernelCheck::is_compatible_kernel((6, 0)));
+        assert!(KernelCheck::is_compatible_kernel((6, 8)));
+        assert!(!KernelCheck::is_compatible_kernel((5, 14)));
+        assert!(!KernelCheck::is_compatible_kernel((5, 4)));
+        assert!(!KernelCheck::is_compatible_kernel((4, 19)));
+        assert!(!KernelCheck::is_compatible_kernel((3, 10)));
+    }
+
+    #[test]
+    fn test_failure_message() {
+        assert_eq!(
+            KernelCheck::failure_message(Some((4, 18)), false),
+            "Failed: Detected Linux v4.18, requires v5.15 or newer (BTF missing). \
+             Set TRACER_SKIP_KERNEL_CHECK=1 to install anyway; Tracer then polls /proc instead \
+             of using eBPF."
+        );
+        assert!(KernelCheck::failure_message(None, true).starts_with(
+            "Failed: Could not detect the Linux version, requires v5.15 or newer (BTF present)."
+        ));
     }
 }
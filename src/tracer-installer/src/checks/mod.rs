@@ -6,11 +6,11 @@ mod storage;
 
 use crate::error_message;
 use crate::installer::{Os, PlatformInfo};
-use crate::utils::{print_status, TagColor};
+use crate::utils::{confirm, print_status, TagColor};
 use colored::Colorize;
 pub(crate) use environment::detect_environment_type;
 use environment::EnvironmentCheck;
-use kernel::KernelCheck;
+use kernel::{KernelCheck, SKIP_KERNEL_CHECK_ENV_VAR};
 
 use crate::checks::storage::StorageCheck;
 use root::RootCheck;
@@ -23,6 +23,10 @@ pub trait InstallCheck {
     fn name(&self) -> &'static str;
     fn error_message(&self) -> String;
     fn success_message(&self) -> String;
+    /// Whether the user may choose to install anyway when the check fails
+    fn skippable(&self) -> bool {
+        false
+    }
 }
 
 pub struct CheckManager {
@@ -35,7 +39,7 @@ impl CheckManager {
 
         match platform.os {
             Os::Linux | Os::AmazonLinux => {
-                let skip_kernel = std::env::var("TRACER_SKIP_KERNEL_CHECK")
+                let skip_kernel = std::env::var(SKIP_KERNEL_CHECK_ENV_VAR)
                     .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
                     .unwrap_or(false);
 
@@ -43,7 +47,7 @@ impl CheckManager {
                     print_status(
                         "SKIPPED",
                         "Kernel eBPF Support",
-                        "Skipped via TRACER_SKIP_KERNEL_CHECK",
+                        &format!("Skipped via {}", SKIP_KERNEL_CHECK_ENV_VAR),
                         TagColor::Cyan,
                     );
                 } else {
@@ -63,6 +67,15 @@ impl CheckManager {
     }
 
     pub async fn run_all(&self) {
+        if !self.report_all(true).await {
+            error_message!("Required environment checks failed. Please contact support.");
+            std::process::exit(1);
+        }
+    }
+
+    /// Runs and prints every check, returning whether they all passed. With `allow_skip`, the
+    /// user may choose to skip the skippable checks that failed.
+    pub async fn report_all(&self, allow_skip: bool) -> bool {
         let mut all_passed = true;
 
         for check in &self.checks {
@@ -74,17 +87,26 @@ impl CheckManager {
                     TagColor::Green,
                 );
             } else {
-                all_passed = false;
                 let reason = check.error_message();
                 print_status("FAILED", check.name(), &reason, TagColor::Red);
+                if allow_skip
+                    && check.skippable()
+                    && confirm(&format!("Install anyway, without {}?", check.name()))
+                {
+                    print_status(
+                        "SKIPPED",
+                        check.name(),
+                        "Skipped by the user",
+                        TagColor::Cyan,
+                    );
+                } else {
+                    all_passed = false;
+                }
             }
         }
 
         println!(); // spacing after checks
 
-        if !all_passed {
-            error_message!("Required environment checks failed. Please contact support.");
-            std::process::exit(1);
-        }
+        all_passed
     }
 }
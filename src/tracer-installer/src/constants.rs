@@ -9,3 +9,10 @@ pub const ENV_AWS_BATCH: &str = "aws_batch";
 pub const ENV_GITHUB_CODESPACES: &str = "github_codespaces";
 pub const ENV_DOCKER: &str = "docker";
 pub const ENV_LOCAL: &str = "local";
+/// Set to `1`/`true` to never send anything to Sentry
+pub const DISABLE_SENTRY_ENV_VAR: &str = "TRACER_DISABLE_SENTRY";
+/// Comma-separated regexes also redacted from Sentry events
+pub const SENTRY_SCRUB_PATTERNS_ENV_VAR: &str = "TRACER_SENTRY_SCRUB_PATTERNS";
+/// Working directory of the Tracer daemon
+// COPY: tracer/src/utils/workdir.rs
+pub const TRACER_WORK_DIR: &str = "/tmp/tracer";
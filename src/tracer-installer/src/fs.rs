@@ -15,6 +15,9 @@ use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 use tokio::fs::File as AsyncFile;
 
+/// Where the tracer binary is installed
+pub const USR_LOCAL_BIN: &str = "/usr/local/bin";
+
 // TODO: very similar code is duplicated in src/tracer/src/utils/file_system.rs. DRY this up.
 
 /// Wraps a path and ensures, as much as possible, that the path is sanitary.
@@ -34,7 +37,7 @@ impl TrustedDir {
     }
 
     pub fn usr_local_bin() -> Result<Self> {
-        Self::new(Path::new("/usr/local/bin"), Some("755"))
+        Self::new(Path::new(USR_LOCAL_BIN), Some("755"))
     }
 
     /// Creates a new `TrustedDir` from an aribtrary path. The path must be sanitary. If the path
@@ -143,6 +146,14 @@ pub fn ensure_dir_with_permissions(path: &PathBuf, permissions: Option<&str>) ->
     Ok(())
 }
 
+/// Whether the current user can create files in `path`, or in its nearest existing ancestor if
+/// it doesn't exist yet (as `TrustedDir` creates missing directories)
+pub fn is_writable(path: &Path) -> bool {
+    path.ancestors()
+        .find(|ancestor| fs::metadata(ancestor).is_ok())
+        .is_some_and(|dir| nix::unistd::access(dir, nix::unistd::AccessFlags::W_OK).is_ok())
+}
+
 impl Display for TrustedDir {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
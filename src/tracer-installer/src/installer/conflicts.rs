@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A `tracer` binary found on `PATH` other than the one being installed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExistingInstall {
+    pub path: PathBuf,
+    /// Output of `tracer version`, if it could be run
+    pub version: Option<String>,
+    /// Whether it comes before the install dir on `PATH` (or the install dir isn't on `PATH`),
+    /// so that it runs instead of the new binary
+    pub shadows_install: bool,
+}
+
+/// Scans the directories of `path_var` for `tracer` executables other than the one in
+/// `install_dir`
+pub fn find_existing_installs(path_var: &OsStr, install_dir: &Path) -> Vec<ExistingInstall> {
+    let install_dir = canonical(install_dir);
+    let installed_binary = install_dir.join("tracer");
+    let mut seen = HashSet::from([canonical(&installed_binary)]);
+    let mut before_install_dir = true;
+    let mut installs = Vec::new();
+
+    for dir in std::env::split_paths(path_var) {
+        if canonical(&dir) == install_dir {
+            before_install_dir = false;
+            continue;
+        }
+        let binary = dir.join("tracer");
+        if !is_executable(&binary) || !seen.insert(canonical(&binary)) {
+            continue;
+        }
+        installs.push(ExistingInstall {
+            version: binary_version(&binary),
+            path: binary,
+            shadows_install: before_install_dir,
+        });
+    }
+    installs
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+fn binary_version(binary: &Path) -> Option<String> {
+    let output = Command::new(binary)
+        .arg("version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    fn write_binary(dir: &Path, script: &str, mode: u32) {
+        let path = dir.join("tracer");
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    fn path_var(dirs: &[&Path]) -> OsString {
+        std::env::join_paths(dirs).unwrap()
+    }
+
+    #[test]
+    fn test_stale_binary_on_path_is_detected() {
+        let stale_dir = tempfile::tempdir().unwrap();
+        let install_dir = tempfile::tempdir().unwrap();
+        let later_dir = tempfile::tempdir().unwrap();
+        write_binary(stale_dir.path(), "#!/bin/sh\necho 2025.1.1\n", 0o755);
+        write_binary(install_dir.path(), "#!/bin/sh\necho 2025.5.15\n", 0o755);
+        write_binary(later_dir.path(), "#!/bin/sh\nexit 1\n", 0o755);
+
+        let installs = find_existing_installs(
+            &path_var(&[stale_dir.path(), install_dir.path(), later_dir.path()]),
+            install_dir.path(),
+        );
+        assert_eq!(
+            installs,
+            vec![
+                ExistingInstall {
+                    path: stale_dir.path().join("tracer"),
+                    version: Some("2025.1.1".to_string()),
+                    shadows_install: true,
+                },
+                ExistingInstall {
+                    path: later_dir.path().join("tracer"),
+                    version: None,
+                    shadows_install: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_executables_and_duplicates_are_ignored() {
+        let install_dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        write_binary(data_dir.path(), "not a binary", 0o644);
+
+        let installs = find_existing_installs(
+            &path_var(&[install_dir.path(), data_dir.path(), install_dir.path()]),
+            install_dir.path(),
+        );
+        assert!(installs.is_empty());
+    }
+}
@@ -1,10 +1,12 @@
+use super::conflicts::{find_existing_installs, ExistingInstall};
+use super::local::{is_gzip, read_local_archive};
 use super::platform::PlatformInfo;
-use crate::fs::{TrustedDir, TrustedFile};
+use crate::fs::{is_writable, TrustedDir, TrustedFile, USR_LOCAL_BIN};
 use crate::installer::url::TrustedUrl;
 use crate::success_message;
 use crate::types::{AnalyticsEventType, AnalyticsPayload, TracerVersion};
-use crate::utils::{print_message, print_status, print_title, TagColor};
-use anyhow::{Context, Result};
+use crate::utils::{confirm, print_message, print_status, print_title, TagColor};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
 use flate2::read::GzDecoder;
 use futures_util::future::join_all;
@@ -14,6 +16,7 @@ use reqwest::Client;
 use std::collections::HashMap;
 use std::fs::Permissions;
 use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use tar::Archive;
 use tokio::io::AsyncWriteExt;
 use tokio::task::JoinHandle;
@@ -28,13 +31,19 @@ pub struct Installer {
     pub platform: PlatformInfo,
     pub channel: TracerVersion,
     pub user_id: Option<String>,
+    /// Install from this local tarball or binary instead of downloading the release
+    pub local_archive: Option<PathBuf>,
 }
 
 impl Installer {
     /// Executes the tracer binary download process:
-    /// - Downloads the appropriate Tracer binary based on platform and version
+    /// - Checks that the binary can be written to `/usr/local/bin` before downloading anything
+    /// - Downloads the appropriate Tracer binary based on platform and version, or takes it from
+    ///   the local archive if one was given
     /// - Extracts and installs it to `/usr/local/bin`
     /// - Updates shell configuration files to include Tracer in the PATH
+    /// - Warns about other `tracer` binaries on the PATH, and offers to remove those that would
+    ///   run instead of the new one
     /// - Emits analytics events if a user ID is provided
     pub async fn run(&self) -> Result<()> {
         let mut analytics_handles = Vec::new();
@@ -46,17 +55,29 @@ impl Installer {
             analytics_handles.push(handle);
         }
 
-        let url = TrustedUrl::tracer_aws_url(&self.channel, &self.platform)?;
+        check_install_dir_writable(Path::new(USR_LOCAL_BIN))?;
 
-        print_message("DOWNLOADING", &url.to_string(), TagColor::Blue);
+        let existing_installs = Self::existing_installs();
+        Self::warn_about_existing_installs(&existing_installs);
 
         let temp_dir = TrustedDir::tempdir()?;
 
-        let extract_path = self
-            .download_and_extract_tarball(&url, &temp_dir, "tracer.tar.gz", "extracted")
-            .await?;
+        let extract_path = match &self.local_archive {
+            Some(path) => {
+                self.stage_local_archive(path, &temp_dir, "tracer.tar.gz", "extracted")?
+            }
+            None => {
+                let url = TrustedUrl::tracer_aws_url(&self.channel, &self.platform)?;
+
+                print_message("DOWNLOADING", &url.to_string(), TagColor::Blue);
+
+                self.download_and_extract_tarball(&url, &temp_dir, "tracer.tar.gz", "extracted")
+                    .await?
+            }
+        };
 
         let _ = self.install_to_final_dir(&extract_path)?;
+        Self::resolve_shadowing_installs(&existing_installs);
 
         if let Some(handle) = self
             .emit_analytic_event(AnalyticsEventType::InstallScriptCompleted)
@@ -90,6 +111,30 @@ impl Installer {
         Ok(extract_path)
     }
 
+    /// Copies the local tarball or binary at `path` to `base_dir` (so that only trusted paths are
+    /// installed from) and extracts it to `dest_subdir` like a downloaded release
+    fn stage_local_archive(
+        &self,
+        path: &Path,
+        base_dir: &TrustedDir,
+        tarball_name: &str,
+        dest_subdir: &str,
+    ) -> Result<TrustedDir> {
+        print_message("LOCAL", &path.display().to_string(), TagColor::Blue);
+
+        let contents = read_local_archive(path)?;
+        let extract_path = base_dir.join_dir(dest_subdir)?;
+        if is_gzip(&contents) {
+            let archive_path = base_dir.join_file(tarball_name)?;
+            std::fs::write(archive_path.as_path(), &contents)?;
+            self.extract_tarball(&archive_path, &extract_path)?;
+        } else {
+            let binary_path = extract_path.join_file("tracer")?;
+            std::fs::write(binary_path.as_path(), &contents)?;
+        }
+        Ok(extract_path)
+    }
+
     async fn download_with_progress(&self, url: &TrustedUrl, dest: &TrustedFile) -> Result<()> {
         let response = url
             .get()
@@ -139,13 +184,93 @@ impl Installer {
 
         extracted_binary
             .copy_to_with_permissions(&final_path, Permissions::from_mode(0o755))
-            .with_context(|| format!("Failed to copy tracer binary from {}", &final_path))?;
+            .with_context(|| format!("Failed to copy tracer binary to {}", &final_path))?;
 
         success_message!("Tracer installed to: {}", final_path);
 
         Ok(final_path)
     }
 
+    /// Prints what `run` would download and where it would install it, without downloading or
+    /// writing anything
+    pub fn print_plan(&self) -> Result<()> {
+        let url = TrustedUrl::tracer_aws_url(&self.channel, &self.platform)?;
+        print_message(
+            "DRY RUN",
+            &format!("Would download {}", url),
+            TagColor::Blue,
+        );
+        print_message(
+            "DRY RUN",
+            &format!(
+                "Would install to {}",
+                Path::new(USR_LOCAL_BIN).join("tracer").display()
+            ),
+            TagColor::Blue,
+        );
+        Self::warn_about_existing_installs(&Self::existing_installs());
+        Ok(())
+    }
+
+    fn existing_installs() -> Vec<ExistingInstall> {
+        std::env::var_os("PATH")
+            .map(|path| find_existing_installs(&path, Path::new(USR_LOCAL_BIN)))
+            .unwrap_or_default()
+    }
+
+    fn warn_about_existing_installs(installs: &[ExistingInstall]) {
+        for install in installs {
+            let version = install.version.as_deref().unwrap_or("unknown version");
+            let note = if install.shadows_install {
+                "; it comes first on PATH and will run instead of the new install"
+            } else {
+                ""
+            };
+            print_status(
+                "WARNING",
+                "Existing Installation",
+                &format!("{} ({}){}", install.path.display(), version, note),
+                TagColor::Cyan,
+            );
+        }
+    }
+
+    /// Offers to remove the binaries that run instead of the new one, or else explains how to
+    /// give it priority
+    fn resolve_shadowing_installs(installs: &[ExistingInstall]) {
+        let mut still_shadowed = false;
+        for install in installs.iter().filter(|install| install.shadows_install) {
+            let path = install.path.display();
+            if !confirm(&format!("Remove the older tracer at {}?", path)) {
+                still_shadowed = true;
+                continue;
+            }
+            match std::fs::remove_file(&install.path) {
+                Ok(()) => success_message!("Removed {}", path),
+                Err(e) => {
+                    still_shadowed = true;
+                    print_status(
+                        "WARNING",
+                        "Existing Installation",
+                        &format!("Failed to remove {}: {}", path, e),
+                        TagColor::Cyan,
+                    );
+                }
+            }
+        }
+        if still_shadowed {
+            print_status(
+                "WARNING",
+                "Existing Installation",
+                &format!(
+                    "Remove the binaries above or put {} first on your PATH to run the new tracer",
+                    USR_LOCAL_BIN
+                ),
+                TagColor::Cyan,
+            );
+        }
+    }
+
     // COPY: tracer/src/utils/analytics/mod.rs
     pub async fn send_analytic_event(
         user_id: &str,
@@ -191,6 +316,9 @@ impl Installer {
 
         map.insert("platform_os".into(), format!("{:?}", self.platform.os));
         map.insert("platform_arch".into(), format!("{:?}", self.platform.arch));
+        if let Some(libc) = &self.platform.libc {
+            map.insert("platform_libc".into(), format!("{:?}", libc));
+        }
         map.insert("channel".into(), format!("{:?}", self.channel));
         map.insert("environment".into(), env_type);
 
@@ -244,3 +372,95 @@ impl Installer {
         );
     }
 }
+
+/// Fails if the binary can't be installed to `install_dir`, so that nothing is downloaded in vain
+fn check_install_dir_writable(install_dir: &Path) -> Result<()> {
+    if is_writable(install_dir) {
+        return Ok(());
+    }
+    bail!(
+        "Cannot write to {}. Re-run the installer with sudo, or make the directory writable \
+         with: sudo chown $USER {}",
+        install_dir.display(),
+        install_dir.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::installer::platform::{Arch, Os};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::fs;
+
+    fn installer(local_archive: &Path) -> Installer {
+        Installer {
+            platform: PlatformInfo {
+                os: Os::Linux,
+                arch: Arch::X86_64,
+                libc: None,
+                full_os: "test".to_string(),
+            },
+            channel: TracerVersion::Production,
+            user_id: None,
+            local_archive: Some(local_archive.to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn test_install_from_local_tarball() {
+        let dir = tempfile::tempdir().unwrap();
+        let tarball = dir.path().join("tracer.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            fs::File::create(&tarball).unwrap(),
+            Compression::default(),
+        ));
+        let binary = b"#!/bin/sh\necho tracer\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(binary.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "tracer", &binary[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let installer = installer(&tarball);
+        let staging_dir = TrustedDir::tempdir().unwrap();
+        let extract_path = installer
+            .stage_local_archive(&tarball, &staging_dir, "tracer.tar.gz", "extracted")
+            .unwrap();
+        let extracted = extract_path.join_file("tracer").unwrap();
+        assert_eq!(fs::read(extracted.as_path()).unwrap(), binary);
+    }
+
+    #[test]
+    fn test_install_from_local_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("tracer");
+        fs::write(&binary, b"\x7fELF").unwrap();
+
+        let staging_dir = TrustedDir::tempdir().unwrap();
+        let extract_path = installer(&binary)
+            .stage_local_archive(&binary, &staging_dir, "tracer.tar.gz", "extracted")
+            .unwrap();
+        let extracted = extract_path.join_file("tracer").unwrap();
+        assert_eq!(fs::read(extracted.as_path()).unwrap(), b"\x7fELF");
+    }
+
+    #[test]
+    fn test_unwritable_install_dir_is_caught_before_download() {
+        let root = tempfile::tempdir().unwrap();
+        let install_dir = root.path().join("bin");
+        fs::create_dir(&install_dir).unwrap();
+        assert!(check_install_dir_writable(&install_dir).is_ok());
+
+        fs::set_permissions(&install_dir, Permissions::from_mode(0o555)).unwrap();
+        // root can write regardless of permissions
+        if !nix::unistd::Uid::effective().is_root() {
+            let error = check_install_dir_writable(&install_dir).unwrap_err();
+            assert!(error.to_string().contains("sudo"));
+        }
+    }
+}
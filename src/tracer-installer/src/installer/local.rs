@@ -0,0 +1,92 @@
+use anyhow::{bail, Context, Result};
+use ring::digest::{digest, SHA256};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Suffix of the SHA-256 checksum published alongside every release archive
+// COPY: tracer/src/cli/handlers/update/verify.rs
+pub const CHECKSUM_SUFFIX: &str = ".sha256";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Reads a locally provided release tarball or binary, verifying it against the checksum file
+/// next to it (`<path>.sha256`) if there is one
+pub fn read_local_archive(path: &Path) -> Result<Vec<u8>> {
+    let contents = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let checksum_path = checksum_path(path);
+    if checksum_path.exists() {
+        let checksum = fs::read_to_string(&checksum_path)
+            .with_context(|| format!("Failed to read {}", checksum_path.display()))?;
+        verify_checksum(&contents, &checksum)
+            .with_context(|| format!("Failed to verify {}", path.display()))?;
+    }
+    Ok(contents)
+}
+
+pub fn is_gzip(contents: &[u8]) -> bool {
+    contents.starts_with(&GZIP_MAGIC)
+}
+
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut checksum_path = OsString::from(path);
+    checksum_path.push(CHECKSUM_SUFFIX);
+    PathBuf::from(checksum_path)
+}
+
+/// Compares the digest of `contents` with a checksum file (`sha256sum` output or just the digest)
+// COPY: tracer/src/cli/handlers/update/verify.rs
+fn verify_checksum(contents: &[u8], checksum: &str) -> Result<()> {
+    let expected = parse_checksum(checksum)?;
+    let actual = hex::encode(digest(&SHA256, contents));
+    if actual != expected {
+        bail!(
+            "Checksum mismatch: expected {}, but the file has {}",
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+// COPY: tracer/src/cli/handlers/update/verify.rs
+fn parse_checksum(contents: &str) -> Result<String> {
+    let checksum = contents
+        .split_whitespace()
+        .next()
+        .context("The checksum file is empty")?
+        .to_lowercase();
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Invalid SHA-256 checksum {:?}", checksum);
+    }
+    Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_sidecar_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("tracer.tar.gz");
+        fs::write(&archive, b"tracer release archive").unwrap();
+        // without a sidecar the file is used as is
+        assert_eq!(
+            read_local_archive(&archive).unwrap(),
+            b"tracer release archive"
+        );
+
+        let checksum = hex::encode(digest(&SHA256, b"tracer release archive"));
+        fs::write(
+            dir.path().join("tracer.tar.gz.sha256"),
+            format!("{}  tracer.tar.gz\n", checksum),
+        )
+        .unwrap();
+        assert!(read_local_archive(&archive).is_ok());
+
+        fs::write(&archive, b"truncated").unwrap();
+        let error = read_local_archive(&archive).unwrap_err();
+        assert!(format!("{:#}", error).contains("Checksum mismatch"));
+    }
+}
@@ -1,7 +1,11 @@
 pub use install::Installer;
 pub use platform::Os;
 pub use platform::PlatformInfo;
+pub use uninstall::Uninstaller;
 
+mod conflicts;
 mod install;
+mod local;
 mod platform;
+mod uninstall;
 mod url;
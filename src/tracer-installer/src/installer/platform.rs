@@ -3,6 +3,7 @@ use crate::utils::{get_total_space_available_bytes, print_status, TagColor};
 use crate::warning_message;
 use anyhow::{anyhow, Result};
 use colored::Colorize;
+use std::path::Path;
 use std::process::Command;
 use sysinfo::System;
 
@@ -19,10 +20,20 @@ pub enum Arch {
     Aarch64,
 }
 
+/// The C library a Linux system is built on, which decides which binaries can run on it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Libc {
+    Glibc,
+    /// e.g. Alpine
+    Musl,
+}
+
 #[derive(Debug, Clone)]
 pub struct PlatformInfo {
     pub os: Os,
     pub arch: Arch,
+    /// `None` on macOS
+    pub libc: Option<Libc>,
     pub full_os: String,
 }
 
@@ -72,7 +83,17 @@ impl PlatformInfo {
 
         Sentry::add_tag("platform", format!("{} ({})", full_os, raw_arch).as_str());
 
-        Ok(PlatformInfo { os, arch, full_os })
+        let libc = match os {
+            Os::Macos => None,
+            Os::Linux | Os::AmazonLinux => Some(detect_libc()),
+        };
+
+        Ok(PlatformInfo {
+            os,
+            arch,
+            libc,
+            full_os,
+        })
     }
 
     pub fn print_summary(&self) {
@@ -88,6 +109,9 @@ impl PlatformInfo {
             &format!("{:?}", self.arch),
             TagColor::Cyan,
         );
+        if let Some(libc) = &self.libc {
+            print_status("INFO", "C Library", &format!("{:?}", libc), TagColor::Cyan);
+        }
         let sys = System::new_all();
 
         let total_ram_gib = sys.total_memory() as f64 / 1024.0 / 1024.0 / 1024.0;
@@ -113,3 +137,56 @@ impl PlatformInfo {
         );
     }
 }
+
+/// Detects musl by its dynamic loader (`/lib/ld-musl-<arch>.so.1`), falling back to the output of
+/// `ldd --version`, and assumes glibc otherwise
+fn detect_libc() -> Libc {
+    let has_musl_loader = std::fs::read_dir(Path::new("/lib"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.file_name().to_string_lossy().starts_with("ld-musl-"))
+        })
+        .unwrap_or(false);
+    if has_musl_loader {
+        return Libc::Musl;
+    }
+
+    // musl's ldd prints its version to stderr and exits with 1
+    Command::new("ldd")
+        .arg("--version")
+        .output()
+        .map(|output| {
+            libc_from_ldd_version(&format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        })
+        .unwrap_or(Libc::Glibc)
+}
+
+fn libc_from_ldd_version(output: &str) -> Libc {
+    if output.to_lowercase().contains("musl") {
+        Libc::Musl
+    } else {
+        Libc::Glibc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_libc_from_ldd_version() {
+        assert_eq!(
+            libc_from_ldd_version("ldd (Ubuntu GLIBC 2.35-0ubuntu3.8) 2.35\nCopyright (C) 2022"),
+            Libc::Glibc
+        );
+        assert_eq!(
+            libc_from_ldd_version("musl libc (aarch64)\nVersion 1.2.4\nDynamic Program Loader"),
+            Libc::Musl
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::constants::{TRACER_WORK_DIR, USER_ID_ENV_VAR};
+use crate::fs::USR_LOCAL_BIN;
+use crate::success_message;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Shell profiles the Tracer environment may have been added to, relative to the home directory
+// COPY: tracer/src/cli/handlers/uninstall.rs
+const PROFILE_FILES: &[&str] = &[
+    ".bashrc",
+    ".bash_profile",
+    ".zshrc",
+    ".zprofile",
+    ".profile",
+];
+
+/// Removes what installing and running Tracer leaves on the machine
+pub struct Uninstaller {
+    binary: PathBuf,
+    work_dir: PathBuf,
+    profiles: Vec<PathBuf>,
+}
+
+impl Uninstaller {
+    pub fn new() -> Self {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        Self::with_paths(
+            Path::new(USR_LOCAL_BIN).join("tracer"),
+            PathBuf::from(TRACER_WORK_DIR),
+            home.as_deref(),
+        )
+    }
+
+    fn with_paths(binary: PathBuf, work_dir: PathBuf, home: Option<&Path>) -> Self {
+        let profiles = home
+            .map(|home| PROFILE_FILES.iter().map(|file| home.join(file)).collect())
+            .unwrap_or_default();
+        Self {
+            binary,
+            work_dir,
+            profiles,
+        }
+    }
+
+    /// Describes each removal `run` would make; empty if Tracer isn't installed
+    pub fn plan(&self) -> Vec<String> {
+        let mut plan = Vec::new();
+        if self.binary.exists() {
+            plan.push(format!("Remove the binary {}", self.binary.display()));
+        }
+        if self.work_dir.exists() {
+            plan.push(format!(
+                "Remove the working directory {}",
+                self.work_dir.display()
+            ));
+        }
+        for profile in &self.profiles {
+            let Ok(content) = fs::read_to_string(profile) else {
+                continue;
+            };
+            let (_, removed) = strip_tracer_lines(&content);
+            for line in removed {
+                plan.push(format!(
+                    "Remove `{}` from {}",
+                    line.trim(),
+                    profile.display()
+                ));
+            }
+        }
+        plan
+    }
+
+    pub fn run(&self) -> Result<()> {
+        if self.binary.exists() {
+            fs::remove_file(&self.binary)
+                .with_context(|| format!("Failed to remove {}", self.binary.display()))?;
+            success_message!("Removed {}", self.binary.display());
+        }
+        if self.work_dir.exists() {
+            fs::remove_dir_all(&self.work_dir)
+                .with_context(|| format!("Failed to remove {}", self.work_dir.display()))?;
+            success_message!("Removed {}", self.work_dir.display());
+        }
+        for profile in &self.profiles {
+            let Ok(content) = fs::read_to_string(profile) else {
+                continue;
+            };
+            let (stripped, removed) = strip_tracer_lines(&content);
+            if removed.is_empty() {
+                continue;
+            }
+            fs::write(profile, stripped)
+                .with_context(|| format!("Failed to update {}", profile.display()))?;
+            success_message!("Removed the Tracer environment from {}", profile.display());
+        }
+        Ok(())
+    }
+}
+
+/// Splits a shell profile into its lines without the Tracer environment (lines mentioning
+/// tracer or the user id variable, and the blank lines after them) and the removed lines
+// COPY: tracer/src/cli/handlers/uninstall.rs
+fn strip_tracer_lines(content: &str) -> (String, Vec<&str>) {
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    let mut in_tracer_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.to_lowercase().contains("tracer") || trimmed.contains(USER_ID_ENV_VAR) {
+            removed.push(line);
+            in_tracer_block = true;
+            continue;
+        }
+        if in_tracer_block && trimmed.is_empty() {
+            continue;
+        }
+        in_tracer_block = false;
+        kept.push(line);
+    }
+
+    let mut stripped = kept.join("\n");
+    if content.ends_with('\n') && !stripped.is_empty() {
+        stripped.push('\n');
+    }
+    (stripped, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = "export PATH=$HOME/bin:$PATH\n\
+                           export TRACER_USER_ID=user-123\n\
+                           alias tracer-demo='tracer demo'\n\
+                           \n\
+                           alias ll='ls -l'\n";
+
+    #[test]
+    fn test_uninstall_removes_installed_files() {
+        let root = tempfile::tempdir().unwrap();
+        let binary = root.path().join("bin/tracer");
+        let work_dir = root.path().join("tmp/tracer");
+        let home = root.path().join("home");
+        fs::create_dir_all(binary.parent().unwrap()).unwrap();
+        fs::write(&binary, "binary").unwrap();
+        fs::create_dir_all(&work_dir).unwrap();
+        fs::write(work_dir.join("tracerd.pid"), "42").unwrap();
+        fs::create_dir_all(&home).unwrap();
+        fs::write(home.join(".bashrc"), PROFILE).unwrap();
+        fs::write(home.join(".zshrc"), "alias ll='ls -l'\n").unwrap();
+
+        let uninstaller = Uninstaller::with_paths(binary.clone(), work_dir.clone(), Some(&home));
+        assert_eq!(uninstaller.plan().len(), 4);
+        uninstaller.run().unwrap();
+
+        assert!(!binary.exists());
+        assert!(!work_dir.exists());
+        assert_eq!(
+            fs::read_to_string(home.join(".bashrc")).unwrap(),
+            "export PATH=$HOME/bin:$PATH\nalias ll='ls -l'\n"
+        );
+        assert_eq!(
+            fs::read_to_string(home.join(".zshrc")).unwrap(),
+            "alias ll='ls -l'\n"
+        );
+        assert!(!home.join(".profile").exists());
+        assert!(uninstaller.plan().is_empty());
+    }
+
+    #[test]
+    fn test_uninstall_without_installation() {
+        let root = tempfile::tempdir().unwrap();
+        let uninstaller =
+            Uninstaller::with_paths(root.path().join("tracer"), root.path().join("work"), None);
+        assert!(uninstaller.plan().is_empty());
+        uninstaller.run().unwrap();
+    }
+}
@@ -1,6 +1,6 @@
-use crate::installer::platform::{Arch, Os, PlatformInfo};
+use crate::installer::platform::{Arch, Libc, Os, PlatformInfo};
 use crate::types::TracerVersion;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use reqwest::Response;
 use std::fmt::{self, Display, Formatter};
 use url::Url;
@@ -54,6 +54,16 @@ fn get_tarball_name(platform: &PlatformInfo, version: &TracerVersion) -> Result<
         TracerVersion::Development | TracerVersion::Feature(_) => "tracer-dev",
     };
 
+    // only glibc binaries are released
+    if platform.libc == Some(Libc::Musl) {
+        bail!(
+            "No Tracer release is available for musl-based Linux ({} {:?}); use a glibc-based \
+             distribution or image instead",
+            platform.full_os,
+            platform.arch
+        );
+    }
+
     let suffix = match (&platform.os, &platform.arch) {
         (Os::Linux, Arch::X86_64) => "x86_64-unknown-linux-gnu.tar.gz",
         (Os::Linux, Arch::Aarch64) => "aarch64-unknown-linux-gnu.tar.gz",
@@ -64,3 +74,73 @@ fn get_tarball_name(platform: &PlatformInfo, version: &TracerVersion) -> Result<
     };
     Ok(format!("{}-{}", prefix, suffix))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(os: Os, arch: Arch, libc: Option<Libc>) -> PlatformInfo {
+        PlatformInfo {
+            os,
+            arch,
+            libc,
+            full_os: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tarball_name_matches_platform() {
+        let cases = [
+            (
+                platform(Os::Linux, Arch::X86_64, Some(Libc::Glibc)),
+                "tracer-prod-x86_64-unknown-linux-gnu.tar.gz",
+            ),
+            (
+                platform(Os::Linux, Arch::Aarch64, Some(Libc::Glibc)),
+                "tracer-prod-aarch64-unknown-linux-gnu.tar.gz",
+            ),
+            (
+                platform(Os::AmazonLinux, Arch::X86_64, Some(Libc::Glibc)),
+                "tracer-prod-x86_64-amazon-linux-gnu.tar.gz",
+            ),
+            (
+                platform(Os::AmazonLinux, Arch::Aarch64, Some(Libc::Glibc)),
+                "tracer-prod-aarch64-unknown-linux-gnu.tar.gz",
+            ),
+            (
+                platform(Os::Macos, Arch::X86_64, None),
+                "tracer-prod-x86_64-apple-darwin.tar.gz",
+            ),
+            (
+                platform(Os::Macos, Arch::Aarch64, None),
+                "tracer-prod-aarch64-apple-darwin.tar.gz",
+            ),
+        ];
+        for (platform, expected) in cases {
+            assert_eq!(
+                get_tarball_name(&platform, &TracerVersion::Production).unwrap(),
+                expected
+            );
+        }
+        assert_eq!(
+            get_tarball_name(
+                &platform(Os::Linux, Arch::Aarch64, Some(Libc::Glibc)),
+                &TracerVersion::Development
+            )
+            .unwrap(),
+            "tracer-dev-aarch64-unknown-linux-gnu.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_musl_has_no_tarball() {
+        for arch in [Arch::X86_64, Arch::Aarch64] {
+            let error = get_tarball_name(
+                &platform(Os::Linux, arch, Some(Libc::Musl)),
+                &TracerVersion::Production,
+            )
+            .unwrap_err();
+            assert!(error.to_string().contains("musl"));
+        }
+    }
+}
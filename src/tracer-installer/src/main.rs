@@ -3,15 +3,18 @@ mod constants;
 mod fs;
 mod installer;
 mod message;
+mod scrubber;
 mod sentry;
 mod types;
 mod utils;
 
 use crate::sentry::Sentry;
-use crate::utils::print_title;
+use crate::utils::{confirm, print_title};
+use crate::{error_message, info_message, success_message, warning_message};
 use checks::CheckManager;
 use clap::Parser;
-use installer::{Installer, PlatformInfo};
+use colored::Colorize;
+use installer::{Installer, PlatformInfo, Uninstaller};
 use tokio::runtime::Runtime;
 use types::{InstallTracerCli, InstallerCommand};
 use utils::print_anteater_banner;
@@ -32,22 +35,15 @@ fn main() {
 
 async fn async_main(args: InstallTracerCli) {
     match args.command {
-        InstallerCommand::Run { channel, user_id } => {
+        InstallerCommand::Run {
+            channel,
+            user_id,
+            from,
+        } => {
             // Run checks
             print_anteater_banner(&channel);
 
-            print_title("System Specification");
-
-            // Platform detection
-            let platform = match PlatformInfo::build() {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("Failed to detect platform: {e}");
-                    std::process::exit(1);
-                }
-            };
-
-            platform.print_summary();
+            let platform = detect_platform();
 
             print_title("Running Environment Checks");
 
@@ -59,11 +55,70 @@ async fn async_main(args: InstallTracerCli) {
                 platform,
                 channel,
                 user_id,
+                local_archive: from,
             };
             if let Err(err) = installer.run().await {
                 eprintln!("Error Running Installer: {err}");
                 std::process::exit(1);
             }
         }
+        InstallerCommand::DryRun { channel } => {
+            print_anteater_banner(&channel);
+            let platform = detect_platform();
+
+            print_title("Running Environment Checks");
+            let requirements = CheckManager::new(&platform).await;
+            if !requirements.report_all(false).await {
+                warning_message!("Required environment checks failed; `run` would stop here.");
+            }
+
+            print_title("Installing Tracer");
+            let installer = Installer {
+                platform,
+                channel,
+                user_id: None,
+                local_archive: None,
+            };
+            if let Err(err) = installer.print_plan() {
+                eprintln!("Error Running Installer: {err}");
+                std::process::exit(1);
+            }
+        }
+        InstallerCommand::Uninstall { yes } => {
+            let uninstaller = Uninstaller::new();
+            let plan = uninstaller.plan();
+            if plan.is_empty() {
+                info_message!("Tracer is not installed.");
+                return;
+            }
+            print_title("Uninstalling Tracer");
+            for step in &plan {
+                println!("  - {}", step);
+            }
+            if !yes && !confirm("Uninstall Tracer?") {
+                info_message!("Uninstall cancelled. Pass --yes to uninstall without a terminal.");
+                return;
+            }
+            if let Err(err) = uninstaller.run() {
+                error_message!("Failed to uninstall Tracer: {err:#}");
+                std::process::exit(1);
+            }
+            success_message!("Tracer uninstalled.");
+        }
     }
 }
+
+fn detect_platform() -> PlatformInfo {
+    print_title("System Specification");
+
+    let platform = match PlatformInfo::build() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Failed to detect platform: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    platform.print_summary();
+    platform
+}
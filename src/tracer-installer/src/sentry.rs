@@ -1,25 +1,58 @@
 use crate::checks::kernel::KernelCheck;
-use crate::constants::SENTRY_DSN;
+use crate::constants::{DISABLE_SENTRY_ENV_VAR, SENTRY_DSN, SENTRY_SCRUB_PATTERNS_ENV_VAR};
+use crate::info_message;
+use crate::scrubber::Scrubber;
+use colored::Colorize;
 use sentry::ClientOptions;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Set once `setup` found Sentry disabled, turning every other call into a no-op
+static DISABLED: AtomicBool = AtomicBool::new(false);
 
 pub struct Sentry;
 
 // COPY: tracer/src/utils/sentry.rs
 impl Sentry {
-    /// Initializes Sentry if a DSN is provided in the config.
+    /// Initializes Sentry if a DSN is provided in the config, unless `TRACER_DISABLE_SENTRY` is
+    /// set. Events are scrubbed of personal information and secrets, plus the patterns in
+    /// `TRACER_SENTRY_SCRUB_PATTERNS`, before being sent.
     /// Returns a guard to keep Sentry active for the program's lifetime.
     pub fn setup() -> Option<sentry::ClientInitGuard> {
         if cfg!(test) {
             return None;
         }
+        let disabled = std::env::var(DISABLE_SENTRY_ENV_VAR)
+            .is_ok_and(|value| matches!(value.as_str(), "1" | "true"));
+        let scrub_patterns: Vec<String> = std::env::var(SENTRY_SCRUB_PATTERNS_ENV_VAR)
+            .map(|patterns| {
+                patterns
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::init(disabled, Scrubber::new(&scrub_patterns))
+    }
+
+    fn init(disabled: bool, scrubber: Scrubber) -> Option<sentry::ClientInitGuard> {
+        if disabled {
+            // no client is created, so nothing can be sent
+            DISABLED.store(true, Ordering::Relaxed);
+            info_message!("Sentry error reporting is disabled");
+            return None;
+        }
 
         let sentry = sentry::init((
             SENTRY_DSN,
             ClientOptions {
                 release: sentry::release_name!(),
-                // Enables capturing user IPs and sensitive headers when using HTTP server integrations.
+                // Don't capture user IPs and sensitive headers in HTTP server integrations.
                 // See: https://docs.sentry.io/platforms/rust/data-management/data-collected/
-                send_default_pii: true,
+                send_default_pii: false,
+                before_send: Some(Arc::new(move |event| Some(scrubber.scrub_event(event)))),
                 ..Default::default()
             },
         ));
@@ -32,9 +65,13 @@ impl Sentry {
         Some(sentry)
     }
 
+    fn is_disabled() -> bool {
+        cfg!(test) || DISABLED.load(Ordering::Relaxed)
+    }
+
     /// Adds a tag (key-value pair) to the Sentry event for short, string-based metadata.
     pub fn add_tag(key: &str, value: &str) {
-        if cfg!(test) {
+        if Self::is_disabled() {
             return;
         }
         sentry::configure_scope(|scope| {
@@ -44,9 +81,24 @@ impl Sentry {
 
     /// Captures a message event in Sentry with the specified level.
     pub fn capture_message(message: &str, level: sentry::Level) {
-        if cfg!(test) {
+        if Self::is_disabled() {
             return;
         }
         sentry::capture_message(message, level);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_sentry_has_no_client() {
+        assert!(Sentry::init(true, Scrubber::new(&[])).is_none());
+        assert!(sentry::Hub::current().client().is_none());
+
+        Sentry::add_tag("type", "installer");
+        Sentry::capture_message("not sent", sentry::Level::Error);
+        assert!(sentry::Hub::current().client().is_none());
+    }
+}
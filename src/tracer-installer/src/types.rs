@@ -2,6 +2,7 @@ use crate::constants::USER_ID_ENV_VAR;
 use clap::{Parser, Subcommand};
 use serde::Serialize;
 use std::fmt;
+use std::path::PathBuf;
 use std::{collections::HashMap, str::FromStr};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -52,6 +53,24 @@ pub enum InstallerCommand {
         /// Optional user ID used to associate this installation with your account.
         #[arg(long, env = USER_ID_ENV_VAR)]
         user_id: Option<String>,
+
+        /// Install from a local release tarball or binary instead of downloading it, e.g. on
+        /// air-gapped machines. Verified against `<PATH>.sha256` if that file exists.
+        #[arg(long, value_name = "PATH")]
+        from: Option<PathBuf>,
+    },
+    /// Show what `run` would do (platform, checks, download and install paths) without
+    /// downloading or writing anything
+    DryRun {
+        /// The channel or branch that would be installed.
+        #[arg(long, default_value = "production")]
+        channel: TracerVersion,
+    },
+    /// Remove the Tracer binary, its shell profile entries and its working directory
+    Uninstall {
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
     },
 }
 
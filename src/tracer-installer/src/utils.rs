@@ -1,6 +1,7 @@
 use crate::types::TracerVersion;
 use colored::Colorize;
 use std::collections::HashSet;
+use std::io::{BufRead, IsTerminal, Write};
 use sysinfo::Disks;
 
 pub enum TagColor {
@@ -10,6 +11,20 @@ pub enum TagColor {
     Cyan,
 }
 
+/// Asks a yes/no question, answered no when the installer isn't run from a terminal
+pub fn confirm(question: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    print!("{} [y/N] ", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
 pub fn print_message(tag: &str, message: &str, color: TagColor) {
     let tag = format!("[{tag}]");
     let tag = match color {
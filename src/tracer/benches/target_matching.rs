@@ -0,0 +1,65 @@
+//! Compares target matching with and without the match cache on a high-fork workload, where the
+//! same few commands are started over and over.
+//!
+//! Run with `cargo bench -p tracer --bench target_matching`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tracer::process_identification::target_process::target_manager::TargetManager;
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+const ITERATIONS: usize = 100_000;
+
+fn workload() -> Vec<ProcessStartTrigger> {
+    [
+        vec!["samtools", "view", "-b", "sample.sam"],
+        vec![
+            "bwa",
+            "mem",
+            "-t",
+            "8",
+            "ref.fa",
+            "reads_1.fq",
+            "reads_2.fq",
+        ],
+        vec!["STAR", "--runMode", "alignReads", "--readFilesIn", "r1.fq"],
+        vec!["cat", "sample.fastq.gz"],
+        vec!["bash", "-c", "echo done"],
+    ]
+    .iter()
+    .enumerate()
+    .map(|(pid, argv)| ProcessStartTrigger::from_name_and_args(pid, 1, argv[0], argv))
+    .collect()
+}
+
+fn run(manager: &TargetManager, processes: &[ProcessStartTrigger]) -> Duration {
+    let started = Instant::now();
+    for i in 0..ITERATIONS {
+        black_box(manager.get_target_match(&processes[i % processes.len()]));
+    }
+    started.elapsed()
+}
+
+fn main() {
+    let processes = workload();
+    let uncached = run(
+        &TargetManager::default().with_match_cache_size(0),
+        &processes,
+    );
+    let cached = run(&TargetManager::default(), &processes);
+
+    println!(
+        "uncached: {:?} ({:?}/match)",
+        uncached,
+        uncached / ITERATIONS as u32
+    );
+    println!(
+        "cached:   {:?} ({:?}/match)",
+        cached,
+        cached / ITERATIONS as u32
+    );
+    println!(
+        "speedup:  {:.1}x",
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
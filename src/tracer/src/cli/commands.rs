@@ -1,5 +1,6 @@
 use crate::cli::handlers::demo_arguments::TracerCliDemoArgs;
 use crate::cli::handlers::init_arguments::TracerCliInitArgs;
+use crate::cli::handlers::UpdateChannel;
 use crate::utils::workdir::TRACER_WORK_DIR;
 use crate::utils::Version;
 use clap::{Parser, Subcommand};
@@ -56,18 +57,48 @@ pub enum Command {
         json: bool,
     },
 
+    /// Shows the latest system, GPU and process metrics of the active run
+    Metrics {
+        /// Output the metrics in JSON format
+        #[clap(long)]
+        json: bool,
+    },
+
     /// Run demo pipelines with automatic tracing
     ///
     /// Executes example bioinformatics pipelines to demonstrate
     /// Tracer's monitoring capabilities in a sandbox environment.
     Demo(Box<TracerCliDemoArgs>),
 
-    /// [DEPRECATED] Use 'demo' command instead
-    #[clap(hide = true)]
-    Test,
+    /// Check that events reach Tracer end to end
+    ///
+    /// Records a test event in the active run and waits until it is exported, reporting the
+    /// stage (daemon, run, recorder or exporter) that failed, if any.
+    Test {
+        /// Seconds to wait for the test event to be exported
+        #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..=25))]
+        timeout: u64,
+    },
 
     /// Update the daemon to the latest version
-    Update,
+    Update {
+        /// Install this release (e.g. v1.2.3) instead of the latest one
+        #[clap(long, conflicts_with = "channel")]
+        version: Option<String>,
+
+        /// Release channel whose latest build is installed
+        #[clap(long, value_enum, default_value = "production")]
+        channel: UpdateChannel,
+
+        /// Reinstall or downgrade even if the release is not newer than this version
+        #[clap(long)]
+        force: bool,
+
+        /// Install the release even if no checksum is published for it; a signature required
+        /// by TRACER_UPDATE_PUBLIC_KEY is still checked
+        #[clap(long)]
+        allow_unverified: bool,
+    },
 
     /// Start a new pipeline run
     Start {
@@ -83,6 +114,29 @@ pub enum Command {
         terminate: bool,
     },
 
+    /// Stream live events recorded by the daemon
+    Watch {
+        /// Only show events with these statuses (comma-separated, e.g. tool_execution), or "all"
+        #[clap(long, default_value = "all")]
+        status: String,
+
+        /// Only show events of this run
+        #[clap(long)]
+        run_id: Option<String>,
+
+        /// Only show events of tools whose name contains this substring
+        #[clap(long)]
+        tool: Option<String>,
+    },
+
+    /// Stream the raw process events reported by the kernel, before target matching (requires root)
+    #[clap(hide = true)]
+    DebugEvents {
+        /// Print the events as newline-delimited JSON
+        #[clap(long)]
+        json: bool,
+    },
+
     /// Shows the current version of the daemon
     Version,
 
@@ -95,6 +149,32 @@ pub enum Command {
         command: OtelCommand,
     },
 
+    /// Show the estimated cost of an instance type, as tracer would price it
+    Cost {
+        /// EC2 instance type, e.g. m5.large
+        instance_type: String,
+
+        /// AWS region of the instance, resolved like the daemon's when not set
+        #[clap(long)]
+        region: Option<String>,
+
+        /// Output information in JSON format
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Target rules files management
+    Rules {
+        #[clap(subcommand)]
+        command: RulesCommand,
+    },
+
+    /// AWS pricing maintenance
+    Pricing {
+        #[clap(subcommand)]
+        command: PricingCommand,
+    },
+
     /// Login to tracer and saves a JWT token inside a token.txt file
     Login {
         /// Environment to authenticate against
@@ -114,6 +194,31 @@ pub enum Command {
     },
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum RulesCommand {
+    /// Check rules files for errors, and for conflicts between them once merged, without
+    /// starting the daemon
+    Validate {
+        /// Paths to the YAML rules files or directories, merged in order
+        #[clap(required = true)]
+        files: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PricingCommand {
+    /// Fetch and cache the pricing of commonly used instance types, so runs on them start faster
+    Warmup {
+        /// AWS region to fetch the pricing for, resolved like the daemon's when not set
+        #[clap(long)]
+        region: Option<String>,
+
+        /// Instance types to cache (comma-separated), defaults to the configured list
+        #[clap(long, value_delimiter = ',')]
+        instance_types: Vec<String>,
+    },
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum OtelCommand {
     /// Setup and install OpenTelemetry collector
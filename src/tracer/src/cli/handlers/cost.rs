@@ -0,0 +1,52 @@
+use crate::cloud_providers::aws::config::{get_aws_default_profile, AwsConfig};
+use crate::cloud_providers::aws::pricing::PricingSource;
+use crate::cloud_providers::aws::region::resolve_aws_region;
+use crate::config::Config;
+use anyhow::{bail, Result};
+use serde_json::json;
+
+/// Prices an instance type through the same pricing source the daemon resolves at startup
+pub async fn cost(
+    instance_type: &str,
+    region: Option<&str>,
+    config: &Config,
+    json: bool,
+) -> Result<()> {
+    let region = resolve_aws_region(region.or(config.aws_region.as_deref()))
+        .await
+        .region;
+    let region = region.as_str();
+    let source = PricingSource::new(AwsConfig::Profile(get_aws_default_profile()), region).await;
+    let Some(pricing) = source
+        .get_aws_price_for_instance_type(region, instance_type)
+        .await
+    else {
+        if matches!(source, PricingSource::Api(_)) {
+            bail!("AWS Pricing API is not available, check your AWS credentials");
+        }
+        bail!("No pricing found for {} in {}", instance_type, region);
+    };
+
+    if json {
+        let output = json!({
+            "instance_type": pricing.instance_type,
+            "region": region,
+            "hourly_cost": pricing.total_hourly_cost,
+            "cost_per_minute": pricing.cost_per_minute,
+            "source": pricing.source,
+            "match_confidence": pricing.match_confidence,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Instance type:   {}", pricing.instance_type);
+    println!("Region:          {}", region);
+    println!("Hourly cost:     ${:.4}", pricing.total_hourly_cost);
+    println!("Cost per minute: ${:.6}", pricing.cost_per_minute);
+    println!("Source:          {}", pricing.source);
+    if let Some(confidence) = pricing.match_confidence {
+        println!("Match:           {:.0}%", confidence);
+    }
+    Ok(())
+}
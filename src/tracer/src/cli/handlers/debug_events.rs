@@ -0,0 +1,129 @@
+use crate::daemon::client::DaemonClient;
+use crate::process_identification::trigger_stream::{RawTrigger, TriggerStreamMessage};
+use crate::utils::system_info::{is_root, is_sudo};
+use crate::{info_message, warning_message};
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+/// Connects to the daemon's raw trigger stream and prints every trigger, before target matching,
+/// until it closes. The triggers include the arguments of every process on the host, so this is
+/// restricted to root.
+pub async fn debug_events(api_client: &DaemonClient, json: bool) -> Result<()> {
+    if !is_root() && !is_sudo() {
+        bail!("Streaming raw process events requires root, re-run with sudo");
+    }
+
+    let mut response = api_client.open_debug_event_stream().await?;
+    if !json {
+        info_message!("Streaming raw process events, press Ctrl+C to stop...");
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(position) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=position).collect();
+            if json {
+                print!("{}", String::from_utf8_lossy(&line));
+                continue;
+            }
+            match serde_json::from_slice::<TriggerStreamMessage>(&line) {
+                Ok(message) => print_message(&message),
+                Err(e) => warning_message!("Failed to parse trigger: {}", e),
+            }
+        }
+    }
+
+    warning_message!("Trigger stream closed by the daemon");
+    Ok(())
+}
+
+fn print_message(message: &TriggerStreamMessage) {
+    match message {
+        TriggerStreamMessage::Trigger(trigger) => println!("{}", format_trigger(trigger)),
+        TriggerStreamMessage::Gap { dropped } => println!(
+            "{}",
+            format!(
+                "... {} triggers dropped, the terminal could not keep up ...",
+                dropped
+            )
+            .yellow()
+        ),
+    }
+}
+
+fn format_trigger(trigger: &RawTrigger) -> String {
+    match trigger {
+        RawTrigger::ProcessStart {
+            pid,
+            ppid,
+            comm,
+            file_name,
+            argv,
+            started_at,
+        } => format!(
+            "{} {} pid={} ppid={} comm={} file={} argv={:?}",
+            started_at.format("%H:%M:%S%.3f").to_string().dimmed(),
+            "[start]".green().bold(),
+            pid,
+            ppid,
+            comm,
+            file_name.as_deref().unwrap_or("-"),
+            argv
+        ),
+        RawTrigger::ProcessEnd {
+            pid,
+            exit_code,
+            exit_reason,
+            finished_at,
+        } => format!(
+            "{} {} pid={} exit_code={} reason={}",
+            finished_at.format("%H:%M:%S%.3f").to_string().dimmed(),
+            "[finish]".cyan().bold(),
+            pid,
+            exit_code.map_or("-".to_string(), |code| code.to_string()),
+            exit_reason.as_deref().unwrap_or("-")
+        ),
+        RawTrigger::OutOfMemory {
+            pid,
+            comm,
+            timestamp,
+        } => format!(
+            "{} {} pid={} comm={}",
+            timestamp.format("%H:%M:%S%.3f").to_string().dimmed(),
+            "[oom]".red().bold(),
+            pid,
+            comm
+        ),
+        RawTrigger::FileOpen {
+            pid,
+            file_name,
+            size_bytes,
+            timestamp,
+        } => format!(
+            "{} {} pid={} file={} size={}",
+            timestamp.format("%H:%M:%S%.3f").to_string().dimmed(),
+            "[open]".blue().bold(),
+            pid,
+            file_name,
+            size_bytes
+        ),
+        RawTrigger::ExecFailed {
+            pid,
+            ppid,
+            file_name,
+            errno,
+            error,
+            timestamp,
+        } => format!(
+            "{} {} pid={} ppid={} file={} errno={} ({})",
+            timestamp.format("%H:%M:%S%.3f").to_string().dimmed(),
+            "[exec-failed]".yellow().bold(),
+            pid,
+            ppid,
+            file_name,
+            errno,
+            error
+        ),
+    }
+}
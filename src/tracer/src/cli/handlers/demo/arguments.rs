@@ -1,8 +1,12 @@
-use crate::cli::handlers::demo::pipeline::Pipeline;
+use crate::cli::handlers::demo::pipeline::{parse_template_var, Pipeline};
 use crate::cli::handlers::init::arguments::TracerCliInitArgs;
 use crate::process_identification::types::pipeline_tags::PipelineTags;
 use anyhow::Result;
 use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+/// Default for `--timeout`
+pub const DEMO_TIMEOUT_ENV_VAR: &str = "TRACER_DEMO_TIMEOUT";
 
 /// Run fastquorum or wdl demo pipelines to test out the Tracer client
 #[derive(Args, Debug, Clone)]
@@ -16,6 +20,8 @@ Examples:
   tracer demo fastquorum                    # run the fastquorum demo pipeline
   tracer demo wdl                           # run the wdl demo pipeline
   tracer demo fastquorum --run-name test123 # run with custom run name
+  tracer demo --tool fastqc --var sample=S1 -- {sample}_R1.fq.gz
+                                            # run a local tool, filling in its args
 
 Help:
   -h, --help            Show this help message
@@ -27,6 +33,9 @@ Common options:
   -e, --environment     Execution context [ci-cd|sandbox|local]
       --instance-type   Cloud compute type (e.g., m5.large)
   -i, --interactive     Input prompts: [none|minimal|required] (default: minimal)
+      --timeout         Interrupt the pipeline after this many seconds
+      --tool            Run a local tool, with the args given after --, instead of a demo
+      --var             NAME=VALUE substituted for {NAME} in the tool's args (repeatable)
 
 "#)]
 pub struct TracerCliDemoArgs {
@@ -36,6 +45,22 @@ pub struct TracerCliDemoArgs {
     #[command(flatten)]
     pub init_args: DemoInitArgs,
 
+    /// Interrupt the pipeline after this many seconds; the run then ends as timed out
+    #[clap(long, value_name = "SECONDS", env = DEMO_TIMEOUT_ENV_VAR)]
+    pub timeout: Option<u64>,
+
+    /// Run a local tool instead of a demo pipeline
+    #[clap(long, value_name = "PATH", conflicts_with = "demo_pipeline_id")]
+    pub tool: Option<PathBuf>,
+
+    /// Value substituted for `{NAME}` in the tool's args (repeatable)
+    #[clap(long = "var", value_name = "NAME=VALUE", value_parser = parse_template_var, requires = "tool")]
+    pub vars: Vec<(String, String)>,
+
+    /// Args of the tool, after `--`
+    #[clap(last = true, value_name = "ARGS", requires = "tool")]
+    pub tool_args: Vec<String>,
+
     /// Show all advanced and metadata options
     #[clap(long)]
     pub help_advanced: bool,
@@ -96,6 +121,13 @@ impl TracerCliDemoArgs {
         // Convert demo init args to full init args
         let full_init_args = self.init_args.to_full_init_args();
 
+        if let Some(tool) = self.tool {
+            let vars = self.vars.into_iter().collect();
+            let pipeline = Pipeline::local_tool(tool, &self.tool_args, &vars)?;
+            pipeline.validate()?;
+            return Ok((full_init_args, pipeline));
+        }
+
         // Determine which pipeline to run
         let pipeline_name = self
             .demo_pipeline_id
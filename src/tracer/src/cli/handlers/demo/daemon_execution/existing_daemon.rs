@@ -1,3 +1,4 @@
+use super::pipeline_output::execute_recording_output;
 use crate::cli::handlers::demo::pipeline::Pipeline;
 use crate::cli::handlers::info;
 
@@ -6,11 +7,13 @@ use crate::{info_message, warning_message};
 
 use anyhow::Result;
 use colored::Colorize;
+use std::time::Duration;
 
 /// Run demo pipeline when daemon is already running
 pub async fn run_demo_with_existing_daemon(
     api_client: &DaemonClient,
     selected_demo_pipeline: Pipeline,
+    timeout: Option<Duration>,
 ) -> Result<()> {
     info_message!(
         "Daemon is already running, executing {} pipeline...",
@@ -20,7 +23,7 @@ pub async fn run_demo_with_existing_daemon(
     let user_id = get_user_id_from_daemon(api_client).await;
     update_run_name_for_demo(api_client, &user_id).await;
 
-    let result = selected_demo_pipeline.execute();
+    let result = execute_recording_output(selected_demo_pipeline, api_client, timeout).await;
 
     // Show info to check if the process where recognized correctly s
     info::info(api_client, false).await;
@@ -1,5 +1,6 @@
 pub mod existing_daemon;
 pub mod new_daemon;
+mod pipeline_output;
 
 pub use existing_daemon::run_demo_with_existing_daemon;
 pub use new_daemon::run_demo_with_new_daemon;
@@ -1,3 +1,4 @@
+use super::pipeline_output::execute_recording_output;
 use crate::cli::handlers::demo::pipeline::Pipeline;
 use crate::cli::handlers::info;
 use crate::cli::handlers::init::arguments::TracerCliInitArgs;
@@ -10,21 +11,24 @@ use crate::utils::workdir::TRACER_WORK_DIR;
 
 use anyhow::Result;
 use colored::Colorize;
+use std::time::Duration;
 
 pub async fn run_demo_with_new_daemon(
     init_args: TracerCliInitArgs,
     config: Config,
     api_client: &DaemonClient,
     selected_demo_pipeline: Pipeline,
+    timeout: Option<Duration>,
 ) -> Result<()> {
     let configured_args = prepare_demo_environment(init_args, &selected_demo_pipeline)?;
 
     // Init daemon, run pipeline, cleanup
     initialize_daemon_for_demo(configured_args, config, api_client).await?;
-    execute_pipeline_and_report(selected_demo_pipeline, api_client).await?;
+    let result = execute_pipeline_and_report(selected_demo_pipeline, api_client, timeout).await;
+    // the daemon is torn down even if the pipeline failed or was interrupted
     cleanup_daemon(api_client).await;
 
-    Ok(())
+    result
 }
 
 fn prepare_demo_environment(
@@ -37,6 +41,11 @@ fn prepare_demo_environment(
 
     init_args.watch_dir = Some(TRACER_WORK_DIR.path.to_string_lossy().to_string());
     init_args.configure_for_test();
+    if init_args.pipeline_dir.is_none() {
+        init_args.pipeline_dir = pipeline
+            .directory()
+            .map(|directory| directory.to_string_lossy().to_string());
+    }
 
     // For demo scenarios, we want the pipeline name to be "{environment}-demo-{pipeline_id}-{user_id}"
     // We'll set a special marker that the resolver will recognize and expand properly
@@ -59,8 +68,12 @@ async fn initialize_daemon_for_demo(
     crate::cli::handlers::init::init(init_args, config, api_client).await
 }
 
-async fn execute_pipeline_and_report(pipeline: Pipeline, api_client: &DaemonClient) -> Result<()> {
-    pipeline.execute()?;
+async fn execute_pipeline_and_report(
+    pipeline: Pipeline,
+    api_client: &DaemonClient,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    execute_recording_output(pipeline, api_client, timeout).await?;
     info::info(api_client, false).await;
     Ok(())
 }
@@ -0,0 +1,61 @@
+use crate::cli::handlers::demo::pipeline::{ExecutionControl, Pipeline, PipelineInterrupted};
+use crate::daemon::client::DaemonClient;
+use crate::warning_message;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Runs `pipeline`, recording the errors it prints as events of the daemon's run. The
+/// pipeline is interrupted after `timeout` or on Ctrl-C, and the run then ends as timed out or
+/// cancelled.
+pub async fn execute_recording_output(
+    pipeline: Pipeline,
+    api_client: &DaemonClient,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let control = ExecutionControl {
+        output: Some(sender),
+        timeout,
+        ..Default::default()
+    };
+
+    let cancelled = control.cancelled.clone();
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            warning_message!("Interrupting the pipeline...");
+            cancelled.store(true, Ordering::SeqCst);
+        }
+    });
+    let execution = tokio::task::spawn_blocking(move || pipeline.execute(&control));
+
+    // ends once the pipeline has exited and its output is read, as the sender is dropped then
+    while let Some(output_match) = receiver.recv().await {
+        match api_client.send_pipeline_output_request(output_match).await {
+            Ok(true) => {}
+            Ok(false) => debug!("The daemon did not record the pipeline output"),
+            Err(e) => debug!("Failed to send the pipeline output to the daemon: {}", e),
+        }
+    }
+
+    let result = execution.await.context("Pipeline execution panicked")?;
+    ctrl_c.abort();
+
+    if let Some(interrupted) = result
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<PipelineInterrupted>())
+    {
+        warning_message!("{}, stopping the run", interrupted);
+        if let Err(e) = api_client
+            .send_stop_request_with_reason(interrupted.end_reason())
+            .await
+        {
+            warning_message!("Failed to stop the run: {}", e);
+        }
+    }
+    result
+}
@@ -130,6 +130,10 @@ mod tests {
                 interactive: PromptMode::Minimal,
                 ..Default::default()
             },
+            timeout: None,
+            tool: None,
+            vars: vec![],
+            tool_args: vec![],
             help_advanced: false,
         };
 
@@ -10,6 +10,7 @@ use crate::daemon::server::DaemonServer;
 use crate::utils::system_info::check_sudo_with_procfs_option;
 
 use anyhow::Result;
+use std::time::Duration;
 
 /// TODO: fastquorum segfault on ARM mac; Rosetta/x86 pixi option may be needed.
 pub async fn demo(args: TracerCliDemoArgs, config: Config, api_client: DaemonClient) -> Result<()> {
@@ -24,13 +25,21 @@ pub async fn demo(args: TracerCliDemoArgs, config: Config, api_client: DaemonCli
     }
 
     // Handle pipeline execution (including default case)
+    let timeout = args.timeout.map(Duration::from_secs);
     let (init_args, selected_demo_pipeline) = args.resolve_demo_arguments()?;
-    check_sudo_with_procfs_option("demo", init_args.force_procfs);
+    check_sudo_with_procfs_option("demo", init_args.force_procfs || config.force_procfs);
     let daemon_was_already_running = DaemonServer::is_running();
 
     if daemon_was_already_running {
-        run_demo_with_existing_daemon(&api_client, selected_demo_pipeline).await
+        run_demo_with_existing_daemon(&api_client, selected_demo_pipeline, timeout).await
     } else {
-        run_demo_with_new_daemon(init_args, config, &api_client, selected_demo_pipeline).await
+        run_demo_with_new_daemon(
+            init_args,
+            config,
+            &api_client,
+            selected_demo_pipeline,
+            timeout,
+        )
+        .await
     }
 }
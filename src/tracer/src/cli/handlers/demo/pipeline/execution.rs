@@ -1,18 +1,80 @@
+use super::github::GithubRepo;
 use super::pixi;
 use super::Pipeline;
+use crate::extracts::syslog::output_scanner::{OutputMatch, OutputScanner, OutputStream};
+use crate::extracts::syslog::PIPELINE_OUTPUT_PATTERNS;
 use crate::info_message;
+use crate::process_identification::types::event::attributes::run_summary::RunEndReason;
 use crate::utils::command::check_status;
 use crate::utils::Sentry;
 use anyhow::Result;
 use colored::Colorize;
 use serde_json::json;
 use std::ffi::OsStr;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How often a running pipeline is checked for its timeout and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long an interrupted pipeline may take to exit before it is killed
+const INTERRUPT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Receives the lines of a pipeline's output matching error patterns
+pub type OutputSink = UnboundedSender<OutputMatch>;
+
+/// How a pipeline run is observed and bounded
+#[derive(Clone, Default)]
+pub struct ExecutionControl {
+    /// With a sink, the output of the pipeline is still shown live, and its lines matching
+    /// error patterns are sent to it
+    pub output: Option<OutputSink>,
+    /// The pipeline is interrupted once it has run this long
+    pub timeout: Option<Duration>,
+    /// Set to interrupt the pipeline, e.g. on Ctrl-C
+    pub cancelled: Arc<AtomicBool>,
+}
+
+/// Error of a pipeline that was interrupted before it exited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PipelineInterrupted {
+    TimedOut(Duration),
+    Cancelled,
+}
+
+impl PipelineInterrupted {
+    pub fn end_reason(&self) -> RunEndReason {
+        match self {
+            PipelineInterrupted::TimedOut(_) => RunEndReason::TimedOut,
+            PipelineInterrupted::Cancelled => RunEndReason::Cancelled,
+        }
+    }
+}
+
+impl fmt::Display for PipelineInterrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineInterrupted::TimedOut(timeout) => {
+                write!(f, "Pipeline timed out after {:?}", timeout)
+            }
+            PipelineInterrupted::Cancelled => write!(f, "Pipeline was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for PipelineInterrupted {}
 
 impl Pipeline {
-    /// Single entry point to execute any pipeline variant.
-    pub fn execute(&self) -> Result<()> {
+    /// Single entry point to execute any pipeline variant. The pipeline runs in its own process
+    /// group, which is interrupted as a whole on timeout or cancellation.
+    pub fn execute(&self, control: &ExecutionControl) -> Result<()> {
         info_message!("Running pipeline...");
 
         // Add pipeline context to Sentry
@@ -31,11 +93,11 @@ impl Pipeline {
 
         let result = match self {
             Pipeline::LocalPixi { manifest, task, .. } => {
-                run_pixi_task(manifest.clone(), task.clone())
+                run_pixi_task(manifest.clone(), task.clone(), control)
             }
-            Pipeline::LocalNextflow { path, args } => run_nextflow(path, args),
-            Pipeline::GithubNextflow { repo, args } => run_nextflow(repo, args),
-            Pipeline::LocalTool { path, args } => run_tool(path, args),
+            Pipeline::LocalNextflow { path, args } => run_nextflow(path, args, control),
+            Pipeline::GithubNextflow { repo, args } => run_github_nextflow(repo, args, control),
+            Pipeline::LocalTool { path, args } => run_tool(path, args, control),
         };
 
         match &result {
@@ -71,7 +133,7 @@ impl Pipeline {
 // Pipeline execution helper functions
 
 /// Install pixi if necessary, then run task in manifest.
-fn run_pixi_task(manifest: PathBuf, task: String) -> Result<()> {
+fn run_pixi_task(manifest: PathBuf, task: String, control: &ExecutionControl) -> Result<()> {
     let workdir_pixi = crate::utils::workdir::TRACER_WORK_DIR.path.join("bin/pixi");
     let pixi_path = if workdir_pixi.exists() {
         info_message!("Using workdir pixi: {}", workdir_pixi.display());
@@ -110,11 +172,15 @@ fn run_pixi_task(manifest: PathBuf, task: String) -> Result<()> {
         }
     }
 
-    exec(&mut cmd, "Pipeline run failed")
+    exec(&mut cmd, "Pipeline run failed", control)
 }
 
 /// Run a Nextflow pipeline (ensures nextflow exists first).
-fn run_nextflow<S: AsRef<OsStr>>(pipeline: S, args: &Vec<String>) -> Result<()> {
+fn run_nextflow<S: AsRef<OsStr>>(
+    pipeline: S,
+    args: &Vec<String>,
+    control: &ExecutionControl,
+) -> Result<()> {
     check_status(
         Command::new("nextflow").arg("-version").status(),
         "Nextflow not found",
@@ -123,16 +189,206 @@ fn run_nextflow<S: AsRef<OsStr>>(pipeline: S, args: &Vec<String>) -> Result<()>
     exec(
         Command::new("nextflow").arg("run").args(args).arg(pipeline),
         "Pipeline run failed",
+        control,
     )
 }
 
+/// Run a Nextflow pipeline from GitHub, at its revision if `repo` names one.
+fn run_github_nextflow(repo: &str, args: &[String], control: &ExecutionControl) -> Result<()> {
+    let repo = GithubRepo::parse(repo)?;
+    let mut args = args.to_vec();
+    if let Some(revision) = &repo.revision {
+        args.extend(["-r".to_string(), revision.clone()]);
+    }
+    run_nextflow(repo.path(), &args, control)
+}
+
 /// Run an arbitrary tool with args.
-fn run_tool<S: AsRef<OsStr>>(tool: S, args: &Vec<String>) -> Result<()> {
-    exec(Command::new(tool).args(args), "Tool run failed")
+fn run_tool<S: AsRef<OsStr>>(
+    tool: S,
+    args: &Vec<String>,
+    control: &ExecutionControl,
+) -> Result<()> {
+    exec(Command::new(tool).args(args), "Tool run failed", control)
+}
+
+/// Uniform spawn/wait + error mapping. With an output sink, stdout and stderr are teed to the
+/// terminal and scanned for errors.
+fn exec(cmd: &mut Command, fail_msg: &str, control: &ExecutionControl) -> Result<()> {
+    // its own process group, so that the whole pipeline can be interrupted at once
+    cmd.process_group(0);
+    if control.output.is_some() {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return check_status(Err(e), fail_msg),
+    };
+
+    let mut copies = Vec::new();
+    if let Some(output) = &control.output {
+        if let Some(stdout) = child.stdout.take() {
+            let output = output.clone();
+            copies.push(thread::spawn(move || {
+                tee(stdout, std::io::stdout(), OutputStream::Stdout, output)
+            }));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let output = output.clone();
+            copies.push(thread::spawn(move || {
+                tee(stderr, std::io::stderr(), OutputStream::Stderr, output)
+            }));
+        }
+    }
+
+    let status = wait(&mut child, control);
+    for copy in copies {
+        let _ = copy.join();
+    }
+    check_status(Ok(status?), fail_msg)
 }
 
-/// Uniform spawn/wait + error mapping.
-fn exec(cmd: &mut Command, fail_msg: &str) -> Result<()> {
-    let status = cmd.spawn().and_then(|mut child| child.wait());
-    check_status(status, fail_msg)
+/// Waits for `child` to exit, interrupting its process group once the timeout is reached or
+/// the run is cancelled
+fn wait(child: &mut Child, control: &ExecutionControl) -> Result<ExitStatus> {
+    let deadline = control.timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        let interrupted = if control.cancelled.load(Ordering::SeqCst) {
+            Some(PipelineInterrupted::Cancelled)
+        } else {
+            control
+                .timeout
+                .zip(deadline)
+                .filter(|(_, deadline)| Instant::now() >= *deadline)
+                .map(|(timeout, _)| PipelineInterrupted::TimedOut(timeout))
+        };
+        if let Some(interrupted) = interrupted {
+            interrupt(child)?;
+            return Err(interrupted.into());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Sends SIGINT to the process group of `child`, as Ctrl-C in a terminal would, then SIGKILL
+/// if it is still running after the grace period
+fn interrupt(child: &mut Child) -> Result<()> {
+    signal_process_group(child.id(), "INT");
+    let deadline = Instant::now() + INTERRUPT_GRACE_PERIOD;
+    while Instant::now() < deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    signal_process_group(child.id(), "KILL");
+    child.wait()?;
+    Ok(())
+}
+
+fn signal_process_group(pgid: u32, signal: &str) {
+    let _ = Command::new("kill")
+        .args([
+            format!("-{}", signal),
+            "--".to_string(),
+            format!("-{}", pgid),
+        ])
+        .output();
+}
+
+/// Copies `source` to `terminal` as it comes, sending its lines matching an error pattern to
+/// `output`
+fn tee(source: impl Read, mut terminal: impl Write, stream: OutputStream, output: OutputSink) {
+    let mut scanner = OutputScanner::new(stream, PIPELINE_OUTPUT_PATTERNS.as_slice().into());
+    let mut reader = BufReader::new(source);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let _ = terminal.write_all(&line).and_then(|_| terminal.flush());
+        let text = String::from_utf8_lossy(&line);
+        if let Some(found) = scanner.scan(text.trim_end_matches(['\n', '\r'])) {
+            // the receiver is gone once nothing records the output anymore
+            let _ = output.send(found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_identification::types::event::attributes::syslog::SyslogSeverity;
+
+    #[test]
+    fn test_error_line_in_pipeline_output_is_reported() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let control = ExecutionControl {
+            output: Some(sender),
+            ..Default::default()
+        };
+        exec(
+            Command::new("sh").args([
+                "-c",
+                "echo 'running STAR'; echo 'write error: No space left on device' >&2",
+            ]),
+            "Pipeline run failed",
+            &control,
+        )
+        .unwrap();
+        drop(control);
+
+        let found = receiver.try_recv().unwrap();
+        assert_eq!(found.stream, OutputStream::Stderr);
+        assert_eq!(found.pattern_id, "disk_full");
+        assert_eq!(found.severity, SyslogSeverity::Critical);
+        assert_eq!(found.line, "write error: No space left on device");
+        assert_eq!(found.line_number, 1);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_hanging_pipeline_times_out() {
+        let control = ExecutionControl {
+            timeout: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+        let started_at = Instant::now();
+        let error = exec(
+            Command::new("sh").args(["-c", "sleep 1000; sleep 1000"]),
+            "Pipeline run failed",
+            &control,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<PipelineInterrupted>(),
+            Some(&PipelineInterrupted::TimedOut(Duration::from_millis(300)))
+        );
+        // the shell and its sleep were both interrupted, without waiting for the grace period
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_cancelled_pipeline_is_interrupted() {
+        let control = ExecutionControl::default();
+        control.cancelled.store(true, Ordering::SeqCst);
+        let error = exec(
+            Command::new("sleep").arg("1000"),
+            "Pipeline run failed",
+            &control,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error
+                .downcast_ref::<PipelineInterrupted>()
+                .map(PipelineInterrupted::end_reason),
+            Some(RunEndReason::Cancelled)
+        );
+    }
 }
@@ -0,0 +1,263 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+/// Token used for GitHub API requests, which raises the rate limit and gives access to private
+/// repositories
+pub const GITHUB_TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+/// Files at the root of a repository that make it a Nextflow pipeline
+const NEXTFLOW_PIPELINE_FILES: &[&str] = &["main.nf", "nextflow.config"];
+
+/// A pipeline on GitHub, as `owner/repo` optionally followed by `@revision` (a branch, tag or
+/// commit)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GithubRepo {
+    pub owner: String,
+    pub name: String,
+    pub revision: Option<String>,
+}
+
+impl GithubRepo {
+    pub fn parse(repo: &str) -> Result<Self> {
+        let (path, revision) = match repo.split_once('@') {
+            Some((path, revision)) if !revision.is_empty() => (path, Some(revision.to_string())),
+            Some(_) => bail!("Invalid GitHub repository '{}': empty revision", repo),
+            None => (repo, None),
+        };
+        match path.split('/').collect::<Vec<_>>()[..] {
+            [owner, name] if !owner.is_empty() && !name.is_empty() => Ok(Self {
+                owner: owner.to_string(),
+                name: name.to_string(),
+                revision,
+            }),
+            _ => bail!(
+                "Invalid GitHub repository '{}': expected owner/repo or owner/repo@revision",
+                repo
+            ),
+        }
+    }
+
+    /// `owner/repo`, as `nextflow run` expects it
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+/// Checks that `repo` exists on GitHub and holds a Nextflow pipeline at its revision (the
+/// default branch if none is given). `fetch` GETs a GitHub API path, returning `None` when it
+/// is not found.
+pub fn validate_github_repo(
+    repo: &str,
+    fetch: impl Fn(&str) -> Result<Option<Value>>,
+) -> Result<()> {
+    let repo = GithubRepo::parse(repo)?;
+    let path = repo.path();
+
+    let Some(metadata) = fetch(&format!("/repos/{}", path))? else {
+        bail!(
+            "GitHub repository {} not found; check its name, or set {} if it is private",
+            path,
+            GITHUB_TOKEN_ENV_VAR
+        );
+    };
+    let revision = match &repo.revision {
+        Some(revision) => revision.clone(),
+        None => metadata["default_branch"]
+            .as_str()
+            .context("Invalid GitHub repository response: no default branch")?
+            .to_string(),
+    };
+
+    let Some(contents) = fetch(&format!("/repos/{}/contents?ref={}", path, revision))? else {
+        bail!(
+            "Revision {} not found in GitHub repository {}",
+            revision,
+            path
+        );
+    };
+    let has_pipeline = contents
+        .as_array()
+        .context("Invalid GitHub contents response")?
+        .iter()
+        .filter_map(|entry| entry["name"].as_str())
+        .any(|name| NEXTFLOW_PIPELINE_FILES.contains(&name));
+    if !has_pipeline {
+        bail!(
+            "{}@{} is not a Nextflow pipeline: it has no {}",
+            path,
+            revision,
+            NEXTFLOW_PIPELINE_FILES.join(" or ")
+        );
+    }
+    Ok(())
+}
+
+/// GETs a path of the GitHub API, authenticated with `GITHUB_TOKEN` if it is set. Returns
+/// `None` if the API answers 404, which it also does for private repositories without a token.
+pub fn fetch_github_api(path: &str) -> Result<Option<Value>> {
+    let url = format!("{}{}", GITHUB_API_URL, path);
+    let mut command = Command::new("curl");
+    command
+        .args(["-sSL", "-w", "\n%{http_code}"])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .arg(&url)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let token = std::env::var(GITHUB_TOKEN_ENV_VAR)
+        .ok()
+        .filter(|token| !token.is_empty());
+    if token.is_some() {
+        // read the header from stdin, so the token doesn't show in the process list
+        command.args(["-H", "@-"]).stdin(Stdio::piped());
+    }
+
+    let mut child = command.spawn().context("Failed to execute curl command")?;
+    if let (Some(token), Some(mut stdin)) = (token, child.stdin.take()) {
+        writeln!(stdin, "Authorization: Bearer {}", token)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to reach the GitHub API: {}", stderr.trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    match status.trim() {
+        "404" => Ok(None),
+        "401" => bail!("GitHub rejected the token in {}", GITHUB_TOKEN_ENV_VAR),
+        "403" | "429" => bail!(
+            "GitHub API rate limit exceeded; set {} to raise it",
+            GITHUB_TOKEN_ENV_VAR
+        ),
+        status if status.starts_with('2') => serde_json::from_str(body)
+            .map(Some)
+            .context("Invalid GitHub API response"),
+        status => bail!("GitHub API request {} failed with status {}", url, status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    /// A GitHub API answering the given paths, and 404 for the others
+    fn mock_api(responses: Vec<(&str, Value)>) -> impl Fn(&str) -> Result<Option<Value>> {
+        let responses: HashMap<String, Value> = responses
+            .into_iter()
+            .map(|(path, response)| (path.to_string(), response))
+            .collect();
+        move |path| Ok(responses.get(path).cloned())
+    }
+
+    fn contents(names: &[&str]) -> Value {
+        Value::Array(names.iter().map(|name| json!({ "name": name })).collect())
+    }
+
+    #[test]
+    fn test_parse_repo() {
+        assert_eq!(
+            GithubRepo::parse("nf-core/rnaseq@3.14.0").unwrap(),
+            GithubRepo {
+                owner: "nf-core".to_string(),
+                name: "rnaseq".to_string(),
+                revision: Some("3.14.0".to_string()),
+            }
+        );
+        assert_eq!(GithubRepo::parse("nf-core/rnaseq").unwrap().revision, None);
+        assert!(GithubRepo::parse("rnaseq").is_err());
+        assert!(GithubRepo::parse("nf-core/rnaseq/main.nf").is_err());
+        assert!(GithubRepo::parse("nf-core/rnaseq@").is_err());
+    }
+
+    #[test]
+    fn test_valid_repo_on_default_branch() {
+        let api = mock_api(vec![
+            (
+                "/repos/nf-core/rnaseq",
+                json!({ "default_branch": "master" }),
+            ),
+            (
+                "/repos/nf-core/rnaseq/contents?ref=master",
+                contents(&["README.md", "main.nf", "nextflow.config"]),
+            ),
+        ]);
+        validate_github_repo("nf-core/rnaseq", api).unwrap();
+    }
+
+    #[test]
+    fn test_valid_repo_at_revision() {
+        let api = mock_api(vec![
+            (
+                "/repos/nf-core/rnaseq",
+                json!({ "default_branch": "master" }),
+            ),
+            (
+                "/repos/nf-core/rnaseq/contents?ref=3.14.0",
+                contents(&["nextflow.config"]),
+            ),
+        ]);
+        validate_github_repo("nf-core/rnaseq@3.14.0", api).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_repo() {
+        let error = validate_github_repo("nf-core/rnaseqq", mock_api(vec![]))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "GitHub repository nf-core/rnaseqq not found; check its name, or set GITHUB_TOKEN if it is private"
+        );
+    }
+
+    #[test]
+    fn test_unknown_revision() {
+        let api = mock_api(vec![(
+            "/repos/nf-core/rnaseq",
+            json!({ "default_branch": "master" }),
+        )]);
+        let error = validate_github_repo("nf-core/rnaseq@9.9.9", api)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Revision 9.9.9 not found in GitHub repository nf-core/rnaseq"
+        );
+    }
+
+    #[test]
+    fn test_repo_without_pipeline() {
+        let api = mock_api(vec![
+            (
+                "/repos/rust-lang/rust",
+                json!({ "default_branch": "master" }),
+            ),
+            (
+                "/repos/rust-lang/rust/contents?ref=master",
+                contents(&["Cargo.toml", "README.md"]),
+            ),
+        ]);
+        let error = validate_github_repo("rust-lang/rust", api)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "rust-lang/rust@master is not a Nextflow pipeline: it has no main.nf or nextflow.config"
+        );
+    }
+
+    #[test]
+    fn test_api_errors_are_reported() {
+        let error = validate_github_repo("nf-core/rnaseq", |_| {
+            bail!("GitHub API rate limit exceeded; set GITHUB_TOKEN to raise it")
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("rate limit"));
+    }
+}
@@ -1,10 +1,16 @@
 mod execution;
+mod github;
 mod pixi;
 mod prompts;
 mod selection;
+mod template;
+
+pub use execution::{ExecutionControl, PipelineInterrupted};
+pub use template::parse_template_var;
 
 use anyhow::{bail, Result};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 pub enum Pipeline {
     LocalPixi {
@@ -41,6 +47,18 @@ impl Pipeline {
         })
     }
 
+    /// A tool run with `args`, whose `{name}` placeholders are filled in from `vars`
+    pub fn local_tool<P: Into<PathBuf>>(
+        path: P,
+        args: &[String],
+        vars: &HashMap<String, String>,
+    ) -> Result<Self> {
+        Ok(Pipeline::LocalTool {
+            path: path.into(),
+            args: template::render_args(args, vars)?,
+        })
+    }
+
     pub fn name(&self) -> &str {
         match self {
             Pipeline::LocalPixi { path, .. } => path.file_name().unwrap().to_str().unwrap(),
@@ -50,12 +68,21 @@ impl Pipeline {
         }
     }
 
+    /// The directory the pipeline's lockfiles are in, for local pipelines
+    pub fn directory(&self) -> Option<&Path> {
+        match self {
+            Pipeline::LocalPixi { manifest, .. } => manifest.parent(),
+            Pipeline::LocalNextflow { path, .. } => Some(path),
+            Pipeline::LocalTool { .. } | Pipeline::GithubNextflow { .. } => None,
+        }
+    }
+
     pub fn validate(&self) -> Result<()> {
         match self {
             Self::LocalPixi {
                 path,
                 manifest,
-                task: _,
+                task,
             } => {
                 if !path.exists() {
                     bail!("Pipeline path does not exist: {path:?}");
@@ -63,18 +90,20 @@ impl Pipeline {
                 if !manifest.exists() {
                     bail!("Pixi manifest file does not exist: {manifest:?}");
                 }
-                // TODO: look for task in manifest
+                pixi::check_manifest_task(manifest, task)?;
             }
             Self::LocalNextflow { path, .. } => {
                 if !path.exists() {
                     bail!("Pipeline path does not exist: {path:?}");
                 }
             }
-            Self::GithubNextflow { repo: _, .. } => {
-                // TODO: validate repo
+            Self::GithubNextflow { repo, .. } => {
+                github::validate_github_repo(repo, github::fetch_github_api)?;
             }
             Self::LocalTool { path, .. } => {
-                if which::which(path.file_name().expect("Invalid file name")).is_err() {
+                if !path.is_file()
+                    && which::which(path.file_name().expect("Invalid file name")).is_err()
+                {
                     bail!("Tool path does not exist: {path:?}");
                 }
             }
@@ -1,7 +1,8 @@
 use crate::utils::command::check_status;
 use crate::utils::workdir::TRACER_WORK_DIR;
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 pub fn install_pixi() -> Result<PathBuf> {
@@ -59,10 +60,136 @@ fn get_possible_pixi_paths() -> Vec<PathBuf> {
     ]
 }
 
+/// Checks that the pixi manifest at `manifest` defines `task`, listing the tasks it defines
+/// otherwise
+pub fn check_manifest_task(manifest: &Path, task: &str) -> Result<()> {
+    let contents = std::fs::read_to_string(manifest)
+        .with_context(|| format!("Failed to read pixi manifest {}", manifest.display()))?;
+    let tasks = manifest_tasks(&contents)
+        .with_context(|| format!("Invalid pixi manifest {}", manifest.display()))?;
+    if tasks.contains(task) {
+        return Ok(());
+    }
+    if tasks.is_empty() {
+        bail!(
+            "Task '{}' not found: pixi manifest {} defines no tasks",
+            task,
+            manifest.display()
+        );
+    }
+    bail!(
+        "Task '{}' not found in pixi manifest {}. Available tasks: {}",
+        task,
+        manifest.display(),
+        tasks.into_iter().collect::<Vec<_>>().join(", ")
+    )
+}
+
+/// Names of the tasks a pixi manifest defines, in its `[tasks]` table and in those of its
+/// features and platform targets
+fn manifest_tasks(contents: &str) -> Result<BTreeSet<String>> {
+    let manifest: toml::Table = toml::from_str(contents)?;
+    let mut tasks = BTreeSet::new();
+    let mut add_tasks = |table: &toml::Table| {
+        tasks.extend(
+            table_at(table, "tasks")
+                .into_iter()
+                .flat_map(|tasks| tasks.keys().cloned()),
+        );
+        for target in table_at(table, "target")
+            .into_iter()
+            .flat_map(|targets| targets.values())
+        {
+            if let Some(target_tasks) = target.get("tasks").and_then(toml::Value::as_table) {
+                tasks.extend(target_tasks.keys().cloned());
+            }
+        }
+    };
+    add_tasks(&manifest);
+    for feature in table_at(&manifest, "feature")
+        .into_iter()
+        .flat_map(|features| features.values())
+        .filter_map(toml::Value::as_table)
+    {
+        add_tasks(feature);
+    }
+    Ok(tasks)
+}
+
+fn table_at<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Table> {
+    table.get(key).and_then(toml::Value::as_table)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const MANIFEST: &str = r#"
+[workspace]
+name = "fastquorum"
+channels = ["conda-forge", "bioconda"]
+platforms = ["linux-64", "osx-arm64"]
+
+[tasks]
+pipeline_full = "nextflow run main.nf -profile test"
+pipeline_quick = { cmd = "nextflow run main.nf -profile quick", depends-on = ["setup"] }
+
+[target.linux-64.tasks]
+setup = "./setup.sh"
+
+[feature.dev.tasks]
+lint = "nf-core lint"
+
+[dependencies]
+nextflow = ">=24.04"
+"#;
+
+    fn write_manifest(contents: &str) -> tempfile::NamedTempFile {
+        let mut manifest = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut manifest, contents.as_bytes()).unwrap();
+        manifest
+    }
+
+    #[test]
+    fn test_manifest_tasks() {
+        let tasks = manifest_tasks(MANIFEST).unwrap();
+        assert_eq!(
+            tasks.into_iter().collect::<Vec<_>>(),
+            vec!["lint", "pipeline_full", "pipeline_quick", "setup"]
+        );
+    }
+
+    #[test]
+    fn test_check_manifest_task_found() {
+        let manifest = write_manifest(MANIFEST);
+        check_manifest_task(manifest.path(), "pipeline_full").unwrap();
+        check_manifest_task(manifest.path(), "lint").unwrap();
+    }
+
+    #[test]
+    fn test_check_manifest_task_lists_available_tasks() {
+        let manifest = write_manifest(MANIFEST);
+        let error = check_manifest_task(manifest.path(), "pipeline_ful")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            format!(
+                "Task 'pipeline_ful' not found in pixi manifest {}. Available tasks: lint, pipeline_full, pipeline_quick, setup",
+                manifest.path().display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_manifest_task_without_tasks() {
+        let manifest = write_manifest("[workspace]\nname = \"empty\"\n");
+        let error = check_manifest_task(manifest.path(), "pipeline_full")
+            .unwrap_err()
+            .to_string();
+        assert!(error.ends_with("defines no tasks"));
+    }
+
     #[test]
     fn test_install_pixi() {
         // Run install
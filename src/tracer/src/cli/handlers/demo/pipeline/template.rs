@@ -0,0 +1,133 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Parses a `NAME=VALUE` template variable given on the command line
+pub fn parse_template_var(var: &str) -> Result<(String, String), String> {
+    match var.split_once('=') {
+        Some((name, value)) if is_var_name(name) => Ok((name.to_string(), value.to_string())),
+        Some((name, _)) => Err(format!(
+            "invalid variable name '{}': use letters, digits and underscores",
+            name
+        )),
+        None => Err(format!("expected NAME=VALUE, got '{}'", var)),
+    }
+}
+
+/// Substitutes the `{name}` placeholders in `args` with their value in `vars`. Braces that don't
+/// enclose a variable name (e.g. `{print $1}` in an awk program) are kept as they are, so args
+/// without placeholders are unchanged. Fails listing every placeholder without a value.
+pub fn render_args(args: &[String], vars: &HashMap<String, String>) -> Result<Vec<String>> {
+    let mut missing = Vec::new();
+    let rendered = args
+        .iter()
+        .map(|arg| render(arg, vars, &mut missing))
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "No value for template variable(s) {}; set them with --var NAME=VALUE",
+            missing.join(", ")
+        );
+    }
+    Ok(rendered)
+}
+
+fn render(arg: &str, vars: &HashMap<String, String>, missing: &mut Vec<String>) -> String {
+    let mut rendered = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        match placeholder[1..]
+            .find('}')
+            .map(|end| &placeholder[1..end + 1])
+            .filter(|name| is_var_name(name))
+        {
+            Some(name) => {
+                match vars.get(name) {
+                    Some(value) => rendered.push_str(value),
+                    None => {
+                        if !missing.iter().any(|m| m == name) {
+                            missing.push(name.to_string());
+                        }
+                    }
+                }
+                rest = &placeholder[name.len() + 2..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_placeholders_are_substituted() {
+        let rendered = render_args(
+            &args(&["-t", "{threads}", "--in={sample}_R1.fq", "{sample}_R2.fq"]),
+            &vars(&[("sample", "data/S1"), ("threads", "8")]),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            args(&["-t", "8", "--in=data/S1_R1.fq", "data/S1_R2.fq"])
+        );
+    }
+
+    #[test]
+    fn test_literal_args_are_unchanged() {
+        let literal = args(&["-n", "{print $1}", "{}", "{1}", "{unclosed", "a}b"]);
+        assert_eq!(render_args(&literal, &HashMap::new()).unwrap(), literal);
+    }
+
+    #[test]
+    fn test_missing_vars_are_all_reported() {
+        let error = render_args(
+            &args(&["{sample}", "{reference}", "{sample}.bam", "{threads}"]),
+            &vars(&[("threads", "4")]),
+        )
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            error,
+            "No value for template variable(s) sample, reference; set them with --var NAME=VALUE"
+        );
+    }
+
+    #[test]
+    fn test_parse_template_var() {
+        assert_eq!(
+            parse_template_var("sample=a=b.fq").unwrap(),
+            ("sample".to_string(), "a=b.fq".to_string())
+        );
+        assert_eq!(
+            parse_template_var("empty=").unwrap(),
+            ("empty".to_string(), String::new())
+        );
+        assert!(parse_template_var("sample").is_err());
+        assert!(parse_template_var("my-sample=x").is_err());
+        assert!(parse_template_var("=x").is_err());
+    }
+}
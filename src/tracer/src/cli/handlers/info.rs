@@ -4,6 +4,7 @@ use crate::daemon::server::DaemonServer;
 use crate::daemon::structs::PipelineMetadata;
 use crate::utils::cli::BoxFormatter;
 use crate::utils::env::is_development_environment;
+use crate::utils::workdir::TRACER_WORK_DIR;
 use crate::utils::Version;
 
 pub async fn info(api_client: &DaemonClient, json: bool) {
@@ -22,6 +23,47 @@ pub async fn info(api_client: &DaemonClient, json: bool) {
     display.print(pipeline_data);
 }
 
+/// Version of the `tracer info --json` schema
+pub const INFO_JSON_SCHEMA_VERSION: u32 = 1;
+/// Top-level keys of the `tracer info --json` output. `error` is `null` unless the daemon
+/// couldn't be reached.
+pub const INFO_JSON_KEYS: [&str; 11] = [
+    "schema_version",
+    "tracer_cli",
+    "daemon",
+    "opentelemetry",
+    "pipeline",
+    "cloud",
+    "run",
+    "pricing",
+    "cost_estimation",
+    "config",
+    "error",
+];
+
+/// The `tracer info --json` output while no daemon is running, with all keys but the version
+/// and daemon status unknown
+fn not_running_json() -> serde_json::Value {
+    let mut json: serde_json::Value = INFO_JSON_KEYS
+        .iter()
+        .map(|key| (key.to_string(), serde_json::Value::Null))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    json["schema_version"] = serde_json::json!(INFO_JSON_SCHEMA_VERSION);
+    json["tracer_cli"] = serde_json::json!({
+        "version": Version::current().to_string(),
+    });
+    json["daemon"] = serde_json::json!({ "running": false });
+    json
+}
+
+/// The `tracer info --json` output when the daemon can't be reached, with the reason in `error`
+fn error_json(error: &str) -> serde_json::Value {
+    let mut json = not_running_json();
+    json["error"] = serde_json::json!(error);
+    json
+}
+
 pub struct InfoDisplay {
     width: usize,
     json: bool,
@@ -48,11 +90,20 @@ impl InfoDisplay {
     }
 
     fn print_json(&self, pipeline: PipelineMetadata) {
-        let mut json = serde_json::json!({});
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&self.to_json(&pipeline)).unwrap()
+        );
+    }
 
-        // Tracer CLI info
-        json["tracer_cli"] = serde_json::json!({
-            "version": Version::current().to_string(),
+    /// The `tracer info --json` output. Every key of `INFO_JSON_KEYS` is always present, `null`
+    /// when unknown, so automation can rely on the shape; bump `INFO_JSON_SCHEMA_VERSION` when
+    /// changing or removing a key.
+    pub fn to_json(&self, pipeline: &PipelineMetadata) -> serde_json::Value {
+        let mut json = not_running_json();
+        json["daemon"] = serde_json::json!({
+            "running": true,
+            "uptime": pipeline.formatted_runtime(),
         });
 
         // OpenTelemetry status
@@ -81,6 +132,14 @@ impl InfoDisplay {
             "organization": pipeline.tags.organization_slug,
             "email": pipeline.tags.email.as_deref().unwrap_or("Not set"),
             "stage": pipeline.stage(),
+            "ebpf_events_lost": pipeline.ebpf_events_lost,
+            "active_rules": pipeline.active_rules,
+            "local_only": pipeline.local_only,
+        });
+
+        json["cloud"] = serde_json::json!({
+            "environment_type": pipeline.tags.environment_type,
+            "instance_type": pipeline.tags.instance_type,
         });
 
         if let Some(run_snapshot) = &pipeline.run_snapshot {
@@ -105,6 +164,11 @@ impl InfoDisplay {
                     "estimated_cost_since_start": format!("{:.4}", summary.get_estimated_total(run_snapshot.start_time)),
                     "detected_ec2_instance_type": summary.instance_type,
                 });
+                json["pricing"] = serde_json::json!({
+                    "source": summary.source,
+                    "instance_type": summary.instance_type,
+                    "hourly": summary.hourly,
+                });
             }
         } else {
             json["run"] = serde_json::json!({
@@ -112,7 +176,12 @@ impl InfoDisplay {
             });
         }
 
-        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        // already redacted by `Config::to_safe_json`
+        if let Some(config) = &pipeline.config {
+            json["config"] = config.clone();
+        }
+
+        json
     }
 
     fn format_output(&self, formatter: &mut BoxFormatter, pipeline: PipelineMetadata) {
@@ -131,7 +200,7 @@ impl InfoDisplay {
         );
 
         let pipeline_environment = pipeline.tags.environment.as_deref().unwrap_or("Not set");
-        let pipeline_user = pipeline.tags.user_id.as_deref().unwrap();
+        let pipeline_user = pipeline.tags.user_id.as_deref().unwrap_or("Not logged in");
 
         let user_email = pipeline.tags.email.as_deref().unwrap_or("Not set");
         let user_organization = pipeline.tags.organization_slug.as_str();
@@ -141,6 +210,16 @@ impl InfoDisplay {
         formatter.add_field("User", pipeline_user, "magenta");
         formatter.add_field("Organization", user_organization, "magenta");
         formatter.add_field("Email", user_email, "magenta");
+        if pipeline.local_only {
+            formatter.add_field(
+                "Events",
+                &format!(
+                    "Recorded locally only, to {} (no token)",
+                    TRACER_WORK_DIR.local_events_file.display()
+                ),
+                "yellow",
+            );
+        }
 
         if let Some(otel_status) = &pipeline.opentelemetry_status {
             let status_text = if otel_status.enabled {
@@ -160,6 +239,16 @@ impl InfoDisplay {
         } else {
             formatter.add_status_field("Logging", "Unknown", "inactive");
         }
+        if pipeline.ebpf_events_lost > 0 {
+            formatter.add_field(
+                "Lost eBPF events",
+                &format!(
+                    "{} (the ring buffer is too small for this workload)",
+                    pipeline.ebpf_events_lost
+                ),
+                "yellow",
+            );
+        }
 
         formatter.add_empty_line();
         if let Some(run_snapshot) = &pipeline.run_snapshot {
@@ -228,6 +317,10 @@ impl InfoDisplay {
             formatter.add_empty_line();
         }
 
+        if let Some(config) = &pipeline.config {
+            self.format_config_info(formatter, config, pipeline.active_rules);
+        }
+
         formatter.add_section_header("Resources");
         formatter.add_empty_line();
         formatter.add_field("Dashboard", self.get_sandbox_url(&pipeline), "blue");
@@ -236,9 +329,50 @@ impl InfoDisplay {
         formatter.add_empty_line();
     }
 
+    /// The effective settings of the daemon, i.e. its configuration merged with the `tracer init`
+    /// arguments; `tracer info --json` prints all of them
+    fn format_config_info(
+        &self,
+        formatter: &mut BoxFormatter,
+        config: &serde_json::Value,
+        active_rules: Option<usize>,
+    ) {
+        formatter.add_section_header("Configuration");
+        formatter.add_empty_line();
+        let polling = if config["force_procfs"].as_bool().unwrap_or(false) {
+            "procfs (forced)"
+        } else {
+            "eBPF, procfs as fallback"
+        };
+        formatter.add_field("Process monitoring", polling, "white");
+        let rules = match (
+            config["rules_file"].as_str(),
+            config["builtin_rules"].as_bool().unwrap_or(true),
+        ) {
+            (Some(rules_file), true) => rules_file.to_string(),
+            (Some(rules_file), false) => format!("{} (built-in rules disabled)", rules_file),
+            (None, true) => "Built-in rules only".to_string(),
+            (None, false) => "None, built-in rules disabled".to_string(),
+        };
+        formatter.add_field("Rules file", &rules, "white");
+        match active_rules {
+            Some(0) => formatter.add_field(
+                "Active rules",
+                "0 (no tools will be recorded, only system metrics)",
+                "red",
+            ),
+            Some(count) => formatter.add_field("Active rules", &count.to_string(), "white"),
+            None => {}
+        }
+        if let Some(interval) = config["process_polling_interval_ms"].as_u64() {
+            formatter.add_field("Polling interval", &format!("{} ms", interval), "white");
+        }
+        formatter.add_empty_line();
+    }
+
     pub fn print_error(&self) {
         if self.json {
-            println!("{}", serde_json::json!({"error": "Daemon not started"}));
+            println!("{}", error_json("Daemon not started"));
             return;
         }
         let mut formatter = BoxFormatter::new(self.width);
@@ -286,3 +420,79 @@ impl InfoDisplay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn pipeline(config: Option<serde_json::Value>) -> PipelineMetadata {
+        PipelineMetadata {
+            name: "test_pipeline".to_string(),
+            run_snapshot: None,
+            tags: Default::default(),
+            is_dev: true,
+            start_time: Default::default(),
+            opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: Some(42),
+            local_only: false,
+            config,
+        }
+    }
+
+    fn keys(json: &serde_json::Value) -> Vec<&str> {
+        let mut keys: Vec<_> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_info_json_schema() {
+        let config = Config {
+            daemon_token: Some("super-secret-token".to_string()),
+            ..Config::default()
+        };
+        let json = InfoDisplay::new(180, true).to_json(&pipeline(Some(config.to_safe_json())));
+
+        let mut expected = INFO_JSON_KEYS.to_vec();
+        expected.sort();
+        assert_eq!(keys(&json), expected);
+        assert_eq!(json["schema_version"], INFO_JSON_SCHEMA_VERSION);
+        assert_eq!(json["daemon"]["running"], true);
+        assert_eq!(json["pipeline"]["active_rules"], 42);
+        assert_eq!(json["run"]["status"], "No run found");
+        assert!(json["pricing"].is_null());
+        assert!(json["error"].is_null());
+
+        // secrets are redacted
+        assert_eq!(json["config"]["daemon_token"], true);
+        assert!(!json.to_string().contains("super-secret-token"));
+    }
+
+    #[test]
+    fn test_info_json_schema_without_daemon() {
+        let json = not_running_json();
+        let mut expected = INFO_JSON_KEYS.to_vec();
+        expected.sort();
+        assert_eq!(keys(&json), expected);
+        assert_eq!(json["daemon"]["running"], false);
+        assert!(json["config"].is_null());
+        assert!(json["error"].is_null());
+    }
+
+    #[test]
+    fn test_info_json_schema_on_error() {
+        let json = error_json("Daemon not started");
+        let mut expected = INFO_JSON_KEYS.to_vec();
+        expected.sort();
+        assert_eq!(keys(&json), expected);
+        assert_eq!(json["daemon"]["running"], false);
+        assert_eq!(json["error"], "Daemon not started");
+    }
+}
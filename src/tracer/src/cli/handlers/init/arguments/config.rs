@@ -1,11 +1,14 @@
+use crate::config::Config;
 use crate::process_identification::types::pipeline_tags::PipelineTags;
 use crate::utils::input_validation::StringValueParser;
+use crate::utils::jwt_utils::token::Token;
 use crate::utils::workdir::TRACER_WORK_DIR;
 use crate::utils::Sentry;
 use clap::{Args, ValueEnum};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
+use std::io::IsTerminal;
 use std::path::Path;
 
 use super::resolver::ArgumentResolver;
@@ -35,6 +38,11 @@ pub struct TracerCliInitArgs {
     #[clap(short = 'i', long, default_value = "minimal")]
     pub interactive_prompts: PromptMode,
 
+    /// fail with an error listing the missing required inputs instead of prompting for them;
+    /// implied when stdin is not a terminal, unless prompts are disabled with '-i none'
+    #[clap(long)]
+    pub non_interactive: bool,
+
     /// force process polling even if eBPF is available; this enables you to use
     /// the client without having root/sudo privileges
     #[clap(long)]
@@ -54,6 +62,11 @@ pub struct TracerCliInitArgs {
     #[clap(long, value_name = "DIR")]
     pub watch_dir: Option<String>,
 
+    /// Directory of the pipeline, where its pixi.lock, conda-lock.yml or environment.yml are
+    /// read from to record the versions of its tools (default: current working directory)
+    #[clap(long, value_name = "DIR")]
+    pub pipeline_dir: Option<String>,
+
     // run client as a standalone process rather than a daemon
     #[clap(long, hide = true)]
     pub no_daemonize: bool,
@@ -67,9 +80,38 @@ pub struct TracerCliInitArgs {
     #[clap(long)]
     pub force: bool,
 
-    /// set a jwt token for authentication
+    /// set a jwt token for authentication, instead of the one 'tracer login' stored or
+    /// TRACER_TOKEN
+    #[clap(long)]
+    pub token: Option<Token>,
+
+    /// file holding the jwt token for authentication, e.g. a mounted secret; takes precedence
+    /// over TRACER_TOKEN and the token 'tracer login' stored, and is never copied elsewhere
+    #[clap(long, value_name = "PATH")]
+    pub token_file: Option<String>,
+
+    /// record events locally only, to the work directory, without logging in; the default
+    /// when no token is found
     #[clap(long)]
-    pub token: Option<String>,
+    pub local_only: bool,
+
+    /// YAML rules files or directories with additional targets to monitor, separated by ':';
+    /// later files override the rules of earlier ones with the same display_name, and their
+    /// rules take precedence over the built-in ones. Validate them first with
+    /// 'tracer rules validate <file>...'
+    #[clap(long, value_name = "PATH")]
+    pub rules_file: Option<String>,
+
+    /// additional target to monitor, as name=<process name>, cmd-contains=<text> or
+    /// path-glob=<glob> (repeatable); these are matched before the rules file and the built-in
+    /// rules
+    #[clap(long = "match", value_name = "RULE")]
+    pub match_rules: Vec<String>,
+
+    /// write every event as a JSON line to the given file or fifo ('-' for stdout, useful with
+    /// --no-daemonize) instead of sending events to Tracer
+    #[clap(long, value_name = "PATH")]
+    pub events_ndjson: Option<String>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, ValueEnum)]
@@ -100,14 +142,82 @@ pub struct FinalizedInitArgs {
     pub log_level: String,
     pub environment_variables: HashMap<String, String>,
     pub watch_dir: Option<String>,
+    pub pipeline_dir: Option<String>,
+    pub rules_file: Option<String>,
+    pub match_rules: Vec<String>,
+    pub events_ndjson: Option<String>,
+    /// Events are not sent to Tracer, as no token was found or `--local-only` was given
+    pub local_only: bool,
+}
+
+impl FinalizedInitArgs {
+    /// Applies these arguments on top of `config`, yielding the configuration the daemon runs
+    /// with.
+    ///
+    /// Precedence, highest first: command line flags, answers to interactive prompts (already
+    /// folded into these arguments by `ArgumentResolver`), then the `TRACER_*` environment
+    /// variables and built-in defaults `Config::default` is made of. Settings only known to one
+    /// side are left untouched.
+    pub fn merge_into(&self, mut config: Config) -> Config {
+        // a flag can only enable procfs polling, not override an environment that asks for it
+        config.force_procfs |= self.force_procfs;
+        if let Some(rules_file) = &self.rules_file {
+            config.rules_file = Some(rules_file.clone());
+        }
+        if !self.match_rules.is_empty() {
+            config.match_rules = self.match_rules.clone();
+        }
+        if let Some(events_ndjson) = &self.events_ndjson {
+            config.events_ndjson = Some(events_ndjson.clone());
+        }
+        // also set when no token was found, which `TRACER_LOCAL_ONLY` can't undo
+        config.local_only |= self.local_only;
+        config
+    }
 }
 
 impl TracerCliInitArgs {
     /// Fill in any missing arguments according to the `PromptMode`.
-    pub async fn resolve_arguments(self) -> FinalizedInitArgs {
+    pub async fn resolve_arguments(self) -> anyhow::Result<FinalizedInitArgs> {
         ArgumentResolver::new(self).resolve().await
     }
 
+    /// Fails if prompting is ruled out (`--non-interactive`, or stdin is not a terminal) while
+    /// inputs that would be prompted for are missing, so e.g. CI jobs fail fast instead of
+    /// hanging on a prompt. With `-i none` missing inputs are generated instead.
+    pub fn ensure_required_inputs(&self) -> anyhow::Result<()> {
+        let non_interactive = self.non_interactive
+            || (self.interactive_prompts != PromptMode::None && !std::io::stdin().is_terminal());
+        if !non_interactive {
+            return Ok(());
+        }
+
+        let missing = self.missing_required_inputs();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Missing required inputs in non-interactive mode: {}",
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    fn missing_required_inputs(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.pipeline_name.is_none() {
+            missing.push("pipeline name (--pipeline-name or TRACER_PIPELINE_NAME)");
+        }
+        if self.interactive_prompts == PromptMode::Required {
+            if self.tags.environment.is_none() {
+                missing.push("environment (--environment or TRACER_ENVIRONMENT)");
+            }
+            if self.tags.pipeline_type.is_none() {
+                missing.push("pipeline type (--pipeline-type or TRACER_PIPELINE_TYPE)");
+            }
+        }
+        missing
+    }
+
     /// Set the prompt mode to non-interactive (no prompts)
     pub fn set_non_interactive(&mut self) {
         self.interactive_prompts = PromptMode::None;
@@ -172,3 +282,137 @@ impl TracerCliInitArgs {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn finalized_args() -> FinalizedInitArgs {
+        FinalizedInitArgs {
+            pipeline_name: "pipeline".to_string(),
+            run_name: None,
+            user_id: "user".to_string(),
+            tags: PipelineTags::default(),
+            no_daemonize: false,
+            dev: false,
+            force_procfs: false,
+            force: false,
+            log_level: "info".to_string(),
+            environment_variables: HashMap::new(),
+            watch_dir: None,
+            pipeline_dir: None,
+            rules_file: None,
+            match_rules: Vec::new(),
+            events_ndjson: None,
+            local_only: false,
+        }
+    }
+
+    fn config() -> Config {
+        Config {
+            force_procfs: false,
+            rules_file: Some("/etc/tracer/rules.yml".to_string()),
+            match_rules: vec!["name=STAR".to_string()],
+            events_ndjson: Some("/var/log/tracer/events.ndjson".to_string()),
+            local_only: false,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_flags_take_precedence_over_config() {
+        let args = FinalizedInitArgs {
+            force_procfs: true,
+            rules_file: Some("/home/user/rules.yml".to_string()),
+            match_rules: vec!["cmd-contains=align".to_string()],
+            events_ndjson: Some("-".to_string()),
+            local_only: true,
+            ..finalized_args()
+        };
+
+        let merged = args.merge_into(config());
+        assert!(merged.force_procfs);
+        assert_eq!(merged.rules_file.as_deref(), Some("/home/user/rules.yml"));
+        assert_eq!(merged.match_rules, vec!["cmd-contains=align"]);
+        assert_eq!(merged.events_ndjson.as_deref(), Some("-"));
+        assert!(merged.local_only);
+    }
+
+    #[test]
+    fn test_config_applies_where_no_flag_is_given() {
+        let merged = finalized_args().merge_into(Config {
+            force_procfs: true,
+            local_only: true,
+            ..config()
+        });
+        assert!(merged.force_procfs);
+        assert_eq!(merged.rules_file.as_deref(), Some("/etc/tracer/rules.yml"));
+        assert_eq!(merged.match_rules, vec!["name=STAR"]);
+        assert_eq!(
+            merged.events_ndjson.as_deref(),
+            Some("/var/log/tracer/events.ndjson")
+        );
+        assert!(merged.local_only);
+    }
+
+    #[test]
+    fn test_defaults_apply_where_neither_sets_a_value() {
+        let merged = finalized_args().merge_into(Config {
+            rules_file: None,
+            match_rules: Vec::new(),
+            events_ndjson: None,
+            ..config()
+        });
+        assert_eq!(merged.rules_file, None);
+        assert!(merged.match_rules.is_empty());
+        assert_eq!(merged.events_ndjson, None);
+        assert!(!merged.local_only);
+    }
+
+    #[test]
+    fn test_missing_pipeline_name_fails_in_non_interactive_mode() {
+        let args = TracerCliInitArgs {
+            non_interactive: true,
+            ..Default::default()
+        };
+        let error = args.ensure_required_inputs().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Missing required inputs in non-interactive mode: pipeline name (--pipeline-name or TRACER_PIPELINE_NAME)"
+        );
+
+        let args = TracerCliInitArgs {
+            pipeline_name: Some("rnaseq".to_string()),
+            ..args
+        };
+        assert!(args.ensure_required_inputs().is_ok());
+    }
+
+    #[test]
+    fn test_required_prompts_need_all_prompted_inputs() {
+        let mut args = TracerCliInitArgs {
+            non_interactive: true,
+            pipeline_name: Some("rnaseq".to_string()),
+            tags: PipelineTags {
+                environment: None,
+                pipeline_type: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        args.set_required_prompts();
+        let error = args.ensure_required_inputs().unwrap_err().to_string();
+        assert!(error.contains("environment (--environment"));
+        assert!(error.contains("pipeline type (--pipeline-type"));
+    }
+
+    #[test]
+    fn test_settings_unknown_to_the_arguments_are_kept() {
+        let config = Config {
+            process_polling_interval_ms: 42,
+            ..config()
+        };
+        let merged = finalized_args().merge_into(config);
+        assert_eq!(merged.process_polling_interval_ms, 42);
+    }
+}
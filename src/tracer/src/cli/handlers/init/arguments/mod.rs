@@ -2,3 +2,6 @@ mod config;
 mod resolver;
 pub use config::*;
 pub use resolver::*;
+
+#[cfg(test)]
+pub(crate) use config::tests::finalized_args;
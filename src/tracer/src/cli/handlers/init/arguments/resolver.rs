@@ -1,16 +1,32 @@
-use super::super::user_prompts::{print_help, UserPrompts};
-use super::{FinalizedInitArgs, PromptMode, TracerCliInitArgs};
+use super::super::user_prompts::{
+    known_option, print_help, UserPrompts, ENVIRONMENTS, PIPELINE_TYPES,
+};
+use super::{FinalizedInitArgs, PromptMode, TracerCliInitArgs, USERNAME_ENV_VAR};
+use crate::constants::{JWT_TOKEN_FILE_PATH, TOKEN_ENV_VAR};
 use crate::utils::env;
 use crate::utils::env::{get_sandbox_url, is_development_environment};
 use crate::utils::jwt_utils::claims::Claims;
-use crate::utils::jwt_utils::jwt::{get_token_claims_from_file, is_jwt_valid};
+use crate::utils::jwt_utils::jwt::is_jwt_valid;
+use crate::utils::jwt_utils::token::{resolve_token, Token};
+use crate::utils::workdir::TRACER_WORK_DIR;
+use crate::warning_message;
 use colored::Colorize;
 use std::collections::HashMap;
+use std::path::Path;
+use tracing::debug;
 
 /// Constants for argument resolution
 pub const DEFAULT_PIPELINE_TYPE: &str = "Preprocessing";
 pub const DEFAULT_ENVIRONMENT: &str = "local";
 
+fn no_token_warning() -> String {
+    format!(
+        "No token found, run 'tracer login' or pass --token, --token-file or {}. Events are recorded locally only, to {}",
+        TOKEN_ENV_VAR,
+        TRACER_WORK_DIR.local_events_file.display()
+    )
+}
+
 /// Handles argument resolution logic
 pub struct ArgumentResolver {
     args: TracerCliInitArgs,
@@ -21,7 +37,9 @@ impl ArgumentResolver {
         Self { args }
     }
 
-    pub async fn resolve(mut self) -> FinalizedInitArgs {
+    pub async fn resolve(mut self) -> anyhow::Result<FinalizedInitArgs> {
+        self.args.ensure_required_inputs()?;
+
         let prompt_mode = self.args.interactive_prompts.clone();
 
         let platform = if is_development_environment() {
@@ -34,17 +52,14 @@ impl ArgumentResolver {
         let organization_id: String;
         let mut user_name: String = "".to_string();
 
-        if self.args.tags.user_id.is_none() {
-            let token_claims_option = self.decode_token(self.args.token.clone(), platform).await;
-
-            if token_claims_option.is_none() {
-                println!("\nUnable to log in automatically. Please open {}, and copy your init code here.", get_sandbox_url().cyan());
-                std::process::exit(1);
-            }
-
-            // checks on userid
-            let token_claims = token_claims_option.unwrap();
+        let token_claims_option = if self.args.tags.user_id.is_some() || self.args.local_only {
+            None
+        } else {
+            let token = self.find_token()?;
+            self.log_in(token, platform).await
+        };
 
+        if let Some(token_claims) = token_claims_option {
             // Get user_name first (borrows token_claims immutably)
             user_name = token_claims.get_name_from_full_name();
 
@@ -68,6 +83,8 @@ impl ArgumentResolver {
             } else {
                 user_id.clone()
             };
+        } else if self.args.local_only && self.args.tags.user_id.is_none() {
+            user_name = env::get_env_var(USERNAME_ENV_VAR).unwrap_or_default();
         }
 
         // Resolve environment type first so it can be used in pipeline name generation
@@ -80,7 +97,7 @@ impl ArgumentResolver {
         self.resolve_pipeline_type(&prompt_mode);
         let environment_variables = self.resolve_environment_variables();
 
-        FinalizedInitArgs {
+        Ok(FinalizedInitArgs {
             pipeline_name,
             run_name,
             user_id,
@@ -92,19 +109,50 @@ impl ArgumentResolver {
             log_level: self.args.log_level,
             environment_variables,
             watch_dir: self.args.watch_dir,
+            pipeline_dir: self.args.pipeline_dir,
+            rules_file: self.args.rules_file,
+            match_rules: self.args.match_rules,
+            events_ndjson: self.args.events_ndjson,
+            local_only: self.args.local_only,
+        })
+    }
+
+    /// The token to log in with, if any; fails if the `--token-file` can't be read
+    fn find_token(&self) -> anyhow::Result<Option<Token>> {
+        let resolved = resolve_token(
+            self.args.token.as_ref(),
+            self.args.token_file.as_deref().map(Path::new),
+            std::env::var(TOKEN_ENV_VAR).ok(),
+            Path::new(JWT_TOKEN_FILE_PATH),
+        )?;
+        Ok(resolved.map(|(token, source)| {
+            debug!("Using the token from {} ({})", source, token);
+            token
+        }))
+    }
+
+    /// Decodes the token if one was found, else switches to recording events locally only
+    async fn log_in(&mut self, token: Option<Token>, platform: &str) -> Option<Claims> {
+        let Some(token) = token else {
+            warning_message!("{}", no_token_warning());
+            self.args.local_only = true;
+            return None;
+        };
+        let token_claims_option = self.decode_token(&token, platform).await;
+        if token_claims_option.is_none() {
+            println!(
+                "\nUnable to log in automatically. Please open {}, and copy your init code here.",
+                get_sandbox_url().cyan()
+            );
+            std::process::exit(1);
         }
+        token_claims_option
     }
 
-    async fn decode_token(&mut self, token: Option<String>, platform: &str) -> Option<Claims> {
-        if let Some(token_result) = token {
-            let token_claims = is_jwt_valid(&token_result, platform).await;
-            if token_claims.0 {
-                Some(token_claims.1.unwrap())
-            } else {
-                None
-            }
-        } else {
-            get_token_claims_from_file(platform).await
+    async fn decode_token(&self, token: &Token, platform: &str) -> Option<Claims> {
+        match is_jwt_valid(token.expose(), platform).await {
+            (true, claims) => claims,
+            (false, _) => None,
         }
     }
 
@@ -118,10 +166,11 @@ impl ArgumentResolver {
             .to_lowercase()
             + "_pipeline";
 
+        let mut prompts = UserPrompts::terminal();
         match (self.args.pipeline_name.clone(), prompt_mode) {
             (Some(name), PromptMode::Required) => {
                 // Only prompt for confirmation in Required mode
-                UserPrompts::prompt_for_pipeline_name(&name)
+                prompts.prompt_for_pipeline_name(&name)
                     .unwrap_or_else(|| {
                         eprintln!("Warning: Using provided pipeline name '{}' (could not prompt for confirmation)", name);
                         name
@@ -129,28 +178,30 @@ impl ArgumentResolver {
             }
             (Some(name), _) => name,
             (None, PromptMode::Minimal | PromptMode::Required) => {
-                UserPrompts::prompt_for_pipeline_name(&pipeline_name_value).unwrap_or_else(|| {
-                    // Generate pipeline name with environment prefix
-                    let env_type = self
-                        .args
-                        .tags
-                        .environment_type
-                        .as_ref()
-                        .map(|env| {
-                            env.to_lowercase()
-                                .replace(" ", "-")
-                                .replace("(", "")
-                                .replace(")", "")
-                        })
-                        .unwrap_or_else(|| "no-terminal".to_string());
-                    let default_name = format!("{}-{}", env_type, pipeline_name_value);
-                    eprintln!(
-                        "Warning: No terminal detected. Using generated pipeline name: '{}'",
+                prompts
+                    .prompt_for_pipeline_name(&pipeline_name_value)
+                    .unwrap_or_else(|| {
+                        // Generate pipeline name with environment prefix
+                        let env_type = self
+                            .args
+                            .tags
+                            .environment_type
+                            .as_ref()
+                            .map(|env| {
+                                env.to_lowercase()
+                                    .replace(" ", "-")
+                                    .replace("(", "")
+                                    .replace(")", "")
+                            })
+                            .unwrap_or_else(|| "no-terminal".to_string());
+                        let default_name = format!("{}-{}", env_type, pipeline_name_value);
+                        eprintln!(
+                            "Warning: No terminal detected. Using generated pipeline name: '{}'",
+                            default_name
+                        );
+                        eprintln!("To specify a custom pipeline name, use: --pipeline-name <name>");
                         default_name
-                    );
-                    eprintln!("To specify a custom pipeline name, use: --pipeline-name <name>");
-                    default_name
-                })
+                    })
             }
             (None, PromptMode::None) => pipeline_name_value.to_string(),
         }
@@ -184,19 +235,18 @@ impl ArgumentResolver {
     }
 
     fn resolve_environment(&mut self, prompt_mode: &PromptMode) {
+        let mut prompts = UserPrompts::terminal();
         let environment = match (&self.args.tags.environment, prompt_mode) {
-            (Some(env), PromptMode::Required) => {
-                Some(UserPrompts::prompt_for_environment_name(env))
-            }
-            (Some(name), _) => Some(name.clone()),
-            (None, PromptMode::Required) if self.args.tags.environment_type.is_some() => {
-                Some(UserPrompts::prompt_for_environment_name(
-                    self.args.tags.environment_type.as_ref().unwrap(),
-                ))
+            (Some(env), PromptMode::Required) => Some(prompts.prompt_for_environment_name(env)),
+            // known environments are stored with their usual spelling
+            (Some(name), _) => Some(known_option(name, ENVIRONMENTS).unwrap_or(name).to_string()),
+            (None, PromptMode::Required) if self.args.tags.environment_type.is_some() => Some(
+                prompts
+                    .prompt_for_environment_name(self.args.tags.environment_type.as_ref().unwrap()),
+            ),
+            (None, PromptMode::Required) => {
+                Some(prompts.prompt_for_environment_name(DEFAULT_ENVIRONMENT))
             }
-            (None, PromptMode::Required) => Some(UserPrompts::prompt_for_environment_name(
-                DEFAULT_ENVIRONMENT,
-            )),
             (None, _) if self.args.tags.environment_type.is_some() => {
                 self.args.tags.environment_type.clone()
             }
@@ -209,12 +259,11 @@ impl ArgumentResolver {
     }
 
     fn resolve_pipeline_type(&mut self, prompt_mode: &PromptMode) {
+        let mut prompts = UserPrompts::terminal();
         let pipeline_type = match (&self.args.tags.pipeline_type, prompt_mode) {
-            (Some(env), PromptMode::Required) => UserPrompts::prompt_for_pipeline_type(env),
-            (Some(env), _) => env.clone(),
-            (None, PromptMode::Required) => {
-                UserPrompts::prompt_for_pipeline_type(DEFAULT_PIPELINE_TYPE)
-            }
+            (Some(env), PromptMode::Required) => prompts.prompt_for_pipeline_type(env),
+            (Some(env), _) => known_option(env, PIPELINE_TYPES).unwrap_or(env).to_string(),
+            (None, PromptMode::Required) => prompts.prompt_for_pipeline_type(DEFAULT_PIPELINE_TYPE),
             (None, _) => DEFAULT_PIPELINE_TYPE.to_string(),
         };
 
@@ -235,3 +284,18 @@ impl ArgumentResolver {
         environment_variables
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_missing_token_switches_to_local_only() {
+        let mut resolver = ArgumentResolver::new(TracerCliInitArgs::default());
+        assert!(resolver.log_in(None, "prod").await.is_none());
+        assert!(resolver.args.local_only);
+        assert!(
+            no_token_warning().contains(&TRACER_WORK_DIR.local_events_file.display().to_string())
+        );
+    }
+}
@@ -3,12 +3,24 @@ use super::arguments::TracerCliInitArgs;
 use super::setup::{
     handle_existing_daemon, setup_daemon_logging, setup_sentry_context, spawn_daemon_process,
 };
+use crate::client::exporters::ndjson::NDJSON_STDOUT;
+use crate::config::audit::record_daemon_config;
 use crate::config::Config;
 use crate::daemon::client::DaemonClient;
+use crate::daemon::server::daemon_lock::DaemonLock;
 use crate::daemon::server::DaemonServer;
+use crate::process_identification::target_process::parser::inline_match::parse_inline_matches;
+use crate::process_identification::target_process::parser::yaml_rules_parser::{
+    load_rules_files, split_rules_paths,
+};
+use crate::process_identification::types::pipeline_tags::PipelineTags;
+use crate::process_identification::types::tag_schema::TagSchema;
 use crate::utils::env::is_development_environment;
 use crate::utils::system_info::check_sudo_with_procfs_option;
 use crate::utils::workdir::TRACER_WORK_DIR;
+use crate::warning_message;
+use colored::Colorize;
+use std::path::Path;
 
 /// Initialize the tracer daemon with the given pipeline prefix
 pub async fn init(
@@ -17,7 +29,17 @@ pub async fn init(
     api_client: &DaemonClient,
 ) -> anyhow::Result<()> {
     // Perform initial setup and validation
-    init_setup_validation(&args, api_client).await?;
+    init_setup_validation(&args, &config, api_client).await?;
+
+    // Pass the daemon absolute rules file paths, so they don't depend on its working directory
+    if let Some(rules_file) = args.rules_file.take() {
+        let rules_paths = split_rules_paths(&rules_file)
+            .into_iter()
+            .map(|path| std::fs::canonicalize(&path).unwrap_or(path));
+        let rules_paths = std::env::join_paths(rules_paths)
+            .map_err(|e| anyhow::anyhow!("Invalid rules file: {}", e))?;
+        args.rules_file = Some(rules_paths.to_string_lossy().into_owned());
+    }
 
     // Force non-interactive mode when running as a daemon process
     if args.no_daemonize {
@@ -27,27 +49,79 @@ pub async fn init(
     // Set dev mode to true if running in the dev environment
     args.dev = is_development_environment();
 
-    let args = args.resolve_arguments().await;
+    let mut args = args.resolve_arguments().await?;
+    let mut config = args.merge_into(config);
+    if let Some(target) = config.events_ndjson.take() {
+        config.events_ndjson = Some(absolute_events_target(&target));
+    }
+
+    // Normalize the tags before they reach the daemon, so events carry the schema's values
+    apply_tag_schema(&mut args.tags, &config)?;
 
     // Set up Sentry context for monitoring
     setup_sentry_context(&args)?;
 
     if args.no_daemonize {
+        // Held for the lifetime of the daemon so no second daemon can share the work dir
+        let _lock = DaemonLock::acquire()?;
         setup_daemon_logging(&args.log_level)?;
+        // Only the daemon records the changes, once it is sure to replace the previous one
+        if let Err(e) = record_daemon_config(
+            &TRACER_WORK_DIR.daemon_config_file,
+            &TRACER_WORK_DIR.config_audit_file,
+            "tracer init",
+            &config.to_safe_json(),
+        ) {
+            tracing::warn!("Failed to record configuration changes: {}", e);
+        }
         DaemonServer::new().await.start(args, config).await
     } else {
         // Spawn the daemon process and wait for it to be ready
-        spawn_daemon_process(&args, api_client).await
+        spawn_daemon_process(&args, &config, api_client).await
+    }
+}
+
+/// Resolves an `--events-ndjson` target against the working directory, so the daemon writes
+/// where the user meant. The file may not exist yet, so its parent directory is resolved; stdout
+/// (`-`) is kept as is.
+fn absolute_events_target(target: &str) -> String {
+    if target == NDJSON_STDOUT {
+        return target.to_string();
+    }
+    let path = Path::new(target);
+    if let Ok(path) = std::fs::canonicalize(path) {
+        return path.display().to_string();
+    }
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    match (std::fs::canonicalize(parent), path.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name).display().to_string(),
+        // the daemon reports the missing directory when it opens the target
+        _ => target.to_string(),
     }
 }
 
 /// Performs initial setup and validation before starting the daemon
 async fn init_setup_validation(
     args: &TracerCliInitArgs,
+    config: &Config,
     api_client: &DaemonClient,
 ) -> anyhow::Result<()> {
     // Check if running with sudo (Linux only, unless force_procfs is enabled)
-    check_sudo_with_procfs_option("init", args.force_procfs);
+    check_sudo_with_procfs_option("init", args.force_procfs || config.force_procfs);
+
+    // Refuse to run blind on rules files that would yield no or partial targets
+    if let Some(rules_file) = args.rules_file.as_ref().or(config.rules_file.as_ref()) {
+        load_rules_files(&split_rules_paths(rules_file))
+            .map_err(|e| anyhow::anyhow!("Invalid rules file: {}", e))?;
+    }
+    if args.match_rules.is_empty() {
+        parse_inline_matches(&config.match_rules)?;
+    } else {
+        parse_inline_matches(&args.match_rules)?;
+    }
 
     // Create a work dir for logging and daemonizing files
     TRACER_WORK_DIR
@@ -59,5 +133,59 @@ async fn init_setup_validation(
         handle_existing_daemon(args, api_client).await?;
     }
 
+    // Fail fast if another daemon (e.g. one still starting up) holds the work dir lock
+    if !args.no_daemonize {
+        if let Some(pid) = DaemonLock::holder_pid() {
+            anyhow::bail!(
+                "Another tracer daemon (PID {}) is using {:?}. Use 'tracer terminate' to stop it first.",
+                pid,
+                TRACER_WORK_DIR.path
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the tags against the tag schema, if one is configured, rewriting or rejecting values
+/// it doesn't allow according to `Config::tag_schema_policy`
+fn apply_tag_schema(tags: &mut PipelineTags, config: &Config) -> anyhow::Result<()> {
+    let Some(schema_file) = &config.tag_schema_file else {
+        return Ok(());
+    };
+    let schema = TagSchema::load(Path::new(schema_file))?;
+    for warning in schema.apply(tags, config.tag_schema_policy)? {
+        warning_message!("Tag not in the tag schema: {}", warning);
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_events_target_is_made_absolute() {
+        let cwd = std::env::current_dir().unwrap().canonicalize().unwrap();
+        assert_eq!(
+            absolute_events_target("out.ndjson"),
+            cwd.join("out.ndjson").display().to_string()
+        );
+
+        let dir = TempDir::new().unwrap();
+        let existing = dir.path().join("existing.ndjson");
+        std::fs::write(&existing, "").unwrap();
+        let canonical_dir = dir.path().canonicalize().unwrap();
+        assert_eq!(
+            absolute_events_target(&existing.display().to_string()),
+            canonical_dir.join("existing.ndjson").display().to_string()
+        );
+
+        assert_eq!(absolute_events_target(NDJSON_STDOUT), NDJSON_STDOUT);
+        assert_eq!(
+            absolute_events_target("/nonexistent/dir/out.ndjson"),
+            "/nonexistent/dir/out.ndjson"
+        );
+    }
+}
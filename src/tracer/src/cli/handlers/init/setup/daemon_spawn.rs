@@ -1,6 +1,7 @@
 use super::super::arguments::FinalizedInitArgs;
 use crate::cli::handlers::{info, otel_start_with_auto_install};
 use crate::cli::helper::wait;
+use crate::config::Config;
 use crate::daemon::client::DaemonClient;
 use crate::daemon::server::DaemonServer;
 use crate::utils::analytics::types::AnalyticsEventType;
@@ -12,6 +13,7 @@ use colored::Colorize;
 /// Spawns a new daemon process and waits for it to be ready
 pub async fn spawn_daemon_process(
     args: &FinalizedInitArgs,
+    config: &Config,
     api_client: &DaemonClient,
 ) -> anyhow::Result<()> {
     DaemonServer::cleanup();
@@ -24,7 +26,7 @@ pub async fn spawn_daemon_process(
         None,
     );
 
-    let spawn_args = build_spawn_args(args);
+    let spawn_args = build_spawn_args(args, config);
     let spawn_args_str: Vec<&str> = spawn_args.iter().map(|s| s.as_str()).collect();
     let child_id = spawn::spawn_child(&spawn_args_str)?;
 
@@ -56,8 +58,9 @@ pub async fn spawn_daemon_process(
     Ok(())
 }
 
-/// Builds the command line arguments for spawning the daemon process
-fn build_spawn_args(args: &FinalizedInitArgs) -> Vec<String> {
+/// Builds the command line arguments for spawning the daemon process, forwarding the settings
+/// `FinalizedInitArgs::merge_into` resolved into `config`
+fn build_spawn_args(args: &FinalizedInitArgs, config: &Config) -> Vec<String> {
     let mut spawn_args = vec![
         "init".to_string(),
         "--no-daemonize".to_string(),
@@ -67,8 +70,6 @@ fn build_spawn_args(args: &FinalizedInitArgs) -> Vec<String> {
         args.tags.environment.as_deref().unwrap_or("").to_string(),
         "--pipeline-type".to_string(),
         args.tags.pipeline_type.as_deref().unwrap_or("").to_string(),
-        "--user-id".to_string(),
-        args.tags.user_id.as_deref().unwrap().to_string(),
         "--log-level".to_string(),
         args.log_level.clone(),
         "--organization-slug".to_string(),
@@ -77,6 +78,15 @@ fn build_spawn_args(args: &FinalizedInitArgs) -> Vec<String> {
         args.tags.user_full_name.clone(),
     ];
 
+    // not set when recording locally only
+    if let Some(user_id) = &args.tags.user_id {
+        spawn_args.push("--user-id".to_string());
+        spawn_args.push(user_id.clone());
+    }
+    if config.local_only {
+        spawn_args.push("--local-only".to_string());
+    }
+
     if args.tags.organization_id.is_some() {
         spawn_args.push("--organization-id".to_string());
         spawn_args.push(args.tags.organization_id.as_ref().unwrap().to_string());
@@ -94,6 +104,21 @@ fn build_spawn_args(args: &FinalizedInitArgs) -> Vec<String> {
     if args.force_procfs {
         spawn_args.push("--force-procfs".to_string());
     }
+    if let Some(pipeline_dir) = &args.pipeline_dir {
+        spawn_args.push("--pipeline-dir".to_string());
+        spawn_args.push(pipeline_dir.clone());
+    }
+    if let Some(rules_file) = &args.rules_file {
+        spawn_args.push("--rules-file".to_string());
+        spawn_args.push(rules_file.clone());
+    }
+    for match_rule in &config.match_rules {
+        spawn_args.push(format!("--match={}", match_rule));
+    }
+    if let Some(events_ndjson) = &config.events_ndjson {
+        spawn_args.push("--events-ndjson".to_string());
+        spawn_args.push(events_ndjson.clone());
+    }
 
     // Add environment variables for OTEL if provided
     for (key, value) in &args.environment_variables {
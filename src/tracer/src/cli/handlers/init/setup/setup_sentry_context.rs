@@ -13,7 +13,9 @@ pub fn setup_sentry_context(args: &FinalizedInitArgs) -> anyhow::Result<()> {
     json_args.extend(tags_json);
 
     Sentry::add_context("Init Arguments", Value::Object(json_args));
-    Sentry::add_tag("user_id", args.tags.user_id.as_ref().unwrap());
+    if let Some(user_id) = &args.tags.user_id {
+        Sentry::add_tag("user_id", user_id);
+    }
     Sentry::add_tag("pipeline_name", &args.pipeline_name);
 
     Ok(())
@@ -1,6 +1,6 @@
 use crate::cli::handlers::INTERACTIVE_THEME;
-use crate::utils::input_validation::get_validated_input;
-use dialoguer::Select;
+use crate::utils::input_validation::validate_input_string;
+use dialoguer::{Input, Select};
 
 /// Constants for user prompting
 pub const ENVIRONMENTS: &[&str] = &["local", "development", "staging", "production", "custom"];
@@ -16,72 +16,168 @@ pub const PIPELINE_TYPES: &[&str] = &[
     "Proteomics",
     "Custom",
 ];
-pub const CUSTOM_INDEX: usize = 9;
+
+/// Source of the answers to prompts, so the prompt flow can run without a terminal
+pub trait Prompter {
+    /// Asks for a line of text, or `None` if there is no terminal to prompt on
+    fn input(&mut self, prompt: &str, default: Option<&str>) -> Option<String>;
+
+    /// Asks to pick one of `items`, returning its index, or `None` if there is no terminal
+    fn select(&mut self, prompt: &str, items: &[String], default: usize) -> Option<usize>;
+
+    /// Tells why an answer was rejected before prompting again
+    fn reject(&mut self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+/// Prompts on the terminal
+pub struct TerminalPrompter;
+
+impl Prompter for TerminalPrompter {
+    fn input(&mut self, prompt: &str, default: Option<&str>) -> Option<String> {
+        let mut input = Input::<String>::with_theme(&*INTERACTIVE_THEME).with_prompt(prompt);
+        if let Some(default) = default {
+            input = input.default(default.to_string());
+        }
+        input
+            .interact_text()
+            .map_err(|e| eprintln!("Warning: Cannot prompt (not a terminal): {}", e))
+            .ok()
+    }
+
+    fn select(&mut self, prompt: &str, items: &[String], default: usize) -> Option<usize> {
+        Select::with_theme(&*INTERACTIVE_THEME)
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact()
+            .map_err(|e| eprintln!("Warning: Cannot prompt (not a terminal): {}", e))
+            .ok()
+    }
+}
 
 /// Handles all user prompting functionality
-pub struct UserPrompts;
+pub struct UserPrompts<P: Prompter = TerminalPrompter> {
+    prompter: P,
+}
 
 impl UserPrompts {
-    pub fn prompt_for_pipeline_name<S: AsRef<str>>(default: S) -> Option<String> {
-        get_validated_input(
-            &INTERACTIVE_THEME,
+    pub fn terminal() -> Self {
+        Self::new(TerminalPrompter)
+    }
+}
+
+impl<P: Prompter> UserPrompts<P> {
+    pub fn new(prompter: P) -> Self {
+        Self { prompter }
+    }
+
+    pub fn prompt_for_pipeline_name<S: AsRef<str>>(&mut self, default: S) -> Option<String> {
+        self.prompt_validated(
             "Enter pipeline name (e.g., RNA-seq_analysis_v1, scRNA-seq_2024)",
             Some(default.as_ref()),
             "pipeline name",
         )
     }
 
-    pub fn prompt_for_environment_name(default: &str) -> String {
-        let default_index = ENVIRONMENTS.iter().position(|e| e == &default).unwrap();
-        let selection = Select::with_theme(&*INTERACTIVE_THEME)
-            .with_prompt("Select environment (or choose 'custom' to enter your own)")
-            .items(ENVIRONMENTS)
-            .default(default_index)
-            .interact()
-            .expect("Error while prompting for environment name");
-        let environment = ENVIRONMENTS[selection];
-        if environment == "custom" {
-            get_validated_input(
-                &INTERACTIVE_THEME,
-                "Enter custom environment name",
-                None,
-                "environment name",
-            )
-            .unwrap_or_else(|| "custom".to_string())
-        } else {
-            environment.to_string()
+    /// Prompts for one of the known environments or a custom one; falls back to `default` if
+    /// there is no terminal
+    pub fn prompt_for_environment_name(&mut self, default: &str) -> String {
+        self.select_or_custom(
+            "Select environment (or choose 'custom' to enter your own)",
+            ENVIRONMENTS,
+            default,
+            "Enter custom environment name",
+            "environment name",
+        )
+    }
+
+    /// Prompts for one of the known pipeline types or a custom one; falls back to `default` if
+    /// there is no terminal
+    pub fn prompt_for_pipeline_type(&mut self, default: &str) -> String {
+        self.select_or_custom(
+            "Select pipeline type (or choose 'Custom' to enter your own)",
+            PIPELINE_TYPES,
+            default,
+            "Enter custom pipeline type",
+            "pipeline type",
+        )
+    }
+
+    /// Prompts until the answer passes `validate_input_string`
+    fn prompt_validated(
+        &mut self,
+        prompt: &str,
+        default: Option<&str>,
+        field_name: &str,
+    ) -> Option<String> {
+        loop {
+            let input = self.prompter.input(prompt, default)?;
+            let input = input.trim().to_string();
+            match validate_input_string(&input, field_name) {
+                Ok(()) => return Some(input),
+                Err(error_msg) => self.prompter.reject(&format!(
+                    "{}\nPlease enter a valid value without control characters, escape characters, path separators.",
+                    error_msg
+                )),
+            }
         }
     }
 
-    pub fn prompt_for_pipeline_type(default: &str) -> String {
-        let default_index = PIPELINE_TYPES
-            .iter()
-            .position(|e| e == &default)
-            .unwrap_or(CUSTOM_INDEX);
-        let selection = Select::with_theme(&*INTERACTIVE_THEME)
-            .with_prompt("Select pipeline type (or choose 'Custom' to enter your own)")
-            .items(PIPELINE_TYPES)
-            .default(default_index)
-            .interact()
-            .expect("Error while prompting for pipeline type");
-        let pipeline_type = PIPELINE_TYPES[selection];
-        if pipeline_type.to_lowercase() == "custom" {
-            let default = if default_index == CUSTOM_INDEX {
-                Some(default)
-            } else {
-                None
-            };
-            get_validated_input(
-                &INTERACTIVE_THEME,
-                "Enter custom pipeline type",
-                default,
-                "pipeline type",
-            )
-            .unwrap_or_else(|| "Custom".to_string())
-        } else {
-            pipeline_type.to_string()
+    /// Offers the `known` values, whose last one is the entry for a custom value, plus `default`
+    /// if it isn't one of them. A custom value that only differs from a known one in case or
+    /// separators is replaced by the known spelling.
+    fn select_or_custom(
+        &mut self,
+        prompt: &str,
+        known: &[&str],
+        default: &str,
+        custom_prompt: &str,
+        field_name: &str,
+    ) -> String {
+        let (custom, options) = known.split_last().expect("no options to select from");
+        let mut items: Vec<String> = options.iter().map(|option| option.to_string()).collect();
+        // a known default, custom entry included, has the same index in `items` as in `known`
+        let default_index = match known_option(default, known) {
+            Some(option) => known.iter().position(|o| *o == option).unwrap(),
+            None => {
+                items.push(default.to_string());
+                items.len() - 1
+            }
+        };
+        items.push(custom.to_string());
+
+        let Some(selection) = self.prompter.select(prompt, &items, default_index) else {
+            return default.to_string();
+        };
+        if selection != items.len() - 1 {
+            return items[selection].clone();
         }
+        match self.prompt_validated(custom_prompt, None, field_name) {
+            Some(value) => known_option(&value, options)
+                .map(str::to_string)
+                .unwrap_or(value),
+            None => custom.to_string(),
+        }
+    }
+}
+
+/// The known option `value` refers to, ignoring case and separators (e.g. `rna seq` for
+/// `RNA-seq`)
+pub fn known_option<'a>(value: &str, options: &[&'a str]) -> Option<&'a str> {
+    fn normalize(value: &str) -> String {
+        value
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
     }
+    let value = normalize(value);
+    options
+        .iter()
+        .find(|option| normalize(option) == value)
+        .copied()
 }
 
 pub fn print_help<T>() -> Option<T> {
@@ -113,3 +209,137 @@ pub fn print_help<T>() -> Option<T> {
     );
     None::<T>
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    enum Answer {
+        Text(&'static str),
+        Select(usize),
+        NoTerminal,
+    }
+
+    /// Answers prompts from a script, recording what was offered and rejected
+    #[derive(Default)]
+    struct ScriptedPrompter {
+        answers: VecDeque<Answer>,
+        offered: Vec<(Vec<String>, usize)>,
+        rejections: usize,
+    }
+
+    impl ScriptedPrompter {
+        fn new(answers: Vec<Answer>) -> Self {
+            Self {
+                answers: answers.into(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Prompter for ScriptedPrompter {
+        fn input(&mut self, _prompt: &str, default: Option<&str>) -> Option<String> {
+            match self.answers.pop_front().expect("unexpected prompt") {
+                Answer::Text("") => default.map(str::to_string).or(Some(String::new())),
+                Answer::Text(text) => Some(text.to_string()),
+                Answer::NoTerminal => None,
+                Answer::Select(_) => panic!("expected a text prompt"),
+            }
+        }
+
+        fn select(&mut self, _prompt: &str, items: &[String], default: usize) -> Option<usize> {
+            self.offered.push((items.to_vec(), default));
+            match self.answers.pop_front().expect("unexpected prompt") {
+                Answer::Select(index) => Some(index),
+                Answer::NoTerminal => None,
+                Answer::Text(_) => panic!("expected a selection"),
+            }
+        }
+
+        fn reject(&mut self, _message: &str) {
+            self.rejections += 1;
+        }
+    }
+
+    #[test]
+    fn test_invalid_pipeline_names_are_prompted_again() {
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![
+            Answer::Text("   "),
+            Answer::Text("rna/seq"),
+            Answer::Text(" rna_seq_v2 "),
+        ]));
+        let name = prompts.prompt_for_pipeline_name("user_pipeline");
+        assert_eq!(name.as_deref(), Some("rna_seq_v2"));
+        assert_eq!(prompts.prompter.rejections, 2);
+    }
+
+    #[test]
+    fn test_pipeline_name_prompt_accepts_default() {
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![Answer::Text("")]));
+        let name = prompts.prompt_for_pipeline_name("user_pipeline");
+        assert_eq!(name.as_deref(), Some("user_pipeline"));
+
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![Answer::NoTerminal]));
+        assert_eq!(prompts.prompt_for_pipeline_name("user_pipeline"), None);
+    }
+
+    #[test]
+    fn test_known_environment_is_selected() {
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![Answer::Select(2)]));
+        assert_eq!(prompts.prompt_for_environment_name("local"), "staging");
+        assert_eq!(
+            prompts.prompter.offered,
+            vec![(
+                vec!["local", "development", "staging", "production", "custom"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                0
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unknown_default_is_offered() {
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![Answer::Select(4)]));
+        assert_eq!(prompts.prompt_for_environment_name("AWS EC2"), "AWS EC2");
+        let (items, default) = &prompts.prompter.offered[0];
+        assert_eq!(items[*default], "AWS EC2");
+        assert_eq!(items.last().unwrap(), "custom");
+    }
+
+    #[test]
+    fn test_custom_values_are_validated_and_normalized() {
+        let custom = PIPELINE_TYPES.len() - 1;
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![
+            Answer::Select(custom),
+            Answer::Text("rna$seq"),
+            Answer::Text("rna seq"),
+        ]));
+        assert_eq!(prompts.prompt_for_pipeline_type("Preprocessing"), "RNA-seq");
+        assert_eq!(prompts.prompter.rejections, 1);
+
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![
+            Answer::Select(custom),
+            Answer::Text("Long-read assembly"),
+        ]));
+        assert_eq!(
+            prompts.prompt_for_pipeline_type("Preprocessing"),
+            "Long-read assembly"
+        );
+    }
+
+    #[test]
+    fn test_selection_without_terminal_keeps_default() {
+        let mut prompts = UserPrompts::new(ScriptedPrompter::new(vec![Answer::NoTerminal]));
+        assert_eq!(prompts.prompt_for_pipeline_type("WGS"), "WGS");
+    }
+
+    #[test]
+    fn test_known_option_ignores_case_and_separators() {
+        assert_eq!(known_option("scrna_seq", PIPELINE_TYPES), Some("scRNA-seq"));
+        assert_eq!(known_option("Production", ENVIRONMENTS), Some("production"));
+        assert_eq!(known_option("prod", ENVIRONMENTS), None);
+    }
+}
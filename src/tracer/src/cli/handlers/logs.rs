@@ -155,7 +155,8 @@ pub async fn otel_start_with_auto_install(
     info_message!("Starting OpenTelemetry collector...");
 
     let config = crate::config::Config::default();
-    let api_client = crate::daemon::client::DaemonClient::new(format!("http://{}", config.server));
+    let api_client = crate::daemon::client::DaemonClient::new(format!("http://{}", config.server))
+        .with_token(config.daemon_token.clone());
 
     let otel_config = match api_client.send_info_request().await {
         Ok(pipeline_data) => {
@@ -169,15 +170,24 @@ pub async fn otel_start_with_auto_install(
                 let run_id = run_snapshot.id.clone();
                 let run_name = run_snapshot.name.clone();
                 let pipeline_name = pipeline_data.name.clone();
-                let user_id = pipeline_data.tags.user_id.unwrap();
+                let user_id = pipeline_data
+                    .tags
+                    .user_id
+                    .unwrap_or_else(|| "standalone".to_string());
                 let organization_slug = pipeline_data.tags.organization_slug.clone();
 
                 let trace_id = run_id.clone();
                 let span_id = run_id.clone();
 
-                let organization_id = pipeline_data.tags.organization_id.unwrap();
+                let organization_id = pipeline_data
+                    .tags
+                    .organization_id
+                    .unwrap_or_else(|| "standalone".to_string());
 
-                let user_email = pipeline_data.tags.email.unwrap();
+                let user_email = pipeline_data
+                    .tags
+                    .email
+                    .unwrap_or_else(|| "unknown".to_string());
 
                 let config = OtelConfig::with_environment_variables(
                     user_id,
@@ -209,7 +219,10 @@ pub async fn otel_start_with_auto_install(
             } else {
                 warning_message!("No active run found, using standalone configuration");
 
-                let user_email = pipeline_data.tags.email.unwrap();
+                let user_email = pipeline_data
+                    .tags
+                    .email
+                    .unwrap_or_else(|| "unknown".to_string());
                 let organization_slug = pipeline_data.tags.organization_slug.clone();
 
                 let run_id = uuid::Uuid::new_v4().to_string();
@@ -0,0 +1,87 @@
+use crate::daemon::client::DaemonClient;
+use crate::extracts::metrics::snapshot::MetricsSnapshot;
+use crate::info_message;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+
+/// Prints the latest metrics gathered by the daemon, with how long ago each was collected
+pub async fn metrics(api_client: &DaemonClient, json: bool) -> Result<()> {
+    let snapshot = api_client.send_metrics_request().await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        return Ok(());
+    }
+    match snapshot {
+        Some(snapshot) => print_snapshot(&snapshot),
+        None => info_message!("No run is active; start one with 'tracer start'"),
+    }
+    Ok(())
+}
+
+fn print_snapshot(snapshot: &MetricsSnapshot) {
+    let now = snapshot.taken_at.unwrap_or_else(Utc::now);
+
+    match &snapshot.system {
+        Some(system) => {
+            let metrics = &system.metrics;
+            println!(
+                "{} ({})",
+                "System".bold(),
+                collected_ago(system.collected_at, now)
+            );
+            println!("  CPU       {:.1}%", metrics.system_cpu_utilization);
+            println!(
+                "  Memory    {:.1}% ({} / {} MiB)",
+                metrics.system_memory_utilization,
+                metrics.system_memory_used / (1024 * 1024),
+                metrics.system_memory_total / (1024 * 1024)
+            );
+            println!(
+                "  Disk      {} / {} GiB",
+                metrics.system_disk_used_space / (1024 * 1024 * 1024),
+                metrics.system_disk_total_space / (1024 * 1024 * 1024)
+            );
+        }
+        None => println!("{} not collected yet", "System".bold()),
+    }
+
+    if let Some(gpu) = &snapshot.gpu {
+        let metrics = &gpu.metrics;
+        println!(
+            "{} ({})",
+            "GPU".bold(),
+            collected_ago(gpu.collected_at, now)
+        );
+        if let Some(utilization) = metrics.avg_utilization {
+            println!("  Usage     {:.1}%", utilization);
+        }
+        if let (Some(used), Some(total)) = (metrics.total_memory_used, metrics.total_memory_total) {
+            println!(
+                "  Memory    {} / {} MiB",
+                used / (1024 * 1024),
+                total / (1024 * 1024)
+            );
+        }
+    }
+
+    println!("{} ({})", "Processes".bold(), snapshot.processes.len());
+    for (pid, process) in &snapshot.processes {
+        let metrics = &process.metrics;
+        println!(
+            "  {:<8} {:<24} CPU {:>6.1}%  Memory {:>8} MiB  ({})",
+            pid,
+            metrics.tool_name,
+            metrics.process_cpu_utilization,
+            metrics.process_memory_usage / (1024 * 1024),
+            collected_ago(process.collected_at, now)
+        );
+    }
+}
+
+fn collected_ago(collected_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    format!(
+        "collected {}s ago",
+        (now - collected_at).num_seconds().max(0)
+    )
+}
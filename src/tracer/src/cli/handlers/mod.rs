@@ -1,29 +1,44 @@
 pub mod auth;
 mod cleanup_port;
+mod cost;
+mod debug_events;
 mod demo;
 mod info;
 mod init;
 mod logs;
+mod metrics;
 mod otel;
+mod pricing;
+mod rules;
+mod self_check;
 mod start;
 mod stop;
 mod terminate;
 mod theme;
 mod uninstall;
 mod update;
+mod watch;
 
 pub(super) use auth::cli_auth::auth;
 pub(super) use cleanup_port::cleanup_port;
+pub(super) use cost::cost;
+pub(super) use debug_events::debug_events;
 pub use demo::arguments as demo_arguments;
 pub(super) use demo::demo;
 pub(super) use info::info;
 pub use init::arguments as init_arguments;
 pub(super) use init::init;
 pub(super) use logs::{logs, otel_start_with_auto_install};
+pub(super) use metrics::metrics;
 pub(super) use otel::handle_otel_command;
+pub(super) use pricing::handle_pricing_command;
+pub(super) use rules::handle_rules_command;
+pub(super) use self_check::self_check;
 pub(super) use start::start;
 pub(super) use stop::stop;
 pub(super) use terminate::terminate;
 pub use theme::INTERACTIVE_THEME;
 pub(super) use uninstall::uninstall;
 pub(super) use update::update;
+pub use update::{Release, UpdateChannel};
+pub(super) use watch::watch;
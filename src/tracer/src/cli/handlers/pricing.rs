@@ -0,0 +1,49 @@
+use crate::cli::commands::PricingCommand;
+use crate::cloud_providers::aws::config::{get_aws_default_profile, AwsConfig};
+use crate::cloud_providers::aws::pricing::PricingSource;
+use crate::cloud_providers::aws::region::resolve_aws_region;
+use crate::config::Config;
+use crate::{info_message, success_message, warning_message};
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+pub async fn handle_pricing_command(command: PricingCommand, config: &Config) -> Result<()> {
+    match command {
+        PricingCommand::Warmup {
+            region,
+            instance_types,
+        } => {
+            let instance_types = if instance_types.is_empty() {
+                config.pricing_warmup_instance_types.clone()
+            } else {
+                instance_types
+            };
+
+            let region = resolve_aws_region(region.as_deref().or(config.aws_region.as_deref()))
+                .await
+                .region;
+
+            info_message!(
+                "Fetching pricing for {} instance types in {}...",
+                instance_types.len(),
+                region
+            );
+            let source =
+                PricingSource::new(AwsConfig::Profile(get_aws_default_profile()), &region).await;
+            let Some(report) = source.warm_up(&region, &instance_types).await else {
+                bail!("AWS Pricing API is not available, check your AWS credentials");
+            };
+
+            if !report.already_cached.is_empty() {
+                info_message!("Already cached: {}", report.already_cached.join(", "));
+            }
+            if !report.fetched.is_empty() {
+                success_message!("Cached: {}", report.fetched.join(", "));
+            }
+            if !report.failed.is_empty() {
+                warning_message!("No pricing found: {}", report.failed.join(", "));
+            }
+            Ok(())
+        }
+    }
+}
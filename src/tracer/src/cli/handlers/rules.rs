@@ -0,0 +1,27 @@
+use crate::cli::commands::RulesCommand;
+use crate::process_identification::target_process::parser::yaml_rules_parser::load_rules_files;
+use crate::{error_message, info_message, success_message};
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Returns whether the command succeeded
+pub fn handle_rules_command(command: RulesCommand) -> bool {
+    match command {
+        RulesCommand::Validate { files } => {
+            let paths: Vec<_> = files.iter().map(PathBuf::from).collect();
+            match load_rules_files(&paths) {
+                Ok(rules) => {
+                    for name in &rules.overridden {
+                        info_message!("rule '{}' is overridden by a later file", name);
+                    }
+                    success_message!("{}: {} valid rules", files.join(", "), rules.targets.len());
+                    true
+                }
+                Err(e) => {
+                    error_message!("{}", e);
+                    false
+                }
+            }
+        }
+    }
+}
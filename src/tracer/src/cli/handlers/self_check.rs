@@ -0,0 +1,51 @@
+use crate::daemon::client::DaemonClient;
+use crate::daemon::server::DaemonServer;
+use crate::{error_message, success_message};
+use colored::Colorize;
+
+/// Sends a test event through the daemon's recorder and exporter, printing the outcome of each
+/// stage. Returns whether the check passed.
+pub async fn self_check(api_client: &DaemonClient, timeout_secs: u64) -> bool {
+    if !DaemonServer::is_running() {
+        print_stage(false, "daemon", "not running; start it with 'tracer init'");
+        return false;
+    }
+
+    let report = match api_client.send_self_check_request(timeout_secs).await {
+        Ok(report) => report,
+        Err(e) => {
+            print_stage(false, "daemon", &e.to_string());
+            return false;
+        }
+    };
+    print_stage(true, "daemon", "reachable");
+
+    for stage in &report.stages {
+        let detail = format!("{} ({} ms)", stage.detail, stage.elapsed_ms);
+        print_stage(stage.passed, &stage.name, &detail);
+    }
+
+    match report.failed_stage() {
+        None if report.passed() => {
+            success_message!("Events reach Tracer end to end.");
+            true
+        }
+        Some(stage) => {
+            error_message!(
+                "Self-check failed at the {} stage: {}",
+                stage.name,
+                stage.detail
+            );
+            false
+        }
+        None => {
+            error_message!("Self-check did not run any stage.");
+            false
+        }
+    }
+}
+
+fn print_stage(passed: bool, name: &str, detail: &str) {
+    let mark = if passed { "✓".green() } else { "✗".red() };
+    println!("  {} {:<10} {}", mark, name, detail);
+}
@@ -1,6 +1,11 @@
 mod process_manager;
+mod release;
+mod replace;
 mod updater;
+mod verify;
+mod version_check;
 
+pub use release::{Release, UpdateChannel};
 pub use updater::update;
 
 #[cfg(test)]
@@ -72,10 +72,12 @@ impl ProcessManager {
     }
 
     fn try_http_termination(&self) -> Result<()> {
+        use crate::config::Config;
         use crate::daemon::client::DaemonClient;
-        use crate::process_identification::constants::DEFAULT_DAEMON_PORT;
 
-        let api_client = DaemonClient::new(format!("http://127.0.0.1:{}", DEFAULT_DAEMON_PORT));
+        let config = Config::default();
+        let api_client =
+            DaemonClient::new(format!("http://{}", config.server)).with_token(config.daemon_token);
 
         // Use async runtime to call the terminate API
         let rt = tokio::runtime::Runtime::new()?;
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::fmt;
+
+/// Releases of the tracer client on GitHub
+const GITHUB_RELEASES_API_URL: &str =
+    "https://api.github.com/repos/Tracer-Cloud/tracer-client/releases";
+/// Assets of tagged releases, by tag
+const GITHUB_RELEASE_DOWNLOAD_URL: &str =
+    "https://github.com/Tracer-Cloud/tracer-client/releases/download";
+/// Bucket the builds of the release channels are published to
+const RELEASE_BUCKET_URL: &str = "https://tracer-releases.s3.us-east-1.amazonaws.com";
+/// Number of release tags listed when a requested version doesn't exist
+const AVAILABLE_VERSIONS_SHOWN: usize = 20;
+
+/// Channel whose latest build `tracer update` installs
+#[derive(Debug, Default, Clone, PartialEq, ValueEnum)]
+pub enum UpdateChannel {
+    /// builds of the latest release
+    #[default]
+    Production,
+    /// builds of the development branch, which are not versioned
+    Development,
+}
+
+/// The build `tracer update` installs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Release {
+    /// The latest build of a channel
+    Latest(UpdateChannel),
+    /// A release, by its exact tag
+    Tag(String),
+}
+
+impl Release {
+    pub fn new(version: Option<String>, channel: UpdateChannel) -> Self {
+        match version {
+            Some(tag) => Self::Tag(tag),
+            None => Self::Latest(channel),
+        }
+    }
+
+    /// Checks that a pinned version is a published release, resolving it to the release tag
+    /// (e.g. `1.2.3` to `v1.2.3`)
+    pub fn validate(self) -> Result<Self> {
+        match self {
+            Self::Tag(version) => {
+                let tags = fetch_release_tags()?;
+                find_release_tag(&version, &tags).map(Self::Tag)
+            }
+            latest => Ok(latest),
+        }
+    }
+
+    /// URL of the archive of this build for the given target triple
+    pub fn download_url(&self, target: &str) -> String {
+        match self {
+            Self::Latest(UpdateChannel::Production) => {
+                format!("{}/main/tracer-{}.tar.gz", RELEASE_BUCKET_URL, target)
+            }
+            Self::Latest(UpdateChannel::Development) => {
+                format!("{}/tracer-dev-{}.tar.gz", RELEASE_BUCKET_URL, target)
+            }
+            Self::Tag(tag) => format!(
+                "{}/{}/tracer-{}.tar.gz",
+                GITHUB_RELEASE_DOWNLOAD_URL, tag, target
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Release {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latest(UpdateChannel::Production) => write!(f, "the latest release"),
+            Self::Latest(UpdateChannel::Development) => write!(f, "the latest development build"),
+            Self::Tag(tag) => write!(f, "release {}", tag),
+        }
+    }
+}
+
+/// The release tag matching `version`, with or without its leading `v`
+fn find_release_tag(version: &str, tags: &[String]) -> Result<String> {
+    let wanted = version.trim().trim_start_matches('v');
+    if let Some(tag) = tags
+        .iter()
+        .find(|tag| tag.trim_start_matches('v') == wanted)
+    {
+        return Ok(tag.clone());
+    }
+
+    let mut available = tags
+        .iter()
+        .take(AVAILABLE_VERSIONS_SHOWN)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if tags.len() > AVAILABLE_VERSIONS_SHOWN {
+        available.push_str(", ...");
+    }
+    anyhow::bail!(
+        "Unknown version {}. Available versions: {}",
+        version,
+        available
+    )
+}
+
+/// Fetches the tag of the latest release
+pub fn fetch_latest_version() -> Result<String> {
+    let release = fetch_github_json(&format!("{}/latest", GITHUB_RELEASES_API_URL))?;
+    release["tag_name"]
+        .as_str()
+        .map(str::to_string)
+        .context("The latest release has no tag")
+}
+
+/// Fetches the tags of the published releases, newest first
+fn fetch_release_tags() -> Result<Vec<String>> {
+    let releases = fetch_github_json(&format!("{}?per_page=100", GITHUB_RELEASES_API_URL))?;
+    let releases = releases
+        .as_array()
+        .context("Invalid release list response")?;
+    Ok(releases
+        .iter()
+        .filter(|release| !release["draft"].as_bool().unwrap_or(false))
+        .filter_map(|release| release["tag_name"].as_str().map(str::to_string))
+        .collect())
+}
+
+fn fetch_github_json(url: &str) -> Result<serde_json::Value> {
+    let output = std::process::Command::new("curl")
+        .args(["-fsSL", "-H", "Accept: application/vnd.github+json", url])
+        .output()
+        .context("Failed to execute curl command")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("Failed to fetch the releases: {}", stderr));
+    }
+
+    serde_json::from_slice(&output.stdout).context("Invalid release response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags() -> Vec<String> {
+        vec![
+            "v1.3.0".to_string(),
+            "v1.2.1".to_string(),
+            "1.2.0".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_pinned_version_matches_tag_with_or_without_prefix() {
+        assert_eq!(find_release_tag("1.2.1", &tags()).unwrap(), "v1.2.1");
+        assert_eq!(find_release_tag("v1.3.0", &tags()).unwrap(), "v1.3.0");
+        assert_eq!(find_release_tag("v1.2.0", &tags()).unwrap(), "1.2.0");
+    }
+
+    #[test]
+    fn test_unknown_version_lists_available_versions() {
+        let error = find_release_tag("1.4.0", &tags()).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Unknown version 1.4.0. Available versions: v1.3.0, v1.2.1, 1.2.0"
+        );
+    }
+
+    #[test]
+    fn test_download_url_by_release() {
+        let target = "x86_64-unknown-linux-gnu";
+        assert_eq!(
+            Release::new(None, UpdateChannel::Production).download_url(target),
+            "https://tracer-releases.s3.us-east-1.amazonaws.com/main/tracer-x86_64-unknown-linux-gnu.tar.gz"
+        );
+        assert_eq!(
+            Release::new(Some("v1.2.1".to_string()), UpdateChannel::Production)
+                .download_url(target),
+            "https://github.com/Tracer-Cloud/tracer-client/releases/download/v1.2.1/tracer-x86_64-unknown-linux-gnu.tar.gz"
+        );
+    }
+}
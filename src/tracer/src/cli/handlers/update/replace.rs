@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use nix::errno::Errno;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Installs `new_binary` at `target` without ever writing to `target` itself.
+///
+/// The binary is first copied to a sidecar file next to `target`, so it is on the same file
+/// system, and synced to disk. Only then is it renamed over `target`, which atomically swaps
+/// the directory entry: an interrupted update leaves either the old or the new binary, never a
+/// truncated one. On failure the sidecar is removed and `target` is left as it was.
+pub fn replace_binary(new_binary: &Path, target: &Path) -> Result<()> {
+    let metadata = fs::metadata(new_binary)
+        .with_context(|| format!("Downloaded binary {} not found", new_binary.display()))?;
+    if metadata.len() == 0 {
+        anyhow::bail!("Downloaded binary {} is empty", new_binary.display());
+    }
+
+    let sidecar = sibling(target, "new");
+    let result = stage(new_binary, &sidecar).and_then(|()| swap_in(&sidecar, target));
+    if result.is_err() {
+        let _ = fs::remove_file(&sidecar);
+    }
+    result
+}
+
+/// Path of a hidden file next to `target`, e.g. `.tracer.new-<pid>`
+fn sibling(target: &Path, kind: &str) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "tracer".to_string());
+    target.with_file_name(format!(".{}.{}-{}", name, kind, std::process::id()))
+}
+
+/// Copies the binary to the sidecar and makes it durable and executable
+fn stage(new_binary: &Path, sidecar: &Path) -> Result<()> {
+    fs::copy(new_binary, sidecar)
+        .with_context(|| format!("Failed to write {}", sidecar.display()))?;
+    File::open(sidecar)
+        .and_then(|file| file.sync_all())
+        .with_context(|| format!("Failed to sync {}", sidecar.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(sidecar, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {} executable", sidecar.display()))?;
+    }
+    Ok(())
+}
+
+/// Renames the sidecar over `target`. Where the running binary can't be replaced in place
+/// ("text file busy"), `target` is moved aside first and restored if the swap fails.
+fn swap_in(sidecar: &Path, target: &Path) -> Result<()> {
+    let error = match fs::rename(sidecar, target) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.raw_os_error() == Some(Errno::ETXTBSY as i32) => e,
+        Err(e) => return Err(e).with_context(|| format!("Failed to replace {}", target.display())),
+    };
+
+    let previous = sibling(target, "old");
+    fs::rename(target, &previous).with_context(|| {
+        format!(
+            "Failed to replace {} ({}), and to move it aside",
+            target.display(),
+            error
+        )
+    })?;
+    if let Err(e) = fs::rename(sidecar, target) {
+        let _ = fs::rename(&previous, target);
+        return Err(e).with_context(|| format!("Failed to replace {}", target.display()));
+    }
+    // the running process keeps its (now unlinked) executable open
+    let _ = fs::remove_file(&previous);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut entries: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_replaces_binary_without_leftovers() {
+        let install_dir = TempDir::new().unwrap();
+        let download_dir = TempDir::new().unwrap();
+        let target = install_dir.path().join("tracer");
+        let new_binary = download_dir.path().join("tracer");
+        fs::write(&target, "old").unwrap();
+        fs::write(&new_binary, "new").unwrap();
+
+        replace_binary(&new_binary, &target).unwrap();
+
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        assert_eq!(entries(install_dir.path()), vec!["tracer"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&target).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+
+    #[test]
+    fn test_interrupted_download_leaves_original_intact() {
+        let install_dir = TempDir::new().unwrap();
+        let download_dir = TempDir::new().unwrap();
+        let target = install_dir.path().join("tracer");
+        fs::write(&target, "old").unwrap();
+
+        // the download never completed, so there is no binary
+        let missing = download_dir.path().join("tracer");
+        assert!(replace_binary(&missing, &target).is_err());
+
+        // or it was cut off before any content arrived
+        let empty = download_dir.path().join("empty");
+        fs::write(&empty, "").unwrap();
+        assert!(replace_binary(&empty, &target).is_err());
+
+        assert_eq!(fs::read_to_string(&target).unwrap(), "old");
+        assert_eq!(entries(install_dir.path()), vec!["tracer"]);
+    }
+
+    #[test]
+    fn test_failed_swap_removes_sidecar() {
+        let install_dir = TempDir::new().unwrap();
+        let download_dir = TempDir::new().unwrap();
+        let new_binary = download_dir.path().join("tracer");
+        fs::write(&new_binary, "new").unwrap();
+
+        // a directory can't be replaced by a file
+        let target = install_dir.path().join("tracer");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("keep"), "").unwrap();
+
+        assert!(replace_binary(&new_binary, &target).is_err());
+        assert!(target.join("keep").exists());
+        assert_eq!(entries(install_dir.path()), vec!["tracer"]);
+    }
+}
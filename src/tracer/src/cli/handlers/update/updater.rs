@@ -1,11 +1,30 @@
-use crate::utils::Sentry;
-use crate::{error_message, info_message, success_message};
+use super::release::{fetch_latest_version, Release, UpdateChannel};
+use super::replace::replace_binary;
+use super::verify::{ReleaseVerifier, CHECKSUM_SUFFIX, SIGNATURE_SUFFIX};
+use super::version_check::VersionCheck;
+use crate::config::Config;
+use crate::utils::{Sentry, Version};
+use crate::{error_message, info_message, success_message, warning_message};
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::path::Path;
 
 /// Main entry point for the tracer update command
-pub fn update() {
-    match update_impl() {
+pub fn update(release: Release, force: bool, allow_unverified: bool, config: &Config) {
+    let prepared = ReleaseVerifier::new(config.update_public_key.as_deref())
+        .map(|verifier| verifier.allow_unverified(allow_unverified))
+        .and_then(|verifier| release.validate().map(|release| (release, verifier)));
+    let (release, verifier) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            error_message!("Failed to update Tracer: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    if !should_update(&release, force) {
+        return;
+    }
+    match update_impl(&release, &verifier) {
         Ok(()) => success_message!("Tracer has been successfully updated!"),
         Err(e) => {
             Sentry::capture_message(&format!("Update failed: {}", e), sentry::Level::Error);
@@ -15,8 +34,58 @@ pub fn update() {
     }
 }
 
+/// Whether the release should be installed: only if it is newer than this version, unless
+/// `force` is set. An unknown latest version doesn't block the update.
+fn should_update(release: &Release, force: bool) -> bool {
+    let version = match release {
+        Release::Tag(tag) => Ok(tag.clone()),
+        Release::Latest(UpdateChannel::Production) => fetch_latest_version(),
+        Release::Latest(UpdateChannel::Development) => {
+            info_message!("Development builds are not versioned, updating to the latest one");
+            return true;
+        }
+    };
+    let check = version.and_then(|version| VersionCheck::compare(Version::current_str(), &version));
+    match check {
+        Ok(VersionCheck::Upgrade { installed, latest }) => {
+            info_message!("Updating Tracer from {} to {}", installed, latest);
+            true
+        }
+        Ok(VersionCheck::UpToDate { installed }) if force => {
+            warning_message!("Tracer {} is already up to date, reinstalling", installed);
+            true
+        }
+        Ok(VersionCheck::UpToDate { installed }) => {
+            success_message!(
+                "Tracer {} is already up to date. Use 'tracer update --force' to reinstall it.",
+                installed
+            );
+            false
+        }
+        Ok(VersionCheck::Downgrade { installed, latest }) if force => {
+            warning_message!("Downgrading Tracer from {} to {}", installed, latest);
+            true
+        }
+        Ok(VersionCheck::Downgrade { installed, latest }) => {
+            warning_message!(
+                "Version {} is older than the installed version ({}); updating would be a downgrade. Use 'tracer update --force' to install it anyway.",
+                latest,
+                installed
+            );
+            false
+        }
+        Err(e) => {
+            warning_message!(
+                "Could not determine the latest version ({:#}), updating anyway",
+                e
+            );
+            true
+        }
+    }
+}
+
 /// Core update implementation - delegate everything to tracer-installer
-fn update_impl() -> Result<()> {
+fn update_impl(release: &Release, verifier: &ReleaseVerifier) -> Result<()> {
     info_message!("Starting Tracer update process...");
     info_message!("Delegating to tracer-installer for complete update management...");
     info_message!("The installer will handle process termination, cleanup, and installation");
@@ -26,7 +95,7 @@ fn update_impl() -> Result<()> {
     // - Daemon cleanup
     // - Binary download and replacement
     // - All error handling and recovery
-    run_installer_script()?;
+    run_installer_script(release, verifier)?;
 
     // Note: This line may never be reached if installer terminates this process
     info_message!("Update completed successfully - tracer is now up to date!");
@@ -36,15 +105,15 @@ fn update_impl() -> Result<()> {
 // Cleanup function removed - tracer-installer handles all cleanup
 
 /// Run the tracer installer script directly
-fn run_installer_script() -> Result<()> {
+fn run_installer_script(release: &Release, verifier: &ReleaseVerifier) -> Result<()> {
     info_message!("Downloading and replacing tracer binary...");
 
     // Download the binary directly and replace it manually (no sudo needed)
-    download_and_replace_binary()
+    download_and_replace_binary(release, verifier)
 }
 
 /// Download and replace the tracer binary without requiring sudo
-fn download_and_replace_binary() -> Result<()> {
+fn download_and_replace_binary(release: &Release, verifier: &ReleaseVerifier) -> Result<()> {
     // Get the current tracer binary path
     let current_binary = get_current_tracer_path()?;
     info_message!("Current tracer binary: {}", current_binary);
@@ -56,30 +125,25 @@ fn download_and_replace_binary() -> Result<()> {
 
     let temp_binary = temp_dir.join("tracer");
 
-    // Download the latest binary
-    info_message!("Downloading latest tracer binary...");
-    download_latest_binary(&temp_binary)?;
-
-    // Make it executable
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&temp_binary)?.permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&temp_binary, perms)?;
+    // Download the binary of the release
+    info_message!("Downloading tracer binary of {}...", release);
+    if let Err(e) = download_binary(release, verifier, &temp_binary) {
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        return Err(e);
     }
 
-    // Skip backup creation to avoid permission issues
-    info_message!("Skipping backup creation to avoid permission issues");
-
-    // Replace the binary atomically
+    // Stage the binary next to the current one and atomically swap it in
     info_message!("Replacing tracer binary...");
-    std::fs::rename(&temp_binary, &current_binary)
-        .with_context(|| format!("Failed to replace binary at {}", current_binary))?;
+    let replaced = replace_binary(&temp_binary, Path::new(&current_binary));
 
     // Clean up
-    std::fs::remove_dir_all(&temp_dir)
-        .with_context(|| format!("Failed to clean up temp directory: {}", temp_dir.display()))?;
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    replaced.with_context(|| {
+        format!(
+            "Failed to replace binary at {}; the installed binary was not changed",
+            current_binary
+        )
+    })?;
 
     info_message!("Binary replacement completed successfully");
     Ok(())
@@ -125,8 +189,12 @@ fn get_current_tracer_path() -> Result<String> {
     }
 }
 
-/// Download the latest tracer binary from S3
-fn download_latest_binary(target_path: &std::path::Path) -> Result<()> {
+/// Download the tracer binary of the release
+fn download_binary(
+    release: &Release,
+    verifier: &ReleaseVerifier,
+    target_path: &std::path::Path,
+) -> Result<()> {
     let arch = if cfg!(target_arch = "x86_64") {
         "x86_64"
     } else if cfg!(target_arch = "aarch64") {
@@ -143,11 +211,8 @@ fn download_latest_binary(target_path: &std::path::Path) -> Result<()> {
         return Err(anyhow::anyhow!("Unsupported operating system"));
     };
 
-    // Download the tar.gz file from S3
-    let download_url = format!(
-        "https://tracer-releases.s3.us-east-1.amazonaws.com/main/tracer-{}-{}.tar.gz",
-        arch, os
-    );
+    // Download the tar.gz file
+    let download_url = release.download_url(&format!("{}-{}", arch, os));
 
     info_message!("Downloading from: {}", download_url);
 
@@ -155,15 +220,7 @@ fn download_latest_binary(target_path: &std::path::Path) -> Result<()> {
     let temp_dir = target_path.parent().unwrap();
     let tar_path = temp_dir.join("tracer.tar.gz");
 
-    let output = std::process::Command::new("curl")
-        .args(["-fsSL", "-o", tar_path.to_str().unwrap(), &download_url])
-        .output()
-        .context("Failed to execute curl command")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!("Failed to download binary: {}", stderr));
-    }
+    download_file(&download_url, &tar_path).context("Failed to download binary")?;
 
     // Verify the download
     if !tar_path.exists() {
@@ -179,6 +236,10 @@ fn download_latest_binary(target_path: &std::path::Path) -> Result<()> {
 
     info_message!("Download completed successfully ({} bytes)", metadata.len());
 
+    // Verify the archive before anything is extracted from it
+    info_message!("Verifying the downloaded archive...");
+    verify_download(verifier, &download_url, &tar_path)?;
+
     // Extract the binary from the tar.gz
     info_message!("Extracting binary from archive...");
     extract_binary_from_tar(&tar_path, target_path)?;
@@ -194,6 +255,56 @@ fn download_latest_binary(target_path: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
+/// Checks the downloaded archive against the checksum, and signature if required, published
+/// alongside it. A release without a published checksum is rejected, unless installed with
+/// `--allow-unverified`, see `ReleaseVerifier::accept_missing_checksum`.
+fn verify_download(
+    verifier: &ReleaseVerifier,
+    download_url: &str,
+    tar_path: &std::path::Path,
+) -> Result<()> {
+    let fetch = |suffix: &str| -> Result<String> {
+        let path = tar_path.with_extension(format!("gz{}", suffix));
+        download_file(&format!("{}{}", download_url, suffix), &path)?;
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    let checksum = match fetch(CHECKSUM_SUFFIX) {
+        Ok(checksum) => checksum,
+        Err(e) => {
+            verifier.accept_missing_checksum(e)?;
+            warning_message!("No checksum is published for this release, installing it unverified");
+            return Ok(());
+        }
+    };
+    let signature = if verifier.requires_signature() {
+        let signature =
+            fetch(SIGNATURE_SUFFIX).context("Failed to fetch the signature of the release")?;
+        Some(signature)
+    } else {
+        None
+    };
+
+    let archive = std::fs::read(tar_path)
+        .with_context(|| format!("Failed to read {}", tar_path.display()))?;
+    verifier
+        .verify(&archive, &checksum, signature.as_deref())
+        .context("The downloaded release failed verification; the installed binary was not changed")
+}
+
+/// Download `url` to `path` with curl
+fn download_file(url: &str, path: &std::path::Path) -> Result<()> {
+    let output = std::process::Command::new("curl")
+        .args(["-fsSL", "-o", path.to_str().unwrap(), url])
+        .output()
+        .context("Failed to execute curl command")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("Failed to download {}: {}", url, stderr));
+    }
+    Ok(())
+}
+
 /// Extract the tracer binary from the downloaded tar.gz file
 fn extract_binary_from_tar(
     tar_path: &std::path::Path,
@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::digest::{digest, SHA256};
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+/// Suffix of the SHA-256 checksum published alongside every release archive
+pub const CHECKSUM_SUFFIX: &str = ".sha256";
+/// Suffix of the detached Ed25519 signature of a release archive
+pub const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// Checks a downloaded release archive before it replaces the installed binary
+pub struct ReleaseVerifier {
+    /// Ed25519 key the archives are signed with; signatures are only checked if it is set
+    public_key: Option<Vec<u8>>,
+    /// Archives without a published checksum are installed, see `accept_missing_checksum`
+    allow_unverified: bool,
+}
+
+impl ReleaseVerifier {
+    /// `public_key` is a base64-encoded Ed25519 public key, see `Config::update_public_key`
+    pub fn new(public_key: Option<&str>) -> Result<Self> {
+        let public_key = public_key
+            .map(|key| {
+                STANDARD
+                    .decode(key.trim())
+                    .context("Invalid update public key: not base64")
+            })
+            .transpose()?;
+        Ok(Self {
+            public_key,
+            allow_unverified: false,
+        })
+    }
+
+    /// Installs archives whose checksum is not published, as `tracer update --allow-unverified`
+    pub fn allow_unverified(mut self, allow_unverified: bool) -> Self {
+        self.allow_unverified = allow_unverified;
+        self
+    }
+
+    pub fn requires_signature(&self) -> bool {
+        self.public_key.is_some()
+    }
+
+    /// Decides on an archive whose checksum could not be fetched (`error`): it is rejected,
+    /// unless unverified installs are allowed and no signature is required
+    pub fn accept_missing_checksum(&self, error: anyhow::Error) -> Result<()> {
+        if self.requires_signature() {
+            Err(error.context("Failed to fetch the checksum of the release"))
+        } else if !self.allow_unverified {
+            Err(error.context(
+                "No checksum is published for this release; use --allow-unverified to install it anyway",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Verifies the archive against the contents of its checksum file (`sha256sum` output or
+    /// just the digest) and, if a public key is configured, its base64-encoded signature
+    pub fn verify(&self, archive: &[u8], checksum: &str, signature: Option<&str>) -> Result<()> {
+        let expected = parse_checksum(checksum)?;
+        let actual = hex::encode(digest(&SHA256, archive));
+        if actual != expected {
+            anyhow::bail!(
+                "Checksum mismatch: expected {}, but the download has {}",
+                expected,
+                actual
+            );
+        }
+
+        if let Some(public_key) = &self.public_key {
+            let signature = signature.context("The release is not signed")?;
+            let signature = STANDARD
+                .decode(signature.trim())
+                .context("Invalid release signature: not base64")?;
+            UnparsedPublicKey::new(&ED25519, public_key)
+                .verify(archive, &signature)
+                .map_err(|_| anyhow::anyhow!("Invalid release signature"))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_checksum(contents: &str) -> Result<String> {
+    let checksum = contents
+        .split_whitespace()
+        .next()
+        .context("The checksum file is empty")?
+        .to_lowercase();
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid SHA-256 checksum {:?}", checksum);
+    }
+    Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    const ARCHIVE: &[u8] = b"tracer release archive";
+
+    fn checksum_of(data: &[u8]) -> String {
+        format!("{}  tracer.tar.gz\n", hex::encode(digest(&SHA256, data)))
+    }
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_matching_checksum_is_accepted() {
+        let verifier = ReleaseVerifier::new(None).unwrap();
+        assert!(verifier
+            .verify(ARCHIVE, &checksum_of(ARCHIVE), None)
+            .is_ok());
+        // a bare, uppercase digest works too
+        let bare = hex::encode_upper(digest(&SHA256, ARCHIVE));
+        assert!(verifier.verify(ARCHIVE, &bare, None).is_ok());
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_rejected() {
+        let verifier = ReleaseVerifier::new(None).unwrap();
+        let error = verifier
+            .verify(b"truncated", &checksum_of(ARCHIVE), None)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Checksum mismatch"));
+    }
+
+    #[test]
+    fn test_malformed_checksum_is_rejected() {
+        let verifier = ReleaseVerifier::new(None).unwrap();
+        assert!(verifier.verify(ARCHIVE, "", None).is_err());
+        assert!(verifier
+            .verify(ARCHIVE, "<html>Not found</html>", None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_signature_is_checked_with_public_key() {
+        let signer = key_pair();
+        let public_key = STANDARD.encode(signer.public_key().as_ref());
+        let verifier = ReleaseVerifier::new(Some(&public_key)).unwrap();
+        let checksum = checksum_of(ARCHIVE);
+
+        let signature = STANDARD.encode(signer.sign(ARCHIVE).as_ref());
+        assert!(verifier
+            .verify(ARCHIVE, &checksum, Some(&signature))
+            .is_ok());
+
+        let error = verifier.verify(ARCHIVE, &checksum, None).unwrap_err();
+        assert_eq!(error.to_string(), "The release is not signed");
+
+        // signed with another key
+        let forged = STANDARD.encode(key_pair().sign(ARCHIVE).as_ref());
+        let error = verifier
+            .verify(ARCHIVE, &checksum, Some(&forged))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Invalid release signature");
+    }
+
+    #[test]
+    fn test_missing_checksum_is_rejected_unless_allowed() {
+        let missing = || anyhow::anyhow!("404 Not Found");
+
+        let verifier = ReleaseVerifier::new(None).unwrap();
+        let error = verifier.accept_missing_checksum(missing()).unwrap_err();
+        assert!(error.to_string().contains("--allow-unverified"));
+
+        let verifier = verifier.allow_unverified(true);
+        assert!(verifier.accept_missing_checksum(missing()).is_ok());
+
+        // a required signature can't be waived
+        let public_key = STANDARD.encode(key_pair().public_key().as_ref());
+        let verifier = ReleaseVerifier::new(Some(&public_key))
+            .unwrap()
+            .allow_unverified(true);
+        assert!(verifier.accept_missing_checksum(missing()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_public_key_is_rejected() {
+        assert!(ReleaseVerifier::new(Some("not base64!")).is_err());
+    }
+}
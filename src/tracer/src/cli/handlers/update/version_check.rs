@@ -0,0 +1,107 @@
+use anyhow::{Context, Result};
+use semver::Version;
+use std::cmp::Ordering;
+
+/// How the release to install compares to the installed version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionCheck {
+    /// The release is newer than the installed version
+    Upgrade { installed: Version, latest: Version },
+    /// The release is the installed version
+    UpToDate { installed: Version },
+    /// The release is older than the installed version, e.g. after switching channels
+    Downgrade { installed: Version, latest: Version },
+}
+
+impl VersionCheck {
+    /// Compares two version strings by semver precedence. A leading `v` (as in release tags) is
+    /// ignored, and so is build metadata: `1.2.3+45` is the same version as `1.2.3`, while
+    /// pre-releases precede their release (`1.2.3-rc.1` < `1.2.3`).
+    pub fn compare(installed: &str, latest: &str) -> Result<Self> {
+        let installed = parse_version(installed)?;
+        let latest = parse_version(latest)?;
+        Ok(match latest.cmp_precedence(&installed) {
+            Ordering::Greater => Self::Upgrade { installed, latest },
+            Ordering::Equal => Self::UpToDate { installed },
+            Ordering::Less => Self::Downgrade { installed, latest },
+        })
+    }
+
+    pub fn is_upgrade(&self) -> bool {
+        matches!(self, Self::Upgrade { .. })
+    }
+}
+
+fn parse_version(version: &str) -> Result<Version> {
+    let trimmed = version.trim();
+    let trimmed = trimmed.strip_prefix('v').unwrap_or(trimmed);
+    Version::parse(trimmed).with_context(|| format!("Invalid version {:?}", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newer_release_is_an_upgrade() {
+        assert!(VersionCheck::compare("1.2.3", "v1.2.4")
+            .unwrap()
+            .is_upgrade());
+        assert!(VersionCheck::compare("1.2.3", "1.10.0")
+            .unwrap()
+            .is_upgrade());
+        assert!(VersionCheck::compare("0.9.9", "1.0.0")
+            .unwrap()
+            .is_upgrade());
+    }
+
+    #[test]
+    fn test_same_release_is_up_to_date() {
+        assert!(matches!(
+            VersionCheck::compare("1.2.3", "v1.2.3").unwrap(),
+            VersionCheck::UpToDate { .. }
+        ));
+        // build metadata of non-release builds doesn't make a version newer
+        assert!(matches!(
+            VersionCheck::compare("1.2.3+45", "1.2.3").unwrap(),
+            VersionCheck::UpToDate { .. }
+        ));
+    }
+
+    #[test]
+    fn test_older_release_is_a_downgrade() {
+        assert!(matches!(
+            VersionCheck::compare("1.3.0", "v1.2.9").unwrap(),
+            VersionCheck::Downgrade { .. }
+        ));
+        assert!(matches!(
+            VersionCheck::compare("2.0.0", "1.99.99").unwrap(),
+            VersionCheck::Downgrade { .. }
+        ));
+    }
+
+    #[test]
+    fn test_pre_releases_follow_semver_precedence() {
+        // a release supersedes its pre-releases
+        assert!(VersionCheck::compare("1.2.3-rc.1", "1.2.3")
+            .unwrap()
+            .is_upgrade());
+        assert!(matches!(
+            VersionCheck::compare("1.2.3", "1.2.3-rc.2").unwrap(),
+            VersionCheck::Downgrade { .. }
+        ));
+        // pre-release identifiers compare numerically where numeric
+        assert!(VersionCheck::compare("1.2.3-rc.2", "1.2.3-rc.10")
+            .unwrap()
+            .is_upgrade());
+        assert!(VersionCheck::compare("1.2.3-alpha", "1.2.3-beta")
+            .unwrap()
+            .is_upgrade());
+    }
+
+    #[test]
+    fn test_invalid_versions_are_rejected() {
+        assert!(VersionCheck::compare("1.2", "1.2.3").is_err());
+        assert!(VersionCheck::compare("1.2.3", "latest").is_err());
+    }
+}
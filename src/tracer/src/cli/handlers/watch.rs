@@ -0,0 +1,45 @@
+use crate::daemon::client::DaemonClient;
+use crate::process_identification::event_stream::{EventFilterQuery, StreamMessage};
+use crate::{info_message, warning_message};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Connects to the daemon's live event stream and pretty-prints events until it closes
+pub async fn watch(api_client: &DaemonClient, filter: EventFilterQuery) -> Result<()> {
+    let mut response = api_client.open_event_stream(&filter).await?;
+    info_message!("Watching live events, press Ctrl+C to stop...");
+
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(position) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=position).collect();
+            match serde_json::from_slice::<StreamMessage>(&line) {
+                Ok(message) => print_message(&message),
+                Err(e) => warning_message!("Failed to parse event: {}", e),
+            }
+        }
+    }
+
+    warning_message!("Event stream closed by the daemon");
+    Ok(())
+}
+
+fn print_message(message: &StreamMessage) {
+    match message {
+        StreamMessage::Event(event) => println!(
+            "{} {} {}",
+            event.timestamp.format("%H:%M:%S").to_string().dimmed(),
+            format!("[{}]", event.process_status).cyan().bold(),
+            event.body
+        ),
+        StreamMessage::Gap { dropped } => println!(
+            "{}",
+            format!(
+                "... {} events dropped, the terminal could not keep up ...",
+                dropped
+            )
+            .yellow()
+        ),
+    }
+}
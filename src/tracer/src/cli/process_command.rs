@@ -1,10 +1,13 @@
 use super::commands::{Cli, Command};
 use super::handlers;
+use super::handlers::Release;
 use crate::cli::handlers::auth::types::AuthType;
 use crate::config::Config;
 use crate::daemon::server::DaemonServer;
-use crate::success_message;
+use crate::utils::http::install_ca_bundle;
+use crate::utils::telemetry::ErrorReporter;
 use crate::utils::{Sentry, Version};
+use crate::{error_message, success_message};
 use clap::Parser;
 use colored::Colorize;
 
@@ -23,7 +26,13 @@ pub fn process_command() {
     // Use the --config flag, if provided, when loading the configuration
     let config = Config::default();
 
-    let _guard = Sentry::setup();
+    // Trust the custom CA bundle before any HTTP client is built
+    if let Err(e) = install_ca_bundle(config.ca_bundle.as_deref()) {
+        error_message!("{:#}", e);
+        std::process::exit(1);
+    }
+
+    let _guard = Sentry::setup(&config);
     Sentry::add_context("Config", config.to_safe_json());
 
     match cli.command {
@@ -35,7 +44,22 @@ pub fn process_command() {
         Command::Version => {
             println!("{}", Version::current());
         }
-        Command::Update => handlers::update(),
+        Command::Update {
+            version,
+            channel,
+            force,
+            allow_unverified,
+        } => handlers::update(
+            Release::new(version, channel),
+            force,
+            allow_unverified,
+            &config,
+        ),
+        Command::Rules { command } => {
+            if !handlers::handle_rules_command(command) {
+                std::process::exit(1);
+            }
+        }
         Command::Uninstall => handlers::uninstall(),
         Command::Login { platform } => {
             let result = tokio::runtime::Runtime::new()
@@ -59,4 +83,6 @@ pub fn process_command() {
             .unwrap()
             .block_on(super::process_daemon_command(command, config)),
     };
+
+    ErrorReporter::report_suppressed();
 }
@@ -2,13 +2,15 @@ use crate::cli::commands::Command;
 use crate::cli::handlers;
 use crate::config::Config;
 use crate::daemon::client::DaemonClient;
+use crate::process_identification::event_stream::EventFilterQuery;
 use crate::utils::Sentry;
 use crate::warning_message;
 use colored::Colorize;
 use serde_json::json;
 
 pub async fn process_daemon_command(command: Command, config: Config) {
-    let api_client = DaemonClient::new(format!("http://{}", config.server));
+    let api_client = DaemonClient::new(format!("http://{}", config.server))
+        .with_token(config.daemon_token.clone());
     match command {
         Command::Init(args) => {
             if let Err(e) = handlers::init(*args, config, &api_client).await {
@@ -52,22 +54,17 @@ pub async fn process_daemon_command(command: Command, config: Config) {
                 std::process::exit(1);
             }
         }
-        Command::Test => {
-            // Redirect users to the new demo command
-            eprintln!();
-            eprintln!("The 'test' command has been renamed to 'demo' to match usage in our sandbox application.");
-            eprintln!(
-                "Use {} instead of {}.",
-                "tracer demo".green(),
-                "tracer test".red()
-            );
-            eprintln!();
-            eprintln!("Run {} for more info.", "tracer demo --help".cyan());
-            eprintln!();
-
-            std::process::exit(1);
+        Command::Test { timeout } => {
+            if !handlers::self_check(&api_client, timeout).await {
+                std::process::exit(1);
+            }
         }
         Command::Info { json } => handlers::info(&api_client, json).await,
+        Command::Metrics { json } => {
+            if let Err(e) = handlers::metrics(&api_client, json).await {
+                warning_message!("Failed to get metrics: {}", e);
+            }
+        }
         Command::Start { json } => {
             let _ = handlers::start(&api_client, json).await;
         }
@@ -80,6 +77,39 @@ pub async fn process_daemon_command(command: Command, config: Config) {
         Command::Terminate => {
             let _ = handlers::terminate(&api_client).await;
         }
+        Command::Watch {
+            status,
+            run_id,
+            tool,
+        } => {
+            let filter = EventFilterQuery {
+                status: Some(status),
+                run_id,
+                tool,
+            };
+            if let Err(e) = handlers::watch(&api_client, filter).await {
+                warning_message!("Failed to watch events: {}", e);
+            }
+        }
+        Command::DebugEvents { json } => {
+            if let Err(e) = handlers::debug_events(&api_client, json).await {
+                warning_message!("Failed to stream raw events: {}", e);
+            }
+        }
+        Command::Cost {
+            instance_type,
+            region,
+            json,
+        } => {
+            if let Err(e) = handlers::cost(&instance_type, region.as_deref(), &config, json).await {
+                warning_message!("Failed to estimate cost: {}", e);
+            }
+        }
+        Command::Pricing { command } => {
+            if let Err(e) = handlers::handle_pricing_command(command, &config).await {
+                warning_message!("Failed to execute pricing command: {}", e);
+            }
+        }
         Command::Otel { command } => {
             if let Err(e) = handlers::handle_otel_command(command).await {
                 warning_message!("Failed to execute OTel command: {}", e);
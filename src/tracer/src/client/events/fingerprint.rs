@@ -0,0 +1,276 @@
+use crate::process_identification::types::event::attributes::system_metrics::{
+    ContainerImage, EnvironmentFingerprint,
+};
+use crate::process_identification::types::lockfiles::{
+    find_lockfiles, load_lockfile, parse_conda_archive_name, LockedEnvironment,
+};
+use anyhow::Result;
+use bollard::query_parameters::ListContainersOptions;
+use bollard::Docker;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::debug;
+
+/// Environment variables recorded in the fingerprint. Others are left out, as they may hold
+/// credentials.
+const ALLOWED_ENV_VARS: &[&str] = &[
+    "CONDA_DEFAULT_ENV",
+    "CONDA_PREFIX",
+    "PIXI_ENVIRONMENT_NAME",
+    "PIXI_PROJECT_MANIFEST",
+    "VIRTUAL_ENV",
+    "NXF_VER",
+    "NXF_EXECUTOR",
+    "SNAKEMAKE_PROFILE",
+    "JAVA_HOME",
+    "R_HOME",
+    "LANG",
+    "LC_ALL",
+    "TZ",
+    "OMP_NUM_THREADS",
+    "MKL_NUM_THREADS",
+    "OPENBLAS_NUM_THREADS",
+    "CUDA_VISIBLE_DEVICES",
+    "SINGULARITY_NAME",
+    "APPTAINER_NAME",
+];
+
+/// How long listing the images of the running containers may take
+const DOCKER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Captures the software environment the daemon was started in, reading the lockfiles of
+/// `pipeline_dir` (the working directory by default). Every part is best effort: what can't be
+/// detected is left empty.
+pub async fn gather_environment_fingerprint(pipeline_dir: Option<&Path>) -> EnvironmentFingerprint {
+    let pixi_environment = env::var("PIXI_ENVIRONMENT_NAME").ok();
+    let pixi_manifest = env::var("PIXI_PROJECT_MANIFEST")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| {
+            pipeline_dir
+                .map(Path::to_path_buf)
+                .or_else(|| env::current_dir().ok())
+                .map(|directory| directory.join("pixi.toml"))
+        })
+        .filter(|manifest| manifest.is_file());
+
+    let mut directories: Vec<PathBuf> = pipeline_dir
+        .map(Path::to_path_buf)
+        .or_else(|| env::current_dir().ok())
+        .into_iter()
+        .collect();
+    if let Some(directory) = pixi_manifest.as_deref().and_then(Path::parent) {
+        if !directories.iter().any(|known| known == directory) {
+            directories.push(directory.to_path_buf());
+        }
+    }
+    let lockfile_environments = lockfile_environments(&directories);
+
+    let packages = pixi_manifest
+        .as_deref()
+        .and_then(|manifest| {
+            pixi_packages(
+                manifest,
+                pixi_environment.as_deref().unwrap_or("default"),
+                &lockfile_environments,
+            )
+        })
+        .or_else(|| {
+            env::var("CONDA_PREFIX")
+                .ok()
+                .map(|prefix| conda_packages(Path::new(&prefix)))
+        })
+        .unwrap_or_default();
+
+    EnvironmentFingerprint {
+        conda_environment: env::var("CONDA_DEFAULT_ENV").ok(),
+        pixi_environment,
+        pixi_manifest: pixi_manifest.map(|manifest| manifest.display().to_string()),
+        packages,
+        lockfile_environments,
+        container_images: container_images().await,
+        environment_variables: allowed_env_vars(env::vars()),
+    }
+}
+
+/// Every environment defined by the lockfiles of `directories`
+fn lockfile_environments(directories: &[PathBuf]) -> Vec<LockedEnvironment> {
+    directories
+        .iter()
+        .flat_map(|directory| find_lockfiles(directory))
+        .flat_map(|lockfile| {
+            load_lockfile(&lockfile).unwrap_or_else(|e| {
+                debug!("Failed to read {}: {:?}", lockfile.display(), e);
+                Vec::new()
+            })
+        })
+        .collect()
+}
+
+/// Packages of a pixi environment: the versions locked in `pixi.lock` next to the manifest,
+/// else the constraints of the manifest itself
+fn pixi_packages(
+    manifest: &Path,
+    environment: &str,
+    locked: &[LockedEnvironment],
+) -> Option<BTreeMap<String, String>> {
+    let lockfile = manifest.with_file_name("pixi.lock").display().to_string();
+    if let Some(locked) = locked
+        .iter()
+        .find(|locked| locked.lockfile == lockfile && locked.name == environment)
+    {
+        return Some(locked.packages.clone());
+    }
+    let contents = std::fs::read_to_string(manifest).ok()?;
+    parse_pixi_manifest(&contents)
+        .map_err(|e| debug!("Failed to read {}: {}", manifest.display(), e))
+        .ok()
+}
+
+/// Version constraints of the dependencies declared in a pixi manifest
+pub fn parse_pixi_manifest(contents: &str) -> Result<BTreeMap<String, String>> {
+    let manifest: toml::Table = toml::from_str(contents)?;
+    let mut packages = BTreeMap::new();
+    for table in ["dependencies", "pypi-dependencies"] {
+        let Some(dependencies) = manifest.get(table).and_then(toml::Value::as_table) else {
+            continue;
+        };
+        for (name, spec) in dependencies {
+            let version = match spec {
+                toml::Value::String(version) => version.as_str(),
+                spec => spec
+                    .get("version")
+                    .and_then(toml::Value::as_str)
+                    .unwrap_or("*"),
+            };
+            packages.insert(name.clone(), version.to_string());
+        }
+    }
+    Ok(packages)
+}
+
+/// Packages installed in the conda environment at `prefix`, from the names of its
+/// `conda-meta` records
+fn conda_packages(prefix: &Path) -> BTreeMap<String, String> {
+    let Ok(entries) = std::fs::read_dir(prefix.join("conda-meta")) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let stem = file_name.to_str()?.strip_suffix(".json")?.to_string();
+            parse_conda_archive_name(&stem)
+        })
+        .collect()
+}
+
+fn allowed_env_vars(vars: impl Iterator<Item = (String, String)>) -> BTreeMap<String, String> {
+    vars.filter(|(name, _)| ALLOWED_ENV_VARS.contains(&name.as_str()))
+        .collect()
+}
+
+/// Images of the running containers, if Docker is available
+async fn container_images() -> Vec<ContainerImage> {
+    let Ok(docker) = Docker::connect_with_unix_defaults() else {
+        return Vec::new();
+    };
+    match tokio::time::timeout(DOCKER_TIMEOUT, list_container_images(&docker)).await {
+        Ok(Ok(images)) => images,
+        Ok(Err(e)) => {
+            debug!("Failed to list container images: {}", e);
+            Vec::new()
+        }
+        Err(_) => {
+            debug!("Timed out listing container images");
+            Vec::new()
+        }
+    }
+}
+
+async fn list_container_images(docker: &Docker) -> Result<Vec<ContainerImage>> {
+    let containers = docker
+        .list_containers(None::<ListContainersOptions>)
+        .await?;
+    let mut images = BTreeMap::new();
+    for container in containers {
+        let Some(image) = container.image else {
+            continue;
+        };
+        if images.contains_key(&image) {
+            continue;
+        }
+        let digest = match docker.inspect_image(&image).await {
+            Ok(inspect) => inspect
+                .repo_digests
+                .and_then(|digests| digests.into_iter().next())
+                .or(inspect.id),
+            Err(_) => container.image_id,
+        };
+        images.insert(image, digest);
+    }
+    Ok(images
+        .into_iter()
+        .map(|(image, digest)| ContainerImage { image, digest })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pixi_manifest() {
+        let manifest = r#"
+[project]
+name = "rnaseq"
+channels = ["conda-forge", "bioconda"]
+platforms = ["linux-64"]
+
+[dependencies]
+star = ">=2.7.11"
+samtools = { version = "1.20.*", channel = "bioconda" }
+
+[pypi-dependencies]
+multiqc = "==1.23"
+"#;
+        let packages = parse_pixi_manifest(manifest).unwrap();
+        assert_eq!(packages["star"], ">=2.7.11");
+        assert_eq!(packages["samtools"], "1.20.*");
+        assert_eq!(packages["multiqc"], "==1.23");
+        assert_eq!(packages.len(), 3);
+    }
+
+    #[test]
+    fn test_conda_packages_from_conda_meta() {
+        let prefix = tempfile::tempdir().unwrap();
+        let conda_meta = prefix.path().join("conda-meta");
+        std::fs::create_dir(&conda_meta).unwrap();
+        std::fs::write(conda_meta.join("samtools-1.20-h50ea8bc_0.json"), "{}").unwrap();
+        std::fs::write(conda_meta.join("history"), "").unwrap();
+
+        let packages = conda_packages(prefix.path());
+        assert_eq!(
+            packages,
+            BTreeMap::from([("samtools".to_string(), "1.20".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_only_allowed_env_vars_are_kept() {
+        let vars = [
+            ("CONDA_DEFAULT_ENV", "rnaseq"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("OMP_NUM_THREADS", "8"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        let kept = allowed_env_vars(vars);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept["CONDA_DEFAULT_ENV"], "rnaseq");
+        assert!(!kept.contains_key("AWS_SECRET_ACCESS_KEY"));
+    }
+}
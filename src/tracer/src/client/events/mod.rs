@@ -1,13 +1,19 @@
+mod fingerprint;
 mod run_details;
 use crate::cloud_providers::aws::aws_metadata::get_aws_instance_metadata;
 use crate::cloud_providers::aws::pricing::PricingSource;
+use crate::extracts::metrics::cgroup_limits::current_cgroup_cpu;
 use crate::extracts::metrics::gpu_monitor::GpuMonitor;
 use crate::extracts::metrics::system_metrics_collector::SystemMetricsCollector;
 use crate::process_identification::types::current_run::{PipelineCostSummary, RunMetadata};
 use crate::process_identification::types::event::attributes::system_metrics::SystemProperties;
+use crate::utils::workdir::TRACER_WORK_DIR;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use run_details::{generate_run_id, generate_run_name};
+use fingerprint::gather_environment_fingerprint;
+pub use run_details::RunNameScheme;
+use run_details::{generate_run_id, generate_unique_run_name, RunNameContext};
+use std::path::Path;
 use sysinfo::System;
 use tracing::{debug, info};
 
@@ -20,6 +26,7 @@ pub struct RunEventOut {
 async fn gather_system_properties(
     system: &System,
     pricing_client: &PricingSource,
+    pipeline_dir: Option<&Path>,
 ) -> SystemProperties {
     let aws_metadata = get_aws_instance_metadata().await;
     let is_aws_instance = aws_metadata.is_some();
@@ -30,7 +37,7 @@ async fn gather_system_properties(
         None
     };
 
-    let system_disk_io = SystemMetricsCollector::gather_disk_data();
+    let system_disk_io = SystemMetricsCollector::gather_disk_data(&[]);
 
     let system_disk_total_space =
         SystemMetricsCollector::calculate_total_disk_space(&system_disk_io);
@@ -42,12 +49,16 @@ async fn gather_system_properties(
     let system_gpu_memory_total = gpu_aggregate.total_memory_total;
     let system_gpu_memory_utilization = gpu_aggregate.memory_utilization;
 
+    let host_cpus = system.cpus().len();
+    let cpu_quota = current_cgroup_cpu();
+
     SystemProperties {
         os: System::name(),
         os_version: System::os_version(),
         kernel_version: System::kernel_version(),
         arch: System::cpu_arch(),
-        num_cpus: system.cpus().len(),
+        num_cpus: cpu_quota.map_or(host_cpus, |quota| quota.effective_cpu_count(host_cpus)),
+        cpu_quota: cpu_quota.map(|quota| quota.quota_cpus),
         hostname: System::host_name(),
         total_memory: system.total_memory(),
         total_swap: system.total_swap(),
@@ -63,16 +74,20 @@ async fn gather_system_properties(
         system_gpu_memory_used,
         system_gpu_memory_total,
         system_gpu_memory_utilization,
+        environment_fingerprint: Some(gather_environment_fingerprint(pipeline_dir).await),
     }
 }
 
 pub async fn init_run(
     system: &System,
     pricing_client: &PricingSource,
+    pipeline_name: &str,
     run_name: &Option<String>,
+    run_name_scheme: &RunNameScheme,
+    pipeline_dir: Option<&Path>,
 ) -> Result<(RunMetadata, SystemProperties)> {
     debug!("Starting new run...");
-    let system_properties = gather_system_properties(system, pricing_client).await;
+    let system_properties = gather_system_properties(system, pricing_client, pipeline_dir).await;
     let timestamp: DateTime<Utc> = Utc::now();
     let cost_summary = system_properties
         .pricing_context
@@ -80,7 +95,13 @@ pub async fn init_run(
         .map(|pricing_context| PipelineCostSummary::new(timestamp, pricing_context));
 
     let run_data = RunMetadata::new(
-        run_name.as_ref().cloned().unwrap_or_else(generate_run_name),
+        run_name.as_ref().cloned().unwrap_or_else(|| {
+            generate_unique_run_name(
+                run_name_scheme,
+                &RunNameContext::current(pipeline_name),
+                &TRACER_WORK_DIR.run_names_file,
+            )
+        }),
         generate_run_id(),
         cost_summary,
     );
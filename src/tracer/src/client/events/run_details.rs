@@ -1,6 +1,15 @@
+use chrono::{DateTime, Utc};
 use rand::seq::IndexedRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::LazyLock;
+use sysinfo::System;
+use tracing::warn;
+
+/// Number of generated run names remembered to avoid collisions, see `RunNameScheme::Timestamped`
+const MAX_REMEMBERED_RUN_NAMES: usize = 100;
 
 static ADJECTIVES: LazyLock<Vec<&str>> = LazyLock::new(|| {
     vec![
@@ -312,3 +321,197 @@ pub(super) fn generate_run_name() -> String {
 pub(super) fn generate_run_id() -> String {
     uuid::Uuid::new_v4().to_string()
 }
+
+/// How the name of a run is generated when none is given with `--run-name`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunNameScheme {
+    /// A random name, e.g. `snowy-frog-042`
+    #[default]
+    Random,
+    /// The pipeline name and start time, e.g. `rnaseq-20250430-120000`, with a numeric suffix
+    /// if another run got the same name
+    Timestamped,
+    /// Interpolates `{pipeline}`, `{date}`, `{git_sha}` and `{hostname}` in the given template
+    Template(String),
+}
+
+impl FromStr for RunNameScheme {
+    type Err = anyhow::Error;
+
+    /// Parses `random`, `timestamped`, or a template containing at least one `{placeholder}`
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "random" => Ok(Self::Random),
+            "timestamped" => Ok(Self::Timestamped),
+            template if template.contains('{') => Ok(Self::Template(template.to_string())),
+            _ => anyhow::bail!(
+                "invalid run name scheme '{}': expected 'random', 'timestamped' or a template",
+                value
+            ),
+        }
+    }
+}
+
+/// Values interpolated by `RunNameScheme::Template`
+pub(super) struct RunNameContext<'a> {
+    pub pipeline: &'a str,
+    pub started_at: DateTime<Utc>,
+    pub git_sha: Option<String>,
+    pub hostname: Option<String>,
+}
+
+impl RunNameContext<'_> {
+    pub fn current(pipeline: &str) -> RunNameContext<'_> {
+        RunNameContext {
+            pipeline,
+            started_at: Utc::now(),
+            git_sha: current_git_sha(),
+            hostname: System::host_name(),
+        }
+    }
+}
+
+/// Generates a run name according to `scheme`. `is_taken` tells whether a timestamped name
+/// was already given to another run.
+pub(super) fn generate_run_name_with_scheme(
+    scheme: &RunNameScheme,
+    context: &RunNameContext,
+    is_taken: impl Fn(&str) -> bool,
+) -> String {
+    match scheme {
+        RunNameScheme::Random => generate_run_name(),
+        RunNameScheme::Timestamped => {
+            let name = format!(
+                "{}-{}",
+                context.pipeline,
+                context.started_at.format("%Y%m%d-%H%M%S")
+            );
+            (1..)
+                .map(|attempt| match attempt {
+                    1 => name.clone(),
+                    _ => format!("{}-{}", name, attempt),
+                })
+                .find(|candidate| !is_taken(candidate))
+                .expect("unbounded range")
+        }
+        RunNameScheme::Template(template) => template
+            .replace("{pipeline}", context.pipeline)
+            .replace("{date}", &context.started_at.format("%Y-%m-%d").to_string())
+            .replace("{git_sha}", context.git_sha.as_deref().unwrap_or("unknown"))
+            .replace(
+                "{hostname}",
+                context.hostname.as_deref().unwrap_or("unknown"),
+            ),
+    }
+}
+
+/// Generates a run name, remembering names in `names_file` so later timestamped runs don't
+/// reuse them
+pub(super) fn generate_unique_run_name(
+    scheme: &RunNameScheme,
+    context: &RunNameContext,
+    names_file: &Path,
+) -> String {
+    let mut names: Vec<String> = std::fs::read_to_string(names_file)
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+    let name = generate_run_name_with_scheme(scheme, context, |candidate| {
+        names.iter().any(|name| name == candidate)
+    });
+
+    names.push(name.clone());
+    let start = names.len().saturating_sub(MAX_REMEMBERED_RUN_NAMES);
+    if let Err(e) = std::fs::write(names_file, names[start..].join("\n")) {
+        warn!(
+            "Failed to remember run name in {}: {}",
+            names_file.display(),
+            e
+        );
+    }
+    name
+}
+
+/// Short sha of the git commit checked out in the working directory, if any
+fn current_git_sha() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    let sha = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !sha.is_empty()).then_some(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    fn context() -> RunNameContext<'static> {
+        RunNameContext {
+            pipeline: "rnaseq",
+            started_at: Utc.with_ymd_and_hms(2025, 4, 30, 12, 0, 5).unwrap(),
+            git_sha: Some("1a2b3c4".to_string()),
+            hostname: Some("node-1".to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case("random", RunNameScheme::Random)]
+    #[case("timestamped", RunNameScheme::Timestamped)]
+    #[case("{pipeline}-{date}", RunNameScheme::Template("{pipeline}-{date}".to_string()))]
+    fn test_parse_run_name_scheme(#[case] value: &str, #[case] expected: RunNameScheme) {
+        assert_eq!(value.parse::<RunNameScheme>().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_invalid_run_name_scheme() {
+        assert!("sortable".parse::<RunNameScheme>().is_err());
+    }
+
+    #[test]
+    fn test_random_scheme() {
+        let name = generate_run_name_with_scheme(&RunNameScheme::Random, &context(), |_| false);
+        assert_eq!(name.split('-').count(), 3);
+    }
+
+    #[test]
+    fn test_timestamped_scheme() {
+        let name =
+            generate_run_name_with_scheme(&RunNameScheme::Timestamped, &context(), |_| false);
+        assert_eq!(name, "rnaseq-20250430-120005");
+    }
+
+    #[test]
+    fn test_timestamped_scheme_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let names_file = dir.path().join("run_names");
+
+        let names: Vec<String> = (0..3)
+            .map(|_| generate_unique_run_name(&RunNameScheme::Timestamped, &context(), &names_file))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "rnaseq-20250430-120005",
+                "rnaseq-20250430-120005-2",
+                "rnaseq-20250430-120005-3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template_scheme() {
+        let scheme = RunNameScheme::Template("{pipeline}-{date}-{git_sha}@{hostname}".to_string());
+        let name = generate_run_name_with_scheme(&scheme, &context(), |_| false);
+        assert_eq!(name, "rnaseq-2025-04-30-1a2b3c4@node-1");
+
+        let context = RunNameContext {
+            git_sha: None,
+            ..context()
+        };
+        let name = generate_run_name_with_scheme(&scheme, &context, |_| false);
+        assert_eq!(name, "rnaseq-2025-04-30-unknown@node-1");
+    }
+}
@@ -65,6 +65,23 @@ impl ExporterManager {
         Ok(())
     }
 
+    /// Sends all queued events in a single batch without retrying, returning the number of events
+    /// sent. Used to confirm the writer acknowledges events; on failure the batch is dropped.
+    pub async fn flush(self: &Arc<Self>) -> anyhow::Result<usize> {
+        let mut receiver = self.receiver.lock().await;
+
+        let mut buff: Vec<Event> = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            buff.push(event);
+        }
+
+        if !buff.is_empty() {
+            self.db_client.batch_insert_events(buff.as_slice()).await?;
+        }
+
+        Ok(buff.len())
+    }
+
     pub async fn close(self: &Arc<Self>) -> anyhow::Result<()> {
         // close the connection pool to aurora
         let _ = self.db_client.close().await;
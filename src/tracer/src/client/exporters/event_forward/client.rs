@@ -2,6 +2,7 @@ use super::retry;
 use crate::client::exporters::event_writer::EventWriter;
 use crate::process_identification::types::event::Event;
 use crate::process_identification::types::extracts::db::EventInsert;
+use crate::utils::http;
 use anyhow::Result;
 use reqwest::Client;
 use std::convert::TryFrom;
@@ -19,7 +20,7 @@ pub struct EventForwardConfig {
 pub async fn create_event_forward_config(endpoint: &str) -> Result<EventForwardConfig> {
     Ok(EventForwardConfig {
         endpoint: endpoint.to_string(),
-        client: Client::new(),
+        client: http::client(),
     })
 }
 
@@ -1,10 +1,14 @@
 use crate::client::exporters::event_forward::EventForward;
+use crate::client::exporters::ndjson::NdjsonWriter;
+use crate::client::exporters::sqlite::SqliteWriter;
 use crate::process_identification::types::event::Event;
 
 use anyhow::Result;
 
 pub enum LogWriterEnum {
     Forward(EventForward),
+    Ndjson(NdjsonWriter),
+    Sqlite(SqliteWriter),
 }
 
 #[allow(async_fn_in_trait)]
@@ -16,6 +20,8 @@ impl EventWriter for LogWriterEnum {
     async fn batch_insert_events(&self, data: impl IntoIterator<Item = &Event>) -> Result<()> {
         match self {
             LogWriterEnum::Forward(client) => client.batch_insert_events(data).await,
+            LogWriterEnum::Ndjson(writer) => writer.batch_insert_events(data).await,
+            LogWriterEnum::Sqlite(writer) => writer.batch_insert_events(data).await,
         }
     }
 }
@@ -25,12 +31,15 @@ impl LogWriterEnum {
     pub async fn close(&self) -> Result<()> {
         match self {
             LogWriterEnum::Forward(client) => client.close().await,
+            LogWriterEnum::Ndjson(_) | LogWriterEnum::Sqlite(_) => Ok(()),
         }
     }
 
     pub fn variant_name(&self) -> &'static str {
         match self {
             LogWriterEnum::Forward(_) => "LogForward",
+            LogWriterEnum::Ndjson(_) => "Ndjson",
+            LogWriterEnum::Sqlite(_) => "Sqlite",
         }
     }
 }
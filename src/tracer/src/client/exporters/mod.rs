@@ -1,3 +1,5 @@
 pub mod client_export_manager;
 pub mod event_forward;
 pub mod event_writer;
+pub mod ndjson;
+pub mod sqlite;
@@ -0,0 +1,84 @@
+use crate::client::exporters::event_writer::EventWriter;
+use crate::process_identification::types::event::Event;
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Target value selecting stdout rather than a file
+pub const NDJSON_STDOUT: &str = "-";
+
+/// Writes each event as a single JSON line, e.g. to pipe events into `jq` while debugging rules
+/// without a database.
+///
+/// Every line is flushed as soon as it is written. Daemon logs go to the log file, so they never
+/// interleave with the events on stdout.
+pub struct NdjsonWriter {
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl NdjsonWriter {
+    pub fn stdout() -> Self {
+        Self::new(Box::new(std::io::stdout()))
+    }
+
+    /// Opens `target` for writing: stdout for `-`, otherwise a file or fifo path
+    pub fn open(target: &str) -> Result<Self> {
+        if target == NDJSON_STDOUT {
+            return Ok(Self::stdout());
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(target)
+            .with_context(|| format!("Failed to open NDJSON output {}", target))?;
+        Ok(Self::new(Box::new(file)))
+    }
+
+    pub fn new(output: Box<dyn Write + Send>) -> Self {
+        Self {
+            output: Mutex::new(output),
+        }
+    }
+}
+
+impl EventWriter for NdjsonWriter {
+    async fn batch_insert_events(&self, data: impl IntoIterator<Item = &Event>) -> Result<()> {
+        let mut output = self
+            .output
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for event in data {
+            let mut line = serde_json::to_vec(event)?;
+            line.push(b'\n');
+            output.write_all(&line)?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_writes_one_json_line_per_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.ndjson");
+        let writer = NdjsonWriter::open(path.to_str().unwrap()).unwrap();
+
+        let events = ["first", "second"]
+            .map(|body| Event::builder().body(body).timestamp(Utc::now()).build());
+        writer.batch_insert_events(events.iter()).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let bodies: Vec<String> = content
+            .lines()
+            .map(|line| serde_json::from_str::<Event>(line).unwrap().body)
+            .collect();
+        assert_eq!(bodies, vec!["first", "second"]);
+    }
+}
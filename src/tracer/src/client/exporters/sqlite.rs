@@ -0,0 +1,226 @@
+use crate::client::exporters::event_writer::EventWriter;
+use crate::process_identification::types::event::Event;
+use crate::process_identification::types::extracts::db::EventInsert;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Schema migrations, applied in order; `PRAGMA user_version` records how many have run. Only
+/// ever append to this list, so existing databases are upgraded in place.
+const MIGRATIONS: &[&str] = &[r#"
+CREATE TABLE events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL,
+    body TEXT NOT NULL,
+    severity_text TEXT,
+    severity_number INTEGER,
+    trace_id TEXT,
+    span_id TEXT,
+    source_type TEXT NOT NULL,
+    instrumentation_version TEXT,
+    instrumentation_type TEXT,
+    environment TEXT,
+    pipeline_type TEXT,
+    user_id TEXT NOT NULL,
+    organization_id TEXT,
+    department TEXT,
+    event_type TEXT NOT NULL,
+    process_type TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    run_name TEXT NOT NULL,
+    pipeline_name TEXT NOT NULL,
+    job_id TEXT,
+    ec2_cost_per_hour REAL,
+    cpu_usage REAL,
+    mem_used REAL,
+    process_status TEXT NOT NULL,
+    attributes TEXT NOT NULL,
+    resource_attributes TEXT NOT NULL,
+    tags TEXT NOT NULL
+);
+CREATE INDEX events_run_id ON events (run_id, timestamp);
+"#];
+
+/// Writes events into a local SQLite database, with the columns of the rows sent to Tracer
+/// (`EventInsert`) and the JSON columns stored as text, so a single node can keep its events
+/// without a database server and query them with `sqlite3`, e.g.
+/// `SELECT body FROM events WHERE json_extract(attributes, '$.process.tool_name') = 'STAR'`.
+pub struct SqliteWriter {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteWriter {
+    /// Opens the database at `path`, creating it and its schema if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let connection = Connection::open(path)
+            .with_context(|| format!("Failed to open SQLite database {}", path.display()))?;
+        Self::new(connection)
+            .with_context(|| format!("Failed to migrate SQLite database {}", path.display()))
+    }
+
+    pub fn new(mut connection: Connection) -> Result<Self> {
+        migrate(&mut connection)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+/// Applies the migrations the database hasn't seen yet, each in its own transaction
+fn migrate(connection: &mut Connection) -> Result<()> {
+    let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        anyhow::bail!(
+            "The database schema (version {}) is newer than this tracer supports ({})",
+            version,
+            MIGRATIONS.len()
+        );
+    }
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+impl EventWriter for SqliteWriter {
+    async fn batch_insert_events(&self, data: impl IntoIterator<Item = &Event>) -> Result<()> {
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let transaction = connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT INTO events (timestamp, body, severity_text, severity_number, trace_id, \
+                 span_id, source_type, instrumentation_version, instrumentation_type, \
+                 environment, pipeline_type, user_id, organization_id, department, event_type, \
+                 process_type, run_id, run_name, pipeline_name, job_id, ec2_cost_per_hour, \
+                 cpu_usage, mem_used, process_status, attributes, resource_attributes, tags) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, \
+                 ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27)",
+            )?;
+            for event in data {
+                let row = EventInsert::try_from(event.clone())?;
+                statement.execute(params![
+                    row.timestamp.to_rfc3339(),
+                    row.body,
+                    row.severity_text,
+                    row.severity_number,
+                    row.trace_id,
+                    row.span_id,
+                    row.source_type,
+                    row.instrumentation_version,
+                    row.instrumentation_type,
+                    row.environment,
+                    row.pipeline_type,
+                    row.user_id,
+                    row.organization_id,
+                    row.department,
+                    row.event_type,
+                    row.process_type,
+                    row.run_id,
+                    row.run_name,
+                    row.pipeline_name,
+                    row.job_id,
+                    row.ec2_cost_per_hour,
+                    row.cpu_usage,
+                    row.mem_used,
+                    row.process_status,
+                    row.attributes.to_string(),
+                    row.resource_attributes.to_string(),
+                    row.tags.to_string(),
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_identification::types::pipeline_tags::PipelineTags;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn event(body: &str, run_id: &str) -> Event {
+        let mut event = Event::builder().body(body).timestamp(Utc::now()).build();
+        event.run_id = Some(run_id.to_string());
+        event.tags = Some(PipelineTags {
+            user_id: Some("user".to_string()),
+            ..Default::default()
+        });
+        event
+    }
+
+    #[tokio::test]
+    async fn test_events_are_queryable() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.db");
+        let writer = SqliteWriter::open(&path).unwrap();
+
+        let events = [event("first", "run-1"), event("second", "run-2")];
+        writer.batch_insert_events(events.iter()).await.unwrap();
+
+        let connection = Connection::open(&path).unwrap();
+        let body: String = connection
+            .query_row(
+                "SELECT body FROM events WHERE run_id = 'run-2'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(body, "second");
+        let user_id: String = connection
+            .query_row(
+                "SELECT json_extract(tags, '$.user_id') FROM events LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(user_id, "user");
+    }
+
+    #[tokio::test]
+    async fn test_reopening_keeps_events_and_schema() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.db");
+        let writer = SqliteWriter::open(&path).unwrap();
+        writer
+            .batch_insert_events([event("first", "run-1")].iter())
+            .await
+            .unwrap();
+        drop(writer);
+
+        let writer = SqliteWriter::open(&path).unwrap();
+        writer
+            .batch_insert_events([event("second", "run-1")].iter())
+            .await
+            .unwrap();
+
+        let connection = Connection::open(&path).unwrap();
+        let count: i64 = connection
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+        let version: usize = connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        assert!(SqliteWriter::new(connection).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod events;
 pub mod exporters;
+pub mod self_check;
 pub mod tracer_client;
 
 pub use tracer_client::TracerClient;
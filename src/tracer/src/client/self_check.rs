@@ -0,0 +1,215 @@
+//! End-to-end check of the event pipeline, run by `tracer test`.
+//!
+//! A `TestEvent` is recorded through the run's event dispatcher, observed on the live event
+//! stream, and then exported with the run's writer. The check passes once the writer
+//! acknowledges the batch containing the event.
+
+use crate::client::exporters::client_export_manager::ExporterManager;
+use crate::process_identification::recorder::EventDispatcher;
+use crate::process_identification::types::event::{Event, ProcessStatus};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+/// Outcome of a single stage of the self-check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfCheckStage {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+    pub elapsed_ms: u64,
+}
+
+/// Outcome of the self-check. Stages run in order and stop at the first failure.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelfCheckReport {
+    pub stages: Vec<SelfCheckStage>,
+}
+
+impl SelfCheckReport {
+    pub fn passed(&self) -> bool {
+        !self.stages.is_empty() && self.stages.iter().all(|stage| stage.passed)
+    }
+
+    /// Returns the first failing stage, if any
+    pub fn failed_stage(&self) -> Option<&SelfCheckStage> {
+        self.stages.iter().find(|stage| !stage.passed)
+    }
+
+    pub fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            stages: vec![SelfCheckStage {
+                name: name.to_string(),
+                passed: false,
+                detail: detail.into(),
+                elapsed_ms: 0,
+            }],
+        }
+    }
+
+    /// Runs `stage` until `deadline`, records its outcome and returns whether it passed
+    async fn run_stage<F>(&mut self, name: &str, deadline: Instant, stage: F) -> bool
+    where
+        F: Future<Output = Result<String>>,
+    {
+        let started = Instant::now();
+        let (passed, detail) = match tokio::time::timeout_at(deadline, stage).await {
+            Ok(Ok(detail)) => (true, detail),
+            Ok(Err(e)) => (false, e.to_string()),
+            Err(_) => (false, "timed out".to_string()),
+        };
+        self.stages.push(SelfCheckStage {
+            name: name.to_string(),
+            passed,
+            detail,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+        passed
+    }
+}
+
+/// Records a `TestEvent` and waits for it to be exported, giving up after `timeout`.
+///
+/// `events` must be subscribed to the stream the dispatcher publishes to before calling this,
+/// so the test event cannot be missed.
+pub async fn run_self_check(
+    dispatcher: &EventDispatcher,
+    mut events: broadcast::Receiver<Event>,
+    exporter: &Arc<ExporterManager>,
+    timeout: Duration,
+) -> SelfCheckReport {
+    let deadline = Instant::now() + timeout;
+    let mut report = SelfCheckReport::default();
+    let body = format!("[CLI] Self-check {}", uuid::Uuid::new_v4());
+
+    let recorded = report
+        .run_stage("recorder", deadline, async {
+            dispatcher
+                .log_with_metadata(ProcessStatus::TestEvent, body.clone(), None, None)
+                .await?;
+            loop {
+                match events.recv().await {
+                    Ok(event) if event.body == body => {
+                        return Ok("test event recorded".to_string());
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(anyhow!("event stream closed"));
+                    }
+                }
+            }
+        })
+        .await;
+    if !recorded {
+        return report;
+    }
+
+    report
+        .run_stage("exporter", deadline, async {
+            let sent = exporter.flush().await?;
+            Ok(format!(
+                "{} acknowledged {} event(s)",
+                exporter.db_client.variant_name(),
+                sent
+            ))
+        })
+        .await;
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::exporters::event_writer::LogWriterEnum;
+    use crate::client::exporters::ndjson::NdjsonWriter;
+    use crate::daemon::structs::PipelineMetadata;
+    use crate::process_identification::event_stream::EventStream;
+    use crate::process_identification::types::current_run::RunMetadata;
+    use chrono::Utc;
+    use std::io::Write;
+    use tokio::sync::{mpsc, Mutex};
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("connection refused"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn setup(
+        output: Box<dyn Write + Send>,
+    ) -> (EventDispatcher, EventStream, Arc<ExporterManager>) {
+        let run = RunMetadata {
+            name: "test_run".to_string(),
+            id: "test-id-123".to_string(),
+            start_time: Utc::now(),
+            started: std::time::Instant::now(),
+            cost_summary: None,
+            trace_id: None,
+        };
+        let pipeline = Arc::new(Mutex::new(PipelineMetadata {
+            name: "test_pipeline".to_string(),
+            run_snapshot: None,
+            tags: Default::default(),
+            is_dev: true,
+            start_time: Default::default(),
+            opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: None,
+            local_only: false,
+            config: None,
+        }));
+        let stream = EventStream::new();
+        let (tx, rx) = mpsc::channel(10);
+        let dispatcher = EventDispatcher::new(pipeline, run, tx).with_stream(stream.clone());
+        let exporter = Arc::new(ExporterManager::new(
+            LogWriterEnum::Ndjson(NdjsonWriter::new(output)),
+            rx,
+        ));
+        (dispatcher, stream, exporter)
+    }
+
+    #[tokio::test]
+    async fn test_self_check_passes() {
+        let (dispatcher, stream, exporter) = setup(Box::new(std::io::sink()));
+
+        let report = run_self_check(
+            &dispatcher,
+            stream.subscribe(),
+            &exporter,
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.stages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_self_check_reports_failing_exporter() {
+        let (dispatcher, stream, exporter) = setup(Box::new(FailingWriter));
+
+        let report = run_self_check(
+            &dispatcher,
+            stream.subscribe(),
+            &exporter,
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(!report.passed());
+        let failed = report.failed_stage().unwrap();
+        assert_eq!(failed.name, "exporter");
+        assert!(failed.detail.contains("connection refused"));
+    }
+}
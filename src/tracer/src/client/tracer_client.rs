@@ -1,33 +1,64 @@
 use crate::cli::handlers::init_arguments::FinalizedInitArgs;
-use crate::client::events::init_run;
+use crate::client::events::{init_run, RunNameScheme};
 use crate::client::exporters::client_export_manager::ExporterManager;
 use crate::client::exporters::event_writer::LogWriterEnum;
+use crate::client::self_check::{run_self_check, SelfCheckReport};
+use crate::cloud_providers::aws::batch::AwsBatchJob;
 use crate::cloud_providers::aws::config::{get_aws_default_profile, AwsConfig};
 use crate::cloud_providers::aws::pricing::PricingSource;
+use crate::cloud_providers::aws::region::resolve_aws_region;
+use crate::config::audit::{record_changes, ConfigChange};
 use crate::config::Config;
 use crate::daemon::structs::{PipelineMetadata, RunSnapshot};
+use crate::extracts::containers::display_name::ContainerDisplayName;
 use crate::extracts::containers::DockerWatcher;
 use crate::extracts::files::file_manager::manager::FileManager;
+use crate::extracts::metrics::snapshot::{MetricsSnapshot, MetricsSnapshots};
 use crate::extracts::metrics::system_metrics_collector::SystemMetricsCollector;
 use crate::extracts::process::process_manager::recorder::EventRecorder;
 use crate::extracts::process_watcher::watcher::ProcessWatcher;
 use crate::extracts::python_monitor::function_monitor::function_monitor_manager::FunctionMonitorManager;
+use crate::extracts::syslog::output_scanner::OutputMatch;
+use crate::extracts::syslog::patterns::load_patterns_file;
+use crate::extracts::syslog::redirect_tailer::RedirectTailer;
+use crate::process_identification::event_stream::EventStream;
 use crate::process_identification::recorder::EventDispatcher;
+use crate::process_identification::target_process::parser::inline_match::parse_inline_matches;
+use crate::process_identification::target_process::parser::yaml_rules_parser::{
+    load_rules_files, split_rules_paths,
+};
+use crate::process_identification::target_process::target_manager::TargetManager;
+use crate::process_identification::target_process::wrapper::load_wrapper_patterns_file;
+use crate::process_identification::trigger_stream::TriggerStream;
 use crate::process_identification::types::current_run::RunMetadata;
+use crate::process_identification::types::event::attributes::clock_skew::ClockSkew;
+use crate::process_identification::types::event::attributes::run_summary::{
+    write_run_summary, RunEndReason, RunSummary,
+};
+use crate::process_identification::types::event::attributes::syslog::{
+    SyslogProperties, SyslogSeverity,
+};
 use crate::process_identification::types::event::attributes::system_metrics::SystemProperties;
 use crate::process_identification::types::event::attributes::EventAttributes;
+use crate::process_identification::types::event::resource::ResourceAttributes;
 use crate::process_identification::types::event::{Event, ProcessStatus};
+use crate::process_identification::types::lockfiles::PackageVersions;
 use crate::utils::env::detect_environment_type;
 use crate::utils::system_info::get_kernel_version;
+use crate::utils::telemetry::ErrorCategory;
+use crate::utils::workdir::TRACER_WORK_DIR;
 use anyhow::{Context, Result};
+use chrono::Utc;
 use std::fs::OpenOptions;
 use std::io::SeekFrom;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use sysinfo::System;
 use tokio::fs::File as TokioFile;
 use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader as TokioBufReader};
 use tokio::sync::Mutex;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
@@ -38,16 +69,21 @@ pub struct TracerClient {
     docker_watcher: Arc<DockerWatcher>,
     pub cancellation_token: CancellationToken,
     metrics_collector: SystemMetricsCollector,
+    metrics_snapshots: MetricsSnapshots,
 
     pipeline: Arc<Mutex<PipelineMetadata>>,
     run: RunMetadata,
+    /// Why the run ends, recorded in its summary
+    end_reason: RunEndReason,
     config: Config,
     force_procfs: bool,
 
     pub exporter: Arc<ExporterManager>,
+    event_dispatcher: EventDispatcher,
     pub file_manager: Arc<RwLock<FileManager>>,
     python_file_pos: Arc<Mutex<u64>>,
     python_function_monitor_manager: FunctionMonitorManager,
+    redirect_tailer: RedirectTailer,
 }
 
 impl TracerClient {
@@ -56,16 +92,52 @@ impl TracerClient {
         config: Config,
         db_client: LogWriterEnum,
         cli_args: FinalizedInitArgs,
+        event_stream: EventStream,
+        trigger_stream: TriggerStream,
     ) -> Result<TracerClient> {
-        info!("Initializing TracerClient");
+        let pricing_client = Self::init_pricing_client(&config).await;
+        Self::with_pricing_source(
+            pricing_client,
+            config,
+            db_client,
+            cli_args,
+            event_stream,
+            trigger_stream,
+        )
+        .await
+    }
 
-        let pricing_client = Self::init_pricing_client().await;
+    /// Like `new`, with the prices of the instance read from `pricing_client` rather than from
+    /// the source of the resolved AWS region, e.g. `PricingSource::Static` in tests
+    pub async fn with_pricing_source(
+        pricing_client: PricingSource,
+        config: Config,
+        db_client: LogWriterEnum,
+        cli_args: FinalizedInitArgs,
+        event_stream: EventStream,
+        trigger_stream: TriggerStream,
+    ) -> Result<TracerClient> {
+        info!("Initializing TracerClient");
 
-        let pipeline = Arc::new(Mutex::new(PipelineMetadata::new(&cli_args)));
+        let pipeline = Arc::new(Mutex::new(PipelineMetadata::new(&cli_args, &config)));
 
         let system = Arc::new(RwLock::new(System::new_all()));
-        let (run, system_properties) =
-            Self::init_run(system.clone(), &cli_args.run_name, pricing_client).await;
+        let (run, system_properties) = Self::init_run(
+            system.clone(),
+            &cli_args.pipeline_name,
+            &cli_args.run_name,
+            &config.run_name_scheme,
+            &pricing_client,
+            cli_args.pipeline_dir.as_deref().map(Path::new),
+        )
+        .await;
+
+        if let Some(metadata) = &system_properties.aws_metadata {
+            pricing_client.spawn_warm_up(
+                metadata.region.clone(),
+                config.pricing_warmup_instance_types.clone(),
+            );
+        }
 
         {
             // Update pipeline tags with instance_type and environment_type
@@ -76,9 +148,35 @@ impl TracerClient {
 
             let environment_type = detect_environment_type(1).await;
             pipeline.tags.environment_type = Some(environment_type);
+
+            pipeline.tags.aws_batch = AwsBatchJob::from_env();
+            if let Some(batch_job) = &pipeline.tags.aws_batch {
+                info!("Detected AWS Batch job {}", batch_job.job_id);
+            }
         }
 
-        let (event_dispatcher, rx) = Self::init_event_dispatcher(pipeline.clone(), run.clone());
+        // The active environment comes first, so its versions win over other environments
+        let package_versions = system_properties
+            .environment_fingerprint
+            .as_ref()
+            .map(|fingerprint| {
+                PackageVersions::from_environments(
+                    std::iter::once(&fingerprint.packages).chain(
+                        fingerprint
+                            .lockfile_environments
+                            .iter()
+                            .map(|environment| &environment.packages),
+                    ),
+                )
+            })
+            .unwrap_or_default();
+
+        let (event_dispatcher, rx) = Self::init_event_dispatcher(
+            pipeline.clone(),
+            run.clone(),
+            event_stream,
+            ResourceAttributes::from(&system_properties),
+        );
 
         event_dispatcher
             .log_with_metadata(
@@ -104,8 +202,89 @@ impl TracerClient {
             &event_dispatcher,
             docker_watcher.clone(),
             file_manager.clone(),
+            config.container_display_name,
+            config.process_tree_in_events,
+            Duration::from_millis(config.process_exit_grace_period_ms),
+            trigger_stream,
         );
 
+        // `--match` rules take precedence over the rules files, which take precedence over the
+        // built-in rules (unless those are disabled); exclude rules still apply to all of them
+        let mut wrapper_patterns = config.wrapper_patterns.clone();
+        if let Some(patterns_file) = &config.wrapper_patterns_file {
+            match load_wrapper_patterns_file(Path::new(patterns_file)) {
+                Ok(patterns) => {
+                    info!(
+                        "Loaded {} wrapper patterns from {}",
+                        patterns.len(),
+                        patterns_file
+                    );
+                    wrapper_patterns.extend(patterns);
+                }
+                Err(e) => error!("Ignoring wrapper patterns file: {:?}", e),
+            }
+        }
+        let mut target_manager = TargetManager::default().with_wrappers(wrapper_patterns);
+        if !config.builtin_rules {
+            info!("Built-in rules are disabled");
+            target_manager = target_manager.without_builtin_targets();
+        }
+        match parse_inline_matches(&config.match_rules) {
+            Ok(targets) => target_manager = target_manager.with_custom_targets(targets),
+            // validated by `tracer init`
+            Err(e) => error!("Ignoring inline match rules: {}", e),
+        }
+        if let Some(rules_file) = &config.rules_file {
+            match load_rules_files(&split_rules_paths(rules_file)) {
+                Ok(rules) => {
+                    info!("Loaded {} rules from {}", rules.targets.len(), rules_file);
+                    for name in &rules.overridden {
+                        info!("Rule '{}' is overridden by a later rules file", name);
+                    }
+                    target_manager = target_manager.with_custom_targets(rules.targets);
+                }
+                // validated by `tracer init`, so a file changed since
+                Err(e) => error!("Ignoring rules files: {}", e),
+            }
+        }
+        let rule_count = target_manager.rule_count();
+        pipeline.lock().await.active_rules = Some(rule_count);
+        event_dispatcher.log_rule_count(rule_count).await?;
+        process_watcher.set_target_manager(target_manager).await;
+        process_watcher
+            .set_tool_metrics_window(Duration::from_millis(config.tool_metrics_window_ms))
+            .await;
+        process_watcher
+            .set_swap_alert_threshold(config.swap_alert_threshold_bytes)
+            .await;
+        process_watcher
+            .set_storage_stall_threshold(Duration::from_millis(config.storage_stall_threshold_ms))
+            .await;
+        process_watcher
+            .set_missed_exit_confirmation_polls(config.missed_exit_confirmation_polls)
+            .await;
+        let mut tool_error_patterns = Vec::new();
+        if let Some(patterns_file) = &config.tool_error_patterns_file {
+            match load_patterns_file(Path::new(patterns_file)) {
+                Ok(patterns) => {
+                    info!(
+                        "Loaded {} tool error patterns from {}",
+                        patterns.len(),
+                        patterns_file
+                    );
+                    tool_error_patterns = patterns;
+                }
+                Err(e) => error!("Ignoring tool error patterns file: {:?}", e),
+            }
+        }
+        let redirect_tailer = RedirectTailer::with_patterns(tool_error_patterns)
+            .with_buffer_lines(config.output_buffer_lines);
+        let metrics_snapshots = MetricsSnapshots::default();
+        process_watcher
+            .set_metrics_snapshots(metrics_snapshots.clone())
+            .await;
+        process_watcher.set_package_versions(package_versions).await;
+
         OpenOptions::new()
             .write(true)
             .truncate(true)
@@ -114,7 +293,10 @@ impl TracerClient {
 
         let exporter = Arc::new(ExporterManager::new(db_client, rx));
 
-        let metrics_collector = Self::init_watchers(&event_dispatcher, &system);
+        let metrics_collector = Self::init_watchers(&event_dispatcher, &system)
+            .with_disk_mounts(config.disk_mounts.clone())
+            .with_gpu(config.enable_gpu)
+            .with_snapshots(metrics_snapshots.clone());
         let cancellation_token = CancellationToken::new();
 
         Ok(TracerClient {
@@ -122,29 +304,46 @@ impl TracerClient {
             system: system.clone(),
             cancellation_token,
             metrics_collector,
+            metrics_snapshots,
             process_watcher,
             exporter,
+            event_dispatcher,
+            force_procfs: config.force_procfs,
             config,
-            force_procfs: cli_args.force_procfs,
             docker_watcher,
             run,
+            end_reason: RunEndReason::Completed,
             pipeline,
             file_manager,
             python_file_pos: Arc::new(Mutex::new(0)),
             python_function_monitor_manager,
+            redirect_tailer,
         })
     }
 
-    async fn init_pricing_client() -> PricingSource {
-        PricingSource::new(AwsConfig::Profile(get_aws_default_profile()).clone()).await
+    async fn init_pricing_client(config: &Config) -> PricingSource {
+        let resolved = resolve_aws_region(config.aws_region.as_deref()).await;
+        info!(
+            "Using AWS region {} (from {})",
+            resolved.region, resolved.source
+        );
+        PricingSource::new(
+            AwsConfig::Profile(get_aws_default_profile()),
+            &resolved.region,
+        )
+        .await
     }
 
     fn init_event_dispatcher(
         pipeline: Arc<Mutex<PipelineMetadata>>,
         run_data: RunMetadata,
+        event_stream: EventStream,
+        resource: ResourceAttributes,
     ) -> (EventDispatcher, mpsc::Receiver<Event>) {
         let (tx, rx) = mpsc::channel::<Event>(100);
-        let event_dispatcher = EventDispatcher::new(pipeline, run_data, tx);
+        let event_dispatcher = EventDispatcher::new(pipeline, run_data, tx)
+            .with_stream(event_stream)
+            .with_resource(resource);
         (event_dispatcher, rx)
     }
 
@@ -152,12 +351,22 @@ impl TracerClient {
         event_dispatcher: &EventDispatcher,
         docker_watcher: Arc<DockerWatcher>,
         file_manager: Arc<RwLock<FileManager>>,
+        container_display_name: ContainerDisplayName,
+        include_process_tree: bool,
+        exit_grace_period: Duration,
+        trigger_stream: TriggerStream,
     ) -> Arc<ProcessWatcher> {
-        Arc::new(ProcessWatcher::new(
-            event_dispatcher.clone(),
-            docker_watcher,
-            file_manager,
-        ))
+        Arc::new(
+            ProcessWatcher::new(
+                event_dispatcher.clone(),
+                docker_watcher,
+                file_manager,
+                container_display_name,
+                include_process_tree,
+                exit_grace_period,
+            )
+            .with_trigger_stream(trigger_stream),
+        )
     }
 
     fn init_watchers(
@@ -173,11 +382,22 @@ impl TracerClient {
     ///
     /// On non-Linux platforms, polling is used by default.
     pub async fn start_monitoring(&self) -> Result<()> {
-        self.start_docker_monitoring().await;
+        if self.config.enable_containers {
+            self.start_docker_monitoring().await;
+        } else {
+            info!("Container monitoring is disabled");
+        }
         if !self.force_procfs && cfg!(target_os = "linux") {
             let kernel_version = get_kernel_version();
             return match kernel_version {
                 Some((major, minor)) if major > 5 || (major == 5 && minor >= 15) => {
+                    if let Err(e) = tracer_ebpf::preflight::check_compatibility() {
+                        warn!(
+                            "eBPF is not supported on Linux kernel {}.{}: {}. Falling back to process polling.",
+                            major, minor, e
+                        );
+                        return self.start_process_polling().await;
+                    }
                     info!(
                         "Starting eBPF monitoring on Linux kernel {}.{}",
                         major, minor
@@ -231,6 +451,32 @@ impl TracerClient {
         }
     }
 
+    /// Runs one full monitoring cycle, i.e. what the daemon's workers do over one interval, for
+    /// tests and embedders that drive monitoring themselves. The sub-polls run in this order:
+    /// 1. process metrics, which records new and exited processes and tool executions
+    /// 2. the sysinfo refresh used by the next process poll
+    /// 3. the error lines of redirected output files
+    /// 4. system metrics, which also updates the metrics snapshot
+    /// 5. file metrics
+    /// 6. Python function events
+    ///
+    /// The first three are `poll_processes`, which the daemon runs more often than the others.
+    /// Stops at the first sub-poll that fails. Batched events are not submitted; use the
+    /// exporter for that.
+    pub async fn tick(&mut self) -> Result<()> {
+        self.poll_processes().await?;
+        self.poll_metrics_data().await?;
+        self.poll_files_metrics().await?;
+        self.monitor_python().await
+    }
+
+    /// The process part of a `tick`: process metrics, the sysinfo refresh and redirected output
+    pub async fn poll_processes(&mut self) -> Result<()> {
+        self.poll_process_metrics().await?;
+        self.refresh_sysinfo().await?;
+        self.poll_redirected_output().await
+    }
+
     pub async fn poll_metrics_data(&self) -> Result<()> {
         self.metrics_collector
             .collect_metrics()
@@ -238,6 +484,61 @@ impl TracerClient {
             .context("Failed to collect metrics")
     }
 
+    /// Records an error line printed by a pipeline the CLI ran, or by a tool to the file its
+    /// output is redirected to. Critical errors and the errors of tools are alerts, the others
+    /// syslog events; both count in the run summary. Returns whether it was recorded, i.e.
+    /// unless `Config::enable_syslog` is off.
+    pub async fn record_pipeline_output(&self, output_match: OutputMatch) -> Result<bool> {
+        if !self.config.enable_syslog {
+            return Ok(false);
+        }
+        let system_metrics = match self.metrics_snapshots.snapshot().system {
+            Some(sample) => sample.metrics,
+            None => {
+                self.metrics_collector
+                    .gather_metrics_object_attributes()
+                    .await
+            }
+        };
+        let status = match (output_match.severity, &output_match.tool) {
+            (SyslogSeverity::Critical, _) | (_, Some(_)) => ProcessStatus::Alert,
+            _ => ProcessStatus::SyslogEvent,
+        };
+        let source = match &output_match.file {
+            Some(file) => format!("{} > {}", output_match.stream, file.display()),
+            None => output_match.stream.to_string(),
+        };
+        let display_name = match &output_match.tool {
+            Some(tool) => format!("{}: {}", tool, output_match.display_name),
+            None => output_match.display_name.clone(),
+        };
+        let error_category = ErrorCategory::from_message(&output_match.line);
+        self.event_dispatcher
+            .log_with_metadata(
+                status,
+                format!("[{}] {}: {}", source, display_name, output_match.line),
+                Some(EventAttributes::Syslog(SyslogProperties {
+                    system_metrics,
+                    error_display_name: output_match.display_name,
+                    error_id: output_match.pattern_id,
+                    error_line: output_match.line,
+                    file_line_number: output_match.line_number,
+                    file_previous_logs: output_match.previous_lines,
+                    severity: output_match.severity,
+                    error_category,
+                    tool: output_match.tool,
+                })),
+                None,
+            )
+            .await?;
+        Ok(true)
+    }
+
+    /// The latest system, GPU and process metrics gathered by the monitor loop
+    pub fn get_metrics_snapshot(&self) -> MetricsSnapshot {
+        self.metrics_snapshots.snapshot()
+    }
+
     pub async fn get_run_snapshot(&self) -> RunSnapshot {
         let run = &self.run;
 
@@ -266,17 +567,38 @@ impl TracerClient {
             "Updating run name from '{}' to '{}'",
             self.run.name, new_run_name
         );
+        let change = ConfigChange::new(
+            "update-run-name",
+            "run_name",
+            self.run.name.clone().into(),
+            new_run_name.clone().into(),
+        );
+        if let Err(e) = record_changes(&TRACER_WORK_DIR.config_audit_file, &[change]) {
+            warn!("Failed to record the run name change: {}", e);
+        }
         self.run.name = new_run_name;
         Ok(())
     }
 
     pub async fn init_run(
         system: Arc<RwLock<System>>,
+        pipeline_name: &str,
         run_name: &Option<String>,
-        pricing_source: PricingSource,
+        run_name_scheme: &RunNameScheme,
+        pricing_source: &PricingSource,
+        pipeline_dir: Option<&Path>,
     ) -> (RunMetadata, SystemProperties) {
         let system = system.read().await;
-        let (run, system_properties) = init_run(&system, &pricing_source, run_name).await.unwrap();
+        let (run, system_properties) = init_run(
+            &system,
+            pricing_source,
+            pipeline_name,
+            run_name,
+            run_name_scheme,
+            pipeline_dir,
+        )
+        .await
+        .unwrap();
         (run, system_properties)
     }
 
@@ -302,6 +624,20 @@ impl TracerClient {
         self.process_watcher.poll_process_metrics().await
     }
 
+    /// Records the error lines written to the files the monitored tools redirect their output
+    /// to, see `Config::tail_redirected_output`
+    #[tracing::instrument(skip(self))]
+    pub async fn poll_redirected_output(&mut self) -> Result<()> {
+        if !self.config.tail_redirected_output || !self.config.enable_syslog {
+            return Ok(());
+        }
+        let redirects = self.process_watcher.get_output_redirects().await;
+        for output_match in self.redirect_tailer.sync(redirects) {
+            self.record_pipeline_output(output_match).await?;
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn poll_files_metrics(&mut self) -> Result<()> {
         self.file_manager.read().await.poll_file_metrics().await
@@ -331,6 +667,76 @@ impl TracerClient {
         "pipeline".to_string()
     }
 
+    /// Emits a `FinishedToolExecution` event flagged as incomplete for each tool that is still
+    /// running, so their durations are not lost when the run stops
+    pub async fn finish_running_tools(&self) -> Result<()> {
+        self.process_watcher.handle_monitoring_stopped().await
+    }
+
+    /// Records an alert for a local clock off from the real time, as the timestamps of the
+    /// events, and the durations derived from them, can't be trusted then
+    pub async fn record_clock_skew(&self, skew: ClockSkew) -> Result<()> {
+        warn!(
+            "The system clock is {} ms off from {}",
+            skew.clock_skew_ms, skew.reference
+        );
+        self.event_dispatcher
+            .log_with_metadata(
+                ProcessStatus::Alert,
+                format!(
+                    "The system clock is {} ms {} the real time, event timestamps may be misordered; check NTP",
+                    skew.clock_skew_ms.unsigned_abs(),
+                    if skew.clock_skew_ms > 0 { "ahead of" } else { "behind" }
+                ),
+                Some(EventAttributes::ClockSkew(skew)),
+                None,
+            )
+            .await
+    }
+
+    /// Records a heartbeat unless other events were recorded within `interval`
+    pub async fn emit_heartbeat(&self, interval: Duration) -> Result<()> {
+        let mut running_tools: Vec<String> = self
+            .process_watcher
+            .get_monitored_processes()
+            .await
+            .into_iter()
+            .collect();
+        running_tools.sort();
+        self.event_dispatcher
+            .log_heartbeat(running_tools, chrono::Duration::from_std(interval)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Sets why the run ends, before it is stopped
+    pub fn set_end_reason(&mut self, end_reason: RunEndReason) {
+        self.end_reason = end_reason;
+    }
+
+    /// Records the end of the run followed by a `RunStatusMessage` carrying its summary, and
+    /// writes the summary to the work dir if enabled
+    pub async fn finish_run(&self) -> Result<RunSummary> {
+        let mut summary = self.event_dispatcher.run_summary(Utc::now()).await;
+        summary.end_reason = self.end_reason;
+        if self.config.write_run_summary {
+            write_run_summary(&TRACER_WORK_DIR.run_summary_file, &summary)?;
+        }
+        self.event_dispatcher
+            .log_run_summary(summary.clone())
+            .await?;
+        Ok(summary)
+    }
+
+    /// Records a `TestEvent` and waits for the exporter to acknowledge it
+    pub async fn self_check(
+        &self,
+        events: broadcast::Receiver<Event>,
+        timeout: Duration,
+    ) -> SelfCheckReport {
+        run_self_check(&self.event_dispatcher, events, &self.exporter, timeout).await
+    }
+
     pub async fn close(&self) -> Result<()> {
         self.exporter.close().await?;
         Ok(())
@@ -380,3 +786,35 @@ impl TracerClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::handlers::init_arguments::finalized_args;
+    use crate::client::exporters::ndjson::NdjsonWriter;
+
+    #[tokio::test]
+    async fn test_tick_runs_a_monitoring_cycle() {
+        TRACER_WORK_DIR.init().unwrap();
+        let config = Config {
+            force_procfs: true,
+            enable_containers: false,
+            enable_gpu: false,
+            ..Config::default()
+        };
+        let mut client = TracerClient::with_pricing_source(
+            PricingSource::Static,
+            config,
+            LogWriterEnum::Ndjson(NdjsonWriter::new(Box::new(std::io::sink()))),
+            finalized_args(),
+            EventStream::new(),
+            TriggerStream::new(),
+        )
+        .await
+        .unwrap();
+        assert!(client.get_metrics_snapshot().system.is_none());
+
+        client.tick().await.unwrap();
+        assert!(client.get_metrics_snapshot().system.is_some());
+    }
+}
@@ -109,6 +109,26 @@ async fn fetch_instance_metadata() -> (Option<String>, Option<String>) {
     (lifecycle, tenancy)
 }
 
+/// Fetches only the region of the instance from the metadata service
+pub async fn get_instance_region() -> Option<String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(METADATA_TIMEOUT_SECS))
+        .build()
+        .ok()?;
+
+    let token = match client
+        .put(format!("{}/api/token", METADATA_BASE_URL))
+        .header("X-aws-ec2-metadata-token-ttl-seconds", "21600")
+        .send()
+        .await
+    {
+        Ok(r) if r.status().is_success() => r.text().await.ok(),
+        _ => None,
+    };
+
+    fetch_metadata_value(&client, &token, "placement/region").await
+}
+
 pub async fn get_aws_instance_metadata() -> Option<AwsInstanceMetaData> {
     let client = ec2_instance_metadata::InstanceMetadataClient::new();
     let metadata = client.get().ok()?;
@@ -0,0 +1,69 @@
+//! Detection of the AWS Batch job tracer runs in, from the variables Batch sets in job containers
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::env::AWS_BATCH_JOB_ID_ENV_VAR;
+
+pub const AWS_BATCH_JQ_NAME_ENV_VAR: &str = "AWS_BATCH_JQ_NAME";
+pub const AWS_BATCH_CE_NAME_ENV_VAR: &str = "AWS_BATCH_CE_NAME";
+pub const AWS_BATCH_JOB_ATTEMPT_ENV_VAR: &str = "AWS_BATCH_JOB_ATTEMPT";
+pub const AWS_BATCH_JOB_ARRAY_INDEX_ENV_VAR: &str = "AWS_BATCH_JOB_ARRAY_INDEX";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AwsBatchJob {
+    pub job_id: String,
+    pub job_queue: Option<String>,
+    pub compute_environment: Option<String>,
+    pub attempt: Option<u32>,
+    pub array_index: Option<u32>,
+}
+
+impl AwsBatchJob {
+    /// Returns the Batch job of this process, or `None` when not running in AWS Batch
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|var| std::env::var(var).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let non_empty = |var: &str| lookup(var).filter(|value| !value.trim().is_empty());
+
+        Some(Self {
+            job_id: non_empty(AWS_BATCH_JOB_ID_ENV_VAR)?,
+            job_queue: non_empty(AWS_BATCH_JQ_NAME_ENV_VAR),
+            compute_environment: non_empty(AWS_BATCH_CE_NAME_ENV_VAR),
+            attempt: non_empty(AWS_BATCH_JOB_ATTEMPT_ENV_VAR).and_then(|v| v.parse().ok()),
+            array_index: non_empty(AWS_BATCH_JOB_ARRAY_INDEX_ENV_VAR).and_then(|v| v.parse().ok()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_batch_job_from_env_vars() {
+        let vars = HashMap::from([
+            (AWS_BATCH_JOB_ID_ENV_VAR, "3f2b1c9e-job"),
+            (AWS_BATCH_JQ_NAME_ENV_VAR, "genomics-queue"),
+            (AWS_BATCH_CE_NAME_ENV_VAR, "spot-ce"),
+            (AWS_BATCH_JOB_ATTEMPT_ENV_VAR, "2"),
+        ]);
+
+        let job = AwsBatchJob::from_lookup(|var| vars.get(var).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(job.job_id, "3f2b1c9e-job");
+        assert_eq!(job.job_queue.as_deref(), Some("genomics-queue"));
+        assert_eq!(job.compute_environment.as_deref(), Some("spot-ce"));
+        assert_eq!(job.attempt, Some(2));
+        assert_eq!(job.array_index, None);
+    }
+
+    #[test]
+    fn test_no_batch_job_without_job_id() {
+        let vars = HashMap::from([(AWS_BATCH_JQ_NAME_ENV_VAR, "genomics-queue")]);
+        assert!(AwsBatchJob::from_lookup(|var| vars.get(var).map(|v| v.to_string())).is_none());
+        assert!(AwsBatchJob::from_lookup(|_| Some(String::new())).is_none());
+    }
+}
@@ -4,6 +4,17 @@ use config::{Value, ValueKind};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
+
+/// Environment variables read by the AWS SDK credential providers
+const AWS_CREDENTIAL_ENV_VARS: &[&str] = &[
+    "AWS_ACCESS_KEY_ID",
+    "AWS_PROFILE",
+    "AWS_ROLE_ARN",
+    "AWS_WEB_IDENTITY_TOKEN_FILE",
+    "AWS_CONTAINER_CREDENTIALS_RELATIVE_URI",
+    "AWS_CONTAINER_CREDENTIALS_FULL_URI",
+];
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -89,7 +100,7 @@ pub async fn get_initialized_aws_conf(
             Some(config)
         }
         Err(err) => {
-            tracing::warn!("Failed to get AWS credentials: {:?}", err);
+            tracing::debug!("Failed to get AWS credentials: {:?}", err);
             None
         }
     }
@@ -102,7 +113,7 @@ pub async fn resolve_available_aws_config(profile: AwsConfig, region: &str) -> O
             tracing::info!("Resolved AWS credentials using profile '{}'", profile_name);
             return profile_conf;
         } else {
-            tracing::warn!(
+            tracing::debug!(
                 "Failed to resolve credentials using profile '{}'",
                 profile_name
             );
@@ -115,10 +126,49 @@ pub async fn resolve_available_aws_config(profile: AwsConfig, region: &str) -> O
         return env_conf;
     }
 
-    tracing::warn!("Could not resolve AWS credentials from profile or environment.");
+    // logged once by the callers, which know how they degrade without credentials
+    tracing::debug!("Could not resolve AWS credentials from profile or environment.");
     None
 }
 
+/// Checks, without any network call, whether there is anywhere the AWS SDK could load
+/// credentials from. Without any, resolving a config only produces failed attempts (and IMDS
+/// timeouts), which is the usual case on developer laptops.
+pub fn has_aws_credential_sources() -> bool {
+    credential_sources_present(
+        |var| std::env::var_os(var).is_some(),
+        dirs_next::home_dir().as_deref(),
+        is_ec2_host(),
+    )
+}
+
+fn credential_sources_present(
+    has_env_var: impl Fn(&str) -> bool,
+    home_dir: Option<&Path>,
+    is_ec2_host: bool,
+) -> bool {
+    // an EC2 instance may get credentials from its instance profile
+    if is_ec2_host {
+        return true;
+    }
+
+    if AWS_CREDENTIAL_ENV_VARS.iter().any(|var| has_env_var(var)) {
+        return true;
+    }
+
+    home_dir.is_some_and(|home| {
+        home.join(".aws/credentials").is_file() || home.join(".aws/config").is_file()
+    })
+}
+
+pub(crate) fn is_ec2_host() -> bool {
+    let read = |path: &str| std::fs::read_to_string(path).unwrap_or_default();
+    read("/sys/devices/virtual/dmi/id/product_uuid")
+        .to_lowercase()
+        .starts_with("ec2")
+        || read("/sys/devices/virtual/dmi/id/sys_vendor").trim() == "Amazon EC2"
+}
+
 pub fn get_aws_default_profile() -> String {
     match dirs_next::home_dir() {
         None => "default",
@@ -135,3 +185,43 @@ pub fn get_aws_default_profile() -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_no_credential_sources_when_creds_unset() {
+        let home = TempDir::new().unwrap();
+        assert!(!credential_sources_present(
+            |_| false,
+            Some(home.path()),
+            false
+        ));
+        assert!(!credential_sources_present(|_| false, None, false));
+    }
+
+    #[test]
+    fn test_credential_sources_from_env_files_or_ec2() {
+        let home = TempDir::new().unwrap();
+        assert!(credential_sources_present(
+            |var| var == "AWS_ACCESS_KEY_ID",
+            Some(home.path()),
+            false
+        ));
+        assert!(credential_sources_present(
+            |_| false,
+            Some(home.path()),
+            true
+        ));
+
+        std::fs::create_dir(home.path().join(".aws")).unwrap();
+        std::fs::write(home.path().join(".aws/credentials"), "[default]\n").unwrap();
+        assert!(credential_sources_present(
+            |_| false,
+            Some(home.path()),
+            false
+        ));
+    }
+}
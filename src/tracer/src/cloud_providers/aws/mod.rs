@@ -1,5 +1,7 @@
 pub mod aws_metadata;
+pub mod batch;
 pub mod config;
 mod ec2;
 pub mod pricing;
+pub mod region;
 pub mod types;
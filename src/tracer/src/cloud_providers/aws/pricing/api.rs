@@ -2,6 +2,7 @@ use crate::cloud_providers::aws::{
     aws_metadata::AwsInstanceMetaData,
     types::pricing::{FlattenedData, InstancePricingContext},
 };
+use crate::utils::http;
 use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use tokio_retry::{strategy::ExponentialBackoff, Retry};
@@ -36,7 +37,7 @@ pub struct ApiPricingClient {
 impl ApiPricingClient {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: http::client(),
         }
     }
 
@@ -0,0 +1,161 @@
+//! On-disk cache of EC2 pricing matches, keyed by region and instance configuration
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cloud_providers::aws::types::pricing::{FilterableInstanceDetails, FlattenedData};
+use crate::constants::PRICING_CACHE_TTL_SECS;
+use crate::utils::workdir::TRACER_WORK_DIR;
+
+/// Serializes read-modify-write cycles of the cache file within this process, since the warm-up
+/// runs in the background while the current instance is being priced
+static CACHE_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEc2Pricing {
+    fetched_at: DateTime<Utc>,
+    matches: Vec<FlattenedData>,
+}
+
+pub struct PricingCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl Default for PricingCache {
+    fn default() -> Self {
+        Self::new(
+            TRACER_WORK_DIR.pricing_cache_file.clone(),
+            Duration::from_secs(PRICING_CACHE_TTL_SECS),
+        )
+    }
+}
+
+impl PricingCache {
+    pub fn new(path: PathBuf, ttl: Duration) -> Self {
+        Self { path, ttl }
+    }
+
+    /// Returns the cached EC2 matches for the instance, unless missing or older than the TTL
+    pub fn lookup(&self, details: &FilterableInstanceDetails) -> Option<Vec<FlattenedData>> {
+        let _guard = CACHE_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = read_entries(&self.path).remove(&cache_key(details))?;
+
+        let age = Utc::now().signed_duration_since(entry.fetched_at);
+        if age.to_std().is_ok_and(|age| age > self.ttl) {
+            return None;
+        }
+
+        Some(entry.matches)
+    }
+
+    pub fn store(&self, details: &FilterableInstanceDetails, matches: &[FlattenedData]) {
+        let _guard = CACHE_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = read_entries(&self.path);
+        entries.insert(
+            cache_key(details),
+            CachedEc2Pricing {
+                fetched_at: Utc::now(),
+                matches: matches.to_vec(),
+            },
+        );
+
+        if let Err(e) = write_entries(&self.path, &entries) {
+            tracing::warn!(error = ?e, path = ?self.path, "Failed to write pricing cache");
+        }
+    }
+}
+
+/// Only the fields selecting a different on-demand price are part of the key
+fn cache_key(details: &FilterableInstanceDetails) -> String {
+    format!(
+        "{}/{}/{}/{}",
+        details.region,
+        details.instance_type,
+        details.operating_system.as_deref().unwrap_or("Linux"),
+        details.tenancy.as_deref().unwrap_or("Shared"),
+    )
+}
+
+/// A missing or unreadable cache is treated as empty
+fn read_entries(path: &Path) -> HashMap<String, CachedEc2Pricing> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Writes to a temporary file first so a concurrent reader never sees a partial cache
+fn write_entries(path: &Path, entries: &HashMap<String, CachedEc2Pricing>) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, serde_json::to_vec(entries)?)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn details(instance_type: &str) -> FilterableInstanceDetails {
+        FilterableInstanceDetails {
+            instance_type: instance_type.to_string(),
+            region: "us-east-1".to_string(),
+            availability_zone: "us-east-1a".to_string(),
+            operating_system: Some("Linux".to_string()),
+            tenancy: Some("Shared".to_string()),
+            vcpu: None,
+            ebs_optimized: None,
+            capacity_status: Some("Used".to_string()),
+        }
+    }
+
+    fn price(instance_type: &str, price_per_unit: f64) -> FlattenedData {
+        FlattenedData {
+            instance_type: instance_type.to_string(),
+            price_per_unit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_store_and_lookup_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = PricingCache::new(
+            temp_dir.path().join("pricing_cache.json"),
+            Duration::from_secs(60),
+        );
+
+        assert!(cache.lookup(&details("m5.large")).is_none());
+
+        cache.store(&details("m5.large"), &[price("m5.large", 0.096)]);
+        cache.store(&details("c5.xlarge"), &[price("c5.xlarge", 0.17)]);
+
+        let matches = cache.lookup(&details("m5.large")).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].price_per_unit, 0.096);
+        assert!(cache.lookup(&details("c5.xlarge")).is_some());
+
+        let mut dedicated = details("m5.large");
+        dedicated.tenancy = Some("Dedicated".to_string());
+        assert!(cache.lookup(&dedicated).is_none());
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = PricingCache::new(temp_dir.path().join("pricing_cache.json"), Duration::ZERO);
+
+        cache.store(&details("m5.large"), &[price("m5.large", 0.096)]);
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert!(cache.lookup(&details("m5.large")).is_none());
+    }
+}
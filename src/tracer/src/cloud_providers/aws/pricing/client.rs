@@ -8,7 +8,7 @@ use crate::cloud_providers::aws::config::{resolve_available_aws_config, AwsConfi
 use crate::cloud_providers::aws::ec2::Ec2Client;
 use crate::cloud_providers::aws::types::pricing::InstancePricingContext;
 
-use super::context_builder::build_pricing_context;
+use super::context_builder::{build_instance_type_pricing_context, build_pricing_context};
 use super::ec2_client_manager::{reinitialize_client_if_needed, update_client_if_needed};
 
 /// Functional AWS Pricing API client
@@ -22,7 +22,7 @@ pub struct PricingClient {
 impl PricingClient {
     /// Creates a new PricingClient instance
     /// The ec2_client will be reinitialized to the correct region when needed
-    pub async fn new(initialization_conf: AwsConfig, initial_region: &'static str) -> Self {
+    pub async fn new(initialization_conf: AwsConfig, initial_region: &str) -> Self {
         // Pricing API requires us-east-1
         let pricing_config =
             resolve_available_aws_config(initialization_conf.clone(), "us-east-1").await;
@@ -53,6 +53,16 @@ impl PricingClient {
         }
     }
 
+    /// Get the on-demand pricing context of an instance type, without EBS costs
+    pub async fn get_instance_type_pricing_context(
+        &self,
+        region: &str,
+        instance_type: &str,
+    ) -> Option<InstancePricingContext> {
+        build_instance_type_pricing_context(self.pricing_client.as_ref()?, region, instance_type)
+            .await
+    }
+
     /// Get complete pricing context for an instance using functional composition
     pub async fn get_instance_pricing_context_from_metadata(
         &self,
@@ -6,15 +6,22 @@ use crate::cloud_providers::aws::aws_metadata::{AwsInstanceMetaData, InstancePur
 use crate::cloud_providers::aws::ec2::Ec2Client;
 use crate::cloud_providers::aws::pricing::filtering::ec2_matcher::EC2MatchEngine;
 use crate::cloud_providers::aws::types::pricing::{
-    FlattenedData, InstancePricingContext, PricingData,
+    FilterableInstanceDetails, FlattenedData, InstancePricingContext, PricingData,
 };
 
+use super::cache::PricingCache;
 use super::ebs_pricing::calculate_total_ebs_cost;
 use super::ec2_pricing::fetch_ec2_pricing_data;
 use super::filter_builder::build_ec2_filters;
 
 const TOP_N_EC2_RESULTS: usize = 2;
 
+/// Best EC2 pricing matches, and whether they came from the pricing cache
+pub(super) struct Ec2Matches {
+    pub matches: Vec<FlattenedData>,
+    pub from_cache: bool,
+}
+
 /// Build complete pricing context using functional composition
 pub async fn build_pricing_context(
     pricing_client: &pricing::Client,
@@ -53,9 +60,17 @@ pub async fn build_pricing_context(
         None
     };
 
-    let ec2_filters = build_ec2_filters(&filterable_data);
-    let ec2_raw = fetch_ec2_pricing_data(pricing_client, ec2_filters).await?;
-    let mut ec2_matches = match_ec2_instances(filterable_data.clone(), ec2_raw)?;
+    // EC2 and EBS pricing are independent queries, so run them concurrently
+    let (ec2_matches, ebs_cost) = tokio::join!(
+        fetch_ec2_matches(pricing_client, &filterable_data),
+        calculate_total_ebs_cost(
+            pricing_client,
+            ec2_client,
+            &metadata.region,
+            &metadata.instance_id,
+        )
+    );
+    let mut ec2_matches = ec2_matches?;
 
     // Override with spot price if available
     if let Some(spot_hourly_price) = spot_price {
@@ -63,20 +78,81 @@ pub async fn build_pricing_context(
             spot_hourly_price,
             "Overriding on-demand price with spot price"
         );
-        if let Some(first_match) = ec2_matches.first_mut() {
+        if let Some(first_match) = ec2_matches.matches.first_mut() {
             first_match.price_per_unit = spot_hourly_price;
         }
     }
 
-    let ebs_cost = calculate_total_ebs_cost(
-        pricing_client,
-        ec2_client,
+    combine_pricing_data(
         &metadata.region,
-        &metadata.instance_id,
+        &metadata.instance_type,
+        ec2_matches,
+        ebs_cost,
+        is_spot,
     )
-    .await;
+}
+
+/// Build the on-demand pricing context of an instance type, without a running instance to
+/// describe. EBS volumes are unknown, so only the EC2 price is included.
+pub async fn build_instance_type_pricing_context(
+    pricing_client: &pricing::Client,
+    region: &str,
+    instance_type: &str,
+) -> Option<InstancePricingContext> {
+    let details = typical_instance_details(region, instance_type);
+    let ec2_matches = fetch_ec2_matches(pricing_client, &details).await?;
+    combine_pricing_data(region, instance_type, ec2_matches, 0.0, false)
+}
 
-    combine_pricing_data(metadata, ec2_matches, ebs_cost, is_spot)
+/// Details of a typical on-demand Linux instance, matching what `describe_instance` returns for one
+pub(super) fn typical_instance_details(
+    region: &str,
+    instance_type: &str,
+) -> FilterableInstanceDetails {
+    FilterableInstanceDetails {
+        instance_type: instance_type.to_string(),
+        region: region.to_string(),
+        availability_zone: String::new(),
+        operating_system: Some("Linux".to_string()),
+        tenancy: Some("Shared".to_string()),
+        vcpu: None,
+        ebs_optimized: None,
+        capacity_status: Some("Used".to_string()),
+    }
+}
+
+/// Fetch the best EC2 matches for the instance, from the pricing cache when possible
+pub(super) async fn fetch_ec2_matches(
+    pricing_client: &pricing::Client,
+    filterable_data: &FilterableInstanceDetails,
+) -> Option<Ec2Matches> {
+    let cache = PricingCache::default();
+    if let Some(matches) = cache.lookup(filterable_data) {
+        tracing::info!(
+            instance_type = %filterable_data.instance_type,
+            "Using cached EC2 pricing"
+        );
+        let matches = matches
+            .into_iter()
+            .map(|mut data| {
+                data.ebs_optimized = filterable_data.ebs_optimized;
+                data
+            })
+            .collect();
+        return Some(Ec2Matches {
+            matches,
+            from_cache: true,
+        });
+    }
+
+    let ec2_filters = build_ec2_filters(filterable_data);
+    let ec2_raw = fetch_ec2_pricing_data(pricing_client, ec2_filters).await?;
+    let matches = match_ec2_instances(filterable_data.clone(), ec2_raw)?;
+    cache.store(filterable_data, &matches);
+    Some(Ec2Matches {
+        matches,
+        from_cache: false,
+    })
 }
 
 /// Describe EC2 instance with error handling and lifecycle detection
@@ -133,17 +209,22 @@ fn match_ec2_instances(
 
 /// Combine EC2 and EBS pricing into final context
 fn combine_pricing_data(
-    metadata: &AwsInstanceMetaData,
-    ec2_matches: Vec<FlattenedData>,
+    region: &str,
+    instance_type: &str,
+    ec2_matches: Ec2Matches,
     ebs_cost: f64,
     is_spot: bool,
 ) -> Option<InstancePricingContext> {
+    let Ec2Matches {
+        matches: ec2_matches,
+        from_cache,
+    } = ec2_matches;
     let ec2_data = ec2_matches.first().cloned()?;
 
     let ebs_data = if ebs_cost > 0.0 {
         Some(FlattenedData {
             instance_type: "EBS_TOTAL".to_string(),
-            region_code: metadata.region.clone(),
+            region_code: region.to_string(),
             vcpu: String::new(),
             memory: String::new(),
             price_per_unit: ebs_cost,
@@ -163,8 +244,11 @@ fn combine_pricing_data(
     let total = ec2_data.price_per_unit + ebs_cost;
     let best_match_score = ec2_matches.first().and_then(|m| m.match_percentage);
 
+    // the spot price is always live, only the on-demand matches can come from the cache
     let source = if is_spot {
         "Live-Spot".to_string()
+    } else if from_cache {
+        "Cached-OnDemand".to_string()
     } else {
         "Live-OnDemand".to_string()
     };
@@ -177,6 +261,6 @@ fn combine_pricing_data(
         source,
         ec2_pricing_best_matches: ec2_matches,
         match_confidence: best_match_score,
-        instance_type: metadata.instance_type.clone(),
+        instance_type: instance_type.to_string(),
     })
 }
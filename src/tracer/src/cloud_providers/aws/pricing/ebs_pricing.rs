@@ -2,6 +2,7 @@
 
 use aws_sdk_pricing as pricing;
 use aws_sdk_pricing::types::Filter as PricingFilters;
+use futures_util::future::join_all;
 use tokio_retry::strategy::ExponentialBackoff;
 use tokio_retry::Retry;
 
@@ -27,14 +28,14 @@ pub async fn calculate_total_ebs_cost(
         .await
         .unwrap_or_default();
 
-    let mut total_cost = 0.0;
-    for vol in volumes {
-        let cost = calculate_volume_cost(pricing_client, region, &vol)
-            .await
-            .unwrap_or(0.0);
-        total_cost += cost;
-    }
-    total_cost
+    let costs = join_all(
+        volumes
+            .iter()
+            .map(|vol| calculate_volume_cost(pricing_client, region, vol)),
+    )
+    .await;
+
+    costs.into_iter().map(|cost| cost.unwrap_or(0.0)).sum()
 }
 
 /// Get volume metadata for an instance
@@ -2,6 +2,7 @@ mod api;
 pub mod filtering;
 
 // New functional modules
+pub mod cache;
 pub mod client;
 pub mod context_builder;
 pub mod ebs_pricing;
@@ -9,14 +10,16 @@ pub mod ec2_client_manager;
 pub mod ec2_pricing;
 pub mod filter_builder;
 pub mod tests;
+pub mod warmup;
 
 pub use api::ApiPricingClient;
 pub use client::PricingClient;
 
 use crate::cloud_providers::aws::aws_metadata::AwsInstanceMetaData;
-use crate::cloud_providers::aws::config::AwsConfig;
+use crate::cloud_providers::aws::config::{has_aws_credential_sources, AwsConfig};
 
 use crate::cloud_providers::aws::types::pricing::{FlattenedData, InstancePricingContext};
+use warmup::{warm_up_pricing_cache, WarmupReport};
 
 pub enum PricingSource {
     Static,
@@ -25,30 +28,56 @@ pub enum PricingSource {
 }
 
 impl PricingSource {
-    pub async fn new(initialization_conf: AwsConfig) -> Self {
-        let client = PricingClient::new(initialization_conf, "us-east-1").await;
+    /// `region` is the resolved AWS region, used for the EC2 client until the instance metadata
+    /// says otherwise
+    pub async fn new(initialization_conf: AwsConfig, region: &str) -> Self {
+        if !has_aws_credential_sources() {
+            tracing::info!("No AWS credentials found, using the Tracer pricing API");
+            return PricingSource::Api(ApiPricingClient::default());
+        }
+
+        let client = PricingClient::new(initialization_conf, region).await;
 
         match client.pricing_client {
             Some(_) => PricingSource::Live(client),
-            None => PricingSource::Api(ApiPricingClient::default()),
+            None => {
+                tracing::info!(
+                    "AWS credentials could not be resolved, using the Tracer pricing API"
+                );
+                PricingSource::Api(ApiPricingClient::default())
+            }
         }
     }
 
+    /// Caches the pricing of `instance_types` in `region`, so runs on them skip the live lookup.
+    /// Returns `None` for sources that don't query AWS directly, since there is nothing to cache.
+    pub async fn warm_up(&self, region: &str, instance_types: &[String]) -> Option<WarmupReport> {
+        let PricingSource::Live(client) = self else {
+            return None;
+        };
+        let pricing_client = client.pricing_client.as_ref()?;
+        Some(warm_up_pricing_cache(pricing_client, region, instance_types).await)
+    }
+
+    /// Same as [`PricingSource::warm_up`], without blocking the caller
+    pub fn spawn_warm_up(&self, region: String, instance_types: Vec<String>) {
+        let PricingSource::Live(client) = self else {
+            return;
+        };
+        let Some(pricing_client) = client.pricing_client.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            warm_up_pricing_cache(&pricing_client, &region, &instance_types).await;
+        });
+    }
+
     pub async fn get_aws_price_for_instance(
         &self,
         metadata: &AwsInstanceMetaData,
     ) -> Option<InstancePricingContext> {
         match self {
-            PricingSource::Static => Some(InstancePricingContext {
-                ec2_pricing: FlattenedData::default(),
-                ebs_pricing: None,
-                source: "Static".into(),
-                total_hourly_cost: 0.0,
-                cost_per_minute: 0.0,
-                ec2_pricing_best_matches: vec![],
-                match_confidence: None,
-                instance_type: metadata.instance_type.clone(),
-            }),
+            PricingSource::Static => Some(static_pricing_context(&metadata.instance_type)),
             PricingSource::Live(client) => {
                 client
                     .get_instance_pricing_context_from_metadata(metadata)
@@ -61,4 +90,35 @@ impl PricingSource {
             }
         }
     }
+
+    /// Prices an instance type without running on it, e.g. for `tracer cost`.
+    /// The tracer API prices running instances only, so it returns `None` for the API source.
+    pub async fn get_aws_price_for_instance_type(
+        &self,
+        region: &str,
+        instance_type: &str,
+    ) -> Option<InstancePricingContext> {
+        match self {
+            PricingSource::Static => Some(static_pricing_context(instance_type)),
+            PricingSource::Live(client) => {
+                client
+                    .get_instance_type_pricing_context(region, instance_type)
+                    .await
+            }
+            PricingSource::Api(_) => None,
+        }
+    }
+}
+
+fn static_pricing_context(instance_type: &str) -> InstancePricingContext {
+    InstancePricingContext {
+        ec2_pricing: FlattenedData::default(),
+        ebs_pricing: None,
+        source: "Static".into(),
+        total_hourly_cost: 0.0,
+        cost_per_minute: 0.0,
+        ec2_pricing_best_matches: vec![],
+        match_confidence: None,
+        instance_type: instance_type.to_string(),
+    }
 }
@@ -176,4 +176,26 @@ mod tests {
         let result = client.get_aws_price_for_instance(&metadata).await;
         assert!(result.is_none());
     }
+
+    #[tokio::test]
+    async fn test_static_price_for_instance_type() {
+        let client = setup_client().await;
+
+        let result = client
+            .get_aws_price_for_instance_type("us-east-1", "m5.large")
+            .await
+            .unwrap();
+        assert_eq!(result.source, "Static");
+        assert_eq!(result.instance_type, "m5.large");
+    }
+
+    #[tokio::test]
+    async fn test_api_source_cannot_price_instance_type() {
+        let client = PricingSource::Api(Default::default());
+
+        let result = client
+            .get_aws_price_for_instance_type("us-east-1", "m5.large")
+            .await;
+        assert!(result.is_none());
+    }
 }
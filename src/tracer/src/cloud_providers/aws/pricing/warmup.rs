@@ -0,0 +1,61 @@
+//! Pre-fetches EC2 pricing for commonly used instance types into the pricing cache
+
+use aws_sdk_pricing as pricing;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+
+use super::cache::PricingCache;
+use super::context_builder::{fetch_ec2_matches, typical_instance_details};
+
+/// Limits the concurrent Pricing API queries, which are throttled per account
+const WARMUP_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct WarmupReport {
+    pub already_cached: Vec<String>,
+    pub fetched: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Fetches and caches the pricing of every instance type in `region` that is not cached yet
+pub async fn warm_up_pricing_cache(
+    pricing_client: &pricing::Client,
+    region: &str,
+    instance_types: &[String],
+) -> WarmupReport {
+    let cache = PricingCache::default();
+    let mut report = WarmupReport::default();
+
+    let (cached, missing): (Vec<_>, Vec<_>) = instance_types
+        .iter()
+        .map(|instance_type| typical_instance_details(region, instance_type))
+        .partition(|details| cache.lookup(details).is_some());
+    report.already_cached = cached.into_iter().map(|d| d.instance_type).collect();
+
+    let results: Vec<_> = stream::iter(missing)
+        .map(|details| async move {
+            let fetched = fetch_ec2_matches(pricing_client, &details).await.is_some();
+            (details.instance_type, fetched)
+        })
+        .buffer_unordered(WARMUP_CONCURRENCY)
+        .collect()
+        .await;
+
+    for (instance_type, fetched) in results {
+        if fetched {
+            report.fetched.push(instance_type);
+        } else {
+            report.failed.push(instance_type);
+        }
+    }
+
+    tracing::info!(
+        region,
+        already_cached = report.already_cached.len(),
+        fetched = report.fetched.len(),
+        failed = report.failed.len(),
+        "Pricing cache warm-up finished"
+    );
+
+    report
+}
@@ -0,0 +1,121 @@
+//! Resolution of the AWS region tracer works in.
+//!
+//! The region is resolved in this order, the first one set wins:
+//! 1. explicit configuration (`TRACER_AWS_REGION`, see `Config::aws_region`)
+//! 2. the `AWS_REGION` environment variable
+//! 3. the region of the EC2 instance, from the instance metadata service
+//! 4. [`DEFAULT_AWS_REGION`]
+//!
+//! Note that AWS serves the Pricing API itself from `us-east-1` only, whatever the resolved
+//! region is; the resolved region selects the prices and the EC2 endpoint.
+
+use std::fmt;
+
+use crate::cloud_providers::aws::aws_metadata::get_instance_region;
+use crate::cloud_providers::aws::config::is_ec2_host;
+
+pub const DEFAULT_AWS_REGION: &str = "us-east-1";
+const AWS_REGION_ENV_VAR: &str = "AWS_REGION";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionSource {
+    Config,
+    Environment,
+    InstanceMetadata,
+    Default,
+}
+
+impl fmt::Display for RegionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionSource::Config => write!(f, "configuration"),
+            RegionSource::Environment => write!(f, "{}", AWS_REGION_ENV_VAR),
+            RegionSource::InstanceMetadata => write!(f, "instance metadata"),
+            RegionSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedRegion {
+    pub region: String,
+    pub source: RegionSource,
+}
+
+/// Resolves the region, only querying the instance metadata service when running on EC2 and
+/// neither the configuration nor the environment set it
+pub async fn resolve_aws_region(configured: Option<&str>) -> ResolvedRegion {
+    let env_region = std::env::var(AWS_REGION_ENV_VAR).ok();
+
+    let metadata_region = if configured.is_none() && env_region.is_none() && is_ec2_host() {
+        get_instance_region().await
+    } else {
+        None
+    };
+
+    resolve_region(
+        configured,
+        env_region.as_deref(),
+        metadata_region.as_deref(),
+    )
+}
+
+fn resolve_region(
+    configured: Option<&str>,
+    env_region: Option<&str>,
+    metadata_region: Option<&str>,
+) -> ResolvedRegion {
+    let non_empty = |region: Option<&str>| region.map(str::trim).filter(|r| !r.is_empty());
+
+    let (region, source) = if let Some(region) = non_empty(configured) {
+        (region, RegionSource::Config)
+    } else if let Some(region) = non_empty(env_region) {
+        (region, RegionSource::Environment)
+    } else if let Some(region) = non_empty(metadata_region) {
+        (region, RegionSource::InstanceMetadata)
+    } else {
+        (DEFAULT_AWS_REGION, RegionSource::Default)
+    };
+
+    ResolvedRegion {
+        region: region.to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_metadata_region_wins_over_default() {
+        let resolved = resolve_region(None, None, Some("eu-west-1"));
+        assert_eq!(resolved.region, "eu-west-1");
+        assert_eq!(resolved.source, RegionSource::InstanceMetadata);
+    }
+
+    #[test]
+    fn test_resolution_order() {
+        let resolved = resolve_region(Some("eu-central-1"), Some("us-west-2"), Some("eu-west-1"));
+        assert_eq!(resolved.source, RegionSource::Config);
+        assert_eq!(resolved.region, "eu-central-1");
+
+        let resolved = resolve_region(None, Some("us-west-2"), Some("eu-west-1"));
+        assert_eq!(resolved.source, RegionSource::Environment);
+
+        let resolved = resolve_region(Some(""), None, None);
+        assert_eq!(resolved.source, RegionSource::Default);
+        assert_eq!(resolved.region, DEFAULT_AWS_REGION);
+    }
+
+    #[test]
+    fn test_configured_region_wins_over_aws_region() {
+        std::env::set_var(crate::constants::TRACER_AWS_REGION_ENV_VAR, "eu-west-3");
+        let config = crate::config::Config::default();
+        std::env::remove_var(crate::constants::TRACER_AWS_REGION_ENV_VAR);
+
+        let resolved = resolve_region(config.aws_region.as_deref(), Some("us-east-2"), None);
+        assert_eq!(resolved.region, "eu-west-3");
+        assert_eq!(resolved.source, RegionSource::Config);
+    }
+}
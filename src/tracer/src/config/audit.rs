@@ -0,0 +1,189 @@
+//! Append-only trail of the changes made to the configuration and run state of the daemon, so
+//! "why did the polling interval change" can be answered on shared machines. Each change is a
+//! JSON line in `TracerWorkDir::config_audit_file`, which is kept across runs.
+
+use crate::cli::handlers::init_arguments::USERNAME_ENV_VAR;
+use crate::utils::append_file::AppendFile;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Set by `sudo` to the user who invoked it, which `USER` no longer names
+const SUDO_USER_ENV_VAR: &str = "SUDO_USER";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub timestamp: DateTime<Utc>,
+    /// User who ran the command making the change, if known
+    pub user: Option<String>,
+    /// What made the change, e.g. `tracer init`
+    pub source: String,
+    /// Setting changed, as named by `Config::to_safe_json`
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+impl ConfigChange {
+    pub fn new(source: &str, field: &str, before: Value, after: Value) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            user: invoking_user(),
+            source: source.to_string(),
+            field: field.to_string(),
+            before,
+            after,
+        }
+    }
+}
+
+/// The settings that differ between two `Config::to_safe_json` snapshots, in name order
+pub fn config_changes(source: &str, before: &Value, after: &Value) -> Vec<ConfigChange> {
+    let empty = serde_json::Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            let new = after.get(field).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| ConfigChange::new(source, field, old, new))
+        })
+        .collect()
+}
+
+/// Appends `changes` to the audit log at `path`
+pub fn record_changes(path: &Path, changes: &[ConfigChange]) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut lines = String::new();
+    for change in changes {
+        lines.push_str(&serde_json::to_string(change)?);
+        lines.push('\n');
+    }
+    // a single write, so the changes of concurrent commands don't interleave
+    AppendFile::new(path).append(lines.as_bytes())?;
+    Ok(())
+}
+
+/// Records the changes a new daemon makes to the configuration of the previous one, saved at
+/// `daemon_config_path`, then saves `config` there in its place. Nothing is recorded for the first
+/// daemon, which has nothing to change.
+///
+/// `config` is the `Config::to_safe_json` of the daemon, as reported in `PipelineMetadata::config`.
+pub fn record_daemon_config(
+    daemon_config_path: &Path,
+    audit_path: &Path,
+    source: &str,
+    config: &Value,
+) -> Result<()> {
+    let previous: Option<Value> = std::fs::read_to_string(daemon_config_path)
+        .ok()
+        .and_then(|previous| serde_json::from_str(&previous).ok());
+    if let Some(previous) = previous {
+        record_changes(audit_path, &config_changes(source, &previous, config))?;
+    }
+    std::fs::write(daemon_config_path, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
+
+fn invoking_user() -> Option<String> {
+    [SUDO_USER_ENV_VAR, USERNAME_ENV_VAR]
+        .into_iter()
+        .find_map(|var| std::env::var(var).ok().filter(|user| !user.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_setup_change_is_recorded() {
+        let before = Config::default();
+        let mut after = before.clone();
+        after.process_polling_interval_ms = before.process_polling_interval_ms * 2;
+        after.force_procfs = !before.force_procfs;
+
+        let changes = config_changes("tracer init", &before.to_safe_json(), &after.to_safe_json());
+        let fields: Vec<_> = changes.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, vec!["force_procfs", "process_polling_interval_ms"]);
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config_audit.ndjson");
+        record_changes(&path, &changes).unwrap();
+        record_changes(&path, &[]).unwrap();
+
+        let recorded: Vec<ConfigChange> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(recorded, changes);
+        assert_eq!(recorded[1].source, "tracer init");
+        assert_eq!(
+            recorded[1].before,
+            Value::from(before.process_polling_interval_ms)
+        );
+        assert_eq!(
+            recorded[1].after,
+            Value::from(after.process_polling_interval_ms)
+        );
+    }
+
+    #[test]
+    fn test_daemon_changes_are_recorded_against_previous_daemon() {
+        let dir = TempDir::new().unwrap();
+        let daemon_config = dir.path().join("daemon_config.json");
+        let audit = dir.path().join("config_audit.ndjson");
+        let first = Config::default();
+        let second = Config {
+            force_procfs: !first.force_procfs,
+            ..first.clone()
+        };
+
+        // the first daemon has nothing to change
+        record_daemon_config(&daemon_config, &audit, "tracer init", &first.to_safe_json()).unwrap();
+        assert!(!audit.exists());
+
+        record_daemon_config(
+            &daemon_config,
+            &audit,
+            "tracer init",
+            &second.to_safe_json(),
+        )
+        .unwrap();
+        // started again with the same settings
+        record_daemon_config(
+            &daemon_config,
+            &audit,
+            "tracer init",
+            &second.to_safe_json(),
+        )
+        .unwrap();
+
+        let recorded: Vec<ConfigChange> = std::fs::read_to_string(&audit)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].field, "force_procfs");
+        assert_eq!(recorded[0].before, Value::from(first.force_procfs));
+        assert_eq!(recorded[0].after, Value::from(second.force_procfs));
+    }
+
+    #[test]
+    fn test_unchanged_config_records_nothing() {
+        let config = Config::default().to_safe_json();
+        assert!(config_changes("tracer init", &config, &config).is_empty());
+    }
+}
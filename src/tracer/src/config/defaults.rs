@@ -1,9 +1,33 @@
+use crate::client::events::RunNameScheme;
 use crate::config::Config;
 use crate::constants::{
     BATCH_SUBMISSION_INTERVAL_MS, BATCH_SUBMISSION_RETRIES, BATCH_SUBMISSION_RETRY_DELAY_MS,
-    PROCESS_METRICS_SEND_INTERVAL_MS, PROCESS_POLLING_INTERVAL_MS,
+    BUILTIN_RULES_ENV_VAR, CA_BUNDLE_ENV_VAR, CLOCK_SKEW_CHECK_INTERVAL_ENV_VAR,
+    CLOCK_SKEW_CHECK_INTERVAL_MS, CLOCK_SKEW_THRESHOLD_ENV_VAR, CLOCK_SKEW_THRESHOLD_MS,
+    CONTAINER_DISPLAY_NAME_ENV_VAR, DAEMON_ADDRESS_ENV_VAR, DAEMON_TOKEN_ENV_VAR,
+    DISABLED_POLLERS_ENV_VAR, DISABLE_SENTRY_ENV_VAR, DISK_MOUNTS_ENV_VAR, EVENTS_NDJSON_ENV_VAR,
+    EVENTS_SQLITE_ENV_VAR, FORCE_PROCFS_ENV_VAR, HEARTBEAT_INTERVAL_ENV_VAR, HEARTBEAT_INTERVAL_MS,
+    LOCAL_ONLY_ENV_VAR, MATCH_RULES_ENV_VAR, MISSED_EXIT_CONFIRMATION_POLLS,
+    MISSED_EXIT_CONFIRMATION_POLLS_ENV_VAR, OUTPUT_BUFFER_LINES, OUTPUT_BUFFER_LINES_ENV_VAR,
+    PRICING_WARMUP_INSTANCE_TYPES, PRICING_WARMUP_INSTANCE_TYPES_ENV_VAR,
+    PROCESS_EXIT_GRACE_PERIOD_ENV_VAR, PROCESS_EXIT_GRACE_PERIOD_MS,
+    PROCESS_METRICS_SEND_INTERVAL_MS, PROCESS_POLLING_INTERVAL_MS, PROCESS_TREE_ENV_VAR,
+    RULES_FILE_ENV_VAR, RUN_LOGS_CLEANUP_INTERVAL_MS, RUN_LOGS_MAX_TOTAL_BYTES,
+    RUN_LOGS_MAX_TOTAL_BYTES_ENV_VAR, RUN_LOGS_RETENTION_ENV_VAR, RUN_LOGS_RETENTION_SECS,
+    RUN_NAME_SCHEME_ENV_VAR, SENTRY_SCRUB_PATTERNS_ENV_VAR, STORAGE_STALL_THRESHOLD_ENV_VAR,
+    STORAGE_STALL_THRESHOLD_MS, SWAP_ALERT_THRESHOLD_BYTES, SWAP_ALERT_THRESHOLD_ENV_VAR,
+    TAG_SCHEMA_ENV_VAR, TAG_SCHEMA_POLICY_ENV_VAR, TAIL_REDIRECTED_OUTPUT_ENV_VAR,
+    TOOL_ERROR_PATTERNS_ENV_VAR, TOOL_METRICS_WINDOW_ENV_VAR, TOOL_METRICS_WINDOW_MS,
+    TRACER_AWS_REGION_ENV_VAR, UPDATE_PUBLIC_KEY_ENV_VAR, WRAPPER_PATTERNS_ENV_VAR,
 };
+use crate::extracts::containers::display_name::ContainerDisplayName;
 use crate::process_identification::constants::DEFAULT_DAEMON_PORT;
+use crate::process_identification::target_process::wrapper::default_wrapper_patterns;
+use crate::process_identification::types::tag_schema::TagSchemaPolicy;
+use std::fmt::Display;
+use std::num::NonZeroU64;
+use std::str::FromStr;
+use tracing::warn;
 
 impl Default for Config {
     fn default() -> Self {
@@ -13,8 +37,409 @@ impl Default for Config {
             batch_submission_retries: BATCH_SUBMISSION_RETRIES,
             batch_submission_retry_delay_ms: BATCH_SUBMISSION_RETRY_DELAY_MS,
             process_metrics_send_interval_ms: PROCESS_METRICS_SEND_INTERVAL_MS,
+            tool_metrics_window_ms: tool_metrics_window_from_env(),
+            swap_alert_threshold_bytes: swap_alert_threshold_from_env(),
+            storage_stall_threshold_ms: storage_stall_threshold_from_env(),
+            clock_skew_threshold_ms: clock_skew_threshold_from_env(),
+            clock_skew_check_interval_ms: clock_skew_check_interval_from_env(),
+            missed_exit_confirmation_polls: missed_exit_confirmation_polls_from_env(),
+            disk_mounts: std::env::var(DISK_MOUNTS_ENV_VAR)
+                .map(|mounts| comma_separated(&mounts))
+                .unwrap_or_default(),
+            process_exit_grace_period_ms: process_exit_grace_period_from_env(),
+            heartbeat_interval_ms: heartbeat_interval_from_env(),
+            run_logs_cleanup_interval_ms: RUN_LOGS_CLEANUP_INTERVAL_MS,
+            run_logs_retention_secs: run_logs_retention_from_env(),
+            run_logs_max_total_bytes: run_logs_max_total_bytes_from_env(),
+            container_display_name: container_display_name_from_env(),
+            pricing_warmup_instance_types: pricing_warmup_instance_types_from_env(),
+            aws_region: std::env::var(TRACER_AWS_REGION_ENV_VAR)
+                .ok()
+                .filter(|region| !region.is_empty()),
+            ca_bundle: std::env::var(CA_BUNDLE_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            wrapper_patterns: default_wrapper_patterns(),
+            wrapper_patterns_file: std::env::var(WRAPPER_PATTERNS_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            write_run_summary: true,
+            process_tree_in_events: std::env::var(PROCESS_TREE_ENV_VAR)
+                .is_ok_and(|value| matches!(value.as_str(), "1" | "true")),
+            run_name_scheme: run_name_scheme_from_env(),
 
-            server: format!("127.0.0.1:{}", DEFAULT_DAEMON_PORT),
+            server: daemon_address(),
+            daemon_token: std::env::var(DAEMON_TOKEN_ENV_VAR)
+                .ok()
+                .filter(|token| !token.is_empty()),
+            force_procfs: std::env::var(FORCE_PROCFS_ENV_VAR)
+                .is_ok_and(|value| matches!(value.as_str(), "1" | "true")),
+            enable_syslog: poller_enabled("syslog"),
+            enable_containers: poller_enabled("containers"),
+            enable_gpu: poller_enabled("gpu"),
+            tail_redirected_output: std::env::var(TAIL_REDIRECTED_OUTPUT_ENV_VAR)
+                .is_ok_and(|value| matches!(value.as_str(), "1" | "true")),
+            tool_error_patterns_file: std::env::var(TOOL_ERROR_PATTERNS_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            output_buffer_lines: output_buffer_lines_from_env(),
+            rules_file: std::env::var(RULES_FILE_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            match_rules: match_rules_from_env(),
+            builtin_rules: !std::env::var(BUILTIN_RULES_ENV_VAR)
+                .is_ok_and(|value| matches!(value.as_str(), "0" | "false")),
+            update_public_key: std::env::var(UPDATE_PUBLIC_KEY_ENV_VAR)
+                .ok()
+                .filter(|key| !key.is_empty()),
+            events_sqlite: std::env::var(EVENTS_SQLITE_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            events_ndjson: std::env::var(EVENTS_NDJSON_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            local_only: std::env::var(LOCAL_ONLY_ENV_VAR)
+                .is_ok_and(|value| matches!(value.as_str(), "1" | "true")),
+            tag_schema_file: std::env::var(TAG_SCHEMA_ENV_VAR)
+                .ok()
+                .filter(|path| !path.is_empty()),
+            tag_schema_policy: tag_schema_policy_from_env(),
+            disable_sentry: std::env::var(DISABLE_SENTRY_ENV_VAR)
+                .is_ok_and(|value| matches!(value.as_str(), "1" | "true")),
+            sentry_scrub_patterns: std::env::var(SENTRY_SCRUB_PATTERNS_ENV_VAR)
+                .map(|patterns| comma_separated(&patterns))
+                .unwrap_or_default(),
         }
     }
 }
+
+/// Whether `poller` is not listed in `TRACER_DISABLED_POLLERS`
+fn poller_enabled(poller: &str) -> bool {
+    !std::env::var(DISABLED_POLLERS_ENV_VAR).is_ok_and(|disabled| {
+        disabled
+            .split(',')
+            .any(|disabled| disabled.trim().eq_ignore_ascii_case(poller))
+    })
+}
+
+/// Address of the daemon control server, from `TRACER_DAEMON_ADDRESS` or the default
+pub fn daemon_address() -> String {
+    std::env::var(DAEMON_ADDRESS_ENV_VAR)
+        .ok()
+        .filter(|address| !address.is_empty())
+        .unwrap_or_else(|| format!("127.0.0.1:{}", DEFAULT_DAEMON_PORT))
+}
+
+/// The non-empty items of a comma-separated list
+fn comma_separated(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// The value of the environment variable `var`, or `default` if it is unset or invalid
+fn parse_env_var<T>(var: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Ok(value) = std::env::var(var) else {
+        return default;
+    };
+    value.parse().unwrap_or_else(|e| {
+        warn!("Ignoring {}: {}", var, e);
+        default
+    })
+}
+
+fn run_name_scheme_from_env() -> RunNameScheme {
+    parse_env_var(RUN_NAME_SCHEME_ENV_VAR, RunNameScheme::default())
+}
+
+fn tag_schema_policy_from_env() -> TagSchemaPolicy {
+    parse_env_var(TAG_SCHEMA_POLICY_ENV_VAR, TagSchemaPolicy::default())
+}
+
+fn tool_metrics_window_from_env() -> u64 {
+    parse_env_var(TOOL_METRICS_WINDOW_ENV_VAR, TOOL_METRICS_WINDOW_MS)
+}
+
+/// Worker intervals must not be zero, `tokio::time::interval` panics on it
+fn nonzero_interval_from_env(var: &str, default: u64) -> u64 {
+    let default = NonZeroU64::new(default).expect("default interval is not zero");
+    parse_env_var(var, default).get()
+}
+
+fn heartbeat_interval_from_env() -> u64 {
+    nonzero_interval_from_env(HEARTBEAT_INTERVAL_ENV_VAR, HEARTBEAT_INTERVAL_MS)
+}
+
+fn process_exit_grace_period_from_env() -> u64 {
+    parse_env_var(
+        PROCESS_EXIT_GRACE_PERIOD_ENV_VAR,
+        PROCESS_EXIT_GRACE_PERIOD_MS,
+    )
+}
+
+/// `;`-separated, as `cmd-contains=` and `path-glob=` values may hold commas
+fn match_rules_from_env() -> Vec<String> {
+    std::env::var(MATCH_RULES_ENV_VAR)
+        .map(|rules| {
+            rules
+                .split(';')
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn swap_alert_threshold_from_env() -> u64 {
+    parse_env_var(SWAP_ALERT_THRESHOLD_ENV_VAR, SWAP_ALERT_THRESHOLD_BYTES)
+}
+
+fn storage_stall_threshold_from_env() -> u64 {
+    parse_env_var(STORAGE_STALL_THRESHOLD_ENV_VAR, STORAGE_STALL_THRESHOLD_MS)
+}
+
+fn missed_exit_confirmation_polls_from_env() -> u32 {
+    parse_env_var(
+        MISSED_EXIT_CONFIRMATION_POLLS_ENV_VAR,
+        MISSED_EXIT_CONFIRMATION_POLLS,
+    )
+}
+
+fn output_buffer_lines_from_env() -> usize {
+    parse_env_var(OUTPUT_BUFFER_LINES_ENV_VAR, OUTPUT_BUFFER_LINES)
+}
+
+fn clock_skew_threshold_from_env() -> u64 {
+    parse_env_var(CLOCK_SKEW_THRESHOLD_ENV_VAR, CLOCK_SKEW_THRESHOLD_MS)
+}
+
+fn clock_skew_check_interval_from_env() -> u64 {
+    nonzero_interval_from_env(
+        CLOCK_SKEW_CHECK_INTERVAL_ENV_VAR,
+        CLOCK_SKEW_CHECK_INTERVAL_MS,
+    )
+}
+
+fn container_display_name_from_env() -> ContainerDisplayName {
+    parse_env_var(
+        CONTAINER_DISPLAY_NAME_ENV_VAR,
+        ContainerDisplayName::default(),
+    )
+}
+
+/// `TRACER_PRICING_WARMUP_INSTANCE_TYPES` if set, empty turning the warm-up off, else the
+/// common instance types
+fn pricing_warmup_instance_types_from_env() -> Vec<String> {
+    match std::env::var(PRICING_WARMUP_INSTANCE_TYPES_ENV_VAR) {
+        Ok(instance_types) => comma_separated(&instance_types),
+        Err(_) => PRICING_WARMUP_INSTANCE_TYPES
+            .iter()
+            .map(|instance_type| instance_type.to_string())
+            .collect(),
+    }
+}
+
+fn run_logs_retention_from_env() -> u64 {
+    parse_env_var(RUN_LOGS_RETENTION_ENV_VAR, RUN_LOGS_RETENTION_SECS)
+}
+
+fn run_logs_max_total_bytes_from_env() -> u64 {
+    parse_env_var(RUN_LOGS_MAX_TOTAL_BYTES_ENV_VAR, RUN_LOGS_MAX_TOTAL_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_display_name_from_env() {
+        std::env::set_var(CONTAINER_DISPLAY_NAME_ENV_VAR, "prefix_image");
+        assert_eq!(
+            container_display_name_from_env(),
+            ContainerDisplayName::PrefixImage
+        );
+        std::env::set_var(CONTAINER_DISPLAY_NAME_ENV_VAR, "image");
+        assert_eq!(
+            container_display_name_from_env(),
+            ContainerDisplayName::Unchanged
+        );
+        std::env::remove_var(CONTAINER_DISPLAY_NAME_ENV_VAR);
+        assert_eq!(
+            container_display_name_from_env(),
+            ContainerDisplayName::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_pricing_warmup_instance_types_from_env() {
+        std::env::set_var(
+            PRICING_WARMUP_INSTANCE_TYPES_ENV_VAR,
+            "r5.large, ,r5.xlarge",
+        );
+        assert_eq!(
+            pricing_warmup_instance_types_from_env(),
+            vec!["r5.large", "r5.xlarge"]
+        );
+        std::env::set_var(PRICING_WARMUP_INSTANCE_TYPES_ENV_VAR, "");
+        assert!(pricing_warmup_instance_types_from_env().is_empty());
+        std::env::remove_var(PRICING_WARMUP_INSTANCE_TYPES_ENV_VAR);
+        assert_eq!(
+            pricing_warmup_instance_types_from_env().len(),
+            PRICING_WARMUP_INSTANCE_TYPES.len()
+        );
+    }
+
+    #[test]
+    fn test_run_logs_retention_from_env() {
+        std::env::set_var(RUN_LOGS_RETENTION_ENV_VAR, "3600");
+        assert_eq!(run_logs_retention_from_env(), 3600);
+        std::env::set_var(RUN_LOGS_RETENTION_ENV_VAR, "a week");
+        assert_eq!(run_logs_retention_from_env(), RUN_LOGS_RETENTION_SECS);
+        std::env::remove_var(RUN_LOGS_RETENTION_ENV_VAR);
+        assert_eq!(run_logs_retention_from_env(), RUN_LOGS_RETENTION_SECS);
+    }
+
+    #[test]
+    fn test_run_logs_max_total_bytes_from_env() {
+        std::env::set_var(RUN_LOGS_MAX_TOTAL_BYTES_ENV_VAR, "1048576");
+        assert_eq!(run_logs_max_total_bytes_from_env(), 1024 * 1024);
+        std::env::set_var(RUN_LOGS_MAX_TOTAL_BYTES_ENV_VAR, "-1");
+        assert_eq!(
+            run_logs_max_total_bytes_from_env(),
+            RUN_LOGS_MAX_TOTAL_BYTES
+        );
+        std::env::remove_var(RUN_LOGS_MAX_TOTAL_BYTES_ENV_VAR);
+        assert_eq!(
+            run_logs_max_total_bytes_from_env(),
+            RUN_LOGS_MAX_TOTAL_BYTES
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_interval_from_env() {
+        std::env::set_var(HEARTBEAT_INTERVAL_ENV_VAR, "5000");
+        assert_eq!(heartbeat_interval_from_env(), 5000);
+        std::env::set_var(HEARTBEAT_INTERVAL_ENV_VAR, "1m");
+        assert_eq!(heartbeat_interval_from_env(), HEARTBEAT_INTERVAL_MS);
+        std::env::set_var(HEARTBEAT_INTERVAL_ENV_VAR, "0");
+        assert_eq!(heartbeat_interval_from_env(), HEARTBEAT_INTERVAL_MS);
+        std::env::remove_var(HEARTBEAT_INTERVAL_ENV_VAR);
+        assert_eq!(heartbeat_interval_from_env(), HEARTBEAT_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_process_exit_grace_period_from_env() {
+        std::env::set_var(PROCESS_EXIT_GRACE_PERIOD_ENV_VAR, "0");
+        assert_eq!(process_exit_grace_period_from_env(), 0);
+        std::env::set_var(PROCESS_EXIT_GRACE_PERIOD_ENV_VAR, "2s");
+        assert_eq!(
+            process_exit_grace_period_from_env(),
+            PROCESS_EXIT_GRACE_PERIOD_MS
+        );
+        std::env::remove_var(PROCESS_EXIT_GRACE_PERIOD_ENV_VAR);
+        assert_eq!(
+            process_exit_grace_period_from_env(),
+            PROCESS_EXIT_GRACE_PERIOD_MS
+        );
+    }
+
+    #[test]
+    fn test_match_rules_from_env() {
+        std::env::set_var(
+            MATCH_RULES_ENV_VAR,
+            "name=STAR; path-glob=/opt/{bwa,bowtie2}/*;",
+        );
+        assert_eq!(
+            match_rules_from_env(),
+            vec!["name=STAR", "path-glob=/opt/{bwa,bowtie2}/*"]
+        );
+        std::env::remove_var(MATCH_RULES_ENV_VAR);
+        assert!(match_rules_from_env().is_empty());
+    }
+
+    #[test]
+    fn test_swap_alert_threshold_from_env() {
+        std::env::set_var(SWAP_ALERT_THRESHOLD_ENV_VAR, "0");
+        assert_eq!(swap_alert_threshold_from_env(), 0);
+        std::env::set_var(SWAP_ALERT_THRESHOLD_ENV_VAR, "512M");
+        assert_eq!(swap_alert_threshold_from_env(), SWAP_ALERT_THRESHOLD_BYTES);
+        std::env::remove_var(SWAP_ALERT_THRESHOLD_ENV_VAR);
+        assert_eq!(swap_alert_threshold_from_env(), SWAP_ALERT_THRESHOLD_BYTES);
+    }
+
+    #[test]
+    fn test_storage_stall_threshold_from_env() {
+        std::env::set_var(STORAGE_STALL_THRESHOLD_ENV_VAR, "0");
+        assert_eq!(storage_stall_threshold_from_env(), 0);
+        std::env::set_var(STORAGE_STALL_THRESHOLD_ENV_VAR, "1m");
+        assert_eq!(
+            storage_stall_threshold_from_env(),
+            STORAGE_STALL_THRESHOLD_MS
+        );
+        std::env::remove_var(STORAGE_STALL_THRESHOLD_ENV_VAR);
+        assert_eq!(
+            storage_stall_threshold_from_env(),
+            STORAGE_STALL_THRESHOLD_MS
+        );
+    }
+
+    #[test]
+    fn test_missed_exit_confirmation_polls_from_env() {
+        std::env::set_var(MISSED_EXIT_CONFIRMATION_POLLS_ENV_VAR, "0");
+        assert_eq!(missed_exit_confirmation_polls_from_env(), 0);
+        std::env::set_var(MISSED_EXIT_CONFIRMATION_POLLS_ENV_VAR, "-1");
+        assert_eq!(
+            missed_exit_confirmation_polls_from_env(),
+            MISSED_EXIT_CONFIRMATION_POLLS
+        );
+        std::env::remove_var(MISSED_EXIT_CONFIRMATION_POLLS_ENV_VAR);
+        assert_eq!(
+            missed_exit_confirmation_polls_from_env(),
+            MISSED_EXIT_CONFIRMATION_POLLS
+        );
+    }
+
+    #[test]
+    fn test_output_buffer_lines_from_env() {
+        std::env::set_var(OUTPUT_BUFFER_LINES_ENV_VAR, "50");
+        assert_eq!(output_buffer_lines_from_env(), 50);
+        std::env::set_var(OUTPUT_BUFFER_LINES_ENV_VAR, "many");
+        assert_eq!(output_buffer_lines_from_env(), OUTPUT_BUFFER_LINES);
+        std::env::remove_var(OUTPUT_BUFFER_LINES_ENV_VAR);
+        assert_eq!(output_buffer_lines_from_env(), OUTPUT_BUFFER_LINES);
+    }
+
+    #[test]
+    fn test_clock_skew_threshold_from_env() {
+        std::env::set_var(CLOCK_SKEW_THRESHOLD_ENV_VAR, "0");
+        assert_eq!(clock_skew_threshold_from_env(), 0);
+        std::env::set_var(CLOCK_SKEW_THRESHOLD_ENV_VAR, "5s");
+        assert_eq!(clock_skew_threshold_from_env(), CLOCK_SKEW_THRESHOLD_MS);
+        std::env::remove_var(CLOCK_SKEW_THRESHOLD_ENV_VAR);
+        assert_eq!(clock_skew_threshold_from_env(), CLOCK_SKEW_THRESHOLD_MS);
+    }
+
+    #[test]
+    fn test_clock_skew_check_interval_from_env() {
+        std::env::set_var(CLOCK_SKEW_CHECK_INTERVAL_ENV_VAR, "60000");
+        assert_eq!(clock_skew_check_interval_from_env(), 60000);
+        std::env::set_var(CLOCK_SKEW_CHECK_INTERVAL_ENV_VAR, "0");
+        assert_eq!(
+            clock_skew_check_interval_from_env(),
+            CLOCK_SKEW_CHECK_INTERVAL_MS
+        );
+        std::env::remove_var(CLOCK_SKEW_CHECK_INTERVAL_ENV_VAR);
+        assert_eq!(
+            clock_skew_check_interval_from_env(),
+            CLOCK_SKEW_CHECK_INTERVAL_MS
+        );
+    }
+}
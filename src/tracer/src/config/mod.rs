@@ -1,4 +1,9 @@
+pub mod audit;
 pub mod defaults;
+use crate::client::events::RunNameScheme;
+use crate::extracts::containers::display_name::ContainerDisplayName;
+use crate::process_identification::target_process::wrapper::WrapperPattern;
+use crate::process_identification::types::tag_schema::TagSchemaPolicy;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
@@ -9,7 +14,146 @@ pub struct Config {
     pub batch_submission_retries: u64,
     pub batch_submission_retry_delay_ms: u64,
     pub process_metrics_send_interval_ms: u64,
+    /// Window the metrics of each tool are aggregated over, so a single `ToolMetricEvent` with
+    /// their min/avg/max is emitted per window rather than one per poll. `0` emits the raw
+    /// samples, e.g. when debugging. Defaults to `TRACER_TOOL_METRICS_WINDOW_MS`.
+    pub tool_metrics_window_ms: u64,
+    /// Swap usage of a tool above which an alert is raised, as a swapping tool is a common
+    /// cause of slow pipelines. `0` disables the alert. Defaults to
+    /// `TRACER_SWAP_ALERT_THRESHOLD_BYTES`, or 512 MiB.
+    pub swap_alert_threshold_bytes: u64,
+    /// How long a tool may stay in uninterruptible sleep while a network file system (NFS,
+    /// Lustre, ...) is mounted before an alert reports a probable storage stall. `0` disables
+    /// the alert. Defaults to `TRACER_STORAGE_STALL_THRESHOLD_MS`, or a minute.
+    pub storage_stall_threshold_ms: u64,
+    /// Difference between the local clock, which timestamps the events, and the real time above
+    /// which an alert reports the clock as skewed. `0` disables the check. Defaults to
+    /// `TRACER_CLOCK_SKEW_THRESHOLD_MS`.
+    pub clock_skew_threshold_ms: u64,
+    /// Interval of the clock skew checks, the first one being made when the daemon starts.
+    /// Defaults to `TRACER_CLOCK_SKEW_CHECK_INTERVAL_MS`, or 10 minutes.
+    pub clock_skew_check_interval_ms: u64,
+    /// Consecutive process polls a monitored tool must be missing from the process table before
+    /// its exit is recorded, for kernels whose exit events are unreliable. `0` only relies on
+    /// exit events, so tools whose exit is missed stay running until the run ends. Defaults to
+    /// `TRACER_MISSED_EXIT_CONFIRMATION_POLLS`.
+    pub missed_exit_confirmation_polls: u32,
+    /// Mount points whose disks the system metrics report, including the mounts below them,
+    /// e.g. to leave out container overlays. All disks if empty, the default. Defaults to the
+    /// comma-separated `TRACER_DISK_MOUNTS`.
+    pub disk_mounts: Vec<String>,
+    /// How long the completion of a tool that exited is deferred, while processes it forked are
+    /// still running, after the last of them started or exited, so the work they carry on
+    /// counts towards the tool. `0` finalizes tools as soon as they exit. Defaults to
+    /// `TRACER_PROCESS_EXIT_GRACE_PERIOD_MS`.
+    pub process_exit_grace_period_ms: u64,
+    /// Interval of the heartbeats emitted while a run records no other events. Defaults to
+    /// `TRACER_HEARTBEAT_INTERVAL_MS`, or a minute.
+    pub heartbeat_interval_ms: u64,
+    pub run_logs_cleanup_interval_ms: u64,
+    /// Age after which the logs of past runs are removed. Defaults to
+    /// `TRACER_RUN_LOGS_RETENTION_SECS`, or a week.
+    pub run_logs_retention_secs: u64,
+    /// Total size of the logs of past runs above which the oldest are removed. Defaults to
+    /// `TRACER_RUN_LOGS_MAX_TOTAL_BYTES`, or 512 MiB.
+    pub run_logs_max_total_bytes: u64,
+    /// How the display names of tools running in containers are derived from their image.
+    /// Defaults to `TRACER_CONTAINER_DISPLAY_NAME`, or the rule's display name as is.
+    pub container_display_name: ContainerDisplayName,
+    /// Instance types whose pricing is cached in the region of the instance when the daemon
+    /// starts, so runs on them skip the live lookup. Defaults to the comma-separated
+    /// `TRACER_PRICING_WARMUP_INSTANCE_TYPES`, empty turning the warm-up off, or common types.
+    pub pricing_warmup_instance_types: Vec<String>,
+    /// Overrides `AWS_REGION` and the instance metadata region, see `cloud_providers::aws::region`.
+    /// Defaults to `TRACER_AWS_REGION`.
+    pub aws_region: Option<String>,
+    /// PEM bundle of CA certificates trusted in addition to the built-in roots by the HTTP
+    /// clients, for services behind an internal CA. Defaults to `TRACER_CA_BUNDLE`.
+    pub ca_bundle: Option<String>,
+    /// Launchers (e.g. `srun`, `bsub`, `conda run`) whose wrapped command is matched against the
+    /// target rules instead of the launcher itself
+    pub wrapper_patterns: Vec<WrapperPattern>,
+    /// YAML file with wrapper patterns used in addition to `wrapper_patterns`, see
+    /// `load_wrapper_patterns_file`. Defaults to `TRACER_WRAPPER_PATTERNS`.
+    pub wrapper_patterns_file: Option<String>,
+    /// Writes the summary of each finished run to `run_summary.json` in the work dir
+    pub write_run_summary: bool,
+    /// Attaches the chain of ancestors observed by the process watcher to tool events, to debug
+    /// how a process was attributed. Off by default since it is bulky. Defaults to
+    /// `TRACER_PROCESS_TREE`.
+    pub process_tree_in_events: bool,
+    /// How runs started without `--run-name` are named. Defaults to `TRACER_RUN_NAME_SCHEME`,
+    /// or random names if unset.
+    pub run_name_scheme: RunNameScheme,
+    /// `host:port` the daemon control server listens on and the CLI connects to. Defaults to
+    /// `TRACER_DAEMON_ADDRESS`, or the loopback interface on the default port.
     pub server: String,
+    /// Shared secret the CLI presents and the daemon control server requires on every request,
+    /// e.g. when the server is reachable from other containers. Defaults to `TRACER_DAEMON_TOKEN`.
+    pub daemon_token: Option<String>,
+    /// Polls procfs for processes even if eBPF is available. Defaults to `TRACER_FORCE_PROCFS`,
+    /// and is enabled by `tracer init --force-procfs`.
+    pub force_procfs: bool,
+    /// Records the error lines pipelines print to stdout/stderr as syslog events. Defaults to
+    /// on, unless `syslog` is in the comma-separated `TRACER_DISABLED_POLLERS`.
+    pub enable_syslog: bool,
+    /// Watches Docker for the containers tools run in, see `DockerWatcher`. Defaults to on,
+    /// unless `containers` is in `TRACER_DISABLED_POLLERS`.
+    pub enable_containers: bool,
+    /// Collects GPU metrics with the system metrics, see `GpuMonitor`. Defaults to on, unless
+    /// `gpu` is in `TRACER_DISABLED_POLLERS`.
+    pub enable_gpu: bool,
+    /// Tails the files tools redirect their output to on their command line (`> tool.log 2>&1`)
+    /// and records their error lines like pipeline output, as long as `enable_syslog` is on.
+    /// Defaults to `TRACER_TAIL_REDIRECTED_OUTPUT`.
+    pub tail_redirected_output: bool,
+    /// YAML file with error patterns matched against the output of tools before the built-in
+    /// ones, see `load_patterns_file`. Defaults to `TRACER_TOOL_ERROR_PATTERNS`.
+    pub tool_error_patterns_file: Option<String>,
+    /// Error lines of each redirected output file recorded per poll. A tool printing more, e.g.
+    /// stuck in a loop, has its oldest lines dropped and replaced by a marker. Defaults to
+    /// `TRACER_OUTPUT_BUFFER_LINES`.
+    pub output_buffer_lines: usize,
+    /// YAML rules files or directories with additional targets, separated like `PATH` (`:` on
+    /// Unix) and merged in order, see `load_rules_files`. Defaults to `TRACER_RULES_FILE`, and is
+    /// overridden by `tracer init --rules-file`.
+    pub rules_file: Option<String>,
+    /// Targets matched before the rules files and the built-in rules, see `parse_inline_match`.
+    /// Defaults to the `;`-separated `TRACER_MATCH`, and is overridden by `tracer init --match`.
+    pub match_rules: Vec<String>,
+    /// Whether the built-in rules for common bioinformatics tools (STAR, bwa, samtools, GATK,
+    /// ...) are loaded. The rules files are matched first either way, so they can extend or
+    /// override the built-in rules; turning these off leaves only the rules files. Defaults to
+    /// true unless `TRACER_BUILTIN_RULES` is `0`/`false`.
+    pub builtin_rules: bool,
+    /// Base64-encoded Ed25519 key whose signature `tracer update` requires on downloaded
+    /// releases, e.g. for a self-hosted release bucket. Defaults to `TRACER_UPDATE_PUBLIC_KEY`;
+    /// without it only the published checksum is verified.
+    pub update_public_key: Option<String>,
+    /// SQLite database (created if missing) the events are written to instead of being sent to
+    /// Tracer, for single nodes without a database server, see `SqliteWriter`. Defaults to
+    /// `TRACER_EVENTS_SQLITE`.
+    pub events_sqlite: Option<String>,
+    /// File or fifo (`-` for stdout) every event is written to as a JSON line instead of being
+    /// sent to Tracer, taking precedence over `events_sqlite`. Defaults to
+    /// `TRACER_EVENTS_NDJSON`, and is overridden by `tracer init --events-ndjson`.
+    pub events_ndjson: Option<String>,
+    /// Records events locally only, to `events_ndjson`, `events_sqlite` or else the work dir,
+    /// without logging in. Defaults to `TRACER_LOCAL_ONLY`, and is enabled by
+    /// `tracer init --local-only` or when no token is found.
+    pub local_only: bool,
+    /// YAML file constraining the values of pipeline tags, see `TagSchema`. Defaults to
+    /// `TRACER_TAG_SCHEMA`; without it any value is accepted.
+    pub tag_schema_file: Option<String>,
+    /// How `tracer init` treats tags the schema doesn't allow. Defaults to
+    /// `TRACER_TAG_SCHEMA_POLICY`, or normalizing if unset.
+    pub tag_schema_policy: TagSchemaPolicy,
+    /// Turns off error reporting to Sentry, e.g. for air-gapped deployments. Defaults to
+    /// `TRACER_DISABLE_SENTRY`.
+    pub disable_sentry: bool,
+    /// Regexes of secrets redacted from Sentry events on top of the built-in ones, see
+    /// `Scrubber`. Defaults to the comma-separated `TRACER_SENTRY_SCRUB_PATTERNS`.
+    pub sentry_scrub_patterns: Vec<String>,
 }
 
 impl Config {
@@ -20,7 +164,47 @@ impl Config {
             "batch_submission_retries": self.batch_submission_retries,
             "batch_submission_retry_delay_ms": self.batch_submission_retry_delay_ms,
             "process_metrics_send_interval_ms": self.process_metrics_send_interval_ms,
-            "server": self.server
+            "tool_metrics_window_ms": self.tool_metrics_window_ms,
+            "swap_alert_threshold_bytes": self.swap_alert_threshold_bytes,
+            "storage_stall_threshold_ms": self.storage_stall_threshold_ms,
+            "clock_skew_threshold_ms": self.clock_skew_threshold_ms,
+            "clock_skew_check_interval_ms": self.clock_skew_check_interval_ms,
+            "missed_exit_confirmation_polls": self.missed_exit_confirmation_polls,
+            "disk_mounts": self.disk_mounts,
+            "process_exit_grace_period_ms": self.process_exit_grace_period_ms,
+            "heartbeat_interval_ms": self.heartbeat_interval_ms,
+            "run_logs_cleanup_interval_ms": self.run_logs_cleanup_interval_ms,
+            "run_logs_retention_secs": self.run_logs_retention_secs,
+            "run_logs_max_total_bytes": self.run_logs_max_total_bytes,
+            "container_display_name": self.container_display_name,
+            "pricing_warmup_instance_types": self.pricing_warmup_instance_types,
+            "aws_region": self.aws_region,
+            "ca_bundle": self.ca_bundle,
+            "wrapper_patterns": self.wrapper_patterns,
+            "wrapper_patterns_file": self.wrapper_patterns_file,
+            "write_run_summary": self.write_run_summary,
+            "process_tree_in_events": self.process_tree_in_events,
+            "run_name_scheme": self.run_name_scheme,
+            "server": self.server,
+            "daemon_token": self.daemon_token.is_some(),
+            "force_procfs": self.force_procfs,
+            "enable_syslog": self.enable_syslog,
+            "enable_containers": self.enable_containers,
+            "enable_gpu": self.enable_gpu,
+            "tail_redirected_output": self.tail_redirected_output,
+            "tool_error_patterns_file": self.tool_error_patterns_file,
+            "output_buffer_lines": self.output_buffer_lines,
+            "rules_file": self.rules_file,
+            "match_rules": self.match_rules,
+            "builtin_rules": self.builtin_rules,
+            "update_public_key": self.update_public_key,
+            "events_sqlite": self.events_sqlite,
+            "events_ndjson": self.events_ndjson,
+            "local_only": self.local_only,
+            "tag_schema_file": self.tag_schema_file,
+            "tag_schema_policy": self.tag_schema_policy,
+            "disable_sentry": self.disable_sentry,
+            "sentry_scrub_patterns": self.sentry_scrub_patterns.len(),
         })
     }
 }
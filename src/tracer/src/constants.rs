@@ -5,7 +5,122 @@ pub const BATCH_SUBMISSION_INTERVAL_MS: u64 = 5000;
 pub const BATCH_SUBMISSION_RETRIES: u64 = 3;
 pub const BATCH_SUBMISSION_RETRY_DELAY_MS: u64 = 2000;
 pub const PROCESS_METRICS_SEND_INTERVAL_MS: u64 = 500;
+pub const TOOL_METRICS_WINDOW_MS: u64 = 10_000;
+pub const HEARTBEAT_INTERVAL_MS: u64 = 60_000;
+/// How long a tool that exited is kept open while its descendants are still running
+pub const PROCESS_EXIT_GRACE_PERIOD_MS: u64 = 5000;
+/// Memory usage, in percent of the cgroup limit, from which an alert is emitted
+pub const CGROUP_MEMORY_ALERT_UTILIZATION: f64 = 90.0;
+pub const SWAP_ALERT_THRESHOLD_BYTES: u64 = 512 * 1024 * 1024;
+/// How long a tool may stay in uninterruptible sleep before it is reported as stalled on storage
+pub const STORAGE_STALL_THRESHOLD_MS: u64 = 60_000;
+/// Difference between the local clock and the real time from which an alert is emitted
+pub const CLOCK_SKEW_THRESHOLD_MS: u64 = 5000;
+pub const CLOCK_SKEW_CHECK_INTERVAL_MS: u64 = 1000 * 60 * 10;
+/// Server whose `Date` header tells the real time the local clock is compared against
+pub const CLOCK_REFERENCE_URL: &str = "https://sts.amazonaws.com/";
+/// Consecutive process polls a tool must be missing from the process table before its exit is
+/// recorded without an exit event
+pub const MISSED_EXIT_CONFIRMATION_POLLS: u32 = 3;
+/// Error lines of a tool's output stream recorded per poll before the oldest are dropped
+pub const OUTPUT_BUFFER_LINES: usize = 1000;
 pub const FILE_SIZE_NOT_CHANGING_PERIOD_MS: u64 = 1000 * 60;
+pub const RUN_LOGS_CLEANUP_INTERVAL_MS: u64 = 1000 * 60 * 10;
+pub const RUN_LOGS_RETENTION_SECS: u64 = 60 * 60 * 24 * 7;
+pub const RUN_LOGS_MAX_TOTAL_BYTES: u64 = 512 * 1024 * 1024;
+/// AWS region taking precedence over `AWS_REGION`, see `Config::aws_region`
+pub const TRACER_AWS_REGION_ENV_VAR: &str = "TRACER_AWS_REGION";
+/// PEM bundle of additional CA certificates to trust, see `Config::ca_bundle`
+pub const CA_BUNDLE_ENV_VAR: &str = "TRACER_CA_BUNDLE";
+/// Set to `1`/`true` to attach the process tree to tool events, see `Config::process_tree_in_events`
+pub const PROCESS_TREE_ENV_VAR: &str = "TRACER_PROCESS_TREE";
+/// `random`, `timestamped` or a template, see `Config::run_name_scheme`
+pub const RUN_NAME_SCHEME_ENV_VAR: &str = "TRACER_RUN_NAME_SCHEME";
+/// `host:port` of the daemon control server, see `Config::server`
+pub const DAEMON_ADDRESS_ENV_VAR: &str = "TRACER_DAEMON_ADDRESS";
+/// Shared secret required by the daemon control server, see `Config::daemon_token`
+pub const DAEMON_TOKEN_ENV_VAR: &str = "TRACER_DAEMON_TOKEN";
+/// Set to `1`/`true` to poll procfs instead of using eBPF, see `Config::force_procfs`
+pub const FORCE_PROCFS_ENV_VAR: &str = "TRACER_FORCE_PROCFS";
+/// Comma-separated sub-pollers the daemon doesn't run (`syslog`, `containers`, `gpu`), see
+/// `Config::enable_syslog`
+pub const DISABLED_POLLERS_ENV_VAR: &str = "TRACER_DISABLED_POLLERS";
+/// Set to `1`/`true` to tail the files tools redirect their output to, see
+/// `Config::tail_redirected_output`
+pub const TAIL_REDIRECTED_OUTPUT_ENV_VAR: &str = "TRACER_TAIL_REDIRECTED_OUTPUT";
+/// YAML file with additional wrapper patterns, see `Config::wrapper_patterns_file`
+pub const WRAPPER_PATTERNS_ENV_VAR: &str = "TRACER_WRAPPER_PATTERNS";
+/// YAML file with additional tool error patterns, see `Config::tool_error_patterns_file`
+pub const TOOL_ERROR_PATTERNS_ENV_VAR: &str = "TRACER_TOOL_ERROR_PATTERNS";
+/// YAML rules files with additional targets, see `Config::rules_file`
+pub const RULES_FILE_ENV_VAR: &str = "TRACER_RULES_FILE";
+/// Set to `0`/`false` to not load the built-in rules, see `Config::builtin_rules`
+pub const BUILTIN_RULES_ENV_VAR: &str = "TRACER_BUILTIN_RULES";
+/// Public key release signatures are verified with, see `Config::update_public_key`
+pub const UPDATE_PUBLIC_KEY_ENV_VAR: &str = "TRACER_UPDATE_PUBLIC_KEY";
+/// Tool metrics aggregation window, `0` for raw samples, see `Config::tool_metrics_window_ms`
+pub const TOOL_METRICS_WINDOW_ENV_VAR: &str = "TRACER_TOOL_METRICS_WINDOW_MS";
+/// Comma-separated mount points whose disks are reported, see `Config::disk_mounts`
+pub const DISK_MOUNTS_ENV_VAR: &str = "TRACER_DISK_MOUNTS";
+/// SQLite database events are written to, see `Config::events_sqlite`
+pub const EVENTS_SQLITE_ENV_VAR: &str = "TRACER_EVENTS_SQLITE";
+/// File or fifo events are written to as JSON lines, see `Config::events_ndjson`
+pub const EVENTS_NDJSON_ENV_VAR: &str = "TRACER_EVENTS_NDJSON";
+/// Set to `1`/`true` to record events locally only, see `Config::local_only`
+pub const LOCAL_ONLY_ENV_VAR: &str = "TRACER_LOCAL_ONLY";
+/// `;`-separated inline target rules, see `Config::match_rules`
+pub const MATCH_RULES_ENV_VAR: &str = "TRACER_MATCH";
+/// YAML file with the allowed tag values, see `Config::tag_schema_file`
+pub const TAG_SCHEMA_ENV_VAR: &str = "TRACER_TAG_SCHEMA";
+/// `strict`, `normalize` or `warn`, see `Config::tag_schema_policy`
+pub const TAG_SCHEMA_POLICY_ENV_VAR: &str = "TRACER_TAG_SCHEMA_POLICY";
+/// Set to `1`/`true` to never send anything to Sentry, see `Config::disable_sentry`
+pub const DISABLE_SENTRY_ENV_VAR: &str = "TRACER_DISABLE_SENTRY";
+/// Comma-separated regexes also redacted from Sentry events, see `Config::sentry_scrub_patterns`
+pub const SENTRY_SCRUB_PATTERNS_ENV_VAR: &str = "TRACER_SENTRY_SCRUB_PATTERNS";
+/// Age in seconds after which run logs are removed, see `Config::run_logs_retention_secs`
+pub const RUN_LOGS_RETENTION_ENV_VAR: &str = "TRACER_RUN_LOGS_RETENTION_SECS";
+/// Total size of the run logs kept, see `Config::run_logs_max_total_bytes`
+pub const RUN_LOGS_MAX_TOTAL_BYTES_ENV_VAR: &str = "TRACER_RUN_LOGS_MAX_TOTAL_BYTES";
+/// `unchanged`, `prefix_image` or `image_name`, see `Config::container_display_name`
+pub const CONTAINER_DISPLAY_NAME_ENV_VAR: &str = "TRACER_CONTAINER_DISPLAY_NAME";
+/// Comma-separated instance types whose pricing the daemon caches when it starts, see
+/// `Config::pricing_warmup_instance_types`
+pub const PRICING_WARMUP_INSTANCE_TYPES_ENV_VAR: &str = "TRACER_PRICING_WARMUP_INSTANCE_TYPES";
+/// Interval of the heartbeats of an idle run, see `Config::heartbeat_interval_ms`
+pub const HEARTBEAT_INTERVAL_ENV_VAR: &str = "TRACER_HEARTBEAT_INTERVAL_MS";
+/// Completion deferral of exited tools whose forks still run, see
+/// `Config::process_exit_grace_period_ms`
+pub const PROCESS_EXIT_GRACE_PERIOD_ENV_VAR: &str = "TRACER_PROCESS_EXIT_GRACE_PERIOD_MS";
+/// Swap usage of a tool raising an alert, see `Config::swap_alert_threshold_bytes`
+pub const SWAP_ALERT_THRESHOLD_ENV_VAR: &str = "TRACER_SWAP_ALERT_THRESHOLD_BYTES";
+/// Time in uninterruptible sleep reported as a storage stall, see
+/// `Config::storage_stall_threshold_ms`
+pub const STORAGE_STALL_THRESHOLD_ENV_VAR: &str = "TRACER_STORAGE_STALL_THRESHOLD_MS";
+/// Polls a tool must be missing for before its exit is recorded, see
+/// `Config::missed_exit_confirmation_polls`
+pub const MISSED_EXIT_CONFIRMATION_POLLS_ENV_VAR: &str = "TRACER_MISSED_EXIT_CONFIRMATION_POLLS";
+/// Error lines of a redirected output file recorded per poll, see `Config::output_buffer_lines`
+pub const OUTPUT_BUFFER_LINES_ENV_VAR: &str = "TRACER_OUTPUT_BUFFER_LINES";
+/// Clock skew reported by an alert, `0` to disable the check, see `Config::clock_skew_threshold_ms`
+pub const CLOCK_SKEW_THRESHOLD_ENV_VAR: &str = "TRACER_CLOCK_SKEW_THRESHOLD_MS";
+/// Interval of the clock skew checks, see `Config::clock_skew_check_interval_ms`
+pub const CLOCK_SKEW_CHECK_INTERVAL_ENV_VAR: &str = "TRACER_CLOCK_SKEW_CHECK_INTERVAL_MS";
+/// Maximum number of ancestors attached to a tool event or checked by `force_ancestor_to_match`
+pub const MAX_PROCESS_TREE_DEPTH: usize = 32;
+pub const PRICING_CACHE_TTL_SECS: u64 = 60 * 60 * 24 * 7;
+pub const PRICING_WARMUP_INSTANCE_TYPES: &[&str] = &[
+    "t3.large",
+    "m5.large",
+    "m5.xlarge",
+    "m5.2xlarge",
+    "m5.4xlarge",
+    "c5.2xlarge",
+    "c5.4xlarge",
+    "r5.xlarge",
+    "r5.2xlarge",
+    "r5.4xlarge",
+];
 pub const EVENT_FORWARD_ENDPOINT_DEV: &str =
     "https://staging.tracer.cloud/api/public/events-forward";
 pub const EVENT_FORWARD_ENDPOINT_PROD: &str = "https://app.tracer.cloud/api/public/events-forward";
@@ -33,6 +148,8 @@ pub const AUTH_REDIRECT_URL_LOCAL_SUCCESS: &str =
 pub const JWT_TOKEN_FOLDER_PATH: &str = "/tmp/tracer";
 pub const JWT_TOKEN_FILE_NAME: &str = "token.txt";
 pub const JWT_TOKEN_FILE_PATH: &str = "/tmp/tracer/token.txt";
+/// JWT token used by `tracer init` instead of the one `tracer login` stored, see `resolve_token`
+pub const TOKEN_ENV_VAR: &str = "TRACER_TOKEN";
 pub const CLERK_JWKS_DOMAIN_DEV: &str =
     "https://superb-jackal-75.clerk.accounts.dev/.well-known/jwks.json";
 pub const CLERK_ISSUER_DOMAIN_DEV: &str = "https://superb-jackal-75.clerk.accounts.dev";
@@ -1,24 +1,37 @@
 use super::structs::PipelineMetadata;
+use crate::client::self_check::SelfCheckReport;
+use crate::constants::DAEMON_TOKEN_ENV_VAR;
+use crate::daemon::handlers::debug_events::DEBUG_EVENTS_ENDPOINT;
+use crate::daemon::handlers::events::EVENTS_STREAM_ENDPOINT;
 use crate::daemon::handlers::get_user_id::{GetUserIdResponse, GET_USER_ID_ENDPOINT};
 use crate::daemon::handlers::info::INFO_ENDPOINT;
+use crate::daemon::handlers::metrics::METRICS_ENDPOINT;
+use crate::daemon::handlers::pipeline_output::PIPELINE_OUTPUT_ENDPOINT;
+use crate::daemon::handlers::self_check::{SelfCheckRequest, SELF_CHECK_ENDPOINT};
 use crate::daemon::handlers::start::START_ENDPOINT;
-use crate::daemon::handlers::stop::STOP_ENDPOINT;
+use crate::daemon::handlers::stop::{StopRequest, STOP_ENDPOINT};
 use crate::daemon::handlers::terminate::TERMINATE_ENDPOINT;
 use crate::daemon::handlers::update_run_name::{
     UpdateRunNameRequest, UpdateRunNameResponse, UPDATE_RUN_NAME_ENDPOINT,
 };
+use crate::daemon::server::auth::DAEMON_TOKEN_HEADER;
 use crate::daemon::server::DaemonServer;
 use crate::error_message;
+use crate::extracts::metrics::snapshot::MetricsSnapshot;
+use crate::extracts::syslog::output_scanner::OutputMatch;
+use crate::process_identification::event_stream::EventFilterQuery;
+use crate::process_identification::types::event::attributes::run_summary::RunEndReason;
 use crate::utils::telemetry::presets;
 use anyhow::{bail, Result};
 use colored::Colorize;
-use reqwest::{Client, Response};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 pub struct DaemonClient {
     base_uri: String,
     client: Client,
+    token: Option<String>,
 }
 
 impl DaemonClient {
@@ -28,7 +41,24 @@ impl DaemonClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { base_uri, client }
+        Self {
+            base_uri,
+            client,
+            token: None,
+        }
+    }
+
+    /// Presents `token` to the daemon on every request, see `Config::daemon_token`
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    fn authorize(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => builder.header(DAEMON_TOKEN_HEADER, token),
+            None => builder,
+        }
     }
 
     async fn request<T, B>(&self, endpoint: &str, body: Option<B>) -> Result<T>
@@ -42,11 +72,11 @@ impl DaemonClient {
         }
 
         let url = format!("{}{}", self.base_uri, endpoint);
-        let builder = if body.is_some() {
+        let builder = self.authorize(if body.is_some() {
             self.client.post(&url).json(&body)
         } else {
             self.client.get(&url)
-        };
+        });
 
         let response = builder.send().await.map_err(|e| {
             let msg = format!("Network error for {}: {}", endpoint, e);
@@ -55,6 +85,7 @@ impl DaemonClient {
             e
         })?;
 
+        check_authorized(&response)?;
         if !response.status().is_success() {
             let status = response.status();
             let msg = format!("HTTP {} from {}", status, endpoint);
@@ -79,6 +110,17 @@ impl DaemonClient {
         self.request(STOP_ENDPOINT, Some(())).await
     }
 
+    /// Stops the active run, recording why it ends in its summary
+    pub async fn send_stop_request_with_reason(&self, end_reason: RunEndReason) -> Result<bool> {
+        self.request(STOP_ENDPOINT, Some(StopRequest { end_reason }))
+            .await
+    }
+
+    pub async fn send_self_check_request(&self, timeout_secs: u64) -> Result<SelfCheckReport> {
+        self.request(SELF_CHECK_ENDPOINT, Some(SelfCheckRequest { timeout_secs }))
+            .await
+    }
+
     pub async fn send_terminate_request(&self) -> Result<String> {
         self.request(TERMINATE_ENDPOINT, Some(())).await
     }
@@ -88,6 +130,18 @@ impl DaemonClient {
         self.request(INFO_ENDPOINT, Option::<()>::None).await
     }
 
+    /// The latest metrics of the active run, `None` if no run is active
+    pub async fn send_metrics_request(&self) -> Result<Option<MetricsSnapshot>> {
+        self.request(METRICS_ENDPOINT, Option::<()>::None).await
+    }
+
+    /// Records an error line of a pipeline's output in the active run; returns whether it was
+    /// recorded
+    pub async fn send_pipeline_output_request(&self, output_match: OutputMatch) -> Result<bool> {
+        self.request(PIPELINE_OUTPUT_ENDPOINT, Some(output_match))
+            .await
+    }
+
     pub async fn send_update_run_name_request(
         &self,
         run_name: String,
@@ -100,12 +154,68 @@ impl DaemonClient {
         self.request(GET_USER_ID_ENDPOINT, Option::<()>::None).await
     }
 
+    /// Opens the live event stream; the response body is newline-delimited JSON
+    pub async fn open_event_stream(&self, filter: &EventFilterQuery) -> Result<Response> {
+        if !DaemonServer::is_running() {
+            error_message!("Tracer daemon is not running");
+            bail!("Tracer daemon is not running");
+        }
+
+        let url = format!("{}{}", self.base_uri, EVENTS_STREAM_ENDPOINT);
+        // the default client has a request timeout, which would cut the stream short
+        let response = self
+            .authorize(Client::new().get(&url).query(filter))
+            .send()
+            .await?;
+
+        check_authorized(&response)?;
+        if !response.status().is_success() {
+            bail!("HTTP error {}", response.status());
+        }
+
+        Ok(response)
+    }
+
+    /// Opens the raw trigger stream; the response body is newline-delimited JSON
+    pub async fn open_debug_event_stream(&self) -> Result<Response> {
+        if !DaemonServer::is_running() {
+            error_message!("Tracer daemon is not running");
+            bail!("Tracer daemon is not running");
+        }
+
+        let url = format!("{}{}", self.base_uri, DEBUG_EVENTS_ENDPOINT);
+        // the default client has a request timeout, which would cut the stream short
+        let response = self.authorize(Client::new().get(&url)).send().await?;
+
+        check_authorized(&response)?;
+        if !response.status().is_success() {
+            bail!("HTTP error {}", response.status());
+        }
+
+        Ok(response)
+    }
+
     pub async fn ping(&self) -> Result<Response> {
         if !DaemonServer::is_running() {
             bail!("Daemon not running");
         }
 
         let url = format!("{}{}", self.base_uri, INFO_ENDPOINT);
-        self.client.get(&url).send().await.map_err(Into::into)
+        self.authorize(self.client.get(&url))
+            .send()
+            .await
+            .map_err(Into::into)
+    }
+}
+
+/// Fails with a hint at the token setting if the daemon rejected the request as unauthenticated
+fn check_authorized(response: &Response) -> Result<()> {
+    if response.status() == StatusCode::UNAUTHORIZED {
+        error_message!(
+            "The daemon requires a token: set {} to the token the daemon was started with",
+            DAEMON_TOKEN_ENV_VAR
+        );
+        bail!("Daemon rejected the request: missing or invalid token");
     }
+    Ok(())
 }
@@ -0,0 +1,25 @@
+use crate::daemon::state::DaemonState;
+use crate::process_identification::trigger_stream::next_trigger_message;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use std::convert::Infallible;
+
+pub const DEBUG_EVENTS_ENDPOINT: &str = "/debug/events";
+
+/// Streams the raw process triggers, before target matching, as newline-delimited JSON until the
+/// client disconnects
+pub async fn stream_debug_events(State(state): State<DaemonState>) -> impl IntoResponse {
+    let receiver = state.subscribe_triggers();
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        next_trigger_message(&mut receiver)
+            .await
+            .map(|message| (Ok::<_, Infallible>(message.to_ndjson()), receiver))
+    });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(stream),
+    )
+}
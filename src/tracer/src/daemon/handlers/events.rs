@@ -0,0 +1,32 @@
+use crate::daemon::state::DaemonState;
+use crate::process_identification::event_stream::{next_message, EventFilter, EventFilterQuery};
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use std::convert::Infallible;
+
+pub const EVENTS_STREAM_ENDPOINT: &str = "/events/stream";
+
+/// Streams newly recorded events matching the query filter as newline-delimited JSON until the
+/// client disconnects
+pub async fn stream_events(
+    State(state): State<DaemonState>,
+    Query(query): Query<EventFilterQuery>,
+) -> axum::response::Result<impl IntoResponse> {
+    let filter =
+        EventFilter::try_from(query).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let receiver = state.subscribe_events();
+    let stream =
+        futures_util::stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+            next_message(&mut receiver, &filter)
+                .await
+                .map(|message| (Ok::<_, Infallible>(message.to_ndjson()), (receiver, filter)))
+        });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(stream),
+    ))
+}
@@ -18,6 +18,8 @@ pub async fn info(State(state): State<DaemonState>) -> axum::response::Result<im
     };
 
     pipeline_data.opentelemetry_status = get_open_telemetry_status().await;
+    pipeline_data.ebpf_events_lost = tracer_ebpf::stats::events_lost();
+    pipeline_data.config = Some(state.get_config().await.to_safe_json());
 
     Ok(Json(pipeline_data))
 }
@@ -0,0 +1,16 @@
+use crate::daemon::state::DaemonState;
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::Json;
+
+pub const METRICS_ENDPOINT: &str = "/metrics";
+
+/// Returns the latest metrics gathered by the monitor loop of the active run, without sampling
+/// again, or `null` if no run is active
+pub async fn metrics(State(state): State<DaemonState>) -> impl IntoResponse {
+    let snapshot = match state.get_tracer_client().await {
+        Some(client) => Some(client.lock().await.get_metrics_snapshot()),
+        None => None,
+    };
+    Json(snapshot)
+}
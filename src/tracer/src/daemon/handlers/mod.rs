@@ -1,5 +1,10 @@
+pub(super) mod debug_events;
+pub(super) mod events;
 pub(super) mod get_user_id;
 pub(super) mod info;
+pub(super) mod metrics;
+pub(super) mod pipeline_output;
+pub(super) mod self_check;
 pub(super) mod start;
 pub(super) mod stop;
 pub(super) mod terminate;
@@ -0,0 +1,32 @@
+use crate::daemon::state::DaemonState;
+use crate::extracts::syslog::output_scanner::OutputMatch;
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub const PIPELINE_OUTPUT_ENDPOINT: &str = "/pipeline-output";
+
+/// Records an error line printed by a pipeline the CLI runs (e.g. a demo pipeline) as an event
+/// of the active run. Returns whether it was recorded, i.e. not if syslog events are disabled.
+pub async fn pipeline_output(
+    State(state): State<DaemonState>,
+    Json(output_match): Json<OutputMatch>,
+) -> impl IntoResponse {
+    let recorded = match state.get_tracer_client().await {
+        Some(client) => match client
+            .lock()
+            .await
+            .record_pipeline_output(output_match)
+            .await
+        {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                error!("Failed to record pipeline output: {:?}", e);
+                false
+            }
+        },
+        None => false,
+    };
+    Json(recorded)
+}
@@ -0,0 +1,35 @@
+use crate::client::self_check::SelfCheckReport;
+use crate::daemon::state::DaemonState;
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const SELF_CHECK_ENDPOINT: &str = "/self-check";
+
+#[derive(Deserialize, Serialize)]
+pub struct SelfCheckRequest {
+    pub timeout_secs: u64,
+}
+
+pub async fn self_check(
+    State(state): State<DaemonState>,
+    Json(request): Json<SelfCheckRequest>,
+) -> axum::response::Result<impl IntoResponse> {
+    let Some(client) = state.get_tracer_client().await else {
+        return Ok(Json(SelfCheckReport::fail(
+            "run",
+            "no active run; start one with 'tracer start'",
+        )));
+    };
+
+    // subscribe before the test event is recorded, so it cannot be missed
+    let events = state.subscribe_events();
+    let client = client.lock().await;
+    let report = client
+        .self_check(events, Duration::from_secs(request.timeout_secs))
+        .await;
+
+    Ok(Json(report))
+}
@@ -4,7 +4,7 @@ use axum::response::IntoResponse;
 use axum::Json;
 pub const START_ENDPOINT: &str = "/start";
 pub async fn start(State(state): State<DaemonState>) -> axum::response::Result<impl IntoResponse> {
-    if let Some(client) = state.start_tracer_client().await {
+    if let Some(client) = state.start_explicit_run().await {
         let client = client.lock().await;
         return Ok(Json(Some(client.get_pipeline_data().await)));
     }
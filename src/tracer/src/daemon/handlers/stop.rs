@@ -1,10 +1,22 @@
 use crate::daemon::state::DaemonState;
+use crate::process_identification::types::event::attributes::run_summary::RunEndReason;
 use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::Json;
+use serde::{Deserialize, Serialize};
 
 pub const STOP_ENDPOINT: &str = "/stop";
 
-pub async fn stop(State(state): State<DaemonState>) -> axum::response::Result<impl IntoResponse> {
-    Ok(Json(state.stop_client().await))
+#[derive(Deserialize, Serialize)]
+pub struct StopRequest {
+    pub end_reason: RunEndReason,
+}
+
+/// Stops the active run; the body is `null`, or a `StopRequest` to tell why the run ends
+pub async fn stop(
+    State(state): State<DaemonState>,
+    Json(request): Json<Option<StopRequest>>,
+) -> axum::response::Result<impl IntoResponse> {
+    let end_reason = request.map_or(RunEndReason::Completed, |request| request.end_reason);
+    Ok(Json(state.stop_client_with_reason(end_reason).await))
 }
@@ -0,0 +1,94 @@
+//! Shared-secret authentication of the daemon control endpoints
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+/// Header carrying the daemon token, see `Config::daemon_token`
+pub const DAEMON_TOKEN_HEADER: &str = "x-tracer-daemon-token";
+
+/// Rejects requests that do not present the daemon token, if one is configured
+pub async fn require_daemon_token(
+    State(token): State<Option<Arc<str>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(expected) = token.as_deref() {
+        let presented = request
+            .headers()
+            .get(DAEMON_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok());
+        if !presented.is_some_and(|presented| tokens_match(presented, expected)) {
+            return (StatusCode::UNAUTHORIZED, "Missing or invalid daemon token").into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// Compares tokens in time independent of where they differ, so the token cannot be guessed
+/// byte by byte from response times
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    let (presented, expected) = (presented.as_bytes(), expected.as_bytes());
+    presented.len() == expected.len()
+        && presented
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::{middleware, Router};
+    use reqwest::Client;
+
+    /// Serves a terminate-like endpoint behind the token check, returning its URL
+    async fn serve(token: Option<&str>) -> String {
+        let router = Router::new()
+            .route("/terminate", post(|| async { "terminated" }))
+            .layer(middleware::from_fn_with_state(
+                token.map(Arc::from),
+                require_daemon_token,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/terminate", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        url
+    }
+
+    async fn send(url: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Client::new().post(url);
+        if let Some(token) = token {
+            request = request.header(DAEMON_TOKEN_HEADER, token);
+        }
+        request.send().await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_no_token_configured_accepts_all_requests() {
+        let url = serve(None).await;
+        assert_eq!(send(&url, None).await, StatusCode::OK);
+        assert_eq!(send(&url, Some("anything")).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_configured_token_is_required() {
+        let url = serve(Some("s3cret")).await;
+        assert_eq!(send(&url, Some("s3cret")).await, StatusCode::OK);
+        assert_eq!(send(&url, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(&url, Some("s3cre")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(&url, Some("S3cret")).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abd", "abc"));
+        assert!(!tokens_match("abcd", "abc"));
+        assert!(!tokens_match("", "abc"));
+    }
+}
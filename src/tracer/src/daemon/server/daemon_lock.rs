@@ -0,0 +1,117 @@
+//! Advisory lock preventing two daemons from working on the same work dir
+
+use crate::utils::workdir::TRACER_WORK_DIR;
+use anyhow::{bail, Context, Result};
+use nix::errno::Errno;
+use nix::fcntl::{Flock, FlockArg};
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Exclusive lock on the daemon lock file, held for the whole lifetime of the daemon.
+///
+/// The lock is released when this value is dropped. Since `flock` locks are released by the
+/// kernel when the holding process exits, a lock file left behind by a crashed daemon is never
+/// considered held; only the PID written inside it is stale, and it is overwritten by the next
+/// daemon that acquires the lock.
+pub struct DaemonLock {
+    _lock: Flock<File>,
+}
+
+impl DaemonLock {
+    /// Acquires the lock on the work dir lock file, failing fast if another process holds it.
+    pub fn acquire() -> Result<Self> {
+        Self::acquire_at(&TRACER_WORK_DIR.lock_file)
+    }
+
+    /// Returns the PID of the process currently holding the lock, or `None` if the lock is free.
+    pub fn holder_pid() -> Option<u32> {
+        Self::holder_pid_at(&TRACER_WORK_DIR.lock_file)
+    }
+
+    fn acquire_at(path: &Path) -> Result<Self> {
+        let file = open_lock_file(path)?;
+
+        let mut lock = match Flock::lock(file, FlockArg::LockExclusiveNonblock) {
+            Ok(lock) => lock,
+            Err((_, Errno::EWOULDBLOCK)) => {
+                let holder = read_pid(path)
+                    .map(|pid| format!("PID {}", pid))
+                    .unwrap_or_else(|| "unknown PID".to_string());
+                bail!(
+                    "Another tracer daemon ({}) holds the lock on {:?}. Use 'tracer terminate' to stop it first.",
+                    holder,
+                    path
+                );
+            }
+            Err((_, errno)) => {
+                bail!("Failed to lock {:?}: {}", path, errno);
+            }
+        };
+
+        lock.set_len(0)?;
+        lock.seek(SeekFrom::Start(0))?;
+        write!(lock, "{}", std::process::id())?;
+        lock.flush()?;
+
+        Ok(Self { _lock: lock })
+    }
+
+    fn holder_pid_at(path: &Path) -> Option<u32> {
+        let file = OpenOptions::new().read(true).open(path).ok()?;
+        match Flock::lock(file, FlockArg::LockSharedNonblock) {
+            // nobody holds the lock, so any PID in the file is stale
+            Ok(_) => None,
+            Err(_) => read_pid(path),
+        }
+    }
+}
+
+fn open_lock_file(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("Failed to open daemon lock file {:?}", path))
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive_and_reports_holder() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tracerd.lock");
+
+        let lock = DaemonLock::acquire_at(&path).unwrap();
+        assert_eq!(DaemonLock::holder_pid_at(&path), Some(std::process::id()));
+
+        let error = DaemonLock::acquire_at(&path).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains(&format!("PID {}", std::process::id())));
+
+        drop(lock);
+        assert_eq!(DaemonLock::holder_pid_at(&path), None);
+        assert!(DaemonLock::acquire_at(&path).is_ok());
+    }
+
+    #[test]
+    fn test_stale_lock_file_is_not_held() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tracerd.lock");
+        std::fs::write(&path, "999999").unwrap();
+
+        assert_eq!(DaemonLock::holder_pid_at(&path), None);
+        let _lock = DaemonLock::acquire_at(&path).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+    }
+}
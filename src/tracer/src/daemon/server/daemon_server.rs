@@ -5,14 +5,24 @@ use tokio::net::TcpListener;
 use crate::cli::handlers::init_arguments::FinalizedInitArgs;
 use crate::client::exporters::event_forward::EventForward;
 use crate::client::exporters::event_writer::LogWriterEnum;
+use crate::config::defaults::daemon_address;
 use crate::config::Config;
-use crate::constants::{EVENT_FORWARD_ENDPOINT_DEV, EVENT_FORWARD_ENDPOINT_PROD};
+use crate::constants::{
+    DAEMON_TOKEN_ENV_VAR, EVENT_FORWARD_ENDPOINT_DEV, EVENT_FORWARD_ENDPOINT_PROD,
+};
+use crate::daemon::handlers::debug_events::{stream_debug_events, DEBUG_EVENTS_ENDPOINT};
+use crate::daemon::handlers::events::{stream_events, EVENTS_STREAM_ENDPOINT};
 use crate::daemon::handlers::get_user_id::{get_user_id, GET_USER_ID_ENDPOINT};
 use crate::daemon::handlers::info::{info, INFO_ENDPOINT};
+use crate::daemon::handlers::metrics::{metrics, METRICS_ENDPOINT};
+use crate::daemon::handlers::pipeline_output::{pipeline_output, PIPELINE_OUTPUT_ENDPOINT};
+use crate::daemon::handlers::self_check::{self_check, SELF_CHECK_ENDPOINT};
 use crate::daemon::handlers::start::{start, START_ENDPOINT};
 use crate::daemon::handlers::stop::{stop, STOP_ENDPOINT};
 use crate::daemon::handlers::terminate::{terminate, TERMINATE_ENDPOINT};
 use crate::daemon::handlers::update_run_name::{update_run_name, UPDATE_RUN_NAME_ENDPOINT};
+use crate::daemon::server::auth::require_daemon_token;
+use crate::daemon::server::listener::bind_daemon_listener;
 use crate::daemon::state::DaemonState;
 use crate::process_identification::constants::DEFAULT_DAEMON_PORT;
 use crate::utils::analytics;
@@ -20,12 +30,13 @@ use crate::utils::analytics::types::AnalyticsEventType;
 use crate::utils::env::is_development_environment;
 use crate::utils::workdir::TRACER_WORK_DIR;
 use axum::routing::{get, post, MethodRouter};
-use axum::Router;
+use axum::{middleware, Router};
 use std::net::SocketAddr;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Get database client based on dev/prod configuration
 pub async fn get_db_client() -> LogWriterEnum {
@@ -45,22 +56,42 @@ static ROUTES: LazyLock<Vec<(&'static str, MethodRouter<DaemonState>)>> = LazyLo
         (START_ENDPOINT, post(start)),
         (STOP_ENDPOINT, post(stop)),
         (INFO_ENDPOINT, get(info)),
+        (METRICS_ENDPOINT, get(metrics)),
+        (PIPELINE_OUTPUT_ENDPOINT, post(pipeline_output)),
         (UPDATE_RUN_NAME_ENDPOINT, post(update_run_name)),
         (GET_USER_ID_ENDPOINT, get(get_user_id)),
+        (EVENTS_STREAM_ENDPOINT, get(stream_events)),
+        (DEBUG_EVENTS_ENDPOINT, get(stream_debug_events)),
+        (SELF_CHECK_ENDPOINT, post(self_check)),
     ]
 });
 
+/// How long `is_running` waits for a daemon at a non-local address to accept a connection
+const REMOTE_DAEMON_PING_TIMEOUT: Duration = Duration::from_millis(500);
+
 pub struct DaemonServer {
     server: Option<JoinHandle<io::Result<()>>>,
 }
 
-fn get_router(state: DaemonState) -> Router {
+fn get_router(state: DaemonState, token: Option<String>) -> Router {
     // todo: set subscriber
     let mut router = Router::new();
     for (path, method_router) in ROUTES.iter() {
         router = router.route(path, method_router.clone());
     }
-    router.with_state(state)
+    router
+        .layer(middleware::from_fn_with_state(
+            token.map(Arc::from),
+            require_daemon_token,
+        ))
+        .with_state(state)
+}
+
+/// Whether the control server can only be reached from this host
+fn is_local_address(server_url: &str) -> bool {
+    server_url
+        .parse::<SocketAddr>()
+        .is_ok_and(|addr| addr.ip().is_loopback())
 }
 
 pub async fn create_listener(server_url: String) -> TcpListener {
@@ -88,7 +119,22 @@ impl DaemonServer {
         info!("Daemon server created!");
         Self { server: None }
     }
-    pub async fn start(mut self, args: FinalizedInitArgs, config: Config) -> anyhow::Result<()> {
+    pub async fn start(self, args: FinalizedInitArgs, config: Config) -> anyhow::Result<()> {
+        self.start_with_shutdown(args, config, CancellationToken::new())
+            .await
+    }
+
+    /// Runs the daemon server and the monitoring loop in the current process (foreground mode)
+    /// until `shutdown` is cancelled, either by the caller or by a terminate request.
+    ///
+    /// This is what `tracer init --no-daemonize` runs, and lets integration tests drive the full
+    /// monitor loop in-process.
+    pub async fn start_with_shutdown(
+        mut self,
+        args: FinalizedInitArgs,
+        config: Config,
+        shutdown: CancellationToken,
+    ) -> anyhow::Result<()> {
         analytics::spawn_event(
             args.user_id.clone(),
             AnalyticsEventType::DaemonStartedSuccessfully,
@@ -96,21 +142,32 @@ impl DaemonServer {
         );
 
         info!("Starting Tracer daemon server...");
-        let termination_token = CancellationToken::new();
+        let termination_token = shutdown;
         let server_url = config.server.clone();
+        let token = config.daemon_token.clone();
+        if !is_local_address(&server_url) {
+            if token.is_some() {
+                info!("Daemon control server listens on {}", server_url);
+            } else {
+                warn!(
+                    "Daemon control server listens on {} without a token: anyone who can reach it can control the daemon. Set {} to require one.",
+                    server_url, DAEMON_TOKEN_ENV_VAR
+                );
+            }
+        }
 
         let state = DaemonState::new(args, config, termination_token.clone());
 
         // Start the HTTP server first so it can respond to ping requests immediately
-        let listener = create_listener(server_url).await;
+        let listener = bind_daemon_listener(&server_url).await?;
         self.server = Some(tokio::spawn(
-            axum::serve(listener, get_router(state.clone())).into_future(),
+            axum::serve(listener, get_router(state.clone(), token)).into_future(),
         ));
 
         // Initialize the TracerClient asynchronously after the server is running
         tokio::spawn(async move {
             info!("Initializing TracerClient...");
-            state.start_tracer_client().await;
+            state.start_implicit_run().await;
             info!("TracerClient initialization completed");
         });
 
@@ -143,7 +200,16 @@ impl DaemonServer {
     }
 
     pub fn is_running() -> bool {
-        let port = DEFAULT_DAEMON_PORT;
+        let address = daemon_address();
+        let port = match address.parse::<SocketAddr>() {
+            // a daemon in another container or host is running if it accepts connections
+            Ok(addr) if !addr.ip().is_loopback() && !addr.ip().is_unspecified() => {
+                return std::net::TcpStream::connect_timeout(&addr, REMOTE_DAEMON_PING_TIMEOUT)
+                    .is_ok();
+            }
+            Ok(addr) => addr.port(),
+            Err(_) => DEFAULT_DAEMON_PORT,
+        };
         if let Err(e) = std::net::TcpListener::bind(format!("127.0.0.1:{}", port)) {
             if e.kind() == io::ErrorKind::AddrInUse {
                 return true;
@@ -156,3 +222,79 @@ impl DaemonServer {
         let _ = &TRACER_WORK_DIR.cleanup_run();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::handlers::init_arguments::finalized_args;
+    use crate::utils::run_logs::{run_log_dir, RUN_LOGS_DIR};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// The events written so far to the NDJSON output at `path`
+    fn recorded_events(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_foreground_mode_records_new_run() {
+        TRACER_WORK_DIR.init().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let events_path = temp_dir.path().join("events.ndjson");
+        let args = FinalizedInitArgs {
+            events_ndjson: Some(events_path.display().to_string()),
+            ..finalized_args()
+        };
+        // no eBPF, Docker or clock reference needed, and events written right away
+        let config = args.merge_into(Config {
+            server: "127.0.0.1:0".to_string(),
+            force_procfs: true,
+            enable_containers: false,
+            enable_gpu: false,
+            clock_skew_threshold_ms: 0,
+            batch_submission_interval_ms: 100,
+            ..Config::default()
+        });
+
+        let shutdown = CancellationToken::new();
+        let daemon = tokio::spawn(DaemonServer::new().await.start_with_shutdown(
+            args,
+            config,
+            shutdown.clone(),
+        ));
+
+        let new_run = tokio::time::timeout(Duration::from_secs(60), async {
+            loop {
+                let new_run = recorded_events(&events_path)
+                    .into_iter()
+                    .find(|event| event["process_status"] == "new_run");
+                if let Some(new_run) = new_run {
+                    return new_run;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await;
+
+        shutdown.cancel();
+        daemon.await.unwrap().unwrap();
+
+        let new_run = new_run.expect("no NewRun event recorded");
+        if let Some(run_id) = new_run["run_id"].as_str() {
+            let _ = std::fs::remove_dir_all(run_log_dir(Path::new(RUN_LOGS_DIR), run_id));
+        }
+        assert_eq!(new_run["pipeline_name"], "pipeline");
+    }
+
+    #[test]
+    fn test_only_loopback_addresses_are_local() {
+        assert!(is_local_address("127.0.0.1:8722"));
+        assert!(is_local_address("[::1]:8722"));
+        assert!(!is_local_address("0.0.0.0:8722"));
+        assert!(!is_local_address("10.0.0.5:8722"));
+    }
+}
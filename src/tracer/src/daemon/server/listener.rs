@@ -0,0 +1,109 @@
+//! Binding of the daemon server socket, tolerating a port still held by a previous daemon
+
+use crate::daemon::server::daemon_lock::DaemonLock;
+use anyhow::{bail, Context, Result};
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::warn;
+
+/// Number of attempts to bind the daemon port before giving up
+const BIND_ATTEMPTS: u32 = 5;
+/// Delay before the second attempt; doubled after every further failed attempt
+const BIND_INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Binds the daemon server socket, retrying with backoff while the port is in use.
+///
+/// Rapid restart cycles (common in CI) can find the port still held by the previous daemon
+/// while it exits. The daemon binds the port only once it holds the `DaemonLock`, so the
+/// previous daemon has released the lock by then and is left to exit on its own; nothing is
+/// killed to reclaim the port.
+pub async fn bind_daemon_listener(server_url: &str) -> Result<TcpListener> {
+    bind_with_retry(server_url, BIND_ATTEMPTS, BIND_INITIAL_BACKOFF).await
+}
+
+async fn bind_with_retry(
+    server_url: &str,
+    attempts: u32,
+    initial_backoff: Duration,
+) -> Result<TcpListener> {
+    let addr: SocketAddr = server_url
+        .parse()
+        .with_context(|| format!("Invalid daemon server address {}", server_url))?;
+
+    for attempt in 1..=attempts {
+        match TcpListener::bind(addr).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+                warn!(
+                    "Port {} is in use (attempt {}/{})",
+                    addr.port(),
+                    attempt,
+                    attempts
+                );
+                if attempt < attempts {
+                    tokio::time::sleep(backoff(initial_backoff, attempt)).await;
+                }
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to bind to {}", addr)),
+        }
+    }
+
+    bail!(
+        "Port {} is still in use after {} attempts{}",
+        addr.port(),
+        attempts,
+        holder_hint(DaemonLock::holder_pid())
+    )
+}
+
+fn backoff(initial: Duration, attempt: u32) -> Duration {
+    initial * 2u32.pow(attempt.saturating_sub(1))
+}
+
+/// How to free the port, depending on the process holding the daemon lock
+fn holder_hint(lock_holder: Option<u32>) -> String {
+    match lock_holder {
+        Some(pid) if pid != std::process::id() => format!(
+            ", while another tracer daemon (PID {}) is running. Use 'tracer terminate' to stop it first.",
+            pid
+        ),
+        _ => ". Run 'tracer cleanup-port' to resolve the port conflict before starting the daemon."
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles() {
+        assert_eq!(backoff(BIND_INITIAL_BACKOFF, 1), Duration::from_millis(250));
+        assert_eq!(backoff(BIND_INITIAL_BACKOFF, 2), Duration::from_millis(500));
+        assert_eq!(backoff(BIND_INITIAL_BACKOFF, 4), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_hint_names_the_other_daemon() {
+        assert!(holder_hint(Some(std::process::id() + 1)).contains("tracer terminate"));
+        assert!(holder_hint(Some(std::process::id())).contains("tracer cleanup-port"));
+        assert!(holder_hint(None).contains("tracer cleanup-port"));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_on_port_held_by_other_process() {
+        let holder = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = holder.local_addr().unwrap().to_string();
+
+        let error = bind_with_retry(&addr, 3, Duration::from_millis(1))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("still in use after 3 attempts"));
+
+        drop(holder);
+        let listener = bind_with_retry(&addr, 3, Duration::from_millis(1)).await;
+        assert!(listener.is_ok());
+    }
+}
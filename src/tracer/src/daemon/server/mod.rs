@@ -1,5 +1,8 @@
 pub mod daemon_server;
 pub use daemon_server::{get_db_client, DaemonServer};
 
+pub mod auth;
+pub mod daemon_lock;
+pub mod listener;
 pub mod process_monitor;
 pub mod termination;
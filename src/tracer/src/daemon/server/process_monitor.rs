@@ -1,4 +1,7 @@
 use crate::client::TracerClient;
+use crate::constants::CLOCK_REFERENCE_URL;
+use crate::extracts::clock_skew::{ClockSkewCheck, HttpDateClock};
+use crate::utils::run_logs::{cleanup_run_logs, run_log_dir, RUN_LOGS_DIR};
 use crate::utils::Sentry;
 use anyhow::Result;
 use serde_json::json;
@@ -7,14 +10,11 @@ use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::error;
+use tracing::{debug, error, info};
 
+/// Runs the process part of `TracerClient::tick`; the other sub-polls have their own workers
 pub(super) async fn monitor_processes(tracer_client: &mut TracerClient) -> Result<()> {
-    tracer_client.poll_process_metrics().await?;
-
-    tracer_client.refresh_sysinfo().await?;
-
-    Ok(())
+    tracer_client.poll_processes().await
 }
 
 fn spawn_worker_thread<F, Fut>(
@@ -62,6 +62,9 @@ pub async fn monitor(client: Arc<Mutex<TracerClient>>, server_token: Cancellatio
         retry_attempts,
         retry_delay,
         client_token,
+        run_logs_cleanup,
+        heartbeat_interval_ms,
+        clock_skew,
     ) = {
         let client = client.lock().await;
         client.start_monitoring().await.unwrap();
@@ -74,6 +77,16 @@ pub async fn monitor(client: Arc<Mutex<TracerClient>>, server_token: Cancellatio
             config.batch_submission_retries,
             config.batch_submission_retry_delay_ms,
             client.cancellation_token.clone(),
+            (
+                config.run_logs_cleanup_interval_ms,
+                Duration::from_secs(config.run_logs_retention_secs),
+                config.run_logs_max_total_bytes,
+            ),
+            config.heartbeat_interval_ms,
+            (
+                config.clock_skew_check_interval_ms,
+                config.clock_skew_threshold_ms,
+            ),
         )
     };
 
@@ -162,6 +175,78 @@ pub async fn monitor(client: Arc<Mutex<TracerClient>>, server_token: Cancellatio
         )
     };
 
+    let mut run_logs_cleanup_handle = {
+        let client = Arc::clone(&client);
+        let (cleanup_interval_ms, retention, max_total_bytes) = run_logs_cleanup;
+        spawn_worker_thread(
+            cleanup_interval_ms,
+            server_token.clone(),
+            client_token.clone(),
+            move || {
+                let client = Arc::clone(&client);
+                async move {
+                    let run_id = client.lock().await.get_run_snapshot().await.id;
+                    cleanup_stale_run_logs(&run_id, retention, max_total_bytes).await;
+                }
+            },
+        )
+    };
+
+    let mut heartbeat_handle = {
+        let client = Arc::clone(&client);
+        spawn_worker_thread(
+            heartbeat_interval_ms,
+            server_token.clone(),
+            client_token.clone(),
+            move || {
+                let client = Arc::clone(&client);
+                async move {
+                    let guard = client.lock().await;
+                    if let Err(e) = guard
+                        .emit_heartbeat(Duration::from_millis(heartbeat_interval_ms))
+                        .await
+                    {
+                        error!("Failed to emit heartbeat: {}", e);
+                    }
+                }
+            },
+        )
+    };
+
+    let mut clock_skew_handle = {
+        let client = Arc::clone(&client);
+        let (check_interval_ms, threshold_ms) = clock_skew;
+        let check = Arc::new(Mutex::new(ClockSkewCheck::new(
+            HttpDateClock::new(CLOCK_REFERENCE_URL),
+            threshold_ms,
+        )));
+        spawn_worker_thread(
+            check_interval_ms,
+            server_token.clone(),
+            client_token.clone(),
+            move || {
+                let client = Arc::clone(&client);
+                let check = Arc::clone(&check);
+                async move {
+                    // the reference clock is read without holding the client, and a failure to
+                    // read it doesn't affect the run
+                    let skew = match check.lock().await.check().await {
+                        Ok(skew) => skew,
+                        Err(e) => {
+                            debug!("Failed to check the clock skew: {:#}", e);
+                            None
+                        }
+                    };
+                    if let Some(skew) = skew {
+                        if let Err(e) = client.lock().await.record_clock_skew(skew).await {
+                            error!("Failed to record the clock skew: {}", e);
+                        }
+                    }
+                }
+            },
+        )
+    };
+
     tokio::select! {
         result = &mut submission_handle => {
             if let Err(join_error) = result {
@@ -203,11 +288,41 @@ pub async fn monitor(client: Arc<Mutex<TracerClient>>, server_token: Cancellatio
             }
         }
     }
+        result = &mut heartbeat_handle => {
+            if let Err(join_error) = result {
+                if join_error.is_panic() {
+                    error!("Heartbeat thread panicked");
+                    server_token.cancel();
+                }
+            }
+        }
+        result = &mut clock_skew_handle => {
+            if let Err(join_error) = result {
+                if join_error.is_panic() {
+                    error!("Clock skew check thread panicked");
+                    server_token.cancel();
+                }
+            }
+        }
+        result = &mut run_logs_cleanup_handle => {
+            if let Err(join_error) = result {
+                if join_error.is_panic() {
+                    error!("Run logs cleanup thread panicked");
+                    server_token.cancel();
+                }
+            }
+        }
 
     }
 
     // submit all data left
     let guard = client.lock().await;
+    if let Err(e) = guard.finish_running_tools().await {
+        error!("Failed to record tools still running: {}", e);
+    }
+    if let Err(e) = guard.finish_run().await {
+        error!("Failed to record run summary: {}", e);
+    }
     let config = guard.get_config();
     guard
         .exporter
@@ -225,9 +340,8 @@ pub async fn monitor(client: Arc<Mutex<TracerClient>>, server_token: Cancellatio
     let run_id = &run_snapshot.id;
 
     // Create log directory and file name (same as in start_tracer_client)
-    let log_base_dir = std::env::current_dir().unwrap().join("tracer-run-logs");
-    let log_dir_name = format!("run-{}", run_id);
-    let log_dir = log_base_dir.join(&log_dir_name);
+    let log_base_dir = std::env::current_dir().unwrap().join(RUN_LOGS_DIR);
+    let log_dir = run_log_dir(&log_base_dir, run_id);
 
     // Create the log directory if it doesn't exist
     if let Err(e) = std::fs::create_dir_all(&log_dir) {
@@ -275,6 +389,34 @@ pub async fn monitor(client: Arc<Mutex<TracerClient>>, server_token: Cancellatio
     let _ = guard.close().await;
 }
 
+/// Removes run log directories that are expired or exceed the size cap, keeping the active run
+async fn cleanup_stale_run_logs(active_run_id: &str, retention: Duration, max_total_bytes: u64) {
+    let log_base_dir = std::env::current_dir().unwrap().join(RUN_LOGS_DIR);
+    let active_run_id = active_run_id.to_string();
+
+    let result = tokio::task::spawn_blocking(move || {
+        cleanup_run_logs(
+            &log_base_dir,
+            retention,
+            max_total_bytes,
+            Some(&active_run_id),
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(report)) if report.removed_dirs > 0 => {
+            info!(
+                "Run logs cleanup removed {} directories, reclaimed {} bytes",
+                report.removed_dirs, report.reclaimed_bytes
+            );
+        }
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => error!("Run logs cleanup failed: {}", e),
+        Err(e) => error!("Run logs cleanup task failed: {}", e),
+    }
+}
+
 async fn sentry_alert(client: &TracerClient) {
     let run_snapshot = client.get_run_snapshot().await;
     let processes = run_snapshot.processes_json();
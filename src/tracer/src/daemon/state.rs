@@ -1,14 +1,30 @@
 use crate::cli::handlers::init_arguments::FinalizedInitArgs;
+use crate::client::exporters::event_writer::LogWriterEnum;
+use crate::client::exporters::ndjson::NdjsonWriter;
+use crate::client::exporters::sqlite::SqliteWriter;
 use crate::client::TracerClient;
 use crate::config::Config;
 use crate::daemon::server::process_monitor::monitor;
 use crate::daemon::structs::PipelineMetadata;
+use crate::process_identification::event_stream::EventStream;
+use crate::process_identification::trigger_stream::{RawTrigger, TriggerStream};
+use crate::process_identification::types::event::attributes::run_summary::RunEndReason;
+use crate::process_identification::types::event::Event;
+use crate::utils::run_logs::{run_log_dir, RUN_LOGS_DIR};
+use crate::utils::workdir::TRACER_WORK_DIR;
 use anyhow::Context;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use tokio_util::sync::CancellationToken;
 
+/// Shared state of the daemon's HTTP handlers.
+///
+/// Run lifecycle: the daemon starts an implicit run as soon as it is initialized, so events of
+/// tools executed before any `tracer start` are still attributed to a run. An explicit
+/// `tracer start` ends the implicit run and begins a new one, while starting over an explicitly
+/// started run is refused until `tracer stop` ends it.
 #[derive(Clone)]
 pub(super) struct DaemonState {
     args: Arc<Mutex<FinalizedInitArgs>>,
@@ -17,11 +33,15 @@ pub(super) struct DaemonState {
     pipeline: Arc<Mutex<PipelineMetadata>>,
     server_token: CancellationToken,
     directory: std::path::PathBuf,
+    event_stream: EventStream,
+    trigger_stream: TriggerStream,
+    /// Whether the active run was started by the daemon itself rather than by `tracer start`
+    implicit_run: Arc<AtomicBool>,
 }
 
 impl DaemonState {
     pub fn new(args: FinalizedInitArgs, config: Config, server_token: CancellationToken) -> Self {
-        let pipeline_data = PipelineMetadata::new(&args);
+        let pipeline_data = PipelineMetadata::new(&args, &config);
         let directory = env::current_dir().unwrap();
         Self {
             args: Arc::new(Mutex::new(args)),
@@ -30,6 +50,9 @@ impl DaemonState {
             server_token,
             pipeline: Arc::new(Mutex::new(pipeline_data)),
             directory,
+            event_stream: EventStream::new(),
+            trigger_stream: TriggerStream::new(),
+            implicit_run: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -43,21 +66,58 @@ impl DaemonState {
         data.clone()
     }
 
+    pub async fn get_config(&self) -> Config {
+        self.config.lock().await.clone()
+    }
+
     pub async fn get_user_id(&self) -> Option<String> {
         let args = self.args.lock().await;
         Some(args.user_id.clone())
     }
 
+    pub fn subscribe_events(&self) -> broadcast::Receiver<Event> {
+        self.event_stream.subscribe()
+    }
+
+    pub fn subscribe_triggers(&self) -> broadcast::Receiver<RawTrigger> {
+        self.trigger_stream.subscribe()
+    }
+
     pub fn terminate_server(&self) {
         self.server_token.cancel();
     }
+    /// Starts the run the daemon creates on initialization
+    pub async fn start_implicit_run(&self) -> Option<Arc<Mutex<TracerClient>>> {
+        let client = self.start_tracer_client().await;
+        if client.is_some() {
+            self.implicit_run.store(true, Ordering::SeqCst);
+        }
+        client
+    }
+
+    /// Starts a run for `tracer start`, replacing the implicit run if that is the active one
+    pub async fn start_explicit_run(&self) -> Option<Arc<Mutex<TracerClient>>> {
+        if self.implicit_run.swap(false, Ordering::SeqCst) {
+            tracing::info!("Ending the implicit run to start a new run");
+            self.stop_client().await;
+        }
+        self.start_tracer_client().await
+    }
+
     pub async fn stop_client(&self) -> bool {
+        self.stop_client_with_reason(RunEndReason::Completed).await
+    }
+
+    /// Stops the active run, recording `end_reason` in its summary
+    pub async fn stop_client_with_reason(&self, end_reason: RunEndReason) -> bool {
+        self.implicit_run.store(false, Ordering::SeqCst);
         let option_client = self.tracer_client.lock().await;
 
         if option_client.is_some() {
             let new_client = option_client.clone().unwrap();
             drop(option_client);
-            let client = new_client.lock().await;
+            let mut client = new_client.lock().await;
+            client.set_end_reason(end_reason);
             client.cancellation_token.cancel();
             self.tracer_client.lock().await.take();
             true
@@ -66,7 +126,7 @@ impl DaemonState {
         }
     }
 
-    pub async fn start_tracer_client(&self) -> Option<Arc<Mutex<TracerClient>>> {
+    async fn start_tracer_client(&self) -> Option<Arc<Mutex<TracerClient>>> {
         let mut option_client = self.tracer_client.lock().await;
         if option_client.is_some() {
             return None;
@@ -74,11 +134,18 @@ impl DaemonState {
 
         let args = self.args.lock().await.clone();
         let config = self.config.lock().await.clone();
-        let db_client = crate::daemon::server::get_db_client().await;
-        let client = TracerClient::new(self.pipeline.clone(), config, db_client, args)
-            .await
-            .context("Failed to create TracerClient")
-            .unwrap();
+        let db_client = open_db_client(&config).await;
+        let client = TracerClient::new(
+            self.pipeline.clone(),
+            config,
+            db_client,
+            args,
+            self.event_stream.clone(),
+            self.trigger_stream.clone(),
+        )
+        .await
+        .context("Failed to create TracerClient")
+        .unwrap();
         let client = Arc::new(Mutex::new(client));
         option_client.replace(client.clone());
 
@@ -89,9 +156,8 @@ impl DaemonState {
             let run_id = &pipeline_data.run_snapshot.unwrap().id;
 
             // Create log directory and file name
-            let log_base_dir = self.directory.join("tracer-run-logs");
-            let log_dir_name = format!("run-{}", run_id);
-            let log_dir = log_base_dir.join(&log_dir_name);
+            let log_base_dir = self.directory.join(RUN_LOGS_DIR);
+            let log_dir = run_log_dir(&log_base_dir, run_id);
 
             // Create the log directory if it doesn't exist
             if let Err(e) = std::fs::create_dir_all(&log_dir) {
@@ -130,3 +196,122 @@ impl DaemonState {
         Some(client)
     }
 }
+
+/// Where the events of a run go
+#[derive(Debug, PartialEq)]
+enum EventOutput {
+    Ndjson(String),
+    Sqlite(String),
+    Tracer,
+}
+
+/// The NDJSON output of `Config::events_ndjson` if set, else the SQLite database of
+/// `Config::events_sqlite` if set, else Tracer, unless recording locally only, which writes to
+/// `TracerWorkDir::local_events_file`
+fn event_output(config: &Config) -> EventOutput {
+    if let Some(target) = &config.events_ndjson {
+        EventOutput::Ndjson(target.clone())
+    } else if let Some(path) = &config.events_sqlite {
+        EventOutput::Sqlite(path.clone())
+    } else if config.local_only {
+        EventOutput::Ndjson(TRACER_WORK_DIR.local_events_file.display().to_string())
+    } else {
+        EventOutput::Tracer
+    }
+}
+
+/// Opens the `event_output` of a run. Falls back to Tracer if a local output can't be opened,
+/// unless recording locally only, where no events are recorded then.
+async fn open_db_client(config: &Config) -> LogWriterEnum {
+    let output = event_output(config);
+    if config.local_only {
+        tracing::warn!("No token, recording events locally only: {:?}", output);
+    }
+    let local = match output {
+        EventOutput::Ndjson(target) => NdjsonWriter::open(&target).map(LogWriterEnum::Ndjson),
+        EventOutput::Sqlite(path) => SqliteWriter::open(path).map(LogWriterEnum::Sqlite),
+        EventOutput::Tracer => return crate::daemon::server::get_db_client().await,
+    };
+    match local {
+        Ok(writer) => writer,
+        Err(e) if config.local_only => {
+            tracing::error!("{:#}, no events will be recorded", e);
+            LogWriterEnum::Ndjson(NdjsonWriter::new(Box::new(std::io::sink())))
+        }
+        Err(e) => {
+            tracing::error!("{:#}, forwarding events instead", e);
+            crate::daemon::server::get_db_client().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::handlers::init_arguments::finalized_args;
+    use crate::client::exporters::event_writer::EventWriter;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn args_without_token() -> FinalizedInitArgs {
+        FinalizedInitArgs {
+            local_only: true,
+            ..finalized_args()
+        }
+    }
+
+    fn config_without_local_output() -> Config {
+        Config {
+            events_sqlite: None,
+            events_ndjson: None,
+            local_only: false,
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_startup_without_token_records_locally() {
+        let args = args_without_token();
+        let config = args.merge_into(config_without_local_output());
+        assert_eq!(
+            event_output(&config),
+            EventOutput::Ndjson(TRACER_WORK_DIR.local_events_file.display().to_string())
+        );
+        assert!(PipelineMetadata::new(&args, &config).local_only);
+
+        // an explicit local output is kept
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("events.ndjson");
+        let args = FinalizedInitArgs {
+            events_ndjson: Some(target.display().to_string()),
+            ..args_without_token()
+        };
+        let config = args.merge_into(config_without_local_output());
+        assert_eq!(
+            event_output(&config),
+            EventOutput::Ndjson(target.display().to_string())
+        );
+        assert!(matches!(
+            open_db_client(&config).await,
+            LogWriterEnum::Ndjson(_)
+        ));
+
+        // and so is the SQLite database, which accepts events without a user
+        let config = args_without_token().merge_into(Config {
+            events_sqlite: Some(temp_dir.path().join("events.db").display().to_string()),
+            ..config_without_local_output()
+        });
+        let writer = open_db_client(&config).await;
+        assert!(matches!(writer, LogWriterEnum::Sqlite(_)));
+        let mut event = Event::builder().body("local").timestamp(Utc::now()).build();
+        event.tags = Some(PipelineMetadata::new(&args_without_token(), &config).tags);
+        writer.batch_insert_events([event].iter()).await.unwrap();
+
+        let args = FinalizedInitArgs {
+            local_only: false,
+            ..args_without_token()
+        };
+        let config = args.merge_into(config_without_local_output());
+        assert_eq!(event_output(&config), EventOutput::Tracer);
+    }
+}
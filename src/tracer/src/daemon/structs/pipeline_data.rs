@@ -1,4 +1,5 @@
 use crate::cli::handlers::init_arguments::FinalizedInitArgs;
+use crate::config::Config;
 use crate::daemon::structs::{OpenTelemetryStatus, RunSnapshot};
 use crate::process_identification::types::pipeline_tags::PipelineTags;
 use crate::utils::env::get_build_channel;
@@ -12,10 +13,24 @@ pub struct PipelineMetadata {
     pub tags: PipelineTags,
     pub run_snapshot: Option<RunSnapshot>,
     pub opentelemetry_status: Option<OpenTelemetryStatus>,
+    /// Events the kernel dropped because the eBPF ring buffer was full
+    #[serde(default)]
+    pub ebpf_events_lost: u64,
+    /// Tool-matching rules the daemon loaded, built-in and user-provided; `None` until the
+    /// client is initialized
+    #[serde(default)]
+    pub active_rules: Option<usize>,
+    /// Events are recorded to `TracerWorkDir::local_events_file` only, as no token was found
+    #[serde(default)]
+    pub local_only: bool,
+    /// Configuration the daemon runs with, after merging the `tracer init` arguments, see
+    /// `Config::to_safe_json`
+    #[serde(default)]
+    pub config: Option<serde_json::Value>,
 }
 
 impl PipelineMetadata {
-    pub fn new(args: &FinalizedInitArgs) -> Self {
+    pub fn new(args: &FinalizedInitArgs, config: &Config) -> Self {
         Self {
             name: args.pipeline_name.clone(),
             start_time: Utc::now(),
@@ -23,6 +38,10 @@ impl PipelineMetadata {
             tags: args.tags.clone(),
             run_snapshot: None,
             opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: None,
+            local_only: config.local_only,
+            config: None,
         }
     }
     fn total_runtime(&self) -> TimeDelta {
@@ -0,0 +1,196 @@
+//! Detection of a local clock drifting away from the real time, e.g. on an instance whose NTP
+//! is broken. Events are timestamped with the local clock, so a skewed clock misorders them and
+//! corrupts the durations derived from their timestamps.
+
+use crate::process_identification::types::event::attributes::clock_skew::ClockSkew;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::DATE;
+use std::time::Duration;
+
+const REFERENCE_TIMEOUT_SECS: u64 = 5;
+
+/// A clock trusted to tell the real time
+#[allow(async_fn_in_trait)]
+pub trait ReferenceClock {
+    /// Name of the clock, reported in the alerts
+    fn name(&self) -> &str;
+
+    async fn now(&self) -> Result<DateTime<Utc>>;
+}
+
+/// The `Date` header of an HTTP server's responses, e.g. of an AWS endpoint. Only precise to
+/// the second, which is enough to notice a clock off by several seconds.
+pub struct HttpDateClock {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpDateClock {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: crate::utils::http::client_builder()
+                .timeout(Duration::from_secs(REFERENCE_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+        }
+    }
+}
+
+impl ReferenceClock for HttpDateClock {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    async fn now(&self) -> Result<DateTime<Utc>> {
+        let response = self
+            .client
+            .head(&self.url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", self.url))?;
+        let date = response
+            .headers()
+            .get(DATE)
+            .ok_or_else(|| anyhow!("{} returned no Date header", self.url))?
+            .to_str()?;
+        Ok(DateTime::parse_from_rfc2822(date)
+            .with_context(|| format!("Invalid Date header {:?}", date))?
+            .with_timezone(&Utc))
+    }
+}
+
+/// Compares the local clock against a `ReferenceClock`, to alert once when the skew goes over a
+/// threshold rather than on every check
+pub struct ClockSkewCheck<C> {
+    reference: C,
+    /// `None` disables the alerts
+    threshold_ms: Option<u64>,
+    skewed: bool,
+}
+
+impl<C: ReferenceClock> ClockSkewCheck<C> {
+    /// A zero threshold disables the alerts
+    pub fn new(reference: C, threshold_ms: u64) -> Self {
+        Self {
+            reference,
+            threshold_ms: (threshold_ms > 0).then_some(threshold_ms),
+            skewed: false,
+        }
+    }
+
+    /// Reads the reference clock, returning the skew if it just went over the threshold. Fails
+    /// if the reference clock can't be read, in which case nothing is known about the skew.
+    pub async fn check(&mut self) -> Result<Option<ClockSkew>> {
+        let Some(threshold_ms) = self.threshold_ms else {
+            return Ok(None);
+        };
+        let before = Utc::now();
+        let reference = self.reference.now().await?;
+        let after = Utc::now();
+        let clock_skew_ms = skew_ms(before, reference, after);
+
+        let skewed = clock_skew_ms.unsigned_abs() > threshold_ms;
+        let went_over = skewed && !self.skewed;
+        self.skewed = skewed;
+        Ok(went_over.then(|| ClockSkew {
+            clock_skew_ms,
+            threshold_ms,
+            reference: self.reference.name().to_string(),
+            reference_time: reference,
+        }))
+    }
+}
+
+/// How far ahead of `reference` the local clock is, in milliseconds, taking the local time
+/// halfway through the reading of the reference
+fn skew_ms(before: DateTime<Utc>, reference: DateTime<Utc>, after: DateTime<Utc>) -> i64 {
+    let local = before + (after - before) / 2;
+    (local - reference).num_milliseconds()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reference clock `offset` behind the local clock
+    struct OffsetClock {
+        offset: chrono::Duration,
+    }
+
+    impl ReferenceClock for OffsetClock {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn now(&self) -> Result<DateTime<Utc>> {
+            Ok(Utc::now() - self.offset)
+        }
+    }
+
+    struct BrokenClock;
+
+    impl ReferenceClock for BrokenClock {
+        fn name(&self) -> &str {
+            "broken"
+        }
+
+        async fn now(&self) -> Result<DateTime<Utc>> {
+            Err(anyhow!("unreachable"))
+        }
+    }
+
+    fn check(offset_secs: i64) -> ClockSkewCheck<OffsetClock> {
+        ClockSkewCheck::new(
+            OffsetClock {
+                offset: chrono::Duration::seconds(offset_secs),
+            },
+            5000,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_skew_over_threshold_is_reported_once() {
+        let mut check = check(30);
+        let skew = check.check().await.unwrap().unwrap();
+        assert!((29_900..=30_100).contains(&skew.clock_skew_ms));
+        assert_eq!(skew.threshold_ms, 5000);
+        assert_eq!(skew.reference, "test");
+        assert!(check.check().await.unwrap().is_none());
+
+        // reported again once back in sync and skewed again
+        check.reference.offset = chrono::Duration::zero();
+        assert!(check.check().await.unwrap().is_none());
+        check.reference.offset = chrono::Duration::seconds(-30);
+        let skew = check.check().await.unwrap().unwrap();
+        assert!(skew.clock_skew_ms < -29_900);
+    }
+
+    #[tokio::test]
+    async fn test_small_skew_is_not_reported() {
+        assert!(check(1).check().await.unwrap().is_none());
+        assert!(check(-1).check().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_or_unreachable_reference() {
+        let mut disabled = ClockSkewCheck::new(BrokenClock, 0);
+        assert!(disabled.check().await.unwrap().is_none());
+        assert!(ClockSkewCheck::new(BrokenClock, 5000)
+            .check()
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_skew_is_taken_halfway_through_the_reading() {
+        let before = Utc::now();
+        let after = before + chrono::Duration::milliseconds(400);
+        assert_eq!(skew_ms(before, before, after), 200);
+        assert_eq!(
+            skew_ms(before, before + chrono::Duration::seconds(10), after),
+            -9800
+        );
+    }
+}
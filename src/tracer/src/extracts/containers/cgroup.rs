@@ -0,0 +1,92 @@
+//! Association of processes to containers through their cgroup path.
+//!
+//! Container runtimes place each container in its own cgroup, whose path ends with the
+//! container id, e.g.:
+//! - cgroup v1: `12:memory:/docker/<id>` or `5:cpu:/kubepods/besteffort/pod<uid>/<id>`
+//! - cgroup v2: `0::/system.slice/docker-<id>.scope`, `0::/.../cri-containerd-<id>.scope`,
+//!   `0::/.../crio-<id>.scope` or `0::/.../libpod-<id>.scope`
+//!
+//! Processes on the host live in cgroups such as `0::/user.slice/...` and have no container id.
+
+use dashmap::DashMap;
+use std::sync::Arc;
+
+const SCOPE_PREFIXES: &[&str] = &["docker-", "cri-containerd-", "crio-", "libpod-"];
+
+/// Reads `/proc/<pid>/cgroup` and returns the id of the container the process runs in, if any
+pub fn container_id_for_pid(pid: u32) -> Option<String> {
+    let content = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    parse_container_id(&content)
+}
+
+/// Extracts the container id from the contents of a `/proc/<pid>/cgroup` file
+pub fn parse_container_id(content: &str) -> Option<String> {
+    content
+        .lines()
+        // v1: <hierarchy_id>:<controllers>:<path>, v2: 0::<path>
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .find_map(|path| path.split('/').rev().find_map(container_id_from_segment))
+        .map(String::from)
+}
+
+fn container_id_from_segment(segment: &str) -> Option<&str> {
+    let id = match segment.strip_suffix(".scope") {
+        Some(scope) => SCOPE_PREFIXES
+            .iter()
+            .find_map(|prefix| scope.strip_prefix(prefix))?,
+        None => segment,
+    };
+    is_container_id(id).then_some(id)
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Per-pid cache of container ids, so the cgroup file is read once per process
+#[derive(Clone, Default)]
+pub struct ContainerIdCache {
+    entries: Arc<DashMap<usize, Option<String>>>,
+}
+
+impl ContainerIdCache {
+    pub fn get_or_resolve(&self, pid: usize) -> Option<String> {
+        self.entries
+            .entry(pid)
+            .or_insert_with(|| container_id_for_pid(pid as u32))
+            .clone()
+    }
+
+    /// Drops the entry of a terminated process, as its pid may be reused
+    pub fn forget(&self, pid: usize) {
+        self.entries.remove(&pid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const ID: &str = "3f4e2a1b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f";
+
+    #[rstest]
+    #[case(format!("12:memory:/docker/{ID}\n11:cpu:/docker/{ID}"))]
+    #[case(format!("0::/system.slice/docker-{ID}.scope"))]
+    #[case(format!("5:cpu:/kubepods/besteffort/pod1234-abcd/{ID}"))]
+    #[case(format!("0::/kubepods.slice/kubepods-pod1.slice/cri-containerd-{ID}.scope"))]
+    #[case(format!("0::/kubepods.slice/kubepods-pod1.slice/crio-{ID}.scope"))]
+    #[case(format!("0::/machine.slice/libpod-{ID}.scope/container"))]
+    fn test_parse_container_id(#[case] content: String) {
+        assert_eq!(parse_container_id(&content).as_deref(), Some(ID));
+    }
+
+    #[rstest]
+    #[case("0::/user.slice/user-1000.slice/session-2.scope")]
+    #[case("12:memory:/\n0::/init.scope")]
+    #[case("0::/system.slice/docker-notanid.scope")]
+    #[case("")]
+    fn test_host_process_has_no_container_id(#[case] content: &str) {
+        assert_eq!(parse_container_id(content), None);
+    }
+}
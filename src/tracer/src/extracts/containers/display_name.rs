@@ -0,0 +1,127 @@
+use crate::extracts::containers::docker_watcher::event::ContainerEvent;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How the display name of a process running inside a container is derived.
+///
+/// Inside a container, tools are usually invoked through in-container paths
+/// (e.g. `/usr/local/bin/bwa`), so the image is often the most reliable way to tell tools apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerDisplayName {
+    /// Use the display name of the matched rule as is
+    #[default]
+    Unchanged,
+    /// Prefix the display name with the short image name, e.g. `star/STAR`
+    PrefixImage,
+    /// Use the short image name as display name, e.g. `star`
+    ImageName,
+}
+
+impl FromStr for ContainerDisplayName {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "unchanged" => Ok(Self::Unchanged),
+            "prefix_image" => Ok(Self::PrefixImage),
+            "image_name" => Ok(Self::ImageName),
+            _ => bail!(
+                "invalid container display name '{}': expected 'unchanged', 'prefix_image' or 'image_name'",
+                value
+            ),
+        }
+    }
+}
+
+/// Returns the short name of a container image, without registry, namespace, tag or digest,
+/// e.g. `quay.io/biocontainers/star:2.7.10b--h9ee0642_0` -> `star`
+pub fn image_short_name(image: &str) -> &str {
+    let image = image.split('@').next().unwrap_or(image);
+    let name = image.rsplit('/').next().unwrap_or(image);
+    name.split(':').next().unwrap_or(name)
+}
+
+/// Resolves the display name of a matched process, taking into account the container it runs
+/// in (if any)
+pub fn resolve_display_name(
+    display_name: &str,
+    container: Option<&ContainerEvent>,
+    mode: ContainerDisplayName,
+) -> String {
+    let image = container
+        .map(|container| image_short_name(&container.image))
+        .filter(|image| !image.is_empty());
+
+    match (mode, image) {
+        (ContainerDisplayName::PrefixImage, Some(image)) if image != display_name => {
+            format!("{}/{}", image, display_name)
+        }
+        (ContainerDisplayName::ImageName, Some(image)) => image.to_string(),
+        _ => display_name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::containers::docker_watcher::event::ContainerState;
+    use chrono::Utc;
+    use rstest::rstest;
+    use std::collections::HashMap;
+
+    fn container(image: &str) -> ContainerEvent {
+        ContainerEvent {
+            id: "abc".to_string(),
+            name: "nf-STAR_ALIGN".to_string(),
+            image: image.to_string(),
+            ip: None,
+            labels: HashMap::new(),
+            timestamp: Utc::now(),
+            state: ContainerState::Started,
+            environment_variables: vec![],
+            trace_id: None,
+            job_id: None,
+        }
+    }
+
+    #[rstest]
+    #[case("quay.io/biocontainers/star:2.7.10b--h9ee0642_0", "star")]
+    #[case("ubuntu", "ubuntu")]
+    #[case("localhost:5000/tools/bwa:0.7.17", "bwa")]
+    #[case("biocontainers/samtools@sha256:abcdef", "samtools")]
+    fn test_image_short_name(#[case] image: &str, #[case] expected: &str) {
+        assert_eq!(image_short_name(image), expected);
+    }
+
+    #[test]
+    fn test_containerized_tool_display_name() {
+        let star = container("quay.io/biocontainers/star:2.7.10b--h9ee0642_0");
+
+        assert_eq!(
+            resolve_display_name("STAR", Some(&star), ContainerDisplayName::Unchanged),
+            "STAR"
+        );
+        assert_eq!(
+            resolve_display_name("STAR", Some(&star), ContainerDisplayName::PrefixImage),
+            "star/STAR"
+        );
+        assert_eq!(
+            resolve_display_name("STAR", Some(&star), ContainerDisplayName::ImageName),
+            "star"
+        );
+    }
+
+    #[test]
+    fn test_host_process_display_name_is_unchanged() {
+        assert_eq!(
+            resolve_display_name("bwa", None, ContainerDisplayName::PrefixImage),
+            "bwa"
+        );
+        assert_eq!(
+            resolve_display_name("bwa", None, ContainerDisplayName::ImageName),
+            "bwa"
+        );
+    }
+}
@@ -247,7 +247,7 @@ impl DockerWatcher {
         let state = self.container_state.read().await;
 
         // Log all keys (container IDs) currently stored
-        tracing::info!(
+        tracing::debug!(
             "Looking for container ID: {:?} | Currently stored IDs: {:?}",
             container_id,
             state.keys().collect::<Vec<&ContainerId>>()
@@ -1,3 +1,5 @@
+pub mod cgroup;
+pub mod display_name;
 pub mod docker_watcher;
 
 pub use docker_watcher::watcher::DockerWatcher;
@@ -0,0 +1,297 @@
+//! Resource limits of the cgroup a process runs in.
+//!
+//! Inside a container, `sysinfo` reports the resources of the host, while the workload is
+//! limited by its cgroup. The cgroup of a process is read from `/proc/<pid>/cgroup`, and its
+//! limits from the cgroup filesystem mounted at `/sys/fs/cgroup`:
+//! - cgroup v2: a single hierarchy (`0::<path>`), with `memory.max` and `memory.current`
+//!   for memory, and `cpu.max` and `cpu.stat` for CPU
+//! - cgroup v1: one hierarchy per controller (`<id>:memory:<path>`), mounted at
+//!   `/sys/fs/cgroup/<controller>`, with `memory.limit_in_bytes` and `memory.usage_in_bytes`
+//!   for memory, and `cpu.cfs_quota_us`, `cpu.cfs_period_us` and `cpuacct.usage` for CPU
+//!
+//! With a cgroup namespace (the default for containers), the cgroup of the container is mounted
+//! at the root, so the root is used when the path of the cgroup is not found under it.
+
+use std::path::{Path, PathBuf};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+/// cgroup v1 reports the absence of a memory limit as a huge value (`PAGE_COUNTER_MAX`)
+const V1_UNLIMITED_MEMORY_BYTES: u64 = 1 << 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupVersion {
+    V1,
+    V2,
+}
+
+/// Memory limit and usage of a cgroup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CgroupMemory {
+    pub limit_bytes: u64,
+    pub usage_bytes: u64,
+}
+
+impl CgroupMemory {
+    /// Usage in percent of the limit
+    pub fn utilization(&self) -> f64 {
+        self.usage_bytes as f64 / self.limit_bytes as f64 * 100.0
+    }
+}
+
+/// CPU quota and usage of a cgroup
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CgroupCpu {
+    /// Number of CPUs the quota allows to use, e.g. `1.5` for 150ms every 100ms
+    pub quota_cpus: f64,
+    /// Total CPU time used by the cgroup, if available
+    pub usage_usec: Option<u64>,
+}
+
+impl CgroupCpu {
+    /// Number of whole CPUs the workload can use, at most `host_cpus`
+    pub fn effective_cpu_count(&self, host_cpus: usize) -> usize {
+        (self.quota_cpus.ceil() as usize).clamp(1, host_cpus.max(1))
+    }
+}
+
+/// Returns the memory limit and usage of the cgroup of the current process, if it is limited
+pub fn current_cgroup_memory() -> Option<CgroupMemory> {
+    let proc_cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    read_cgroup_memory(Path::new(CGROUP_ROOT), &proc_cgroup)
+}
+
+/// Returns the memory limit and usage of the cgroup of the given process, if it is limited
+pub fn cgroup_memory_for_pid(pid: u32) -> Option<CgroupMemory> {
+    let proc_cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    read_cgroup_memory(Path::new(CGROUP_ROOT), &proc_cgroup)
+}
+
+/// Reads the memory limit and usage of the cgroup described by `proc_cgroup` (the contents of
+/// a `/proc/<pid>/cgroup` file) from the cgroup filesystem mounted at `root`
+pub fn read_cgroup_memory(root: &Path, proc_cgroup: &str) -> Option<CgroupMemory> {
+    let version = detect_cgroup_version(root)?;
+    let dir = cgroup_dir(root, version, "memory", proc_cgroup)?;
+    let (limit_bytes, usage_bytes) = match version {
+        CgroupVersion::V2 => (
+            // `max` when unlimited
+            read_u64(&dir.join("memory.max"))?,
+            read_u64(&dir.join("memory.current"))?,
+        ),
+        CgroupVersion::V1 => (
+            read_u64(&dir.join("memory.limit_in_bytes"))
+                .filter(|limit| *limit < V1_UNLIMITED_MEMORY_BYTES)?,
+            read_u64(&dir.join("memory.usage_in_bytes"))?,
+        ),
+    };
+    Some(CgroupMemory {
+        limit_bytes,
+        usage_bytes,
+    })
+}
+
+/// Returns the CPU quota and usage of the cgroup of the current process, if it has a quota
+pub fn current_cgroup_cpu() -> Option<CgroupCpu> {
+    let proc_cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    read_cgroup_cpu(Path::new(CGROUP_ROOT), &proc_cgroup)
+}
+
+/// Reads the CPU quota and usage of the cgroup described by `proc_cgroup` (the contents of a
+/// `/proc/<pid>/cgroup` file) from the cgroup filesystem mounted at `root`
+pub fn read_cgroup_cpu(root: &Path, proc_cgroup: &str) -> Option<CgroupCpu> {
+    let version = detect_cgroup_version(root)?;
+    let dir = cgroup_dir(root, version, "cpu", proc_cgroup)?;
+    let (quota_usec, period_usec, usage_usec) = match version {
+        CgroupVersion::V2 => {
+            // `<quota> <period>`, with `max` as quota when unlimited
+            let cpu_max = std::fs::read_to_string(dir.join("cpu.max")).ok()?;
+            let mut fields = cpu_max.split_whitespace();
+            let quota = fields.next()?.parse::<u64>().ok()?;
+            let period = fields.next()?.parse::<u64>().ok()?;
+            let usage = std::fs::read_to_string(dir.join("cpu.stat"))
+                .ok()
+                .and_then(|stat| {
+                    stat.lines()
+                        .find_map(|line| line.strip_prefix("usage_usec "))
+                        .and_then(|usage| usage.trim().parse().ok())
+                });
+            (quota, period, usage)
+        }
+        CgroupVersion::V1 => {
+            // -1 when unlimited
+            let quota = std::fs::read_to_string(dir.join("cpu.cfs_quota_us"))
+                .ok()?
+                .trim()
+                .parse::<u64>()
+                .ok()?;
+            let period = read_u64(&dir.join("cpu.cfs_period_us"))?;
+            let usage = cgroup_dir(root, version, "cpuacct", proc_cgroup)
+                .and_then(|dir| read_u64(&dir.join("cpuacct.usage")))
+                .map(|usage_nsec| usage_nsec / 1000);
+            (quota, period, usage)
+        }
+    };
+    (period_usec > 0).then(|| CgroupCpu {
+        quota_cpus: quota_usec as f64 / period_usec as f64,
+        usage_usec,
+    })
+}
+
+pub fn detect_cgroup_version(root: &Path) -> Option<CgroupVersion> {
+    if root.join("cgroup.controllers").exists() {
+        Some(CgroupVersion::V2)
+    } else if root.join("memory").is_dir() || root.join("cpu").is_dir() {
+        Some(CgroupVersion::V1)
+    } else {
+        None
+    }
+}
+
+/// Returns the directory of the cgroup of `controller` described by `proc_cgroup`
+pub(crate) fn cgroup_dir(
+    root: &Path,
+    version: CgroupVersion,
+    controller: &str,
+    proc_cgroup: &str,
+) -> Option<PathBuf> {
+    let path = proc_cgroup.lines().find_map(|line| {
+        // v1: <hierarchy_id>:<controllers>:<path>, v2: 0::<path>
+        let mut fields = line.splitn(3, ':');
+        let (_, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+        let matches = match version {
+            CgroupVersion::V2 => controllers.is_empty(),
+            CgroupVersion::V1 => controllers.split(',').any(|name| name == controller),
+        };
+        matches.then_some(path)
+    })?;
+
+    let base = match version {
+        CgroupVersion::V2 => root.to_path_buf(),
+        CgroupVersion::V1 => root.join(controller),
+    };
+    let dir = base.join(path.trim_start_matches('/'));
+    Some(if dir.is_dir() { dir } else { base })
+}
+
+pub(crate) fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_read_cgroup_v2_memory() {
+        let root = TempDir::new().unwrap();
+        write(root.path(), "cgroup.controllers", "cpu memory");
+        write(root.path(), "memory.max", "max\n");
+        write(root.path(), "memory.current", "1000\n");
+        let scope = "system.slice/docker-abc.scope";
+        write(root.path(), &format!("{scope}/memory.max"), "4294967296\n");
+        write(
+            root.path(),
+            &format!("{scope}/memory.current"),
+            "1073741824\n",
+        );
+
+        let memory = read_cgroup_memory(root.path(), &format!("0::/{scope}\n")).unwrap();
+        assert_eq!(memory.limit_bytes, 4 * 1024 * 1024 * 1024);
+        assert_eq!(memory.usage_bytes, 1024 * 1024 * 1024);
+        assert_eq!(memory.utilization(), 25.0);
+
+        // namespaced: the cgroup of the container is mounted at the root, which is unlimited
+        assert_eq!(read_cgroup_memory(root.path(), "0::/\n"), None);
+    }
+
+    #[test]
+    fn test_read_cgroup_v1_memory() {
+        let root = TempDir::new().unwrap();
+        write(
+            root.path(),
+            "memory/memory.limit_in_bytes",
+            "9223372036854771712\n",
+        );
+        write(root.path(), "memory/memory.usage_in_bytes", "1000\n");
+        let proc_cgroup = "12:memory:/docker/abc\n11:cpu,cpuacct:/docker/abc\n";
+
+        // no limit
+        assert_eq!(read_cgroup_memory(root.path(), proc_cgroup), None);
+
+        write(
+            root.path(),
+            "memory/docker/abc/memory.limit_in_bytes",
+            "2048\n",
+        );
+        write(
+            root.path(),
+            "memory/docker/abc/memory.usage_in_bytes",
+            "512\n",
+        );
+        assert_eq!(
+            read_cgroup_memory(root.path(), proc_cgroup),
+            Some(CgroupMemory {
+                limit_bytes: 2048,
+                usage_bytes: 512
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_cgroup_v2_cpu() {
+        let root = TempDir::new().unwrap();
+        write(root.path(), "cgroup.controllers", "cpu memory");
+        write(root.path(), "cpu.max", "max 100000\n");
+
+        // no quota
+        assert_eq!(read_cgroup_cpu(root.path(), "0::/\n"), None);
+
+        write(root.path(), "cpu.max", "150000 100000\n");
+        write(
+            root.path(),
+            "cpu.stat",
+            "usage_usec 2500000\nuser_usec 2000000\nsystem_usec 500000\n",
+        );
+        let cpu = read_cgroup_cpu(root.path(), "0::/\n").unwrap();
+        assert_eq!(cpu.quota_cpus, 1.5);
+        assert_eq!(cpu.usage_usec, Some(2_500_000));
+        assert_eq!(cpu.effective_cpu_count(64), 2);
+        assert_eq!(cpu.effective_cpu_count(1), 1);
+    }
+
+    #[test]
+    fn test_read_cgroup_v1_cpu() {
+        let root = TempDir::new().unwrap();
+        let proc_cgroup = "4:cpuacct:/docker/abc\n3:cpu:/docker/abc\n";
+        write(root.path(), "cpu/docker/abc/cpu.cfs_quota_us", "-1\n");
+        write(root.path(), "cpu/docker/abc/cpu.cfs_period_us", "100000\n");
+
+        // no quota
+        assert_eq!(read_cgroup_cpu(root.path(), proc_cgroup), None);
+
+        write(root.path(), "cpu/docker/abc/cpu.cfs_quota_us", "200000\n");
+        write(
+            root.path(),
+            "cpuacct/docker/abc/cpuacct.usage",
+            "3000000000\n",
+        );
+        let cpu = read_cgroup_cpu(root.path(), proc_cgroup).unwrap();
+        assert_eq!(cpu.quota_cpus, 2.0);
+        assert_eq!(cpu.usage_usec, Some(3_000_000));
+    }
+
+    #[test]
+    fn test_no_cgroup_filesystem() {
+        let root = TempDir::new().unwrap();
+        assert_eq!(detect_cgroup_version(root.path()), None);
+        assert_eq!(read_cgroup_memory(root.path(), "0::/\n"), None);
+        assert_eq!(read_cgroup_cpu(root.path(), "0::/\n"), None);
+    }
+}
@@ -10,7 +10,7 @@ use amd::AmdGpuMonitor;
 use apple::AppleGpuMonitor;
 use nvidia::NvidiaGpuMonitor;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct GpuAggregateStats {
     pub avg_utilization: Option<f32>,
     pub total_memory_used: Option<u64>,
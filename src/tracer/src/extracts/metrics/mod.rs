@@ -1,2 +1,4 @@
+pub mod cgroup_limits;
 pub mod gpu_monitor;
+pub mod snapshot;
 pub mod system_metrics_collector;
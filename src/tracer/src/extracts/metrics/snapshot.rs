@@ -0,0 +1,165 @@
+use crate::extracts::metrics::gpu_monitor::{GpuAggregateStats, GpuMonitor};
+use crate::process_identification::types::event::attributes::process::FullProcessProperties;
+use crate::process_identification::types::event::attributes::system_metrics::SystemMetric;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// A metric and when it was gathered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample<T> {
+    pub collected_at: DateTime<Utc>,
+    pub metrics: T,
+}
+
+/// The most recent metrics of the run, as served by the daemon's metrics endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// When the snapshot was read, to tell how stale each sample is
+    pub taken_at: Option<DateTime<Utc>>,
+    pub system: Option<Sample<SystemMetric>>,
+    /// Aggregates over all GPUs, gathered with the system metrics; `None` without GPUs
+    pub gpu: Option<Sample<GpuAggregateStats>>,
+    /// Latest sample of each monitored process, by pid
+    pub processes: BTreeMap<usize, Sample<FullProcessProperties>>,
+}
+
+/// Keeps the last values gathered by the monitor loop, so they can be read at any time without
+/// sampling again. Clones share the same values.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshots {
+    latest: Arc<RwLock<MetricsSnapshot>>,
+}
+
+impl MetricsSnapshots {
+    pub fn record_system(&self, metric: &SystemMetric, collected_at: DateTime<Utc>) {
+        let gpu = (!metric.system_gpu_stats.is_empty()).then(|| Sample {
+            collected_at,
+            metrics: GpuMonitor::calculate_aggregate_gpu_metrics(&metric.system_gpu_stats),
+        });
+        let mut latest = self.write();
+        latest.system = Some(Sample {
+            collected_at,
+            metrics: metric.clone(),
+        });
+        latest.gpu = gpu;
+    }
+
+    pub fn record_process(
+        &self,
+        pid: usize,
+        properties: &FullProcessProperties,
+        collected_at: DateTime<Utc>,
+    ) {
+        self.write().processes.insert(
+            pid,
+            Sample {
+                collected_at,
+                metrics: properties.clone(),
+            },
+        );
+    }
+
+    /// Forgets the processes not in `monitored`
+    pub fn retain_processes(&self, monitored: &HashSet<usize>) {
+        self.write()
+            .processes
+            .retain(|pid, _| monitored.contains(pid));
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut snapshot = self
+            .latest
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        snapshot.taken_at = Some(Utc::now());
+        snapshot
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, MetricsSnapshot> {
+        self.latest
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::process::extract_process_data::create_short_lived_process_object;
+    use crate::process_identification::types::event::attributes::process::ProcessProperties;
+    use chrono::TimeDelta;
+    use std::collections::HashMap;
+    use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+    fn system_metric(cpu: f32) -> SystemMetric {
+        SystemMetric {
+            events_name: "global_system_metrics".to_string(),
+            system_memory_total: 0,
+            system_memory_used: 0,
+            system_memory_available: 0,
+            system_memory_utilization: 0.0,
+            system_memory_cgroup_scoped: false,
+            system_memory_swap_total: 0,
+            system_memory_swap_used: 0,
+            system_cpu_utilization: cpu,
+            system_cpu_quota: None,
+            system_cpu_quota_utilization: None,
+            system_disk_total_space: 0,
+            system_disk_used_space: 0,
+            system_disk_io: HashMap::new(),
+            system_gpu_utilization: None,
+            system_gpu_memory_used: None,
+            system_gpu_memory_total: None,
+            system_gpu_memory_utilization: None,
+            system_gpu_stats: HashMap::new(),
+            ebpf_events_lost: 0,
+        }
+    }
+
+    fn process(pid: usize, cpu: f32) -> FullProcessProperties {
+        let trigger = ProcessStartTrigger::from_name_and_args(pid, 1, "STAR", &["STAR"]);
+        let ProcessProperties::Full(mut properties) =
+            create_short_lived_process_object(&trigger, "STAR".to_string());
+        properties.process_cpu_utilization = cpu;
+        *properties
+    }
+
+    #[test]
+    fn test_snapshot_reflects_latest_poll() {
+        let snapshots = MetricsSnapshots::default();
+        let first_poll = Utc::now();
+        let second_poll = first_poll + TimeDelta::seconds(5);
+
+        snapshots.record_system(&system_metric(10.0), first_poll);
+        snapshots.record_process(42, &process(42, 10.0), first_poll);
+        snapshots.record_process(43, &process(43, 20.0), first_poll);
+
+        snapshots.record_system(&system_metric(80.0), second_poll);
+        snapshots.retain_processes(&HashSet::from([42]));
+        snapshots.record_process(42, &process(42, 90.0), second_poll);
+
+        let snapshot = snapshots.snapshot();
+        let system = snapshot.system.unwrap();
+        assert_eq!(system.collected_at, second_poll);
+        assert_eq!(system.metrics.system_cpu_utilization, 80.0);
+        assert!(snapshot.gpu.is_none());
+        assert_eq!(snapshot.processes.len(), 1);
+        let process = &snapshot.processes[&42];
+        assert_eq!(process.collected_at, second_poll);
+        assert_eq!(process.metrics.process_cpu_utilization, 90.0);
+        assert!(snapshot.taken_at.unwrap() >= first_poll);
+    }
+
+    #[test]
+    fn test_clones_share_values() {
+        let snapshots = MetricsSnapshots::default();
+        assert!(snapshots.snapshot().system.is_none());
+        snapshots
+            .clone()
+            .record_system(&system_metric(10.0), Utc::now());
+        assert!(snapshots.snapshot().system.is_some());
+    }
+}
@@ -7,15 +7,33 @@ use crate::process_identification::types::event::ProcessStatus;
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use sysinfo::{Disks, System};
 use tokio::sync::RwLock;
 
+use crate::constants::CGROUP_MEMORY_ALERT_UTILIZATION;
+use crate::extracts::metrics::cgroup_limits::{
+    current_cgroup_cpu, current_cgroup_memory, CgroupCpu,
+};
 use crate::extracts::metrics::gpu_monitor::GpuMonitor;
+use crate::extracts::metrics::snapshot::MetricsSnapshots;
 
 pub struct SystemMetricsCollector {
     event_dispatcher: EventDispatcher,
     system: Arc<RwLock<System>>,
+    /// Whether memory usage was near the cgroup limit at the last collection
+    near_memory_limit: AtomicBool,
+    /// CPU time used by the cgroup at the last collection, to compute its utilization
+    last_cgroup_cpu_usage: std::sync::Mutex<Option<(Instant, u64)>>,
+    /// Mount points whose disks are reported, all if empty, see `Config::disk_mounts`
+    disk_mounts: Vec<String>,
+    /// Whether GPU metrics are collected, see `Config::enable_gpu`
+    collect_gpu: bool,
+    /// Where the last collected metric is kept for the daemon's metrics endpoint
+    snapshots: MetricsSnapshots,
 }
 
 impl SystemMetricsCollector {
@@ -23,12 +41,35 @@ impl SystemMetricsCollector {
         Self {
             event_dispatcher,
             system,
+            near_memory_limit: AtomicBool::new(false),
+            last_cgroup_cpu_usage: std::sync::Mutex::new(None),
+            disk_mounts: Vec::new(),
+            collect_gpu: true,
+            snapshots: MetricsSnapshots::default(),
         }
     }
 
-    pub fn gather_disk_data() -> HashMap<String, DiskStatistic> {
+    pub fn with_disk_mounts(mut self, disk_mounts: Vec<String>) -> Self {
+        self.disk_mounts = disk_mounts;
+        self
+    }
+
+    pub fn with_gpu(mut self, collect_gpu: bool) -> Self {
+        self.collect_gpu = collect_gpu;
+        self
+    }
+
+    pub fn with_snapshots(mut self, snapshots: MetricsSnapshots) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Space statistics of the mounted disks, by disk name. Only disks mounted at or below one
+    /// of `mounts` are included, unless it is empty.
+    pub fn gather_disk_data(mounts: &[String]) -> HashMap<String, DiskStatistic> {
         Disks::new_with_refreshed_list()
             .iter()
+            .filter(|disk| is_reported_mount(disk.mount_point(), mounts))
             .filter_map(|disk| {
                 let d_name = disk.name().to_str()?;
 
@@ -44,6 +85,8 @@ impl SystemMetricsCollector {
                     disk_used_space: used_space,
                     disk_available_space: available_space,
                     disk_utilization,
+                    fs_type: disk.file_system().to_string_lossy().to_string(),
+                    mount_point: disk.mount_point().to_string_lossy().to_string(),
                 };
 
                 Some((d_name.to_string(), disk_data))
@@ -54,20 +97,39 @@ impl SystemMetricsCollector {
     pub async fn gather_metrics_object_attributes(&self) -> SystemMetric {
         let system = self.system.read().await;
 
-        let used_memory = system.used_memory();
-        let total_memory = system.total_memory();
+        // inside a memory-limited container, report the memory of the container
+        let cgroup_memory =
+            current_cgroup_memory().filter(|memory| memory.limit_bytes < system.total_memory());
+        let (total_memory, used_memory, available_memory) = match cgroup_memory {
+            Some(memory) => (
+                memory.limit_bytes,
+                memory.usage_bytes,
+                memory.limit_bytes.saturating_sub(memory.usage_bytes),
+            ),
+            None => (
+                system.total_memory(),
+                system.used_memory(),
+                system.available_memory(),
+            ),
+        };
 
         let memory_utilization = (used_memory as f64 / total_memory as f64) * 100.0;
 
         let cpu_usage = system.global_cpu_usage();
+        let cgroup_cpu = current_cgroup_cpu();
+        let cpu_quota_utilization = cgroup_cpu.and_then(|cpu| self.cgroup_cpu_utilization(&cpu));
 
-        let disk_stats = Self::gather_disk_data();
+        let disk_stats = Self::gather_disk_data(&self.disk_mounts);
 
         let system_disk_total_space = Self::calculate_total_disk_space(&disk_stats);
         let system_disk_used_space = Self::calculate_total_disk_used_space(&disk_stats);
 
         // Collect GPU metrics
-        let gpu_stats = GpuMonitor::collect_gpu_stats().unwrap_or_default();
+        let gpu_stats = if self.collect_gpu {
+            GpuMonitor::collect_gpu_stats().unwrap_or_default()
+        } else {
+            Default::default()
+        };
         let gpu_aggregate = GpuMonitor::calculate_aggregate_gpu_metrics(&gpu_stats);
         let system_gpu_utilization = gpu_aggregate.avg_utilization;
         let system_gpu_memory_used = gpu_aggregate.total_memory_used;
@@ -78,11 +140,14 @@ impl SystemMetricsCollector {
             events_name: "global_system_metrics".to_string(),
             system_memory_total: total_memory,
             system_memory_used: used_memory,
-            system_memory_available: system.available_memory(),
+            system_memory_available: available_memory,
             system_memory_utilization: memory_utilization,
+            system_memory_cgroup_scoped: cgroup_memory.is_some(),
             system_memory_swap_total: system.total_swap(),
             system_memory_swap_used: system.used_swap(),
             system_cpu_utilization: cpu_usage,
+            system_cpu_quota: cgroup_cpu.map(|cpu| cpu.quota_cpus),
+            system_cpu_quota_utilization: cpu_quota_utilization,
             system_disk_total_space,
             system_disk_used_space,
             system_disk_io: disk_stats,
@@ -91,12 +156,29 @@ impl SystemMetricsCollector {
             system_gpu_memory_total,
             system_gpu_memory_utilization,
             system_gpu_stats: gpu_stats,
+            ebpf_events_lost: tracer_ebpf::stats::events_lost(),
         }
     }
 
     pub async fn collect_metrics(&self) -> Result<()> {
-        let attributes =
-            EventAttributes::SystemMetric(self.gather_metrics_object_attributes().await);
+        let metric = self.gather_metrics_object_attributes().await;
+        self.snapshots.record_system(&metric, Utc::now());
+        if self.reached_memory_limit(&metric) {
+            self.event_dispatcher
+                .log_with_metadata(
+                    ProcessStatus::Alert,
+                    format!(
+                        "Memory usage at {:.1}% of the container limit ({} of {} bytes)",
+                        metric.system_memory_utilization,
+                        metric.system_memory_used,
+                        metric.system_memory_total
+                    ),
+                    Some(EventAttributes::SystemMetric(metric.clone())),
+                    None,
+                )
+                .await?;
+        }
+        let attributes = EventAttributes::SystemMetric(metric);
 
         self.event_dispatcher
             .log_with_metadata(
@@ -110,6 +192,32 @@ impl SystemMetricsCollector {
         Ok(())
     }
 
+    /// CPU usage of the cgroup since the previous call, in percent of its quota
+    fn cgroup_cpu_utilization(&self, cpu: &CgroupCpu) -> Option<f32> {
+        let usage_usec = cpu.usage_usec?;
+        let now = Instant::now();
+        let previous = self
+            .last_cgroup_cpu_usage
+            .lock()
+            .expect("cgroup cpu usage lock poisoned")
+            .replace((now, usage_usec));
+        let (previous_at, previous_usage_usec) = previous?;
+        quota_utilization(
+            usage_usec.saturating_sub(previous_usage_usec),
+            now - previous_at,
+            cpu.quota_cpus,
+        )
+    }
+
+    /// Whether memory usage just reached `CGROUP_MEMORY_ALERT_UTILIZATION` of the cgroup limit.
+    /// Only true again once usage went back below it.
+    fn reached_memory_limit(&self, metric: &SystemMetric) -> bool {
+        let near_limit = metric.system_memory_cgroup_scoped
+            && metric.system_memory_utilization >= CGROUP_MEMORY_ALERT_UTILIZATION;
+        let was_near_limit = self.near_memory_limit.swap(near_limit, Ordering::Relaxed);
+        near_limit && !was_near_limit
+    }
+
     pub fn calculate_total_disk_space(system_disks: &HashMap<String, DiskStatistic>) -> u64 {
         // for each DiskStatistic object in the hashmap, summing the value of the disk_total_space
         // to retrieve the total disk available in the machine
@@ -127,17 +235,26 @@ impl SystemMetricsCollector {
     }
 }
 
+/// CPU time used over `elapsed`, in percent of what `quota_cpus` allows
+fn quota_utilization(used_usec: u64, elapsed: Duration, quota_cpus: f64) -> Option<f32> {
+    let available_usec = elapsed.as_micros() as f64 * quota_cpus;
+    (available_usec > 0.0).then(|| (used_usec as f64 / available_usec * 100.0) as f32)
+}
+
+/// Whether a disk mounted at `mount_point` is at or below one of `mounts`, or `mounts` is empty
+fn is_reported_mount(mount_point: &Path, mounts: &[String]) -> bool {
+    mounts.is_empty() || mounts.iter().any(|mount| mount_point.starts_with(mount))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::daemon::structs::PipelineMetadata;
     use crate::process_identification::types::current_run::RunMetadata;
+    use crate::process_identification::types::event::Event;
     use tokio::sync::Mutex;
 
-    #[tokio::test]
-    async fn test_collect_metrics() {
-        let system = System::new_all();
-
+    fn recorder() -> (EventDispatcher, tokio::sync::mpsc::Receiver<Event>) {
         let pipeline = Arc::new(Mutex::new(PipelineMetadata {
             name: "test_pipeline".to_string(),
             run_snapshot: None,
@@ -145,6 +262,10 @@ mod tests {
             is_dev: true,
             start_time: Default::default(),
             opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: None,
+            local_only: false,
+            config: None,
         }));
 
         let run = RunMetadata {
@@ -152,16 +273,26 @@ mod tests {
             name: "test_run_name".to_string(),
             trace_id: Option::from("test_trace_id".to_string()),
             start_time: Utc::now(),
+            started: std::time::Instant::now(),
             cost_summary: None,
         };
 
-        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        (EventDispatcher::new(pipeline, run, tx), rx)
+    }
+
+    #[tokio::test]
+    async fn test_collect_metrics() {
+        let system = System::new_all();
 
-        let recorder = EventDispatcher::new(pipeline, run, tx);
+        let (recorder, mut rx) = recorder();
 
-        let collector = SystemMetricsCollector::new(recorder, Arc::new(RwLock::new(system)));
+        let snapshots = MetricsSnapshots::default();
+        let collector = SystemMetricsCollector::new(recorder, Arc::new(RwLock::new(system)))
+            .with_snapshots(snapshots.clone());
 
         collector.collect_metrics().await.unwrap();
+        assert!(snapshots.snapshot().system.is_some());
 
         assert_eq!(1, rx.len());
         let event = rx.recv().await.unwrap();
@@ -182,6 +313,95 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_gpu_metrics_are_skipped_when_disabled() {
+        let (recorder, _rx) = recorder();
+        let collector = SystemMetricsCollector::new(recorder, Arc::new(RwLock::new(System::new())))
+            .with_gpu(false);
+
+        let metric = collector.gather_metrics_object_attributes().await;
+        assert!(metric.system_gpu_stats.is_empty());
+        assert_eq!(metric.system_gpu_memory_total, None);
+    }
+
+    #[test]
+    fn test_quota_utilization() {
+        // 1.5 CPUs used over 1 second with a quota of 2 CPUs
+        assert_eq!(
+            quota_utilization(1_500_000, Duration::from_secs(1), 2.0),
+            Some(75.0)
+        );
+        assert_eq!(quota_utilization(1_000, Duration::ZERO, 2.0), None);
+    }
+
+    #[test]
+    fn test_disk_mounts_filter() {
+        let mounts = vec!["/scratch".to_string(), "/mnt/lustre".to_string()];
+        assert!(is_reported_mount(Path::new("/scratch"), &mounts));
+        assert!(is_reported_mount(Path::new("/mnt/lustre/project"), &mounts));
+        assert!(!is_reported_mount(Path::new("/"), &mounts));
+        // whole path components only
+        assert!(!is_reported_mount(Path::new("/scratch2"), &mounts));
+        // everything is reported without a filter
+        assert!(is_reported_mount(Path::new("/"), &[]));
+    }
+
+    #[test]
+    fn test_disk_data_has_mount_and_fs_type() {
+        for (name, disk) in SystemMetricsCollector::gather_disk_data(&[]) {
+            assert!(!disk.mount_point.is_empty(), "{} has no mount point", name);
+        }
+        assert!(SystemMetricsCollector::gather_disk_data(&["/nonexistent".to_string()]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit_alert_on_crossing() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        let run = RunMetadata {
+            id: "test_run_id".to_string(),
+            name: "test_run_name".to_string(),
+            trace_id: None,
+            start_time: Utc::now(),
+            started: std::time::Instant::now(),
+            cost_summary: None,
+        };
+        let pipeline = Arc::new(Mutex::new(PipelineMetadata {
+            name: "test_pipeline".to_string(),
+            run_snapshot: None,
+            tags: Default::default(),
+            is_dev: true,
+            start_time: Default::default(),
+            opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: None,
+            local_only: false,
+            config: None,
+        }));
+        let collector = SystemMetricsCollector::new(
+            EventDispatcher::new(pipeline, run, tx),
+            Arc::new(RwLock::new(System::new())),
+        );
+
+        let mut metric = collector.gather_metrics_object_attributes().await;
+        metric.system_memory_cgroup_scoped = true;
+        let reached = |collector: &SystemMetricsCollector, metric: &mut SystemMetric, util| {
+            metric.system_memory_utilization = util;
+            collector.reached_memory_limit(metric)
+        };
+
+        assert!(!reached(&collector, &mut metric, 50.0));
+        assert!(reached(&collector, &mut metric, 95.0));
+        // still near the limit
+        assert!(!reached(&collector, &mut metric, 97.0));
+        assert!(!reached(&collector, &mut metric, 60.0));
+        assert!(reached(&collector, &mut metric, 91.0));
+
+        // host memory is not alerted on
+        metric.system_memory_cgroup_scoped = false;
+        assert!(!reached(&collector, &mut metric, 50.0));
+        assert!(!reached(&collector, &mut metric, 99.0));
+    }
+
     #[test]
     fn test_gpu_aggregate_calculation() {
         use crate::process_identification::types::event::attributes::system_metrics::GpuStatistic;
@@ -1,3 +1,4 @@
+pub mod clock_skew;
 pub mod containers;
 mod data_samples;
 pub mod files;
@@ -5,3 +6,4 @@ pub mod metrics;
 pub mod process;
 pub mod process_watcher;
 pub mod python_monitor;
+pub mod syslog;
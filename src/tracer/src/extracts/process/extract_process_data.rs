@@ -1,3 +1,5 @@
+use crate::extracts::containers::cgroup;
+use crate::extracts::process::swap;
 use crate::process_identification::types::event::attributes::process::{
     FullProcessProperties, ProcessProperties,
 };
@@ -26,6 +28,7 @@ pub trait ProcessTrait {
     fn memory(&self) -> u64;
     fn virtual_memory(&self) -> u64;
     fn status(&self) -> ProcessStatus;
+    fn swap_used(&self) -> Option<u64>;
 }
 
 // Implement the trait for the real Process
@@ -81,6 +84,10 @@ impl ProcessTrait for sysinfo::Process {
     fn status(&self) -> ProcessStatus {
         self.status()
     }
+
+    fn swap_used(&self) -> Option<u64> {
+        swap::swap_used(self.pid().as_u32())
+    }
 }
 
 pub async fn gather_process_data<P: ProcessTrait>(
@@ -129,6 +136,7 @@ pub async fn gather_process_data<P: ProcessTrait>(
         process_disk_usage_write_last_interval: proc.disk_usage().written_bytes,
         process_memory_usage: proc.memory(),
         process_memory_virtual: proc.virtual_memory(),
+        swap_used: proc.swap_used(),
         process_status: proc.status().to_string(),
         container_id,
         job_id,
@@ -136,6 +144,9 @@ pub async fn gather_process_data<P: ProcessTrait>(
         trace_id,
         container_event: None,
         tool_id,
+        process_tree: None,
+        metric_window: None,
+        tool_version: None,
     }))
 }
 
@@ -160,6 +171,7 @@ pub fn create_short_lived_process_object(
         process_disk_usage_write_last_interval: 0,
         process_memory_usage: 0,
         process_memory_virtual: 0,
+        swap_used: None,
         process_status: ProcessStatus::Unknown(0).to_string(),
         container_id: None,
         job_id: None,
@@ -167,6 +179,9 @@ pub fn create_short_lived_process_object(
         trace_id: None,
         container_event: None,
         tool_id: construct_tool_id(&process.pid.to_string(), process.started_at),
+        process_tree: None,
+        metric_window: None,
+        tool_version: None,
     }))
 }
 
@@ -195,54 +210,13 @@ fn get_process_environment_variables<P: ProcessTrait>(
         trace_id = None;
     }
 
-    let container_id = get_container_id_from_cgroup(proc.pid().as_u32());
+    let container_id = cgroup::container_id_for_pid(proc.pid().as_u32());
 
     trace!("Got container_ID from cgroup: {:?}", container_id);
 
     (container_id, job_id, trace_id)
 }
 
-/// Extracts the container ID (if any) from a process's cgroup file
-/// Returns `Some(container_id)` if found, else `None`
-fn get_container_id_from_cgroup(pid: u32) -> Option<String> {
-    let cgroup_path = PathBuf::from(format!("/proc/{}/cgroup", pid));
-    let content = std::fs::read_to_string(cgroup_path).ok()?;
-
-    for line in content.lines() {
-        // cgroup v1 format: <hierarchy_id>:<controllers>:<path>
-        // cgroup v2 format (single unified hierarchy): 0::/path
-        let fields: Vec<&str> = line.split(':').collect();
-        if fields.len() != 3 {
-            continue;
-        }
-
-        let path = fields[2];
-
-        // Try to match full container ID (64 hex chars)
-        if let Some(id) = path
-            .split('/')
-            .find(|part| part.len() == 64 && part.chars().all(|c| c.is_ascii_hexdigit()))
-        {
-            return Some(id.to_string());
-        }
-
-        // Fallback: check for systemd slice format: docker-<container_id>.scope
-        if let Some(slice) = path
-            .split('/')
-            .find(|part| part.starts_with("docker-") && part.ends_with(".scope"))
-        {
-            let id = slice
-                .trim_start_matches("docker-")
-                .trim_end_matches(".scope");
-            if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
-                return Some(id.to_string());
-            }
-        }
-    }
-
-    None
-}
-
 pub fn get_process_argv(pid: i32) -> Vec<String> {
     Command::new("ps")
         .args(["-p", &pid.to_string(), "-o", "command="])
@@ -425,6 +399,9 @@ mod tests {
         mock_process
             .expect_virtual_memory()
             .return_const(1024 * 1024 * 200_u64); // 200MB
+        mock_process
+            .expect_swap_used()
+            .return_const(Some(1024 * 1024 * 64_u64)); // 64MB
         mock_process
             .expect_status()
             .return_const(ProcessStatus::Run);
@@ -452,6 +429,7 @@ mod tests {
                 assert_eq!(props.process_disk_usage_write_last_interval, 256);
                 assert_eq!(props.process_memory_usage, 1024 * 1024 * 100);
                 assert_eq!(props.process_memory_virtual, 1024 * 1024 * 200);
+                assert_eq!(props.swap_used, Some(1024 * 1024 * 64));
                 assert_eq!(props.container_id, None);
                 assert_eq!(props.job_id, Some("test-job-123".to_string()));
                 assert_eq!(props.trace_id, None);
@@ -489,6 +467,7 @@ mod tests {
         mock_process.expect_disk_usage().return_const(disk_usage);
         mock_process.expect_memory().return_const(0u64);
         mock_process.expect_virtual_memory().return_const(0u64);
+        mock_process.expect_swap_used().return_const(None::<u64>);
         mock_process
             .expect_status()
             .return_const(ProcessStatus::Sleep);
@@ -511,6 +490,7 @@ mod tests {
                 assert_eq!(props.job_id, None);
                 assert_eq!(props.trace_id, None);
                 assert_eq!(props.working_directory, None);
+                assert_eq!(props.swap_used, None);
             }
         }
     }
@@ -541,6 +521,7 @@ mod tests {
         mock_process.expect_disk_usage().return_const(disk_usage);
         mock_process.expect_memory().return_const(0u64);
         mock_process.expect_virtual_memory().return_const(0u64);
+        mock_process.expect_swap_used().return_const(None::<u64>);
         mock_process
             .expect_status()
             .return_const(ProcessStatus::Run);
@@ -0,0 +1,95 @@
+//! Exits the process watcher missed, e.g. on kernels whose exit tracepoints are unreliable,
+//! found by reconciling the monitored processes with the process table.
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+/// A process is identified by its pid and start time, as pids are reused
+type ProcessKey = (usize, DateTime<Utc>);
+
+/// Counts the consecutive polls each monitored process was missing from the process table, to
+/// confirm it exited only once it has been missing for a few polls rather than racing a process
+/// table that is being refreshed
+#[derive(Debug, Default)]
+pub struct MissedExits {
+    /// `None` disables the reconciliation
+    confirmation_polls: Option<u32>,
+    missing_polls: HashMap<ProcessKey, u32>,
+}
+
+impl MissedExits {
+    /// Zero confirmation polls disables the reconciliation
+    pub fn new(confirmation_polls: u32) -> Self {
+        Self {
+            confirmation_polls: (confirmation_polls > 0).then_some(confirmation_polls),
+            missing_polls: HashMap::new(),
+        }
+    }
+
+    /// Whether `process` has just been missing for the confirmation polls, i.e. exited without
+    /// its exit being recorded. Being seen again resets its count.
+    pub fn confirm_exit(&mut self, process: &ProcessStartTrigger, present: bool) -> bool {
+        let Some(confirmation_polls) = self.confirmation_polls else {
+            return false;
+        };
+        let key = (process.pid, process.started_at);
+        if present {
+            self.missing_polls.remove(&key);
+            return false;
+        }
+        let missing_polls = self.missing_polls.entry(key).or_default();
+        *missing_polls += 1;
+        *missing_polls == confirmation_polls
+    }
+
+    /// Forgets the processes not in `monitored`
+    pub fn retain(&mut self, monitored: &HashSet<ProcessKey>) {
+        self.missing_polls.retain(|key, _| monitored.contains(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn process() -> ProcessStartTrigger {
+        ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"])
+    }
+
+    #[test]
+    fn test_exit_is_confirmed_after_consecutive_missing_polls() {
+        let process = process();
+        let mut missed_exits = MissedExits::new(3);
+        assert!(!missed_exits.confirm_exit(&process, false));
+        assert!(!missed_exits.confirm_exit(&process, false));
+        // seen again, e.g. the process table was being refreshed
+        assert!(!missed_exits.confirm_exit(&process, true));
+        assert!(!missed_exits.confirm_exit(&process, false));
+        assert!(!missed_exits.confirm_exit(&process, false));
+        assert!(missed_exits.confirm_exit(&process, false));
+        // confirmed only once
+        assert!(!missed_exits.confirm_exit(&process, false));
+    }
+
+    #[test]
+    fn test_processes_sharing_a_pid_are_counted_apart() {
+        let old = process();
+        let mut new = process();
+        new.started_at = old.started_at + TimeDelta::seconds(60);
+        let mut missed_exits = MissedExits::new(2);
+        assert!(!missed_exits.confirm_exit(&old, false));
+        assert!(!missed_exits.confirm_exit(&new, true));
+        assert!(missed_exits.confirm_exit(&old, false));
+    }
+
+    #[test]
+    fn test_zero_confirmation_polls_disables_reconciliation() {
+        let process = process();
+        let mut missed_exits = MissedExits::new(0);
+        for _ in 0..10 {
+            assert!(!missed_exits.confirm_exit(&process, false));
+        }
+    }
+}
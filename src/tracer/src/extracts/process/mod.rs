@@ -1,3 +1,7 @@
 pub mod extract_process_data;
+pub mod missed_exits;
+pub mod pid_reuse;
 pub mod process_manager;
+pub mod storage_stall;
+pub mod swap;
 pub mod types;
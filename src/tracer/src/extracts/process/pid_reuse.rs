@@ -0,0 +1,43 @@
+//! Tells apart a monitored process and another process that reused its pid after it exited.
+//!
+//! A process that execs keeps its pid and its start time in the process table, so the start time
+//! of the process now running as a pid tells whether it is still the monitored one (or a program
+//! it exec'd into) or a process started after the monitored one exited.
+
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+/// Slack for the process table, which only has a resolution of a second, and for the start times
+/// of the triggers, which are converted from the kernel's monotonic clock
+const START_TIME_TOLERANCE_SECS: i64 = 1;
+
+/// Whether the process running as `tracked.pid`, which started at `process_start_time_secs` (Unix
+/// time, see `sysinfo::Process::start_time`), is another process than `tracked`
+pub fn reuses_pid(tracked: &ProcessStartTrigger, process_start_time_secs: u64) -> bool {
+    process_start_time_secs as i64 > tracked.started_at.timestamp() + START_TIME_TOLERANCE_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn tracked(started_at: DateTime<Utc>) -> ProcessStartTrigger {
+        let mut process = ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"]);
+        process.started_at = started_at;
+        process
+    }
+
+    #[test]
+    fn test_later_process_reuses_pid() {
+        let started_at = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let tracked = tracked(started_at);
+        let start_secs = started_at.timestamp() as u64;
+
+        assert!(reuses_pid(&tracked, start_secs + 60));
+        // the same process, within the resolution of the process table
+        assert!(!reuses_pid(&tracked, start_secs));
+        assert!(!reuses_pid(&tracked, start_secs + 1));
+        // forked before it exec'd the tool
+        assert!(!reuses_pid(&tracked, start_secs - 30));
+    }
+}
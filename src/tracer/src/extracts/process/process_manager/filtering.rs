@@ -1,3 +1,4 @@
+use crate::constants::MAX_PROCESS_TREE_DEPTH;
 use crate::extracts::process::types::process_state::ProcessState;
 use crate::process_identification::utils::log_matched_process;
 use std::collections::{HashMap, HashSet};
@@ -9,10 +10,19 @@ pub fn filter_processes_by_target<'a>(
     triggers: &'a [ProcessStartTrigger],
     state: &ProcessState,
 ) -> HashMap<String, HashSet<&'a ProcessStartTrigger>> {
+    let target_manager = state.get_target_manager();
+    // walking the process tree is only needed for rules with `force_ancestor_to_match`
+    let needs_ancestors = target_manager.has_forced_ancestors();
+
     triggers
         .iter()
         .flat_map(|trigger| {
-            let target = state.get_target_manager().get_target_match(trigger);
+            let ancestors = if needs_ancestors {
+                state.get_ancestor_triggers(trigger, MAX_PROCESS_TREE_DEPTH)
+            } else {
+                Vec::new()
+            };
+            let target = target_manager.get_target_match_in_tree(trigger, &ancestors);
             if let Some(matched_target) = target {
                 log_matched_process(trigger, &matched_target, true);
                 Some((trigger, matched_target))
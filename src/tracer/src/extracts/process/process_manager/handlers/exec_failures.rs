@@ -0,0 +1,149 @@
+use crate::constants::MAX_PROCESS_TREE_DEPTH;
+use crate::extracts::process::process_manager::recorder::EventRecorder;
+use crate::extracts::process::process_manager::state::StateManager;
+use crate::extracts::process::types::process_state::ProcessState;
+use anyhow::Result;
+use tracer_ebpf::ebpf_trigger::{ExecFailedTrigger, ProcessEndTrigger, ProcessStartTrigger};
+use tracing::debug;
+
+/// Handles failed `execve` calls of matched tools.
+///
+/// A failure is only reported once the process that attempted it exits: running a command by
+/// name makes the shell (or `execvp`) try every `PATH` entry in turn, so failures followed by a
+/// successful exec of the same process are expected and discarded.
+pub struct ExecFailureHandler;
+
+impl ExecFailureHandler {
+    /// Keeps the failed execs of commands matching a target until the process either starts
+    /// successfully or exits
+    pub async fn handle_exec_failures(
+        state_manager: &StateManager,
+        triggers: Vec<ExecFailedTrigger>,
+    ) {
+        let mut state = state_manager.get_state_mut().await;
+        for failure in triggers {
+            match Self::match_target(&state, &failure) {
+                Some(target) => {
+                    debug!(
+                        "Tracking failed exec of {} by pid {}: {}",
+                        failure.file_name, failure.pid, failure.error
+                    );
+                    state.insert_exec_failure(target, failure);
+                }
+                None => debug!("Ignoring failed exec of unmatched {}", failure.file_name),
+            }
+        }
+    }
+
+    /// Matches the attempted command as if it had started, with the file name as its only
+    /// argument since the arguments of a failed exec are not captured
+    fn match_target(state: &ProcessState, failure: &ExecFailedTrigger) -> Option<String> {
+        let attempted = ProcessStartTrigger::from_name_and_args(
+            failure.pid,
+            failure.ppid,
+            failure.command_name(),
+            &[failure.file_name.as_str()],
+        );
+        let target_manager = state.get_target_manager();
+        let ancestors = if target_manager.has_forced_ancestors() {
+            state.get_ancestor_triggers(&attempted, MAX_PROCESS_TREE_DEPTH)
+        } else {
+            Vec::new()
+        };
+        target_manager.get_target_match_in_tree(&attempted, &ancestors)
+    }
+
+    /// Discards failures superseded by a later successful exec of the same process
+    pub async fn handle_successful_execs(
+        state_manager: &StateManager,
+        triggers: &[ProcessStartTrigger],
+    ) {
+        let mut state = state_manager.get_state_mut().await;
+        for trigger in triggers {
+            let superseded = state
+                .get_exec_failures()
+                .get(&trigger.pid)
+                .is_some_and(|(_, failure)| failure.timestamp <= trigger.started_at);
+            if superseded {
+                debug!("Discarding failed exec superseded by pid {}", trigger.pid);
+                state.remove_exec_failure(&trigger.pid);
+            }
+        }
+    }
+
+    /// Reports the failed execs of processes that exited without starting the tool
+    pub async fn handle_exec_failure_terminations(
+        state_manager: &StateManager,
+        event_recorder: &EventRecorder,
+        finish_triggers: &[ProcessEndTrigger],
+    ) -> Result<()> {
+        for finish in finish_triggers {
+            let failure = state_manager
+                .get_state_mut()
+                .await
+                .remove_exec_failure(&finish.pid);
+            if let Some((target, failure)) = failure {
+                event_recorder
+                    .record_exec_failure(&target, &failure)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn failure(pid: usize, file_name: &str) -> ExecFailedTrigger {
+        ExecFailedTrigger::new(pid, 1, file_name.to_string(), 2, Utc::now())
+    }
+
+    #[tokio::test]
+    async fn test_only_matched_failures_are_tracked() {
+        let state_manager = StateManager::default();
+        ExecFailureHandler::handle_exec_failures(
+            &state_manager,
+            vec![failure(10, "/opt/bin/salmon"), failure(11, "/usr/bin/ls")],
+        )
+        .await;
+
+        let state = state_manager.get_state().await;
+        let failures = state.get_exec_failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[&10].0, "salmon");
+    }
+
+    #[tokio::test]
+    async fn test_successful_exec_discards_earlier_failure() {
+        let state_manager = StateManager::default();
+        let failed = failure(10, "/usr/local/bin/salmon");
+        let mut started =
+            ProcessStartTrigger::from_name_and_args(10, 1, "salmon", &["/opt/bin/salmon"]);
+        started.started_at = failed.timestamp + Duration::milliseconds(1);
+
+        ExecFailureHandler::handle_exec_failures(&state_manager, vec![failed]).await;
+        ExecFailureHandler::handle_successful_execs(&state_manager, &[started]).await;
+
+        assert!(state_manager
+            .get_state()
+            .await
+            .get_exec_failures()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_earlier_exec_keeps_later_failure() {
+        let state_manager = StateManager::default();
+        let failed = failure(10, "/opt/bin/salmon");
+        let mut started = ProcessStartTrigger::from_name_and_args(10, 1, "bash", &["bash"]);
+        started.started_at = failed.timestamp - Duration::seconds(1);
+
+        ExecFailureHandler::handle_exec_failures(&state_manager, vec![failed]).await;
+        ExecFailureHandler::handle_successful_execs(&state_manager, &[started]).await;
+
+        assert_eq!(state_manager.get_state().await.get_exec_failures().len(), 1);
+    }
+}
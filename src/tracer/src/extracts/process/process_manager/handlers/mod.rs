@@ -1,3 +1,4 @@
+pub mod exec_failures;
 pub mod oom;
 pub mod process_starts;
 pub mod process_terminations;
@@ -1,3 +1,5 @@
+use crate::constants::MAX_PROCESS_TREE_DEPTH;
+use crate::extracts::containers::display_name::{resolve_display_name, ContainerDisplayName};
 use crate::extracts::process::process_manager::filtering;
 use crate::extracts::process::process_manager::recorder::EventRecorder;
 use crate::extracts::process::process_manager::state::StateManager;
@@ -14,13 +16,23 @@ impl ProcessStartHandler {
         state_manager: &StateManager,
         event_recorder: &EventRecorder,
         system_refresher: &SystemRefresher,
+        container_display_name: ContainerDisplayName,
+        include_process_tree: bool,
         triggers: Vec<ProcessStartTrigger>,
     ) -> Result<()> {
         debug!("Handling {} process start triggers", triggers.len());
 
+        let triggers = Self::enrich_with_containers(event_recorder, triggers).await;
+
         Self::store_triggers(state_manager, triggers.clone()).await;
 
         let matched_processes = Self::match_processes(state_manager, &triggers).await;
+        let matched_processes = Self::resolve_container_display_names(
+            event_recorder,
+            container_display_name,
+            matched_processes,
+        )
+        .await;
 
         if matched_processes.is_empty() {
             debug!("No matching processes found; exiting early.");
@@ -29,8 +41,14 @@ impl ProcessStartHandler {
 
         Self::refresh_process_data(system_refresher, &matched_processes).await?;
 
-        Self::record_matched_processes(event_recorder, system_refresher, &matched_processes)
-            .await?;
+        Self::record_matched_processes(
+            event_recorder,
+            state_manager,
+            system_refresher,
+            include_process_tree,
+            &matched_processes,
+        )
+        .await?;
 
         Self::record_matching_tasks(event_recorder, state_manager, &triggers, &matched_processes)
             .await?;
@@ -42,6 +60,29 @@ impl ProcessStartHandler {
         Ok(())
     }
 
+    /// Sets the owning container on each trigger, so rules can match on it.
+    ///
+    /// A process is associated with a container by reading the container id from its cgroup
+    /// (`/proc/<pid>/cgroup`, see `extracts::containers::cgroup`) and looking it up among the
+    /// containers tracked by the docker watcher for its name and image. Host processes keep no
+    /// container.
+    async fn enrich_with_containers(
+        event_recorder: &EventRecorder,
+        mut triggers: Vec<ProcessStartTrigger>,
+    ) -> Vec<ProcessStartTrigger> {
+        for trigger in triggers.iter_mut() {
+            let Some(container_id) = event_recorder.container_id_for_pid(trigger.pid) else {
+                continue;
+            };
+            if let Some(container) = event_recorder.container_for_pid(trigger.pid).await {
+                trigger.container_name = Some(container.name);
+                trigger.container_image = Some(container.image);
+            }
+            trigger.container_id = Some(container_id);
+        }
+        triggers
+    }
+
     async fn store_triggers(state_manager: &StateManager, triggers: Vec<ProcessStartTrigger>) {
         debug!("Storing {} triggers in state.", triggers.len());
         for trigger in &triggers {
@@ -63,6 +104,27 @@ impl ProcessStartHandler {
         filtering::filter_processes_by_target(triggers, &state)
     }
 
+    /// Renames matched processes running inside containers according to `mode`
+    async fn resolve_container_display_names<'a>(
+        event_recorder: &EventRecorder,
+        mode: ContainerDisplayName,
+        matched_processes: HashMap<String, HashSet<&'a ProcessStartTrigger>>,
+    ) -> HashMap<String, HashSet<&'a ProcessStartTrigger>> {
+        if mode == ContainerDisplayName::Unchanged {
+            return matched_processes;
+        }
+
+        let mut resolved: HashMap<String, HashSet<&'a ProcessStartTrigger>> = HashMap::new();
+        for (target, processes) in matched_processes {
+            for process in processes {
+                let container = event_recorder.container_for_pid(process.pid).await;
+                let display_name = resolve_display_name(&target, container.as_ref(), mode);
+                resolved.entry(display_name).or_default().insert(process);
+            }
+        }
+        resolved
+    }
+
     async fn refresh_process_data(
         system_refresher: &SystemRefresher,
         matched_processes: &HashMap<String, HashSet<&ProcessStartTrigger>>,
@@ -79,7 +141,9 @@ impl ProcessStartHandler {
 
     async fn record_matched_processes(
         event_recorder: &EventRecorder,
+        state_manager: &StateManager,
         system_refresher: &SystemRefresher,
+        include_process_tree: bool,
         matched_processes: &HashMap<String, HashSet<&ProcessStartTrigger>>,
     ) -> Result<()> {
         let mut count = 0;
@@ -87,10 +151,16 @@ impl ProcessStartHandler {
         for (target, processes) in matched_processes {
             count += processes.len();
             for process in processes {
+                let process_tree = if include_process_tree {
+                    let state = state_manager.get_state().await;
+                    Some(state.get_process_ancestors(process, MAX_PROCESS_TREE_DEPTH))
+                } else {
+                    None
+                };
                 let system = system_refresher.get_system().read().await;
                 let sys_proc = system.process(process.pid.into());
                 let _ = event_recorder
-                    .record_new_process(target, process, sys_proc)
+                    .record_new_process(target, process, sys_proc, process_tree)
                     .await?;
             }
         }
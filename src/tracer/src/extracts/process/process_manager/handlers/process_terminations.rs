@@ -1,48 +1,66 @@
 use crate::extracts::process::process_manager::recorder::EventRecorder;
 use crate::extracts::process::process_manager::state::StateManager;
+use crate::extracts::process::types::deferred_completion::DeferredCompletion;
+use crate::extracts::process::types::tool_clock::TriggerClock;
+use crate::extracts::process::types::tool_concurrency::ToolConcurrency;
 use anyhow::Result;
+use chrono::Utc;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use tracer_ebpf::ebpf_trigger::ProcessEndTrigger;
 use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 use tracing::{debug, error};
 
 pub struct ProcessTerminationHandler;
 
+/// A tool that exited or is no longer monitored, with its concurrency peaks and monotonic start
+type TerminatedTool = (ProcessStartTrigger, ToolConcurrency, Option<Instant>);
+
 impl ProcessTerminationHandler {
-    /// Handles process terminations by removing them from state and logging completion
+    /// Handles process terminations by removing them from state and logging completion.
+    ///
+    /// With a non-zero `exit_grace_period`, a tool whose descendants are still running is only
+    /// finalized once they have all exited, or the grace period is over.
     pub async fn handle_process_terminations(
         state_manager: &StateManager,
         event_recorder: &EventRecorder,
+        exit_grace_period: Duration,
         triggers: Vec<ProcessEndTrigger>,
     ) -> Result<()> {
         debug!("Processing {} process terminations", triggers.len());
+        let clock = TriggerClock::now();
 
         for trigger in &triggers {
             debug!("Processing termination trigger: {:?}", trigger);
+            event_recorder.forget_process(trigger.pid);
         }
 
         // Remove terminated processes from the state
         Self::remove_processes_from_state(state_manager, &triggers).await?;
 
         // Map PIDs to finish triggers for easy lookup
-        let mut pid_to_finish: HashMap<_, _> =
+        let pid_to_finish: HashMap<_, _> =
             triggers.into_iter().map(|proc| (proc.pid, proc)).collect();
 
         // Find all processes that we were monitoring that have terminated
-        let terminated_processes: HashMap<String, Vec<ProcessStartTrigger>> = {
+        let terminated_processes: HashMap<String, Vec<TerminatedTool>> = {
             let mut state = state_manager.get_state_mut().await;
 
             let monitoring = state.get_monitoring_mut();
 
             debug!("Monitoring processes: {:?}", monitoring);
 
-            monitoring
+            let terminated: HashMap<String, Vec<ProcessStartTrigger>> = monitoring
                 .iter_mut()
                 .map(|(target, procs)| {
-                    // Partition processes into terminated and still running
-                    let (terminated, still_running): (Vec<_>, Vec<_>) = procs
-                        .drain()
-                        .partition(|proc| pid_to_finish.contains_key(&proc.pid));
+                    // Partition processes into terminated and still running. A process that
+                    // started after the exit reused the pid, so it is still running.
+                    let (terminated, still_running): (Vec<_>, Vec<_>) =
+                        procs.drain().partition(|proc| {
+                            pid_to_finish
+                                .get(&proc.pid)
+                                .is_some_and(|finish| proc.started_at <= finish.finished_at)
+                        });
 
                     // Update monitoring with still running processes
                     *procs = still_running.into_iter().collect();
@@ -50,6 +68,22 @@ impl ProcessTerminationHandler {
                     // Return terminated processes
                     (target.clone(), terminated)
                 })
+                .collect();
+
+            // Reset the concurrency counters and clocks of the terminated tools
+            terminated
+                .into_iter()
+                .map(|(target, procs)| {
+                    let procs = procs
+                        .into_iter()
+                        .map(|proc| {
+                            let concurrency = state.finish_tool_concurrency(&proc);
+                            let started = state.finish_tool_clock(&proc);
+                            (proc, concurrency, started)
+                        })
+                        .collect();
+                    (target, procs)
+                })
                 .collect()
         };
 
@@ -62,18 +96,126 @@ impl ProcessTerminationHandler {
 
         // Log completion events for each terminated process
         for (target, start_triggers) in terminated_processes {
-            for start_trigger in start_triggers {
-                let Some(finish_trigger) = pid_to_finish.remove(&start_trigger.pid) else {
+            for (start_trigger, concurrency, started) in start_triggers {
+                let Some(finish_trigger) = pid_to_finish.get(&start_trigger.pid) else {
                     error!("Process doesn't exist: start_trigger={:?}", start_trigger);
                     continue;
                 };
 
-                event_recorder
-                    .record_process_completion(&target, &start_trigger, &finish_trigger)
-                    .await?;
+                if exit_grace_period.is_zero() {
+                    let elapsed =
+                        started.map(|started| clock.elapsed(started, finish_trigger.finished_at));
+                    event_recorder
+                        .record_process_completion(
+                            &target,
+                            &start_trigger,
+                            finish_trigger,
+                            concurrency,
+                            elapsed,
+                        )
+                        .await?;
+                } else {
+                    state_manager
+                        .get_state_mut()
+                        .await
+                        .defer_completion(DeferredCompletion::new(
+                            target.clone(),
+                            start_trigger,
+                            finish_trigger.clone(),
+                            concurrency,
+                            started,
+                            &clock,
+                            exit_grace_period,
+                        ));
+                }
             }
         }
 
+        let exits: Vec<ProcessEndTrigger> = pid_to_finish.into_values().collect();
+        Self::finalize_deferred_completions(state_manager, event_recorder, &exits).await
+    }
+
+    /// Records the completion of the deferred tools whose descendants have all exited, or whose
+    /// grace period is over
+    pub async fn finalize_deferred_completions(
+        state_manager: &StateManager,
+        event_recorder: &EventRecorder,
+        exits: &[ProcessEndTrigger],
+    ) -> Result<()> {
+        let due = state_manager.get_state_mut().await.take_due_completions(
+            exits,
+            &TriggerClock::now(),
+            Utc::now(),
+        );
+        Self::record_deferred_completions(event_recorder, due).await
+    }
+
+    async fn record_deferred_completions(
+        event_recorder: &EventRecorder,
+        completions: Vec<DeferredCompletion>,
+    ) -> Result<()> {
+        for completion in completions {
+            event_recorder
+                .record_process_completion(
+                    &completion.target,
+                    &completion.start_trigger,
+                    &completion.finish_trigger,
+                    completion.concurrency,
+                    completion.elapsed,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Records a best-effort completion for every monitored process that is still running, e.g.
+    /// when the daemon shuts down, so no tool is left without a duration
+    pub async fn handle_monitoring_stopped(
+        state_manager: &StateManager,
+        event_recorder: &EventRecorder,
+    ) -> Result<()> {
+        // tools that already exited are finalized as they are, not as interrupted
+        let deferred = state_manager
+            .get_state_mut()
+            .await
+            .take_deferred_completions();
+        Self::record_deferred_completions(event_recorder, deferred).await?;
+
+        let running: Vec<(String, TerminatedTool)> = {
+            let mut state = state_manager.get_state_mut().await;
+            let running: Vec<_> = state
+                .get_monitoring_mut()
+                .drain()
+                .flat_map(|(target, procs)| {
+                    procs.into_iter().map(move |proc| (target.clone(), proc))
+                })
+                .collect();
+            running
+                .into_iter()
+                .map(|(target, proc)| {
+                    let concurrency = state.finish_tool_concurrency(&proc);
+                    let started = state.finish_tool_clock(&proc);
+                    (target, (proc, concurrency, started))
+                })
+                .collect()
+        };
+
+        debug!("Recording {} interrupted processes", running.len());
+
+        let (stopped_at, stopped) = (Utc::now(), Instant::now());
+        for (target, (start_trigger, concurrency, started)) in running {
+            let elapsed = started.map(|started| stopped - started);
+            event_recorder
+                .record_interrupted_process(
+                    &target,
+                    &start_trigger,
+                    stopped_at,
+                    concurrency,
+                    elapsed,
+                )
+                .await?;
+        }
+
         Ok(())
     }
 
@@ -82,9 +224,17 @@ impl ProcessTerminationHandler {
         state_manager: &StateManager,
         triggers: &[ProcessEndTrigger],
     ) -> Result<()> {
+        let mut state = state_manager.get_state_mut().await;
         for trigger in triggers.iter() {
-            debug!("Removing process from state: {:?}", trigger);
-            state_manager.remove_process(&trigger.pid).await;
+            // a process that started after the exit reused the pid
+            let exited = state
+                .get_processes()
+                .get(&trigger.pid)
+                .is_some_and(|process| process.started_at <= trigger.finished_at);
+            if exited {
+                debug!("Removing process from state: {:?}", trigger);
+                state.remove_process(&trigger.pid);
+            }
         }
         Ok(())
     }
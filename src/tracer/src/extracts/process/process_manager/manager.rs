@@ -1,23 +1,48 @@
+use crate::extracts::containers::display_name::ContainerDisplayName;
+use crate::extracts::metrics::snapshot::MetricsSnapshots;
+use crate::extracts::process::missed_exits::MissedExits;
+use crate::extracts::process::process_manager::handlers::exec_failures::ExecFailureHandler;
 use crate::extracts::process::process_manager::handlers::oom::OomHandler;
 use crate::extracts::process::process_manager::handlers::process_starts::ProcessStartHandler;
 use crate::extracts::process::process_manager::handlers::process_terminations::ProcessTerminationHandler;
-use crate::extracts::process::process_manager::metrics::ProcessMetricsHandler;
+use crate::extracts::process::process_manager::metric_window::MetricWindows;
+use crate::extracts::process::process_manager::metrics::{MetricsPollState, ProcessMetricsHandler};
 use crate::extracts::process::process_manager::recorder::EventRecorder;
 use crate::extracts::process::process_manager::state::StateManager;
 use crate::extracts::process::process_manager::system_refresher::SystemRefresher;
+use crate::extracts::process::storage_stall::StallDetector;
+use crate::extracts::process::swap::SwapAlerts;
+use crate::extracts::syslog::redirects::{parse_redirects, resolve_redirects, OutputRedirect};
+use crate::process_identification::target_process::target_manager::TargetManager;
+use crate::process_identification::types::lockfiles::PackageVersions;
 use anyhow::Result;
+use chrono::TimeDelta;
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tracer_ebpf::ebpf_trigger::{OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger};
+use tracer_ebpf::ebpf_trigger::{
+    ExecFailedTrigger, OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger,
+};
+use tracing::warn;
 
 pub struct ProcessManager {
     pub state_manager: StateManager,
     pub event_recorder: EventRecorder,
     pub system_refresher: SystemRefresher,
+    container_display_name: ContainerDisplayName,
+    include_process_tree: bool,
+    exit_grace_period: Duration,
+    metrics_poll_state: Mutex<MetricsPollState>,
 }
 
 impl ProcessManager {
-    pub fn new(event_recorder: EventRecorder) -> Self {
+    pub fn new(
+        event_recorder: EventRecorder,
+        container_display_name: ContainerDisplayName,
+        include_process_tree: bool,
+        exit_grace_period: Duration,
+    ) -> Self {
         let state_manager = StateManager::default();
         let system_refresher = SystemRefresher::new();
 
@@ -25,9 +50,53 @@ impl ProcessManager {
             state_manager,
             event_recorder,
             system_refresher,
+            container_display_name,
+            include_process_tree,
+            exit_grace_period,
+            metrics_poll_state: Mutex::new(MetricsPollState::default()),
         }
     }
 
+    /// Replaces the window tool metrics are aggregated over, zero for raw samples. Windows
+    /// that are open are discarded.
+    pub async fn set_tool_metrics_window(&self, window: Duration) {
+        let window = TimeDelta::from_std(window).unwrap_or(TimeDelta::MAX);
+        self.metrics_poll_state.lock().await.metric_windows = MetricWindows::new(window);
+    }
+
+    /// Replaces the swap usage above which a tool triggers an alert, zero to disable the alerts
+    pub async fn set_swap_alert_threshold(&self, threshold_bytes: u64) {
+        self.metrics_poll_state.lock().await.swap_alerts = SwapAlerts::new(threshold_bytes);
+    }
+
+    /// Replaces how long a tool may stay in uninterruptible sleep before it is reported as
+    /// stalled on network storage, zero to disable the detection
+    pub async fn set_storage_stall_threshold(&self, threshold: Duration) {
+        let threshold = TimeDelta::from_std(threshold).unwrap_or(TimeDelta::MAX);
+        self.metrics_poll_state.lock().await.storage_stalls = StallDetector::new(threshold);
+    }
+
+    /// Replaces the number of consecutive polls a monitored process must be missing from the
+    /// process table before its exit is recorded, zero to only rely on exit events
+    pub async fn set_missed_exit_confirmation_polls(&self, confirmation_polls: u32) {
+        self.metrics_poll_state.lock().await.missed_exits = MissedExits::new(confirmation_polls);
+    }
+
+    /// Replaces where the latest sample of each process is kept
+    pub async fn set_metrics_snapshots(&self, snapshots: MetricsSnapshots) {
+        self.metrics_poll_state.lock().await.snapshots = snapshots;
+    }
+
+    /// Replaces the packages of the run's environment tool versions are looked up in
+    pub fn set_package_versions(&self, package_versions: PackageVersions) {
+        self.event_recorder.set_package_versions(package_versions);
+    }
+
+    /// Replaces the target manager used to match new processes
+    pub async fn set_target_manager(&self, target_manager: TargetManager) {
+        self.state_manager.set_target_manager(target_manager).await;
+    }
+
     /// Sets the eBPF task handle
     pub async fn set_ebpf_task(&self, task: JoinHandle<()>) {
         self.state_manager.set_ebpf_task(task).await;
@@ -49,6 +118,24 @@ impl ProcessManager {
         OomHandler::handle_out_of_memory_signals(&self.state_manager, triggers).await
     }
 
+    /// Handles failed execs, reported once the attempting process exits
+    pub async fn handle_exec_failures(&self, triggers: Vec<ExecFailedTrigger>) {
+        ExecFailureHandler::handle_exec_failures(&self.state_manager, triggers).await;
+    }
+
+    /// Reports the failed execs of terminated processes
+    pub async fn handle_exec_failure_terminations(
+        &self,
+        finish_triggers: &[ProcessEndTrigger],
+    ) -> Result<()> {
+        ExecFailureHandler::handle_exec_failure_terminations(
+            &self.state_manager,
+            &self.event_recorder,
+            finish_triggers,
+        )
+        .await
+    }
+
     /// Handles process terminations
     pub async fn handle_process_terminations(
         &self,
@@ -57,30 +144,65 @@ impl ProcessManager {
         ProcessTerminationHandler::handle_process_terminations(
             &self.state_manager,
             &self.event_recorder,
+            self.exit_grace_period,
             triggers,
         )
         .await
     }
 
+    /// Records the processes still running when monitoring stops as incomplete
+    pub async fn handle_monitoring_stopped(&self) -> Result<()> {
+        ProcessTerminationHandler::handle_monitoring_stopped(
+            &self.state_manager,
+            &self.event_recorder,
+        )
+        .await
+    }
+
     /// Handles newly started processes
     pub async fn handle_process_starts(&self, triggers: Vec<ProcessStartTrigger>) -> Result<()> {
+        ExecFailureHandler::handle_successful_execs(&self.state_manager, &triggers).await;
         ProcessStartHandler::handle_process_starts(
             &self.state_manager,
             &self.event_recorder,
             &self.system_refresher,
+            self.container_display_name,
+            self.include_process_tree,
             triggers,
         )
         .await
     }
 
-    /// Polls and updates metrics for all monitored processes
+    /// Polls and updates metrics for all monitored processes, and terminates those that exited
+    /// without an exit event
     pub async fn poll_process_metrics(&self) -> Result<()> {
-        ProcessMetricsHandler::poll_process_metrics(
+        // finalize deferred tools whose grace period ran out without any further exit
+        ProcessTerminationHandler::finalize_deferred_completions(
+            &self.state_manager,
+            &self.event_recorder,
+            &[],
+        )
+        .await?;
+        let missed_exits = ProcessMetricsHandler::poll_process_metrics(
             &self.state_manager,
             &self.event_recorder,
             &self.system_refresher,
+            &self.metrics_poll_state,
         )
-        .await
+        .await?;
+        if missed_exits.is_empty() {
+            return Ok(());
+        }
+
+        warn!(
+            "Recording the exit of {} processes that exited without an exit event: {:?}",
+            missed_exits.len(),
+            missed_exits
+                .iter()
+                .map(|trigger| trigger.pid)
+                .collect::<Vec<_>>()
+        );
+        self.handle_process_terminations(missed_exits).await
     }
 
     /// Returns a set of monitored process names
@@ -92,4 +214,230 @@ impl ProcessManager {
     pub async fn get_matched_tasks(&self) -> HashMap<String, usize> {
         self.state_manager.get_matched_tasks().await
     }
+
+    /// Returns the files the monitored tools' output is redirected to by their command line,
+    /// or by the script of the shell that launched them, by tool
+    pub async fn get_output_redirects(&self) -> Vec<(String, OutputRedirect)> {
+        let state = self.state_manager.get_state().await;
+        let processes = state.get_processes();
+        state
+            .get_monitoring()
+            .iter()
+            .flat_map(|(target, tools)| tools.iter().map(move |tool| (target, tool)))
+            .flat_map(|(target, tool)| {
+                std::iter::once(tool)
+                    .chain(processes.get(&tool.ppid))
+                    .flat_map(|process| {
+                        let redirects = parse_redirects(&process.argv);
+                        if redirects.is_empty() {
+                            return redirects;
+                        }
+                        let cwd = std::fs::read_link(format!("/proc/{}/cwd", process.pid)).ok();
+                        resolve_redirects(redirects, cwd.as_deref())
+                    })
+                    .map(move |redirect| (target.clone(), redirect))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::structs::PipelineMetadata;
+    use crate::extracts::containers::DockerWatcher;
+    use crate::process_identification::recorder::EventDispatcher;
+    use crate::process_identification::types::current_run::RunMetadata;
+    use crate::process_identification::types::event::attributes::EventAttributes;
+    use crate::process_identification::types::event::{Event, ProcessStatus};
+    use chrono::Utc;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    fn process_manager() -> (ProcessManager, mpsc::Receiver<Event>) {
+        let run = RunMetadata {
+            name: "test_run".to_string(),
+            id: "test-id-123".to_string(),
+            start_time: Utc::now(),
+            started: std::time::Instant::now(),
+            cost_summary: None,
+            trace_id: None,
+        };
+        let pipeline = Arc::new(Mutex::new(PipelineMetadata {
+            name: "test_pipeline".to_string(),
+            run_snapshot: None,
+            tags: Default::default(),
+            is_dev: true,
+            start_time: Default::default(),
+            opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: None,
+            local_only: false,
+            config: None,
+        }));
+        let (tx, rx) = mpsc::channel(100);
+        let event_dispatcher = EventDispatcher::new(pipeline, run, tx);
+        let docker_watcher = Arc::new(DockerWatcher::new_lazy(event_dispatcher.clone()));
+        let process_manager = ProcessManager::new(
+            EventRecorder::new(event_dispatcher, docker_watcher),
+            ContainerDisplayName::default(),
+            false,
+            Duration::ZERO,
+        );
+        (process_manager, rx)
+    }
+
+    fn finished_tools(rx: &mut mpsc::Receiver<Event>) -> usize {
+        let mut finished = 0;
+        while let Ok(event) = rx.try_recv() {
+            if event.process_status == ProcessStatus::FinishedToolExecution {
+                finished += 1;
+            }
+        }
+        finished
+    }
+
+    async fn monitor(process_manager: &ProcessManager, processes: Vec<ProcessStartTrigger>) {
+        process_manager
+            .state_manager
+            .update_monitoring(HashMap::from([(
+                "STAR".to_string(),
+                processes.into_iter().collect(),
+            )]))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vanished_process_is_finished_after_confirmation_polls() {
+        let (process_manager, mut rx) = process_manager();
+        process_manager.set_missed_exit_confirmation_polls(2).await;
+
+        // a tool that exited without an exit event
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        let pid = child.id() as usize;
+        let start = ProcessStartTrigger::from_name_and_args(pid, 1, "STAR", &["STAR"]);
+        monitor(&process_manager, vec![start]).await;
+
+        process_manager.poll_process_metrics().await.unwrap();
+        assert_eq!(finished_tools(&mut rx), 0);
+        assert!(process_manager
+            .state_manager
+            .get_monitored_processes_pids()
+            .await
+            .contains(&pid));
+
+        process_manager.poll_process_metrics().await.unwrap();
+        assert_eq!(finished_tools(&mut rx), 1);
+        assert!(process_manager
+            .state_manager
+            .get_monitored_processes_pids()
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_output_redirects_of_the_launching_shell() {
+        let (process_manager, _rx) = process_manager();
+        let shell = ProcessStartTrigger::from_name_and_args(
+            41,
+            1,
+            "bash",
+            &["bash", "-c", "STAR --runThreadN 8 > /data/star.log 2>&1"],
+        );
+        let tool = ProcessStartTrigger::from_name_and_args(42, 41, "STAR", &["STAR"]);
+        process_manager
+            .state_manager
+            .insert_process(41, shell)
+            .await;
+        monitor(&process_manager, vec![tool]).await;
+
+        let redirects = process_manager.get_output_redirects().await;
+        assert_eq!(redirects.len(), 1);
+        assert_eq!(redirects[0].0, "STAR");
+        assert_eq!(redirects[0].1.path, std::path::Path::new("/data/star.log"));
+    }
+
+    #[tokio::test]
+    async fn test_exit_only_finishes_the_process_that_had_the_pid() {
+        let (process_manager, mut rx) = process_manager();
+        let old = ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"]);
+        let mut new = old.clone();
+        new.started_at = old.started_at + TimeDelta::seconds(60);
+        monitor(&process_manager, vec![old.clone(), new.clone()]).await;
+
+        // the exit of the old process is only received after the pid was reused
+        process_manager
+            .handle_process_terminations(vec![ProcessEndTrigger {
+                pid: 42,
+                finished_at: old.started_at + TimeDelta::seconds(30),
+                exit_reason: None,
+            }])
+            .await
+            .unwrap();
+
+        assert_eq!(finished_tools(&mut rx), 1);
+        let state = process_manager.state_manager.get_state().await;
+        let monitored: Vec<_> = state.get_monitoring().values().flatten().collect();
+        assert_eq!(monitored, vec![&new]);
+    }
+
+    #[tokio::test]
+    async fn test_system_clock_step_does_not_affect_tool_duration() {
+        let (process_manager, mut rx) = process_manager();
+        let tool = ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"]);
+        monitor(&process_manager, vec![tool.clone()]).await;
+
+        // the system clock was stepped an hour forward while the tool ran
+        process_manager
+            .handle_process_terminations(vec![ProcessEndTrigger {
+                pid: 42,
+                finished_at: tool.started_at + TimeDelta::hours(1),
+                exit_reason: None,
+            }])
+            .await
+            .unwrap();
+
+        let durations: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event.attributes {
+                Some(EventAttributes::CompletedProcess(process)) => Some(process.duration_ms),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(durations.len(), 1);
+        assert!(durations[0] < 60_000);
+    }
+
+    #[tokio::test]
+    async fn test_process_reusing_the_pid_is_not_attributed_to_the_tool() {
+        let (process_manager, mut rx) = process_manager();
+        process_manager.set_missed_exit_confirmation_polls(2).await;
+        let snapshots = MetricsSnapshots::default();
+        process_manager
+            .set_metrics_snapshots(snapshots.clone())
+            .await;
+
+        // a tool that exited an hour ago, whose pid now belongs to this test
+        let mut old = ProcessStartTrigger::from_name_and_args(
+            std::process::id() as usize,
+            1,
+            "STAR",
+            &["STAR"],
+        );
+        old.started_at = Utc::now() - TimeDelta::hours(1);
+        monitor(&process_manager, vec![old]).await;
+
+        process_manager.poll_process_metrics().await.unwrap();
+        assert!(snapshots.snapshot().processes.is_empty());
+        assert_eq!(finished_tools(&mut rx), 0);
+
+        process_manager.poll_process_metrics().await.unwrap();
+        assert_eq!(finished_tools(&mut rx), 1);
+        assert!(process_manager
+            .state_manager
+            .get_monitored_processes_pids()
+            .await
+            .is_empty());
+    }
 }
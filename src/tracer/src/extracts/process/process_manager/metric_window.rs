@@ -0,0 +1,242 @@
+use crate::process_identification::types::event::attributes::process::{
+    FullProcessProperties, MetricWindowSummary, ProcessProperties,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{HashMap, HashSet};
+
+/// Accumulates the per-poll metric samples of each monitored process, so a single
+/// `ToolMetricEvent` summarizing a window is emitted instead of one per poll.
+///
+/// A window starts with the first sample of a process and is closed by the first sample taken
+/// once it has lasted `window`. With a zero `window` every sample is emitted as is.
+#[derive(Debug)]
+pub struct MetricWindows {
+    window: TimeDelta,
+    /// open windows by pid
+    windows: HashMap<usize, Window>,
+}
+
+#[derive(Debug)]
+struct Window {
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    samples: u32,
+    cpu_min: f32,
+    cpu_max: f32,
+    cpu_sum: f64,
+    memory_min: u64,
+    memory_max: u64,
+    memory_sum: u128,
+    read_bytes: u64,
+    written_bytes: u64,
+    /// the most recent sample, which the summary is based on
+    latest: Box<FullProcessProperties>,
+}
+
+impl Window {
+    fn new(properties: Box<FullProcessProperties>, now: DateTime<Utc>) -> Self {
+        Self {
+            started_at: now,
+            ended_at: now,
+            samples: 1,
+            cpu_min: properties.process_cpu_utilization,
+            cpu_max: properties.process_cpu_utilization,
+            cpu_sum: properties.process_cpu_utilization as f64,
+            memory_min: properties.process_memory_usage,
+            memory_max: properties.process_memory_usage,
+            memory_sum: properties.process_memory_usage as u128,
+            read_bytes: properties.process_disk_usage_read_last_interval,
+            written_bytes: properties.process_disk_usage_write_last_interval,
+            latest: properties,
+        }
+    }
+
+    fn add(&mut self, properties: Box<FullProcessProperties>, now: DateTime<Utc>) {
+        let cpu = properties.process_cpu_utilization;
+        let memory = properties.process_memory_usage;
+        self.ended_at = now;
+        self.samples += 1;
+        self.cpu_min = self.cpu_min.min(cpu);
+        self.cpu_max = self.cpu_max.max(cpu);
+        self.cpu_sum += cpu as f64;
+        self.memory_min = self.memory_min.min(memory);
+        self.memory_max = self.memory_max.max(memory);
+        self.memory_sum += memory as u128;
+        self.read_bytes += properties.process_disk_usage_read_last_interval;
+        self.written_bytes += properties.process_disk_usage_write_last_interval;
+        self.latest = properties;
+    }
+
+    /// The latest sample, with the window averages as its utilization and memory usage and
+    /// the disk usage of the whole window
+    fn summarize(self) -> ProcessProperties {
+        let summary = MetricWindowSummary {
+            samples: self.samples,
+            window_start: self.started_at,
+            window_end: self.ended_at,
+            cpu_utilization_min: self.cpu_min,
+            cpu_utilization_avg: (self.cpu_sum / self.samples as f64) as f32,
+            cpu_utilization_max: self.cpu_max,
+            memory_usage_min: self.memory_min,
+            memory_usage_avg: (self.memory_sum / self.samples as u128) as u64,
+            memory_usage_max: self.memory_max,
+        };
+        let mut properties = self.latest;
+        properties.process_cpu_utilization = summary.cpu_utilization_avg;
+        properties.process_memory_usage = summary.memory_usage_avg;
+        properties.process_disk_usage_read_last_interval = self.read_bytes;
+        properties.process_disk_usage_write_last_interval = self.written_bytes;
+        properties.metric_window = Some(summary);
+        ProcessProperties::Full(properties)
+    }
+}
+
+impl MetricWindows {
+    pub fn new(window: TimeDelta) -> Self {
+        Self {
+            window,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Adds a sample of `pid` taken at `now`, returning the properties to emit: the sample
+    /// itself when not aggregating, the window summary when this sample closes the window
+    pub fn add(
+        &mut self,
+        pid: usize,
+        properties: ProcessProperties,
+        now: DateTime<Utc>,
+    ) -> Option<ProcessProperties> {
+        if self.window.is_zero() {
+            return Some(properties);
+        }
+        let ProcessProperties::Full(properties) = properties;
+        let Some(window) = self.windows.get_mut(&pid) else {
+            self.windows.insert(pid, Window::new(properties, now));
+            return None;
+        };
+        window.add(properties, now);
+        if now - window.started_at < self.window {
+            return None;
+        }
+        self.windows.remove(&pid).map(Window::summarize)
+    }
+
+    /// Closes the windows of the processes not in `monitored`, e.g. because they exited,
+    /// returning their summaries
+    pub fn close_others(&mut self, monitored: &HashSet<usize>) -> Vec<ProcessProperties> {
+        let closed: Vec<usize> = self
+            .windows
+            .keys()
+            .filter(|pid| !monitored.contains(pid))
+            .copied()
+            .collect();
+        closed
+            .into_iter()
+            .filter_map(|pid| self.windows.remove(&pid))
+            .map(Window::summarize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::process::extract_process_data::create_short_lived_process_object;
+    use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+    fn sample(cpu: f32, memory: u64, read_bytes: u64) -> ProcessProperties {
+        let trigger = ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"]);
+        let ProcessProperties::Full(mut properties) =
+            create_short_lived_process_object(&trigger, "STAR".to_string());
+        properties.process_cpu_utilization = cpu;
+        properties.process_memory_usage = memory;
+        properties.process_disk_usage_read_last_interval = read_bytes;
+        ProcessProperties::Full(properties)
+    }
+
+    fn full(properties: ProcessProperties) -> Box<FullProcessProperties> {
+        let ProcessProperties::Full(properties) = properties;
+        properties
+    }
+
+    #[test]
+    fn test_window_summarizes_samples() {
+        let mut windows = MetricWindows::new(TimeDelta::seconds(10));
+        let start = Utc::now();
+
+        assert!(windows.add(42, sample(10.0, 100, 5), start).is_none());
+        assert!(windows
+            .add(42, sample(50.0, 300, 5), start + TimeDelta::seconds(5))
+            .is_none());
+        let summary = windows
+            .add(42, sample(30.0, 200, 5), start + TimeDelta::seconds(10))
+            .map(full)
+            .unwrap();
+
+        let window = summary.metric_window.clone().unwrap();
+        assert_eq!(window.samples, 3);
+        assert_eq!(window.window_start, start);
+        assert_eq!(window.window_end, start + TimeDelta::seconds(10));
+        assert_eq!(window.cpu_utilization_min, 10.0);
+        assert_eq!(window.cpu_utilization_avg, 30.0);
+        assert_eq!(window.cpu_utilization_max, 50.0);
+        assert_eq!(window.memory_usage_min, 100);
+        assert_eq!(window.memory_usage_avg, 200);
+        assert_eq!(window.memory_usage_max, 300);
+        // the event carries the averages and the disk usage of the whole window
+        assert_eq!(summary.process_cpu_utilization, 30.0);
+        assert_eq!(summary.process_memory_usage, 200);
+        assert_eq!(summary.process_disk_usage_read_last_interval, 15);
+    }
+
+    #[test]
+    fn test_next_window_starts_after_summary() {
+        let mut windows = MetricWindows::new(TimeDelta::seconds(10));
+        let start = Utc::now();
+        windows.add(42, sample(10.0, 100, 0), start);
+        assert!(windows
+            .add(42, sample(10.0, 100, 0), start + TimeDelta::seconds(10))
+            .is_some());
+
+        let next = start + TimeDelta::seconds(11);
+        assert!(windows.add(42, sample(90.0, 900, 0), next).is_none());
+        let summary = windows
+            .add(42, sample(70.0, 700, 0), next + TimeDelta::seconds(10))
+            .map(full)
+            .unwrap()
+            .metric_window
+            .unwrap();
+        assert_eq!(summary.samples, 2);
+        assert_eq!(summary.window_start, next);
+        assert_eq!(summary.cpu_utilization_min, 70.0);
+        assert_eq!(summary.memory_usage_avg, 800);
+    }
+
+    #[test]
+    fn test_windows_of_exited_processes_are_closed() {
+        let mut windows = MetricWindows::new(TimeDelta::seconds(10));
+        let now = Utc::now();
+        windows.add(42, sample(10.0, 100, 0), now);
+        windows.add(43, sample(20.0, 200, 0), now);
+
+        let closed = windows.close_others(&HashSet::from([43]));
+        assert_eq!(closed.len(), 1);
+        let summary = full(closed.into_iter().next().unwrap());
+        assert_eq!(summary.metric_window.unwrap().samples, 1);
+        assert_eq!(summary.process_cpu_utilization, 10.0);
+
+        assert!(windows.close_others(&HashSet::from([43])).is_empty());
+    }
+
+    #[test]
+    fn test_zero_window_emits_raw_samples() {
+        let mut windows = MetricWindows::new(TimeDelta::zero());
+        let raw = windows
+            .add(42, sample(10.0, 100, 0), Utc::now())
+            .map(full)
+            .unwrap();
+        assert!(raw.metric_window.is_none());
+        assert_eq!(raw.process_cpu_utilization, 10.0);
+    }
+}
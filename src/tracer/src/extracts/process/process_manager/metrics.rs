@@ -1,7 +1,22 @@
+use crate::constants::{
+    MISSED_EXIT_CONFIRMATION_POLLS, STORAGE_STALL_THRESHOLD_MS, SWAP_ALERT_THRESHOLD_BYTES,
+    TOOL_METRICS_WINDOW_MS,
+};
+use crate::extracts::metrics::snapshot::MetricsSnapshots;
+use crate::extracts::process::missed_exits::MissedExits;
+use crate::extracts::process::pid_reuse::reuses_pid;
+use crate::extracts::process::process_manager::metric_window::MetricWindows;
 use crate::extracts::process::process_manager::recorder::EventRecorder;
 use crate::extracts::process::process_manager::state::StateManager;
 use crate::extracts::process::process_manager::system_refresher::SystemRefresher;
+use crate::extracts::process::storage_stall::{self, StallDetector};
+use crate::extracts::process::swap::SwapAlerts;
+use crate::process_identification::types::event::attributes::storage_stall::StorageStall;
 use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashSet;
+use tokio::sync::Mutex;
+use tracer_ebpf::ebpf_trigger::ProcessEndTrigger;
 use tracing::debug;
 
 /// Handles periodic polling and updating of process metrics for monitored processes.
@@ -14,30 +29,86 @@ use tracing::debug;
 /// This is separate from event-driven process handling and runs on a periodic schedule.
 pub struct ProcessMetricsHandler;
 
+/// What the metrics polling carries over from one poll to the next
+pub struct MetricsPollState {
+    pub metric_windows: MetricWindows,
+    pub swap_alerts: SwapAlerts,
+    pub storage_stalls: StallDetector,
+    pub missed_exits: MissedExits,
+    /// Latest sample of each process, for the daemon's metrics endpoint
+    pub snapshots: MetricsSnapshots,
+}
+
+impl Default for MetricsPollState {
+    fn default() -> Self {
+        Self {
+            metric_windows: MetricWindows::new(TimeDelta::milliseconds(
+                TOOL_METRICS_WINDOW_MS as i64,
+            )),
+            swap_alerts: SwapAlerts::new(SWAP_ALERT_THRESHOLD_BYTES),
+            storage_stalls: StallDetector::new(TimeDelta::milliseconds(
+                STORAGE_STALL_THRESHOLD_MS as i64,
+            )),
+            missed_exits: MissedExits::new(MISSED_EXIT_CONFIRMATION_POLLS),
+            snapshots: MetricsSnapshots::default(),
+        }
+    }
+}
+
 impl ProcessMetricsHandler {
     /// Polls and updates metrics for all monitored processes.
     ///
     /// This method:
     /// 1. Gets the list of all currently monitored process PIDs
     /// 2. Refreshes system data for those processes
-    /// 3. Iterates through all monitored processes and logs updated metrics
-    /// 4. Detects and logs processes that are no longer running
+    /// 3. Iterates through all monitored processes and logs updated metrics, or adds them to
+    ///    the aggregation window of the process
+    /// 4. Alerts on tools stalled in uninterruptible sleep while a network file system is
+    ///    mounted
+    /// 5. Updates the peak thread and child counts of each monitored tool
     ///
     /// This is typically called on a periodic schedule (e.g., every few seconds)
     /// to keep process metrics up to date.
+    ///
+    /// A process whose pid now belongs to a process started after it is treated as missing,
+    /// so the metrics of the other process are not attributed to it.
+    ///
+    /// Returns exit triggers for the monitored processes confirmed to have exited although their
+    /// exit was never recorded, as they have been missing from the process table for several
+    /// polls.
     pub async fn poll_process_metrics(
         state_manager: &StateManager,
         event_recorder: &EventRecorder,
         system_refresher: &SystemRefresher,
-    ) -> Result<()> {
+        poll_state: &Mutex<MetricsPollState>,
+    ) -> Result<Vec<ProcessEndTrigger>> {
         debug!("Starting periodic process metrics polling");
 
         // Step 1: Get all monitored process PIDs
         let monitored_pids = state_manager.get_monitored_processes_pids().await;
 
+        let mut poll_state = poll_state.lock().await;
+
+        // Emit what was aggregated for processes that are no longer monitored
+        for properties in poll_state.metric_windows.close_others(&monitored_pids) {
+            event_recorder.record_tool_metrics(properties).await?;
+        }
+        poll_state.swap_alerts.retain(&monitored_pids);
+        poll_state.storage_stalls.retain(&monitored_pids);
+        let monitored_processes: HashSet<_> = state_manager
+            .get_state()
+            .await
+            .get_monitoring()
+            .values()
+            .flatten()
+            .map(|proc| (proc.pid, proc.started_at))
+            .collect();
+        poll_state.missed_exits.retain(&monitored_processes);
+        poll_state.snapshots.retain_processes(&monitored_pids);
+
         if monitored_pids.is_empty() {
             debug!("No processes are currently monitored - skipping metrics poll");
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         debug!(
@@ -50,6 +121,10 @@ impl ProcessMetricsHandler {
         debug!("System data refreshed for {} PIDs", monitored_pids.len());
 
         // Step 3: Extract and log metrics for each monitored process
+        let mut thread_counts = Vec::new();
+        let mut stalls = Vec::new();
+        let mut missed_exits = Vec::new();
+        let now = Utc::now();
         for (target, processes) in state_manager.get_state().await.get_monitoring().iter() {
             for proc in processes {
                 let system = system_refresher.get_system().read().await;
@@ -57,17 +132,78 @@ impl ProcessMetricsHandler {
                     "Extracting metrics for PID {}: {}, with target: {}",
                     proc.pid, proc.comm, target
                 );
-                let sys_proc = system.process(proc.pid.into());
+                // the pid may have been reused by another process after this one exited
+                let (sys_proc, reused_at) = match system.process(proc.pid.into()) {
+                    Some(sys_proc) if reuses_pid(proc, sys_proc.start_time()) => (
+                        None,
+                        DateTime::from_timestamp(sys_proc.start_time() as i64, 0),
+                    ),
+                    sys_proc => (sys_proc, None),
+                };
                 debug!("System process for {}: {:?}", target, sys_proc);
+                if let Some(sys_proc) = sys_proc {
+                    thread_counts.push((proc.pid, sys_proc.tasks().map(|tasks| tasks.len())));
+                }
+                // an exited process that wasn't reaped yet is a zombie
+                let running = sys_proc
+                    .is_some_and(|sys_proc| sys_proc.status() != sysinfo::ProcessStatus::Zombie);
+                if poll_state.missed_exits.confirm_exit(proc, running) {
+                    missed_exits.push(ProcessEndTrigger {
+                        pid: proc.pid,
+                        // the exit time is unknown, it was last seen a few polls ago
+                        finished_at: reused_at.unwrap_or(now),
+                        exit_reason: None,
+                    });
+                }
+                if poll_state.storage_stalls.is_enabled() && reused_at.is_none() {
+                    let state = storage_stall::process_state(proc.pid);
+                    if let Some(since) = poll_state.storage_stalls.observe(proc.pid, state, now) {
+                        let working_directory = sys_proc
+                            .and_then(|sys_proc| sys_proc.cwd())
+                            .map(|cwd| cwd.to_string_lossy().to_string());
+                        stalls.push((target.clone(), proc.pid, since, working_directory));
+                    }
+                }
                 let result = event_recorder
-                    .record_process_metrics(target, proc, sys_proc)
+                    .record_process_metrics(target, proc, sys_proc, &mut poll_state)
                     .await?;
                 debug!("Metrics extracted for PID {}: {:?}", proc.pid, result);
             }
         }
+        drop(poll_state);
+
+        // Step 4: Alert on tools stalled while a network file system is mounted
+        if !stalls.is_empty() {
+            let mounts = storage_stall::network_mounts();
+            for (target, pid, since, working_directory) in stalls {
+                let Some(mount) =
+                    storage_stall::probable_mount(&mounts, working_directory.as_deref())
+                else {
+                    debug!("{} (PID {}) is in uninterruptible sleep, but no network file system is mounted", target, pid);
+                    continue;
+                };
+                event_recorder
+                    .record_storage_stall(StorageStall {
+                        tool_name: target,
+                        pid,
+                        stalled_since: since,
+                        stalled_for_secs: (now - since).num_seconds().max(0) as u64,
+                        mount_point: mount.mount_point.clone(),
+                        fs_type: mount.fs_type.clone(),
+                    })
+                    .await?;
+            }
+        }
+
+        // Step 5: Update the peak thread and child counts of each tool
+        let mut state = state_manager.get_state_mut().await;
+        for (pid, thread_count) in thread_counts {
+            state.observe_tool_concurrency(pid, thread_count);
+        }
+        drop(state);
 
         debug!("Metrics polling completed");
 
-        Ok(())
+        Ok(missed_exits)
     }
 }
@@ -1,6 +1,7 @@
 pub mod filtering;
 pub mod handlers;
 pub mod manager;
+pub mod metric_window;
 pub mod metrics;
 pub mod recorder;
 pub mod state;
@@ -1,21 +1,34 @@
+use crate::extracts::containers::cgroup::ContainerIdCache;
+use crate::extracts::containers::docker_watcher::event::ContainerEvent;
 use crate::extracts::containers::DockerWatcher;
 use crate::extracts::process::extract_process_data;
 use crate::extracts::process::extract_process_data::construct_tool_id;
+use crate::extracts::process::process_manager::metrics::MetricsPollState;
 use crate::extracts::process::types::process_result::ProcessResult;
+use crate::extracts::process::types::tool_concurrency::ToolConcurrency;
 use crate::extracts::python_monitor::function_monitor::function_monitor_manager::PythonFunctionCall;
 use crate::process_identification::recorder::EventDispatcher;
 use crate::process_identification::target_pipeline::pipeline_manager::TaskMatch;
-use crate::process_identification::types::event::attributes::process::ProcessProperties;
+use crate::process_identification::types::event::attributes::exec_failure::ExecFailure;
+use crate::process_identification::types::event::attributes::process::{
+    CompletedProcess, FullProcessProperties, ProcessProperties, ProcessTreeNode,
+};
+use crate::process_identification::types::event::attributes::storage_stall::StorageStall;
 use crate::process_identification::types::event::attributes::EventAttributes;
 use crate::process_identification::types::event::ProcessStatus as TracerProcessStatus;
+use crate::process_identification::types::lockfiles::PackageVersions;
 use crate::utils::string_validation::is_valid_uuid;
+use crate::utils::telemetry::ErrorCategory;
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 use sysinfo::Process;
 use tokio::sync::RwLock;
-use tracer_ebpf::ebpf_trigger::{FileOpenTrigger, ProcessEndTrigger, ProcessStartTrigger};
+use tracer_ebpf::ebpf_trigger::{
+    ExecFailedTrigger, ExitReason, FileOpenTrigger, ProcessEndTrigger, ProcessStartTrigger,
+};
 use tracing::{debug, info};
 
 /// Handles recording of process-related events
@@ -25,8 +38,12 @@ pub struct EventRecorder {
     /// shared reference to the docker watcher - used to get the ContainerEvent associated
     /// with a process
     docker_watcher: Arc<DockerWatcher>,
+    /// container id (if any) of each process, resolved from its cgroup
+    container_ids: ContainerIdCache,
     /// trace IDs that have already been logged
     logged_trace_ids: Arc<RwLock<HashSet<String>>>,
+    /// packages of the run's environment, to tell the version of each tool
+    package_versions: Arc<std::sync::RwLock<PackageVersions>>,
 }
 
 impl EventRecorder {
@@ -41,16 +58,58 @@ impl EventRecorder {
         Self {
             event_dispatcher,
             docker_watcher,
+            container_ids: ContainerIdCache::default(),
             logged_trace_ids: Arc::new(RwLock::new(trace_ids)),
+            package_versions: Arc::default(),
         }
     }
 
+    /// Replaces the packages the versions of tools are looked up in
+    pub fn set_package_versions(&self, package_versions: PackageVersions) {
+        *self
+            .package_versions
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = package_versions;
+    }
+
+    /// Version of the package named like the tool or its binary
+    fn tool_version(&self, properties: &FullProcessProperties) -> Option<String> {
+        let package_versions = self
+            .package_versions
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let binary = std::path::Path::new(&properties.tool_binary_path)
+            .file_name()
+            .and_then(|name| name.to_str());
+        package_versions
+            .version_of(&properties.tool_name)
+            .or_else(|| binary.and_then(|binary| package_versions.version_of(binary)))
+            .map(str::to_string)
+    }
+
+    /// Returns the id of the container the given process runs in, if any
+    pub fn container_id_for_pid(&self, pid: usize) -> Option<String> {
+        self.container_ids.get_or_resolve(pid)
+    }
+
+    /// Returns the container the given process runs in, if it is tracked by the docker watcher
+    pub async fn container_for_pid(&self, pid: usize) -> Option<ContainerEvent> {
+        let container_id = self.container_id_for_pid(pid)?;
+        self.docker_watcher.get_container_event(&container_id).await
+    }
+
+    /// Forgets the cached container of a terminated process
+    pub fn forget_process(&self, pid: usize) {
+        self.container_ids.forget(pid);
+    }
+
     /// Records information about a newly detected process
     pub async fn record_new_process(
         &self,
         target: &String,
         process: &ProcessStartTrigger,
         system_process: Option<&Process>,
+        process_tree: Option<Vec<ProcessTreeNode>>,
     ) -> Result<ProcessResult> {
         debug!("Processing pid={}", process.pid);
 
@@ -76,6 +135,8 @@ impl EventRecorder {
         };
 
         let ProcessProperties::Full(full) = &mut properties;
+        full.process_tree = process_tree;
+        full.tool_version = self.tool_version(full);
 
         // If we have a container ID, fetch and attach the container event
         if let Some(container_id) = &full.container_id {
@@ -111,12 +172,15 @@ impl EventRecorder {
         Ok(ProcessResult::Found)
     }
 
-    /// Records metrics update for an already running process
+    /// Records metrics update for an already running process, or adds them to its aggregation
+    /// window, and keeps them as its latest sample. Alerts when the process starts using more
+    /// swap than the threshold.
     pub async fn record_process_metrics(
         &self,
         target: &String,
         process: &ProcessStartTrigger,
         system_process: Option<&Process>,
+        poll_state: &mut MetricsPollState,
     ) -> Result<ProcessResult> {
         let display_name = target;
         let Some(system_process) = system_process else {
@@ -134,7 +198,7 @@ impl EventRecorder {
         );
 
         // Don't process input files for update events
-        let properties = extract_process_data::gather_process_data(
+        let mut properties = extract_process_data::gather_process_data(
             system_process,
             display_name.clone(),
             process.started_at,
@@ -144,16 +208,47 @@ impl EventRecorder {
 
         debug!("Process data completed. PID={}", process.pid);
 
+        let now = Utc::now();
+        let ProcessProperties::Full(full) = &mut properties;
+        full.tool_version = self.tool_version(full);
+        let ProcessProperties::Full(full) = &properties;
+        poll_state.snapshots.record_process(process.pid, full, now);
+        if poll_state
+            .swap_alerts
+            .went_over(process.pid, full.swap_used)
+        {
+            self.event_dispatcher
+                .log_with_metadata(
+                    TracerProcessStatus::Alert,
+                    format!(
+                        "{} is swapping: {} MiB of its memory is swapped out",
+                        display_name,
+                        full.swap_used.unwrap_or_default() / (1024 * 1024)
+                    ),
+                    Some(EventAttributes::Process(properties.clone())),
+                    None,
+                )
+                .await?;
+        }
+
+        if let Some(properties) = poll_state.metric_windows.add(process.pid, properties, now) {
+            self.record_tool_metrics(properties).await?;
+        }
+
+        Ok(ProcessResult::Found)
+    }
+
+    /// Records a `ToolMetricEvent`, with a single sample or the summary of a window
+    pub async fn record_tool_metrics(&self, properties: ProcessProperties) -> Result<()> {
+        let ProcessProperties::Full(full) = &properties;
         self.event_dispatcher
             .log_with_metadata(
                 TracerProcessStatus::ToolMetricEvent,
-                format!("[{}] Tool metric event: {}", Utc::now(), &display_name),
+                format!("[{}] Tool metric event: {}", Utc::now(), full.tool_name),
                 Some(EventAttributes::Process(properties)),
                 None,
             )
-            .await?;
-
-        Ok(ProcessResult::Found)
+            .await
     }
 
     /// Records completion of a process
@@ -162,43 +257,65 @@ impl EventRecorder {
         target: &str,
         start_trigger: &ProcessStartTrigger,
         finish_trigger: &ProcessEndTrigger,
+        concurrency: ToolConcurrency,
+        elapsed: Option<Duration>,
     ) -> Result<()> {
-        let duration_sec = (finish_trigger.finished_at - start_trigger.started_at)
-            .num_seconds()
-            .try_into()
-            .unwrap_or(0);
-
         info!(
             "record_process_completion: finish trigger: {:?}",
             finish_trigger
         );
 
-        let completed_process_run_time_milliseconds = (finish_trigger.finished_at
-            - start_trigger.started_at)
-            .num_milliseconds()
-            .max(0) as u64;
-        // CompletedProcess contains the exit reason, the tool_id, the tool_name, and started and ended at
-        // started and ended at might not seem very useful, but might help in the future with duration calculations
-        let properties =
-            crate::process_identification::types::event::attributes::process::CompletedProcess {
-                tool_id: construct_tool_id(
-                    &start_trigger.pid.to_string(),
-                    start_trigger.started_at,
-                ),
-                tool_name: target.to_owned(),
-                tool_pid: start_trigger.pid.to_string(),
-                duration_sec,
-                exit_reason: finish_trigger.exit_reason.clone(),
-                started_at: start_trigger.started_at,
-                ended_at: finish_trigger.finished_at,
-                process_run_time: completed_process_run_time_milliseconds,
-                tool_args: start_trigger.argv.join(" "),
-            };
+        let properties = completed_process(
+            target,
+            start_trigger,
+            finish_trigger.finished_at,
+            finish_trigger.exit_reason.clone(),
+            concurrency,
+            elapsed,
+            false,
+        );
+        self.dispatch_completion(start_trigger, properties).await
+    }
+
+    /// Records a best-effort completion for a process that is still running when monitoring
+    /// stops, with its duration measured up to `stopped_at` and the `incomplete` flag set
+    pub async fn record_interrupted_process(
+        &self,
+        target: &str,
+        start_trigger: &ProcessStartTrigger,
+        stopped_at: DateTime<Utc>,
+        concurrency: ToolConcurrency,
+        elapsed: Option<Duration>,
+    ) -> Result<()> {
+        let properties = completed_process(
+            target,
+            start_trigger,
+            stopped_at,
+            None,
+            concurrency,
+            elapsed,
+            true,
+        );
+        self.dispatch_completion(start_trigger, properties).await
+    }
 
+    async fn dispatch_completion(
+        &self,
+        start_trigger: &ProcessStartTrigger,
+        properties: CompletedProcess,
+    ) -> Result<()> {
         self.event_dispatcher
             .log_with_metadata(
                 TracerProcessStatus::FinishedToolExecution,
-                format!("[{}] {} exited", Utc::now(), &start_trigger.comm),
+                match &properties.exit_reason {
+                    Some(exit_reason) => format!(
+                        "[{}] {} exited: {}",
+                        Utc::now(),
+                        &start_trigger.comm,
+                        exit_reason
+                    ),
+                    None => format!("[{}] {} exited", Utc::now(), &start_trigger.comm),
+                },
                 Some(EventAttributes::CompletedProcess(properties)),
                 None,
             )
@@ -207,6 +324,50 @@ impl EventRecorder {
         Ok(())
     }
 
+    /// Records an alert for a matched tool whose `execve` failed, so it never started
+    pub async fn record_exec_failure(
+        &self,
+        target: &str,
+        exec_failure: &ExecFailedTrigger,
+    ) -> Result<()> {
+        self.event_dispatcher
+            .log_with_metadata(
+                TracerProcessStatus::Alert,
+                format!(
+                    "{} failed to start: {} ({})",
+                    target, exec_failure.error, exec_failure.file_name
+                ),
+                Some(EventAttributes::ExecFailure(ExecFailure {
+                    tool_name: target.to_string(),
+                    command: exec_failure.file_name.clone(),
+                    errno: exec_failure.errno,
+                    error: exec_failure.error.clone(),
+                    error_category: ErrorCategory::from_errno(exec_failure.errno),
+                    pid: exec_failure.pid,
+                    ppid: exec_failure.ppid,
+                    attempted_at: exec_failure.timestamp,
+                })),
+                Some(exec_failure.timestamp),
+            )
+            .await
+    }
+
+    /// Records an alert for a tool stuck in uninterruptible sleep while a network file system is
+    /// mounted, most likely waiting on an unresponsive storage server
+    pub async fn record_storage_stall(&self, stall: StorageStall) -> Result<()> {
+        self.event_dispatcher
+            .log_with_metadata(
+                TracerProcessStatus::Alert,
+                format!(
+                    "{} has been in uninterruptible sleep for {}s: probable storage stall on {} ({})",
+                    stall.tool_name, stall.stalled_for_secs, stall.mount_point, stall.fs_type
+                ),
+                Some(EventAttributes::StorageStall(stall)),
+                None,
+            )
+            .await
+    }
+
     /// Record a match for a set of processes to a job.
     pub async fn record_task_match(&self, task_match: TaskMatch) -> Result<()> {
         self.event_dispatcher
@@ -255,3 +416,161 @@ impl EventRecorder {
             .await
     }
 }
+
+/// Builds the attributes of a `FinishedToolExecution` event. `started_at`/`ended_at` are kept so
+/// consumers can still correlate with `ToolExecution` events, but `duration_ms` saves them from
+/// having to join the two. It is the monotonic `elapsed` time of the tool if known, which a step
+/// of the system clock while the tool ran doesn't affect, else the wall-clock difference.
+fn completed_process(
+    target: &str,
+    start_trigger: &ProcessStartTrigger,
+    ended_at: DateTime<Utc>,
+    exit_reason: Option<ExitReason>,
+    concurrency: ToolConcurrency,
+    elapsed: Option<Duration>,
+    incomplete: bool,
+) -> CompletedProcess {
+    let duration_ms = match elapsed {
+        Some(elapsed) => elapsed.as_millis() as u64,
+        None => (ended_at - start_trigger.started_at)
+            .num_milliseconds()
+            .max(0) as u64,
+    };
+
+    CompletedProcess {
+        tool_id: construct_tool_id(&start_trigger.pid.to_string(), start_trigger.started_at),
+        tool_name: target.to_owned(),
+        tool_pid: start_trigger.pid.to_string(),
+        duration_sec: duration_ms / 1000,
+        duration_ms,
+        incomplete,
+        peak_thread_count: concurrency.peak_thread_count as u64,
+        peak_child_count: concurrency.peak_child_count as u64,
+        exit_reason,
+        started_at: start_trigger.started_at,
+        ended_at,
+        process_run_time: duration_ms,
+        tool_args: start_trigger.argv.join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use rstest::rstest;
+
+    fn start_trigger() -> ProcessStartTrigger {
+        ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR", "--runThreadN", "4"])
+    }
+
+    #[test]
+    fn test_completed_process_has_duration() {
+        let start = start_trigger();
+        let ended_at = start.started_at + TimeDelta::milliseconds(2500);
+        let exit_reason = ExitReason::success();
+
+        let completed = completed_process(
+            "STAR",
+            &start,
+            ended_at,
+            Some(exit_reason),
+            ToolConcurrency {
+                peak_thread_count: 8,
+                peak_child_count: 2,
+            },
+            None,
+            false,
+        );
+
+        assert_eq!(completed.duration_ms, 2500);
+        assert_eq!(completed.duration_sec, 2);
+        assert_eq!(completed.ended_at, ended_at);
+        assert!(!completed.incomplete);
+        assert!(completed.exit_reason.is_some());
+        assert_eq!(completed.peak_thread_count, 8);
+        assert_eq!(completed.peak_child_count, 2);
+    }
+
+    #[test]
+    fn test_interrupted_process_is_incomplete() {
+        let start = start_trigger();
+        let stopped_at = start.started_at + TimeDelta::seconds(60);
+
+        let completed = completed_process(
+            "STAR",
+            &start,
+            stopped_at,
+            None,
+            ToolConcurrency::default(),
+            None,
+            true,
+        );
+
+        assert_eq!(completed.duration_ms, 60_000);
+        assert!(completed.incomplete);
+        assert!(completed.exit_reason.is_none());
+
+        // a clock going backwards never yields a negative duration
+        let completed = completed_process(
+            "STAR",
+            &start,
+            start.started_at - TimeDelta::seconds(1),
+            None,
+            ToolConcurrency::default(),
+            None,
+            true,
+        );
+        assert_eq!(completed.duration_ms, 0);
+    }
+
+    #[test]
+    fn test_clock_jump_does_not_affect_duration() {
+        let start = start_trigger();
+        // the system clock was stepped back an hour while the tool ran for 2.5s
+        let ended_at = start.started_at - TimeDelta::hours(1);
+
+        let completed = completed_process(
+            "STAR",
+            &start,
+            ended_at,
+            Some(ExitReason::success()),
+            ToolConcurrency::default(),
+            Some(Duration::from_millis(2500)),
+            false,
+        );
+
+        assert_eq!(completed.duration_ms, 2500);
+        assert_eq!(completed.duration_sec, 2);
+        assert_eq!(completed.process_run_time, 2500);
+        // the wall-clock timestamps are kept as measured
+        assert_eq!(completed.started_at, start.started_at);
+        assert_eq!(completed.ended_at, ended_at);
+    }
+
+    #[rstest]
+    #[case::success(Some(0), Some(("INFO", 9)))]
+    #[case::nonzero(Some(1 << 8), Some(("ERROR", 17)))]
+    #[case::killed(Some(9), Some(("ERROR", 17)))]
+    #[case::segfault(Some(0x80 | 11), Some(("ERROR", 17)))]
+    #[case::unknown(None, None)]
+    fn test_finished_tool_severity_follows_exit(
+        #[case] wait_status: Option<i64>,
+        #[case] severity: Option<(&str, u8)>,
+    ) {
+        let start = start_trigger();
+        let completed = completed_process(
+            "STAR",
+            &start,
+            start.started_at,
+            wait_status.map(ExitReason::from),
+            ToolConcurrency::default(),
+            None,
+            wait_status.is_none(),
+        );
+        assert_eq!(
+            EventAttributes::CompletedProcess(completed).severity(),
+            severity
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::extracts::process::types::process_state::ProcessState;
+use crate::process_identification::target_process::target_manager::TargetManager;
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -23,6 +24,12 @@ impl StateManager {
         self.state.read().await
     }
 
+    /// Replaces the target manager used to match new processes
+    pub async fn set_target_manager(&self, target_manager: TargetManager) {
+        let mut state = self.get_state_mut().await;
+        state.set_target_manager(target_manager);
+    }
+
     /// Sets the eBPF task handle
     pub async fn set_ebpf_task(&self, task: JoinHandle<()>) {
         let mut state = self.get_state_mut().await;
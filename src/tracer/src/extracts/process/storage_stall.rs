@@ -0,0 +1,278 @@
+//! Detection of tools stalled on network storage. A process blocked on an unresponsive NFS or
+//! Lustre server sits in uninterruptible sleep (state `D` in `/proc/<pid>/stat`) without making
+//! progress, so a tool that stays in that state for long while a network file system is
+//! mounted most likely waits on it.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// File system types served over the network
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "lustre",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ceph",
+    "glusterfs",
+    "fuse.glusterfs",
+    "fuse.sshfs",
+    "beegfs",
+    "gpfs",
+    "panfs",
+];
+
+/// Process state of uninterruptible sleep, usually waiting on I/O
+const UNINTERRUPTIBLE_SLEEP: char = 'D';
+
+/// A mounted file system, from `/proc/mounts`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mount {
+    pub mount_point: String,
+    pub fs_type: String,
+}
+
+/// The state letter of a process (e.g. `R`, `S` or `D`), or `None` if it exited
+#[cfg(target_os = "linux")]
+pub fn process_state(pid: usize) -> Option<char> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat_state(&stat)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_state(_pid: usize) -> Option<char> {
+    None
+}
+
+/// Extracts the state from the contents of `/proc/<pid>/stat`. It follows the command name,
+/// which is in parentheses and may itself contain spaces and parentheses.
+pub fn parse_stat_state(stat: &str) -> Option<char> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.trim_start().chars().next()
+}
+
+/// The network file systems currently mounted
+#[cfg(target_os = "linux")]
+pub fn network_mounts() -> Vec<Mount> {
+    std::fs::read_to_string("/proc/mounts")
+        .map(|mounts| parse_network_mounts(&mounts))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn network_mounts() -> Vec<Mount> {
+    Vec::new()
+}
+
+/// Extracts the network file systems from the contents of `/proc/mounts`, whose lines are
+/// `<device> <mount point> <fs type> <options> <dump> <pass>`
+pub fn parse_network_mounts(mounts: &str) -> Vec<Mount> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let mount_point = fields.next()?;
+            let fs_type = fields.next()?;
+            NETWORK_FS_TYPES.contains(&fs_type).then(|| Mount {
+                // spaces in mount points are escaped as \040
+                mount_point: mount_point.replace("\\040", " "),
+                fs_type: fs_type.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The mount a process working in `working_directory` most likely waits on: the innermost one
+/// holding the directory, else the first one
+pub fn probable_mount<'a>(
+    mounts: &'a [Mount],
+    working_directory: Option<&str>,
+) -> Option<&'a Mount> {
+    working_directory
+        .and_then(|directory| {
+            mounts
+                .iter()
+                .filter(|mount| Path::new(directory).starts_with(&mount.mount_point))
+                .max_by_key(|mount| mount.mount_point.len())
+        })
+        .or_else(|| mounts.first())
+}
+
+/// Tracks how long each process has been in uninterruptible sleep, to report each stall once
+/// it lasts longer than a threshold
+#[derive(Debug, Default)]
+pub struct StallDetector {
+    /// `None` disables the detection
+    threshold: Option<TimeDelta>,
+    /// When each process in uninterruptible sleep was first seen in it
+    stalled_since: HashMap<usize, DateTime<Utc>>,
+    /// Processes whose current stall was reported
+    reported: HashSet<usize>,
+}
+
+impl StallDetector {
+    /// A zero threshold disables the detection
+    pub fn new(threshold: TimeDelta) -> Self {
+        Self {
+            threshold: (threshold > TimeDelta::zero()).then_some(threshold),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.threshold.is_some()
+    }
+
+    /// Records the state of `pid` at `now`, returning when its stall started if it just
+    /// outlasted the threshold. An unknown state, e.g. of a process that exited, leaves the
+    /// stall as is.
+    pub fn observe(
+        &mut self,
+        pid: usize,
+        state: Option<char>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let threshold = self.threshold?;
+        match state? {
+            UNINTERRUPTIBLE_SLEEP => {
+                let since = *self.stalled_since.entry(pid).or_insert(now);
+                (now - since >= threshold && self.reported.insert(pid)).then_some(since)
+            }
+            _ => {
+                self.stalled_since.remove(&pid);
+                self.reported.remove(&pid);
+                None
+            }
+        }
+    }
+
+    /// Forgets the processes not in `monitored`
+    pub fn retain(&mut self, monitored: &HashSet<usize>) {
+        self.stalled_since.retain(|pid, _| monitored.contains(pid));
+        self.reported.retain(|pid| monitored.contains(pid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_state() {
+        let stat = "4242 (STAR) D 4200 4242 4200 0 -1 4194560 2090 0 0 0 12 3";
+        assert_eq!(parse_stat_state(stat), Some('D'));
+        // command names may contain spaces and parentheses
+        let stat = "4243 (my tool (v2)) S 1 4243 4243 0 -1";
+        assert_eq!(parse_stat_state(stat), Some('S'));
+        assert_eq!(parse_stat_state(""), None);
+    }
+
+    #[test]
+    fn test_parse_network_mounts() {
+        let mounts = "\
+/dev/nvme0n1p1 / ext4 rw,relatime 0 0
+filer:/export/home /home nfs4 rw,vers=4.1 0 0
+10.0.0.5@tcp:/lustre /mnt/lustre lustre rw 0 0
+tmpfs /tmp tmpfs rw 0 0
+//server/share /mnt/my\\040share cifs rw 0 0
+";
+        assert_eq!(
+            parse_network_mounts(mounts),
+            vec![
+                Mount {
+                    mount_point: "/home".to_string(),
+                    fs_type: "nfs4".to_string()
+                },
+                Mount {
+                    mount_point: "/mnt/lustre".to_string(),
+                    fs_type: "lustre".to_string()
+                },
+                Mount {
+                    mount_point: "/mnt/my share".to_string(),
+                    fs_type: "cifs".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_probable_mount_holds_working_directory() {
+        let mount = |mount_point: &str| Mount {
+            mount_point: mount_point.to_string(),
+            fs_type: "nfs4".to_string(),
+        };
+        let mounts = vec![
+            mount("/home"),
+            mount("/mnt/lustre"),
+            mount("/mnt/lustre/scratch"),
+        ];
+
+        let probable = |directory| {
+            probable_mount(&mounts, directory)
+                .unwrap()
+                .mount_point
+                .clone()
+        };
+        assert_eq!(
+            probable(Some("/mnt/lustre/scratch/run1")),
+            "/mnt/lustre/scratch"
+        );
+        assert_eq!(probable(Some("/mnt/lustre/project")), "/mnt/lustre");
+        // not on a network mount, or unknown
+        assert_eq!(probable(Some("/tmp/work")), "/home");
+        assert_eq!(probable(None), "/home");
+        assert!(probable_mount(&[], Some("/home")).is_none());
+    }
+
+    #[test]
+    fn test_stall_is_reported_once_past_threshold() {
+        let mut detector = StallDetector::new(TimeDelta::seconds(30));
+        let start = Utc::now();
+        let at = |secs| start + TimeDelta::seconds(secs);
+
+        assert_eq!(detector.observe(42, Some('D'), at(0)), None);
+        assert_eq!(detector.observe(42, Some('D'), at(20)), None);
+        // vanished from /proc for a poll
+        assert_eq!(detector.observe(42, None, at(25)), None);
+        assert_eq!(detector.observe(42, Some('D'), at(30)), Some(start));
+        assert_eq!(detector.observe(42, Some('D'), at(60)), None);
+
+        // recovered, then stalled again
+        assert_eq!(detector.observe(42, Some('R'), at(61)), None);
+        assert_eq!(detector.observe(42, Some('D'), at(62)), None);
+        assert_eq!(detector.observe(42, Some('D'), at(92)), Some(at(62)));
+    }
+
+    #[test]
+    fn test_short_io_waits_are_not_stalls() {
+        let mut detector = StallDetector::new(TimeDelta::seconds(30));
+        let start = Utc::now();
+        for secs in 0..10 {
+            let state = if secs % 2 == 0 { 'D' } else { 'S' };
+            let now = start + TimeDelta::seconds(secs * 10);
+            assert_eq!(detector.observe(42, Some(state), now), None);
+        }
+    }
+
+    #[test]
+    fn test_exited_processes_are_forgotten() {
+        let mut detector = StallDetector::new(TimeDelta::seconds(30));
+        let start = Utc::now();
+        detector.observe(42, Some('D'), start);
+        detector.retain(&HashSet::new());
+        // a new process with the same pid starts a new stall
+        assert_eq!(
+            detector.observe(42, Some('D'), start + TimeDelta::seconds(30)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_detection() {
+        let mut detector = StallDetector::new(TimeDelta::zero());
+        assert!(!detector.is_enabled());
+        assert_eq!(detector.observe(42, Some('D'), Utc::now()), None);
+    }
+}
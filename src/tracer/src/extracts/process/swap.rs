@@ -0,0 +1,120 @@
+//! Swap usage of a process, from the `VmSwap` line of `/proc/<pid>/status`, e.g.
+//! `VmSwap:	   10240 kB`. Only available on Linux.
+
+use std::collections::HashSet;
+
+/// Bytes of the process's memory that are swapped out, or `None` if unknown, e.g. because the
+/// process exited since it was last seen
+#[cfg(target_os = "linux")]
+pub fn swap_used(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    parse_vm_swap(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn swap_used(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// Extracts `VmSwap` in bytes from the contents of a `/proc/<pid>/status` file. Kernel threads
+/// have no `VmSwap` line.
+pub fn parse_vm_swap(status: &str) -> Option<u64> {
+    let value = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmSwap:"))?;
+    let mut parts = value.split_whitespace();
+    let amount: u64 = parts.next()?.parse().ok()?;
+    match parts.next() {
+        Some("kB") | None => Some(amount * 1024),
+        Some(_) => None,
+    }
+}
+
+/// Tracks the processes using more swap than a threshold, to alert once when a process goes
+/// over it rather than on every poll
+#[derive(Debug, Default)]
+pub struct SwapAlerts {
+    /// `None` disables the alerts
+    threshold_bytes: Option<u64>,
+    swapping: HashSet<usize>,
+}
+
+impl SwapAlerts {
+    /// A zero threshold disables the alerts
+    pub fn new(threshold_bytes: u64) -> Self {
+        Self {
+            threshold_bytes: (threshold_bytes > 0).then_some(threshold_bytes),
+            swapping: HashSet::new(),
+        }
+    }
+
+    /// Whether `pid` just went over the threshold. An unknown swap usage, e.g. from a process
+    /// that exited, leaves its state unchanged.
+    pub fn went_over(&mut self, pid: usize, swap_used: Option<u64>) -> bool {
+        let (Some(threshold), Some(swap_used)) = (self.threshold_bytes, swap_used) else {
+            return false;
+        };
+        if swap_used > threshold {
+            self.swapping.insert(pid)
+        } else {
+            self.swapping.remove(&pid);
+            false
+        }
+    }
+
+    /// Forgets the processes not in `monitored`
+    pub fn retain(&mut self, monitored: &HashSet<usize>) {
+        self.swapping.retain(|pid| monitored.contains(pid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_swap() {
+        let status = "Name:\tSTAR\nVmRSS:\t  204800 kB\nVmSwap:\t   10240 kB\nThreads:\t8\n";
+        assert_eq!(parse_vm_swap(status), Some(10240 * 1024));
+        assert_eq!(parse_vm_swap("VmSwap:\t       0 kB\n"), Some(0));
+    }
+
+    #[test]
+    fn test_missing_or_malformed_vm_swap() {
+        // kernel threads
+        assert_eq!(parse_vm_swap("Name:\tkthreadd\nThreads:\t1\n"), None);
+        assert_eq!(parse_vm_swap("VmSwap:\tlots\n"), None);
+        assert_eq!(parse_vm_swap(""), None);
+    }
+
+    #[test]
+    fn test_alerts_once_per_crossing() {
+        let mut alerts = SwapAlerts::new(100);
+        assert!(!alerts.went_over(42, Some(50)));
+        assert!(alerts.went_over(42, Some(150)));
+        assert!(!alerts.went_over(42, Some(200)));
+        // the process vanished for a poll
+        assert!(!alerts.went_over(42, None));
+        assert!(!alerts.went_over(42, Some(200)));
+        // back under the threshold, then over again
+        assert!(!alerts.went_over(42, Some(10)));
+        assert!(alerts.went_over(42, Some(120)));
+
+        alerts.retain(&HashSet::new());
+        assert!(alerts.went_over(42, Some(120)));
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_alerts() {
+        let mut alerts = SwapAlerts::new(0);
+        assert!(!alerts.went_over(42, Some(u64::MAX)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_swap_of_exited_process_is_unknown() {
+        assert!(swap_used(std::process::id()).is_some());
+        // beyond the default pid_max
+        assert_eq!(swap_used(u32::MAX), None);
+    }
+}
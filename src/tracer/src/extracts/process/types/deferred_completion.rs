@@ -0,0 +1,289 @@
+use crate::extracts::process::types::tool_clock::TriggerClock;
+use crate::extracts::process::types::tool_concurrency::ToolConcurrency;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracer_ebpf::ebpf_trigger::{ProcessEndTrigger, ProcessStartTrigger};
+
+/// A monitored tool that exited while some of its descendants were still running.
+///
+/// Some tools fork a child that carries on the work and exit right away, so the tool is only
+/// finalized once the last of its descendants exits, or no descendant started or exited for the
+/// grace period.
+#[derive(Debug, Clone)]
+pub struct DeferredCompletion {
+    pub target: String,
+    pub start_trigger: ProcessStartTrigger,
+    /// Exit of the tool, with `finished_at` moved to the latest exit of one of its descendants
+    pub finish_trigger: ProcessEndTrigger,
+    pub concurrency: ToolConcurrency,
+    /// Monotonic time from the start of the tool to `finish_trigger`, see `ToolClocks`
+    pub elapsed: Option<Duration>,
+    started: Option<Instant>,
+    /// The tool and every descendant seen so far, running or not: the children of a descendant
+    /// that exited still name it as their parent
+    lineage: HashSet<usize>,
+    grace_period: chrono::Duration,
+    /// Latest start or exit in the lineage, which the grace period runs from
+    last_activity: DateTime<Utc>,
+}
+
+impl DeferredCompletion {
+    pub fn new(
+        target: String,
+        start_trigger: ProcessStartTrigger,
+        finish_trigger: ProcessEndTrigger,
+        concurrency: ToolConcurrency,
+        started: Option<Instant>,
+        clock: &TriggerClock,
+        grace_period: Duration,
+    ) -> Self {
+        let elapsed = started.map(|started| clock.elapsed(started, finish_trigger.finished_at));
+        let last_activity = finish_trigger.finished_at;
+        Self {
+            target,
+            lineage: HashSet::from([start_trigger.pid]),
+            start_trigger,
+            finish_trigger,
+            concurrency,
+            elapsed,
+            started,
+            grace_period: chrono::Duration::from_std(grace_period)
+                .unwrap_or(chrono::Duration::zero()),
+            last_activity,
+        }
+    }
+
+    /// Takes the given exits and running processes into account, and returns whether the tool
+    /// can be finalized: none of its descendants is running anymore, or none started or exited
+    /// for the grace period
+    pub fn update(
+        &mut self,
+        processes: &HashMap<usize, ProcessStartTrigger>,
+        exits: &[ProcessEndTrigger],
+        clock: &TriggerClock,
+        now: DateTime<Utc>,
+    ) -> bool {
+        for exit in exits {
+            if !self.lineage.contains(&exit.pid) {
+                continue;
+            }
+            self.last_activity = self.last_activity.max(exit.finished_at);
+            if exit.finished_at > self.finish_trigger.finished_at {
+                self.finish_trigger.finished_at = exit.finished_at;
+                self.elapsed = self
+                    .started
+                    .map(|started| clock.elapsed(started, exit.finished_at));
+            }
+        }
+
+        loop {
+            let descendants: Vec<&ProcessStartTrigger> = processes
+                .values()
+                .filter(|process| {
+                    self.lineage.contains(&process.ppid) && !self.lineage.contains(&process.pid)
+                })
+                .collect();
+            if descendants.is_empty() {
+                break;
+            }
+            for descendant in descendants {
+                self.last_activity = self.last_activity.max(descendant.started_at);
+                self.lineage.insert(descendant.pid);
+            }
+        }
+
+        let tool_pid = self.start_trigger.pid;
+        let has_running_descendants = self
+            .lineage
+            .iter()
+            .any(|pid| *pid != tool_pid && processes.contains_key(pid));
+        !has_running_descendants || now >= self.last_activity + self.grace_period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::process::types::process_state::ProcessState;
+
+    const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+    fn process(pid: usize, ppid: usize, comm: &str) -> ProcessStartTrigger {
+        ProcessStartTrigger::from_name_and_args(pid, ppid, comm, &[comm])
+    }
+
+    /// A clock without eBPF, as in the tests
+    fn clock() -> TriggerClock {
+        TriggerClock::at(None, Instant::now())
+    }
+
+    fn exit(pid: usize, finished_at: DateTime<Utc>) -> ProcessEndTrigger {
+        ProcessEndTrigger {
+            pid,
+            finished_at,
+            exit_reason: None,
+        }
+    }
+
+    /// `salmon` (pid 10) forks pid 11 and exits at `exited_at`; returns the state after the exit
+    fn fork_and_exit(exited_at: DateTime<Utc>) -> ProcessState {
+        let mut state = ProcessState::default();
+        let tool = process(10, 1, "salmon");
+        state.insert_process(10, tool.clone());
+        let mut child = process(11, 10, "salmon");
+        child.started_at = exited_at - chrono::Duration::seconds(1);
+        state.insert_process(11, child);
+
+        state.remove_process(&10);
+        state.defer_completion(DeferredCompletion::new(
+            "salmon".to_string(),
+            tool,
+            exit(10, exited_at),
+            ToolConcurrency::default(),
+            None,
+            &clock(),
+            GRACE_PERIOD,
+        ));
+        state
+    }
+
+    #[test]
+    fn test_tool_without_descendants_is_finalized() {
+        let mut state = ProcessState::default();
+        let tool = process(10, 1, "salmon");
+        let exited_at = Utc::now();
+        state.defer_completion(DeferredCompletion::new(
+            "salmon".to_string(),
+            tool,
+            exit(10, exited_at),
+            ToolConcurrency::default(),
+            None,
+            &clock(),
+            GRACE_PERIOD,
+        ));
+
+        let due = state.take_due_completions(&[exit(10, exited_at)], &clock(), exited_at);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].finish_trigger.finished_at, exited_at);
+    }
+
+    #[test]
+    fn test_tool_is_finalized_when_surviving_child_exits() {
+        let exited_at = Utc::now();
+        let mut state = fork_and_exit(exited_at);
+
+        let due = state.take_due_completions(&[exit(10, exited_at)], &clock(), exited_at);
+        assert!(due.is_empty());
+
+        // the child forks a grandchild and exits, the grandchild keeps the tool open
+        state.insert_process(12, process(12, 11, "salmon"));
+        let child_exited_at = exited_at + chrono::Duration::seconds(1);
+        state.remove_process(&11);
+        let due =
+            state.take_due_completions(&[exit(11, child_exited_at)], &clock(), child_exited_at);
+        assert!(due.is_empty());
+
+        let grandchild_exited_at = exited_at + chrono::Duration::seconds(2);
+        state.remove_process(&12);
+        let due = state.take_due_completions(
+            &[exit(12, grandchild_exited_at)],
+            &clock(),
+            grandchild_exited_at,
+        );
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].start_trigger.pid, 10);
+        assert_eq!(due[0].finish_trigger.finished_at, grandchild_exited_at);
+    }
+
+    #[test]
+    fn test_tool_is_finalized_after_grace_period() {
+        let exited_at = Utc::now();
+        let mut state = fork_and_exit(exited_at);
+
+        let before_deadline = exited_at + chrono::Duration::seconds(4);
+        assert!(state
+            .take_due_completions(&[], &clock(), before_deadline)
+            .is_empty());
+
+        let after_deadline = exited_at + chrono::Duration::seconds(5);
+        let due = state.take_due_completions(&[], &clock(), after_deadline);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].finish_trigger.finished_at, exited_at);
+        assert!(state.take_deferred_completions().is_empty());
+    }
+
+    #[test]
+    fn test_busy_child_outlives_grace_period() {
+        let exited_at = Utc::now();
+        let mut state = fork_and_exit(exited_at);
+        let at = |secs| exited_at + chrono::Duration::seconds(secs);
+
+        // the child is still busy forking after the grace period of the exit, 5s
+        let mut grandchild = process(12, 11, "salmon");
+        grandchild.started_at = at(4);
+        state.insert_process(12, grandchild);
+        assert!(state.take_due_completions(&[], &clock(), at(6)).is_empty());
+
+        state.remove_process(&12);
+        assert!(state
+            .take_due_completions(&[exit(12, at(8))], &clock(), at(8))
+            .is_empty());
+        assert!(state.take_due_completions(&[], &clock(), at(12)).is_empty());
+
+        state.remove_process(&11);
+        let due = state.take_due_completions(&[exit(11, at(12))], &clock(), at(12));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].finish_trigger.finished_at, at(12));
+    }
+
+    #[test]
+    fn test_idle_child_is_given_up_on_after_grace_period() {
+        let exited_at = Utc::now();
+        let mut state = fork_and_exit(exited_at);
+        let mut grandchild = process(12, 11, "salmon");
+        grandchild.started_at = exited_at + chrono::Duration::seconds(1);
+        state.insert_process(12, grandchild);
+
+        // nothing started or exited in the lineage since the grandchild
+        let due =
+            state.take_due_completions(&[], &clock(), exited_at + chrono::Duration::seconds(6));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].finish_trigger.finished_at, exited_at);
+    }
+
+    #[test]
+    fn test_duration_extends_to_the_last_descendant_exit() {
+        let exited_at = Utc::now();
+        let mut state = ProcessState::default();
+        let tool = process(10, 1, "salmon");
+        state.insert_process(11, process(11, 10, "salmon"));
+        // the tool started 10s before its exit, which is handled 100ms late
+        let handled = Instant::now();
+        let started = handled - Duration::from_millis(10_100);
+        let kernel_now = exited_at + chrono::Duration::milliseconds(100);
+        state.defer_completion(DeferredCompletion::new(
+            "salmon".to_string(),
+            tool,
+            exit(10, exited_at),
+            ToolConcurrency::default(),
+            Some(started),
+            &TriggerClock::at(Some(kernel_now), handled),
+            GRACE_PERIOD,
+        ));
+        assert!(state
+            .take_due_completions(&[], &clock(), exited_at)
+            .is_empty());
+
+        // the child exits 2s later, handled 1s late
+        let child_exited_at = exited_at + chrono::Duration::seconds(2);
+        state.remove_process(&11);
+        let clock = TriggerClock::at(
+            Some(child_exited_at + chrono::Duration::seconds(1)),
+            handled + Duration::from_millis(2900),
+        );
+        let due = state.take_due_completions(&[exit(11, child_exited_at)], &clock, child_exited_at);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].elapsed, Some(Duration::from_secs(12)));
+    }
+}
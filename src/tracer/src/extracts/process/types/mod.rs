@@ -1,2 +1,5 @@
+pub mod deferred_completion;
 pub mod process_result;
 pub mod process_state;
+pub mod tool_clock;
+pub mod tool_concurrency;
@@ -1,26 +1,48 @@
 use crate::error_message;
+use crate::extracts::process::types::deferred_completion::DeferredCompletion;
+use crate::extracts::process::types::tool_clock::{ToolClocks, TriggerClock};
+use crate::extracts::process::types::tool_concurrency::{ToolConcurrency, ToolConcurrencyTracker};
 use crate::process_identification::target_pipeline::pipeline_manager::TargetPipelineManager;
 use crate::process_identification::target_process::target_manager::TargetManager;
+use crate::process_identification::types::event::attributes::process::ProcessTreeNode;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 use tokio::task::JoinHandle;
-use tracer_ebpf::ebpf_trigger::{OutOfMemoryTrigger, ProcessStartTrigger};
+use tracer_ebpf::ebpf_trigger::{
+    ExecFailedTrigger, OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger,
+};
 
 /// Internal state of the process manager
 #[derive(Default)]
 pub struct ProcessState {
     processes: HashMap<usize, ProcessStartTrigger>,
+    /// Number of `processes` naming each pid as their parent, kept up to date as processes are
+    /// added and removed so `child_count` doesn't scan every process on each exec
+    child_counts: HashMap<usize, usize>,
     monitoring: HashMap<String, HashSet<ProcessStartTrigger>>,
     target_manager: TargetManager,
     pipeline_manager: TargetPipelineManager,
     ebpf_task: Option<JoinHandle<()>>,
     out_of_memory_victims: HashMap<usize, OutOfMemoryTrigger>,
+    /// Latest failed exec of a matched tool by each process, with the matched target
+    exec_failures: HashMap<usize, (String, ExecFailedTrigger)>,
+    tool_concurrency: ToolConcurrencyTracker,
+    tool_clocks: ToolClocks,
+    /// Tools that exited while their descendants were still running
+    deferred_completions: Vec<DeferredCompletion>,
 }
 
 impl ProcessState {
     /// Removes a process trigger and returns it if it existed
     pub fn remove_process(&mut self, pid: &usize) -> Option<ProcessStartTrigger> {
-        self.processes.remove(pid)
+        let removed = self.processes.remove(pid);
+        if let Some(process) = &removed {
+            self.forget_child(process);
+        }
+        removed
     }
 
     /// Returns a reference to all processes
@@ -30,6 +52,10 @@ impl ProcessState {
 
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn set_processes(&mut self, processes: HashMap<usize, ProcessStartTrigger>) {
+        self.child_counts.clear();
+        for process in processes.values() {
+            self.count_child(process);
+        }
         self.processes = processes;
     }
 
@@ -57,7 +83,58 @@ impl ProcessState {
     }
 
     pub fn insert_process(&mut self, pid: usize, process_start_trigger: ProcessStartTrigger) {
-        self.processes.insert(pid, process_start_trigger);
+        let ppid = process_start_trigger.ppid;
+        self.count_child(&process_start_trigger);
+        // the pid was reused by a process we missed the exit of
+        if let Some(replaced) = self.processes.insert(pid, process_start_trigger) {
+            self.forget_child(&replaced);
+        }
+        // a monitored tool forked a helper
+        self.observe_tool_concurrency(ppid, None);
+    }
+
+    /// Returns the number of known, still running, direct children of `pid`
+    pub fn child_count(&self, pid: usize) -> usize {
+        self.child_counts.get(&pid).copied().unwrap_or_default()
+    }
+
+    fn count_child(&mut self, process: &ProcessStartTrigger) {
+        if process.ppid != process.pid {
+            *self.child_counts.entry(process.ppid).or_default() += 1;
+        }
+    }
+
+    fn forget_child(&mut self, process: &ProcessStartTrigger) {
+        if process.ppid == process.pid {
+            return;
+        }
+        if let Entry::Occupied(mut count) = self.child_counts.entry(process.ppid) {
+            *count.get_mut() -= 1;
+            if *count.get() == 0 {
+                count.remove();
+            }
+        }
+    }
+
+    /// Updates the peak thread and child counts of the monitored tools running as `pid`
+    pub fn observe_tool_concurrency(&mut self, pid: usize, thread_count: Option<usize>) {
+        let child_count = self.child_count(pid);
+        for processes in self.monitoring.values() {
+            for process in processes.iter().filter(|process| process.pid == pid) {
+                self.tool_concurrency
+                    .observe(process, thread_count, child_count);
+            }
+        }
+    }
+
+    /// Stops tracking the concurrency of a tool that exited and returns its peaks
+    pub fn finish_tool_concurrency(&mut self, process: &ProcessStartTrigger) -> ToolConcurrency {
+        self.tool_concurrency.finish(process)
+    }
+
+    /// Stops the clock of a tool that exited and returns its monotonic start, see `ToolClocks`
+    pub fn finish_tool_clock(&mut self, process: &ProcessStartTrigger) -> Option<Instant> {
+        self.tool_clocks.finish(process)
     }
 
     pub fn insert_out_of_memory_victim(
@@ -69,10 +146,60 @@ impl ProcessState {
             .insert(pid, out_of_memory_trigger);
     }
 
+    // Deferred completions related methods
+
+    pub fn defer_completion(&mut self, completion: DeferredCompletion) {
+        self.deferred_completions.push(completion);
+    }
+
+    /// Removes and returns the deferred completions that can be finalized, after taking `exits`
+    /// into account
+    pub fn take_due_completions(
+        &mut self,
+        exits: &[ProcessEndTrigger],
+        clock: &TriggerClock,
+        now: DateTime<Utc>,
+    ) -> Vec<DeferredCompletion> {
+        let mut due = Vec::new();
+        for mut completion in std::mem::take(&mut self.deferred_completions) {
+            if completion.update(&self.processes, exits, clock, now) {
+                due.push(completion);
+            } else {
+                self.deferred_completions.push(completion);
+            }
+        }
+        due
+    }
+
+    /// Removes and returns all deferred completions, e.g. when monitoring stops
+    pub fn take_deferred_completions(&mut self) -> Vec<DeferredCompletion> {
+        std::mem::take(&mut self.deferred_completions)
+    }
+
+    // Exec failures related methods
+
+    pub fn insert_exec_failure(&mut self, target: String, exec_failure: ExecFailedTrigger) {
+        self.exec_failures
+            .insert(exec_failure.pid, (target, exec_failure));
+    }
+
+    /// Removes the pending exec failure of `pid`, if any
+    pub fn remove_exec_failure(&mut self, pid: &usize) -> Option<(String, ExecFailedTrigger)> {
+        self.exec_failures.remove(pid)
+    }
+
+    pub fn get_exec_failures(&self) -> &HashMap<usize, (String, ExecFailedTrigger)> {
+        &self.exec_failures
+    }
+
     pub fn get_target_manager(&self) -> &TargetManager {
         &self.target_manager
     }
 
+    pub fn set_target_manager(&mut self, target_manager: TargetManager) {
+        self.target_manager = target_manager;
+    }
+
     pub fn get_pipeline_manager(&self) -> &TargetPipelineManager {
         &self.pipeline_manager
     }
@@ -85,9 +212,20 @@ impl ProcessState {
         &mut self,
         interested_in: HashMap<String, HashSet<ProcessStartTrigger>>,
     ) {
+        let mut new_pids = HashSet::new();
+        let clock = TriggerClock::now();
         for (target, processes) in interested_in.into_iter() {
+            for process in &processes {
+                self.tool_concurrency.track(process);
+                self.tool_clocks.start(process, &clock);
+                new_pids.insert(process.pid);
+            }
             self.monitoring.entry(target).or_default().extend(processes);
         }
+        // children may have been seen before their parent was matched
+        for pid in new_pids {
+            self.observe_tool_concurrency(pid, None);
+        }
     }
 
     pub fn get_monitored_processes_pids(&self) -> HashSet<usize> {
@@ -97,6 +235,43 @@ impl ProcessState {
             .collect()
     }
 
+    /// Returns the known ancestors of the given process, parent first, up to `max_depth` of them
+    pub fn get_process_ancestors(
+        &self,
+        process: &ProcessStartTrigger,
+        max_depth: usize,
+    ) -> Vec<ProcessTreeNode> {
+        self.get_ancestor_triggers(process, max_depth)
+            .into_iter()
+            .map(|ancestor| ProcessTreeNode {
+                pid: ancestor.pid,
+                comm: ancestor.comm.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the start triggers of the known ancestors of the given process, parent first, up
+    /// to `max_depth` of them
+    pub fn get_ancestor_triggers(
+        &self,
+        process: &ProcessStartTrigger,
+        max_depth: usize,
+    ) -> Vec<&ProcessStartTrigger> {
+        let mut ancestors = Vec::new();
+        let mut seen = HashSet::from([process.pid]);
+        let mut parent_pid = process.ppid;
+
+        while ancestors.len() < max_depth && seen.insert(parent_pid) {
+            let Some(parent) = self.processes.get(&parent_pid) else {
+                break;
+            };
+            ancestors.push(parent);
+            parent_pid = parent.ppid;
+        }
+
+        ancestors
+    }
+
     /// Returns the PID of the task that contains the given process.
     ///
     /// Panics if a cycle is detected in the process lineage.
@@ -129,3 +304,69 @@ impl ProcessState {
         parent_pid
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: usize, ppid: usize, comm: &str) -> ProcessStartTrigger {
+        ProcessStartTrigger::from_name_and_args(pid, ppid, comm, &[comm])
+    }
+
+    #[test]
+    fn test_child_count_follows_inserts_and_removals() {
+        let mut state = ProcessState::default();
+        state.insert_process(10, process(10, 1, "nextflow"));
+        state.insert_process(20, process(20, 10, "bash"));
+        state.insert_process(21, process(21, 10, "bash"));
+        assert_eq!(state.child_count(10), 2);
+        assert_eq!(state.child_count(1), 1);
+
+        state.remove_process(&20);
+        assert_eq!(state.child_count(10), 1);
+        // the pid is reused by a process of another parent, the exit of 21 was missed
+        state.insert_process(21, process(21, 1, "bash"));
+        assert_eq!(state.child_count(10), 0);
+        assert_eq!(state.child_count(1), 2);
+        assert!(state.remove_process(&99).is_none());
+
+        state.set_processes(HashMap::from([(30, process(30, 10, "bash"))]));
+        assert_eq!(state.child_count(10), 1);
+        assert_eq!(state.child_count(1), 0);
+    }
+
+    #[test]
+    fn test_get_process_ancestors() {
+        let mut state = ProcessState::default();
+        for trigger in [
+            process(10, 1, "nextflow"),
+            process(20, 10, "bash"),
+            process(30, 20, "python"),
+        ] {
+            state.insert_process(trigger.pid, trigger);
+        }
+        let tool = process(40, 30, "STAR");
+
+        let comms = |ancestors: Vec<ProcessTreeNode>| -> Vec<String> {
+            ancestors.into_iter().map(|node| node.comm).collect()
+        };
+        assert_eq!(
+            comms(state.get_process_ancestors(&tool, 10)),
+            ["python", "bash", "nextflow"]
+        );
+        assert_eq!(
+            comms(state.get_process_ancestors(&tool, 2)),
+            ["python", "bash"]
+        );
+    }
+
+    #[test]
+    fn test_get_process_ancestors_stops_on_cycle() {
+        let mut state = ProcessState::default();
+        state.insert_process(10, process(10, 20, "a"));
+        state.insert_process(20, process(20, 10, "b"));
+
+        let ancestors = state.get_process_ancestors(&process(30, 10, "tool"), 10);
+        assert_eq!(ancestors.len(), 2);
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracer_ebpf::binding::kernel_clock_now;
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+/// Monotonic start of every monitored tool, so its duration is measured with a clock the system
+/// clock being stepped (e.g. by NTP) while it runs doesn't affect. The wall-clock `started_at`
+/// and `ended_at` of the tool are still reported, for display and correlation.
+///
+/// Tools are keyed by pid and start time, like in `ToolConcurrencyTracker`.
+#[derive(Debug, Default)]
+pub struct ToolClocks {
+    started: HashMap<(usize, DateTime<Utc>), Instant>,
+}
+
+impl ToolClocks {
+    /// Starts the clock of a matched tool at the instant it started, see `TriggerClock`
+    pub fn start(&mut self, process: &ProcessStartTrigger, clock: &TriggerClock) {
+        self.started
+            .entry((process.pid, process.started_at))
+            .or_insert_with(|| clock.instant_of(process.started_at));
+    }
+
+    /// Stops tracking a tool that exited, returning its monotonic start if it was tracked
+    pub fn finish(&mut self, process: &ProcessStartTrigger) -> Option<Instant> {
+        self.started.remove(&(process.pid, process.started_at))
+    }
+}
+
+/// A reading of the clock the triggers are timestamped with, to place them on the monotonic
+/// clock by how long ago they happened rather than when they are handled.
+///
+/// eBPF triggers are timestamped by the kernel with the monotonic clock shifted by a constant, so
+/// their age is exact. Other triggers are timestamped with the system clock when they are
+/// observed, so they are taken to have just happened.
+#[derive(Debug, Clone, Copy)]
+pub struct TriggerClock {
+    /// Current time on the eBPF clock, `None` without eBPF
+    kernel_now: Option<DateTime<Utc>>,
+    instant: Instant,
+}
+
+impl TriggerClock {
+    pub fn now() -> Self {
+        Self::at(kernel_clock_now(), Instant::now())
+    }
+
+    pub fn at(kernel_now: Option<DateTime<Utc>>, instant: Instant) -> Self {
+        Self {
+            kernel_now,
+            instant,
+        }
+    }
+
+    /// The monotonic instant of a trigger timestamp
+    pub fn instant_of(&self, timestamp: DateTime<Utc>) -> Instant {
+        let age = self
+            .kernel_now
+            .and_then(|now| (now - timestamp).to_std().ok())
+            .unwrap_or_default();
+        self.instant.checked_sub(age).unwrap_or(self.instant)
+    }
+
+    /// Monotonic time from a tool's start to its exit at `finished_at`
+    pub fn elapsed(&self, started: Instant, finished_at: DateTime<Utc>) -> Duration {
+        self.instant_of(finished_at)
+            .saturating_duration_since(started)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_clock_is_started_once() {
+        let process = ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"]);
+        let mut clocks = ToolClocks::default();
+        let now = Instant::now();
+        clocks.start(&process, &TriggerClock::at(None, now));
+        // matched again, e.g. by another rule
+        clocks.start(
+            &process,
+            &TriggerClock::at(None, now + Duration::from_secs(5)),
+        );
+
+        assert_eq!(clocks.finish(&process), Some(now));
+        assert_eq!(clocks.finish(&process), None);
+    }
+
+    #[test]
+    fn test_duration_spans_the_kernel_timestamps() {
+        // far from the system clock, which plays no part
+        let started_at = DateTime::from_timestamp(946_684_800, 0).unwrap();
+        let mut process = ProcessStartTrigger::from_name_and_args(42, 1, "STAR", &["STAR"]);
+        process.started_at = started_at;
+        let matched = Instant::now();
+
+        // matched 1s after it started, and its exit 5s after the start handled 300ms late
+        let mut clocks = ToolClocks::default();
+        clocks.start(
+            &process,
+            &TriggerClock::at(Some(started_at + chrono::Duration::seconds(1)), matched),
+        );
+        let started = clocks.finish(&process).unwrap();
+        let handled = TriggerClock::at(
+            Some(started_at + chrono::Duration::milliseconds(5300)),
+            matched + Duration::from_millis(4300),
+        );
+        let finished_at = started_at + chrono::Duration::seconds(5);
+        assert_eq!(
+            handled.elapsed(started, finished_at),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_observed_triggers_happen_when_handled() {
+        let now = Instant::now();
+        let clock = TriggerClock::at(None, now);
+        let long_ago = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(clock.instant_of(long_ago), now);
+        assert_eq!(
+            clock.elapsed(now - Duration::from_secs(3), long_ago),
+            Duration::from_secs(3)
+        );
+    }
+}
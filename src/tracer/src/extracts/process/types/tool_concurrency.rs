@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+/// Peak number of threads and child processes observed for a tool while it was running
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ToolConcurrency {
+    pub peak_thread_count: usize,
+    pub peak_child_count: usize,
+}
+
+impl ToolConcurrency {
+    fn observe(&mut self, thread_count: Option<usize>, child_count: usize) {
+        if let Some(thread_count) = thread_count {
+            self.peak_thread_count = self.peak_thread_count.max(thread_count);
+        }
+        self.peak_child_count = self.peak_child_count.max(child_count);
+    }
+}
+
+/// Concurrency of every monitored tool.
+///
+/// A process that re-execs keeps its pid but is reported as a new tool, so tools are keyed by pid
+/// *and* start time, and only the latest exec of a pid is updated: the threads and children seen
+/// after the re-exec belong to the new program, not the one it replaced.
+#[derive(Debug, Default)]
+pub struct ToolConcurrencyTracker {
+    tools: HashMap<(usize, DateTime<Utc>), ToolConcurrency>,
+    latest_exec: HashMap<usize, DateTime<Utc>>,
+}
+
+impl ToolConcurrencyTracker {
+    /// Starts tracking a tool
+    pub fn track(&mut self, process: &ProcessStartTrigger) {
+        self.tools
+            .entry((process.pid, process.started_at))
+            .or_default();
+        let latest = self
+            .latest_exec
+            .entry(process.pid)
+            .or_insert(process.started_at);
+        *latest = (*latest).max(process.started_at);
+    }
+
+    /// Records the current thread and child counts of a tool, keeping the peaks
+    pub fn observe(
+        &mut self,
+        process: &ProcessStartTrigger,
+        thread_count: Option<usize>,
+        child_count: usize,
+    ) {
+        if self.latest_exec.get(&process.pid) != Some(&process.started_at) {
+            return;
+        }
+        if let Some(concurrency) = self.tools.get_mut(&(process.pid, process.started_at)) {
+            concurrency.observe(thread_count, child_count);
+        }
+    }
+
+    /// Stops tracking a tool that exited, returning its peaks
+    pub fn finish(&mut self, process: &ProcessStartTrigger) -> ToolConcurrency {
+        if self.latest_exec.get(&process.pid) == Some(&process.started_at) {
+            self.latest_exec.remove(&process.pid);
+        }
+        self.tools
+            .remove(&(process.pid, process.started_at))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn process(pid: usize, started_at: DateTime<Utc>) -> ProcessStartTrigger {
+        let mut process = ProcessStartTrigger::from_name_and_args(pid, 1, "STAR", &["STAR"]);
+        process.started_at = started_at;
+        process
+    }
+
+    #[test]
+    fn test_tracks_peaks_and_resets_on_exit() {
+        let mut tracker = ToolConcurrencyTracker::default();
+        let star = process(10, Utc::now());
+        tracker.track(&star);
+
+        tracker.observe(&star, Some(8), 1);
+        tracker.observe(&star, None, 3);
+        tracker.observe(&star, Some(4), 0);
+
+        assert_eq!(
+            tracker.finish(&star),
+            ToolConcurrency {
+                peak_thread_count: 8,
+                peak_child_count: 3,
+            }
+        );
+        assert_eq!(tracker.finish(&star), ToolConcurrency::default());
+    }
+
+    #[test]
+    fn test_reexec_only_updates_latest_program() {
+        let mut tracker = ToolConcurrencyTracker::default();
+        let started_at = Utc::now();
+        let wrapper = process(10, started_at);
+        let tool = process(10, started_at + TimeDelta::seconds(1));
+
+        tracker.track(&wrapper);
+        tracker.observe(&wrapper, Some(1), 0);
+        tracker.track(&tool);
+        tracker.observe(&wrapper, Some(16), 2);
+        tracker.observe(&tool, Some(16), 2);
+
+        assert_eq!(tracker.finish(&wrapper).peak_thread_count, 1);
+        assert_eq!(tracker.finish(&tool).peak_thread_count, 16);
+    }
+}
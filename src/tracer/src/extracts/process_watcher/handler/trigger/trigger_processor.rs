@@ -4,7 +4,7 @@ use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracer_ebpf::ebpf_trigger::{
-    FileOpenTrigger, OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger,
+    ExecFailedTrigger, FileOpenTrigger, OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger,
 };
 use tracing::{debug, info};
 
@@ -44,6 +44,24 @@ impl TriggerProcessor {
         }
     }
 
+    pub async fn process_exec_failure_triggers(
+        &self,
+        exec_failure_triggers: Vec<ExecFailedTrigger>,
+    ) {
+        if !exec_failure_triggers.is_empty() {
+            for trigger in &exec_failure_triggers {
+                debug!(
+                    "Processing failed exec of {} for PID: {}",
+                    trigger.file_name, trigger.pid
+                );
+            }
+            let process_manager = self.process_manager.write().await;
+            process_manager
+                .handle_exec_failures(exec_failure_triggers)
+                .await;
+        }
+    }
+
     pub async fn process_process_end_triggers(
         &self,
         mut process_end_triggers: Vec<ProcessEndTrigger>,
@@ -62,6 +80,10 @@ impl TriggerProcessor {
                 .handle_out_of_memory_terminations(&mut process_end_triggers)
                 .await;
 
+            process_manager
+                .handle_exec_failure_terminations(&process_end_triggers)
+                .await?;
+
             process_manager
                 .handle_process_terminations(process_end_triggers)
                 .await?;
@@ -1,20 +1,28 @@
+use crate::extracts::containers::display_name::ContainerDisplayName;
 use crate::extracts::containers::DockerWatcher;
 use crate::extracts::files::file_manager::manager::FileManager;
+use crate::extracts::metrics::snapshot::MetricsSnapshots;
 use crate::extracts::process::extract_process_data::get_process_argv;
 use crate::extracts::process::process_manager::recorder::EventRecorder;
 use crate::extracts::process::process_manager::ProcessManager;
 use crate::extracts::process_watcher::handler::trigger::trigger_processor::TriggerProcessor;
+use crate::extracts::syslog::redirects::OutputRedirect;
 use crate::process_identification::recorder::EventDispatcher;
+use crate::process_identification::target_process::target_manager::TargetManager;
+use crate::process_identification::trigger_stream::TriggerStream;
+use crate::process_identification::types::lockfiles::PackageVersions;
 use crate::utils::workdir::TRACER_WORK_DIR;
 use anyhow::{Error, Result};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self};
 use std::sync::Arc;
+use std::time::Duration;
 use sysinfo::ProcessesToUpdate;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tracer_ebpf::binding::start_processing_events;
 use tracer_ebpf::ebpf_trigger::{
-    FileOpenTrigger, OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger, Trigger,
+    ExecFailedTrigger, FileOpenTrigger, OutOfMemoryTrigger, ProcessEndTrigger, ProcessStartTrigger,
+    Trigger,
 };
 use tracing::{debug, error, info};
 
@@ -23,6 +31,7 @@ pub struct ProcessWatcher {
     ebpf_initialized: Arc<Mutex<bool>>,
     process_manager: Arc<RwLock<ProcessManager>>,
     trigger_processor: TriggerProcessor,
+    trigger_stream: Option<TriggerStream>,
 }
 
 impl ProcessWatcher {
@@ -30,18 +39,33 @@ impl ProcessWatcher {
         event_dispatcher: EventDispatcher,
         docker_watcher: Arc<DockerWatcher>,
         file_manager: Arc<RwLock<FileManager>>,
+        container_display_name: ContainerDisplayName,
+        include_process_tree: bool,
+        exit_grace_period: Duration,
     ) -> Self {
         // instantiate the process manager
         let event_recorder = EventRecorder::new(event_dispatcher.clone(), docker_watcher.clone());
-        let process_manager = Arc::new(RwLock::new(ProcessManager::new(event_recorder.clone())));
+        let process_manager = Arc::new(RwLock::new(ProcessManager::new(
+            event_recorder.clone(),
+            container_display_name,
+            include_process_tree,
+            exit_grace_period,
+        )));
 
         ProcessWatcher {
             ebpf_initialized: Arc::new(Mutex::new(false)),
             trigger_processor: TriggerProcessor::new(Arc::clone(&process_manager), file_manager),
             process_manager,
+            trigger_stream: None,
         }
     }
 
+    /// Also publishes every raw trigger, before target matching, to live subscribers of `stream`
+    pub fn with_trigger_stream(mut self, stream: TriggerStream) -> Self {
+        self.trigger_stream = Some(stream);
+        self
+    }
+
     pub async fn start_ebpf(self: &Arc<Self>) -> Result<()> {
         let mut initialized = self.ebpf_initialized.lock().await;
         if !*initialized {
@@ -230,9 +254,14 @@ impl ProcessWatcher {
         let mut process_end_triggers: Vec<ProcessEndTrigger> = vec![];
         let mut out_of_memory_triggers: Vec<OutOfMemoryTrigger> = vec![];
         let mut file_opening_triggers: Vec<FileOpenTrigger> = vec![];
+        let mut exec_failure_triggers: Vec<ExecFailedTrigger> = vec![];
 
         debug!("ProcessWatcher: processing {} triggers", triggers.len());
 
+        if let Some(stream) = &self.trigger_stream {
+            stream.publish(&triggers);
+        }
+
         // Create the directory if it doesn't exist
         let log_dir = &TRACER_WORK_DIR.path;
         if !log_dir.exists() {
@@ -269,9 +298,21 @@ impl ProcessWatcher {
                     );
                     file_opening_triggers.push(file_opened);
                 }
+                Trigger::ExecFailed(exec_failed) => {
+                    debug!(
+                        "Exec failure trigger pid={}, file={}, errno={}",
+                        exec_failed.pid, exec_failed.file_name, exec_failed.errno
+                    );
+                    exec_failure_triggers.push(exec_failed);
+                }
             }
         }
 
+        // before the starts, so a failure followed by a successful exec is discarded
+        self.trigger_processor
+            .process_exec_failure_triggers(exec_failure_triggers)
+            .await;
+
         self.trigger_processor
             .process_process_start_triggers(process_start_triggers)
             .await?;
@@ -291,6 +332,78 @@ impl ProcessWatcher {
         Ok(())
     }
 
+    /// Replaces the target manager, e.g. to add user-provided rules
+    pub async fn set_target_manager(&self, target_manager: TargetManager) {
+        self.process_manager
+            .read()
+            .await
+            .set_target_manager(target_manager)
+            .await
+    }
+
+    /// Replaces the window tool metrics are aggregated over, see `Config::tool_metrics_window_ms`
+    pub async fn set_tool_metrics_window(&self, window: Duration) {
+        self.process_manager
+            .read()
+            .await
+            .set_tool_metrics_window(window)
+            .await
+    }
+
+    /// Replaces the swap alert threshold, see `Config::swap_alert_threshold_bytes`
+    pub async fn set_swap_alert_threshold(&self, threshold_bytes: u64) {
+        self.process_manager
+            .read()
+            .await
+            .set_swap_alert_threshold(threshold_bytes)
+            .await
+    }
+
+    /// Replaces the polls a process must be missing before its exit is recorded, see
+    /// `Config::missed_exit_confirmation_polls`
+    pub async fn set_missed_exit_confirmation_polls(&self, confirmation_polls: u32) {
+        self.process_manager
+            .read()
+            .await
+            .set_missed_exit_confirmation_polls(confirmation_polls)
+            .await
+    }
+
+    /// Replaces the storage stall threshold, see `Config::storage_stall_threshold_ms`
+    pub async fn set_storage_stall_threshold(&self, threshold: Duration) {
+        self.process_manager
+            .read()
+            .await
+            .set_storage_stall_threshold(threshold)
+            .await
+    }
+
+    /// Keeps the latest sample of each process in `snapshots`
+    pub async fn set_metrics_snapshots(&self, snapshots: MetricsSnapshots) {
+        self.process_manager
+            .read()
+            .await
+            .set_metrics_snapshots(snapshots)
+            .await
+    }
+
+    /// Looks up the versions of tools in the packages of the run's environment
+    pub async fn set_package_versions(&self, package_versions: PackageVersions) {
+        self.process_manager
+            .read()
+            .await
+            .set_package_versions(package_versions)
+    }
+
+    /// Records the processes still running when monitoring stops as incomplete
+    pub async fn handle_monitoring_stopped(&self) -> Result<()> {
+        self.process_manager
+            .read()
+            .await
+            .handle_monitoring_stopped()
+            .await
+    }
+
     pub async fn poll_process_metrics(&self) -> Result<()> {
         self.process_manager
             .write()
@@ -310,4 +423,13 @@ impl ProcessWatcher {
     pub async fn get_matched_tasks(&self) -> HashMap<String, usize> {
         self.process_manager.read().await.get_matched_tasks().await
     }
+
+    /// Returns the files the monitored tools' output is redirected to, by tool
+    pub async fn get_output_redirects(&self) -> Vec<(String, OutputRedirect)> {
+        self.process_manager
+            .read()
+            .await
+            .get_output_redirects()
+            .await
+    }
 }
@@ -0,0 +1,7 @@
+pub mod output_buffer;
+pub mod output_scanner;
+pub mod patterns;
+pub mod redirect_tailer;
+pub mod redirects;
+
+pub use patterns::{SyslogPattern, PIPELINE_OUTPUT_PATTERNS, SYSLOG_PATTERNS};
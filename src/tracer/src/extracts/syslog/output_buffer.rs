@@ -0,0 +1,101 @@
+use crate::extracts::syslog::output_scanner::OutputMatch;
+use crate::process_identification::types::event::attributes::syslog::SyslogSeverity;
+use std::collections::VecDeque;
+
+/// Id of the marker reporting the lines an `OutputBuffer` dropped
+pub const OUTPUT_TRUNCATED_ID: &str = "output_truncated";
+
+/// Error lines of an output stream waiting to be recorded, bounded so a tool stuck in a loop
+/// printing errors can't grow it without limit. Once full, the oldest lines are dropped and
+/// counted, and draining reports them with a marker line.
+pub struct OutputBuffer {
+    matches: VecDeque<OutputMatch>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl OutputBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            matches: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, found: OutputMatch) {
+        if self.matches.len() == self.capacity {
+            self.matches.pop_front();
+            self.dropped += 1;
+        }
+        self.matches.push_back(found);
+    }
+
+    /// Takes the buffered lines, preceded by a marker if lines were dropped since the last
+    /// drain
+    pub fn drain(&mut self) -> Vec<OutputMatch> {
+        let mut drained = Vec::with_capacity(self.matches.len() + 1);
+        if self.dropped > 0 {
+            if let Some(first) = self.matches.front() {
+                drained.push(truncation_marker(first, self.dropped));
+            }
+            self.dropped = 0;
+        }
+        drained.extend(self.matches.drain(..));
+        drained
+    }
+}
+
+/// Marker taking the place of the `dropped` lines before `next`
+fn truncation_marker(next: &OutputMatch, dropped: u64) -> OutputMatch {
+    OutputMatch {
+        stream: next.stream,
+        pattern_id: OUTPUT_TRUNCATED_ID.to_string(),
+        display_name: "Output truncated".to_string(),
+        severity: SyslogSeverity::Warn,
+        line: format!("output truncated, {} lines dropped", dropped),
+        line_number: next.line_number.saturating_sub(1),
+        previous_lines: Vec::new(),
+        file: next.file.clone(),
+        tool: next.tool.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::syslog::output_scanner::{OutputScanner, OutputStream};
+    use crate::extracts::syslog::PIPELINE_OUTPUT_PATTERNS;
+
+    #[test]
+    fn test_flooded_stream_keeps_the_newest_lines() {
+        let mut scanner = OutputScanner::new(
+            OutputStream::Stderr,
+            PIPELINE_OUTPUT_PATTERNS.as_slice().into(),
+        );
+        let mut buffer = OutputBuffer::new(100);
+        for i in 0..100_000 {
+            let found = scanner
+                .scan(&format!(
+                    "[E::bgzf_read] Read block operation failed ({})",
+                    i
+                ))
+                .unwrap();
+            buffer.push(found);
+        }
+
+        let drained = buffer.drain();
+        assert_eq!(drained.len(), 101);
+        assert_eq!(drained[0].pattern_id, OUTPUT_TRUNCATED_ID);
+        assert_eq!(drained[0].line, "output truncated, 99900 lines dropped");
+        assert_eq!(drained[0].stream, OutputStream::Stderr);
+        assert_eq!(drained[0].line_number, 99_900);
+        assert_eq!(drained[1].line_number, 99_901);
+        assert_eq!(drained[100].line_number, 100_000);
+
+        // the counter is reset once reported
+        assert!(buffer.drain().is_empty());
+        buffer.push(scanner.scan("[E::hts_open] failed").unwrap());
+        assert_eq!(buffer.drain().len(), 1);
+    }
+}
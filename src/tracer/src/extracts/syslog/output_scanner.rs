@@ -0,0 +1,136 @@
+use crate::extracts::syslog::patterns::find_match;
+use crate::extracts::syslog::SyslogPattern;
+use crate::process_identification::types::event::attributes::syslog::SyslogSeverity;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Number of lines before a match kept as its context
+const PREVIOUS_LINES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl fmt::Display for OutputStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputStream::Stdout => write!(f, "stdout"),
+            OutputStream::Stderr => write!(f, "stderr"),
+        }
+    }
+}
+
+/// A line of a pipeline's output matching an error pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputMatch {
+    pub stream: OutputStream,
+    pub pattern_id: String,
+    pub display_name: String,
+    pub severity: SyslogSeverity,
+    pub line: String,
+    /// 1-based number of the line in its stream
+    pub line_number: u64,
+    /// Lines printed just before, oldest first
+    pub previous_lines: Vec<String>,
+    /// File the stream was redirected to, if the line was read from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// Tool that printed the line, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+/// Matches the lines of one output stream of a pipeline against error patterns
+pub struct OutputScanner {
+    stream: OutputStream,
+    patterns: Arc<[SyslogPattern]>,
+    line_number: u64,
+    previous_lines: VecDeque<String>,
+}
+
+impl OutputScanner {
+    pub fn new(stream: OutputStream, patterns: Arc<[SyslogPattern]>) -> Self {
+        Self {
+            stream,
+            patterns,
+            line_number: 0,
+            previous_lines: VecDeque::with_capacity(PREVIOUS_LINES),
+        }
+    }
+
+    /// Scans the next line of the stream, without its line ending
+    pub fn scan(&mut self, line: &str) -> Option<OutputMatch> {
+        self.line_number += 1;
+        let found = find_match(&self.patterns, line).map(|pattern| OutputMatch {
+            stream: self.stream,
+            pattern_id: pattern.id.clone(),
+            display_name: pattern.display_name.clone(),
+            severity: pattern.severity,
+            line: line.to_string(),
+            line_number: self.line_number,
+            previous_lines: self.previous_lines.iter().cloned().collect(),
+            file: None,
+            tool: None,
+        });
+        if self.previous_lines.len() == PREVIOUS_LINES {
+            self.previous_lines.pop_front();
+        }
+        self.previous_lines.push_back(line.to_string());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::syslog::PIPELINE_OUTPUT_PATTERNS;
+
+    #[test]
+    fn test_error_line_is_matched_with_its_context() {
+        let mut scanner = OutputScanner::new(
+            OutputStream::Stderr,
+            PIPELINE_OUTPUT_PATTERNS.as_slice().into(),
+        );
+        assert_eq!(scanner.scan("executor >  local (3)"), None);
+        assert_eq!(
+            scanner.scan("[4f/1a2b3c] process > FASTQC (1) [100%]"),
+            None
+        );
+
+        let found = scanner
+            .scan("ERROR ~ Error executing process > 'FASTQC (1)'")
+            .unwrap();
+        assert_eq!(found.stream, OutputStream::Stderr);
+        assert_eq!(found.pattern_id, "nextflow_process_failed");
+        assert_eq!(found.severity, SyslogSeverity::Error);
+        assert_eq!(found.line_number, 3);
+        assert_eq!(
+            found.previous_lines,
+            vec![
+                "executor >  local (3)",
+                "[4f/1a2b3c] process > FASTQC (1) [100%]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_context_is_bounded() {
+        let mut scanner = OutputScanner::new(
+            OutputStream::Stdout,
+            PIPELINE_OUTPUT_PATTERNS.as_slice().into(),
+        );
+        for i in 0..100 {
+            scanner.scan(&format!("line {}", i));
+        }
+        let found = scanner.scan("bash: STAR: command not found").unwrap();
+        assert_eq!(found.line_number, 101);
+        assert_eq!(found.previous_lines.len(), PREVIOUS_LINES);
+        assert_eq!(found.previous_lines.last().unwrap(), "line 99");
+    }
+}
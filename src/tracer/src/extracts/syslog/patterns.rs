@@ -0,0 +1,383 @@
+use crate::process_identification::types::event::attributes::syslog::SyslogSeverity;
+use crate::utils::yaml::{load_from_yaml_array_str, Yaml, YamlExt};
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// A syslog line pattern worth reporting, with the severity attached to matching events
+#[derive(Debug, Clone)]
+pub struct SyslogPattern {
+    pub id: String,
+    pub display_name: String,
+    pub severity: SyslogSeverity,
+    regex: Regex,
+}
+
+/// User-defined syslog pattern, as deserialized from configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyslogPatternDefinition {
+    pub id: String,
+    pub display_name: String,
+    pub regex: String,
+    #[serde(default)]
+    pub severity: SyslogSeverity,
+}
+
+impl SyslogPattern {
+    pub fn new(
+        id: impl Into<String>,
+        display_name: impl Into<String>,
+        regex: &str,
+        severity: SyslogSeverity,
+    ) -> Result<Self> {
+        Ok(Self {
+            id: id.into(),
+            display_name: display_name.into(),
+            severity,
+            regex: Regex::new(regex)?,
+        })
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        self.regex.is_match(line)
+    }
+}
+
+impl TryFrom<Yaml> for SyslogPatternDefinition {
+    type Error = anyhow::Error;
+
+    fn try_from(yaml: Yaml) -> Result<Self> {
+        let severity = match yaml.optional_string("severity")? {
+            Some(severity) => serde_json::from_value(serde_json::Value::String(severity.clone()))
+                .map_err(|_| anyhow!("invalid severity '{}'", severity))?,
+            None => SyslogSeverity::default(),
+        };
+        Ok(Self {
+            id: yaml.required_string("id")?,
+            display_name: yaml.required_string("display_name")?,
+            regex: yaml.required_string("regex")?,
+            severity,
+        })
+    }
+}
+
+impl TryFrom<SyslogPatternDefinition> for SyslogPattern {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: SyslogPatternDefinition) -> Result<Self> {
+        Self::new(
+            definition.id,
+            definition.display_name,
+            &definition.regex,
+            definition.severity,
+        )
+    }
+}
+
+/// Built-in syslog patterns
+pub static SYSLOG_PATTERNS: LazyLock<Vec<SyslogPattern>> = LazyLock::new(|| {
+    [
+        (
+            "oom_kill",
+            "Out of memory: process killed",
+            r"(?i)(out of memory: killed process|oom-kill|oom_reaper)",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "nfs_server_not_responding",
+            "NFS server not responding",
+            r"(?i)nfs: server \S+ not responding",
+            SyslogSeverity::Error,
+        ),
+        (
+            "nfs_server_ok",
+            "NFS server recovered",
+            r"(?i)nfs: server \S+ ok",
+            SyslogSeverity::Info,
+        ),
+        (
+            "disk_full",
+            "No space left on device",
+            r"(?i)no space left on device",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "disk_io_error",
+            "Disk I/O error",
+            r"(?i)(i/o error, dev|buffer i/o error|blk_update_request: i/o error)",
+            SyslogSeverity::Error,
+        ),
+        (
+            "fs_read_only",
+            "Filesystem remounted read-only",
+            r"(?i)remounting filesystem read-only",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "hung_task",
+            "Task blocked for too long",
+            r"(?i)task \S+ blocked for more than \d+ seconds",
+            SyslogSeverity::Warn,
+        ),
+    ]
+    .into_iter()
+    .map(|(id, display_name, regex, severity)| {
+        SyslogPattern::new(id, display_name, regex, severity).expect("invalid built-in pattern")
+    })
+    .collect()
+});
+
+/// Errors common bioinformatics tools print to stdout/stderr
+pub static TOOL_ERROR_PATTERNS: LazyLock<Vec<SyslogPattern>> = LazyLock::new(|| {
+    [
+        (
+            "htslib_error",
+            "htslib error",
+            r"^\[E::\w+\]",
+            SyslogSeverity::Error,
+        ),
+        (
+            "java_out_of_memory",
+            "Java heap space exhausted",
+            r"java\.lang\.OutOfMemoryError",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "java_exception",
+            "Uncaught Java exception",
+            r"^Exception in thread \S+ \S+",
+            SyslogSeverity::Error,
+        ),
+        (
+            "segmentation_fault",
+            "Segmentation fault",
+            r"(?i)(segmentation fault|SIGSEGV)",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "core_dumped",
+            "Tool crashed",
+            r"(?i)(core dumped|bus error|illegal instruction)",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "python_traceback",
+            "Python exception",
+            r"^Traceback \(most recent call last\):",
+            SyslogSeverity::Error,
+        ),
+        (
+            "memory_allocation_failed",
+            "Memory allocation failed",
+            r"(?i)(std::bad_alloc|cannot allocate memory|failed to allocate)",
+            SyslogSeverity::Critical,
+        ),
+        (
+            "truncated_input",
+            "Truncated input file",
+            r"(?i)(truncated file|unexpected end of file|EOF marker is absent)",
+            SyslogSeverity::Error,
+        ),
+    ]
+    .into_iter()
+    .map(|(id, display_name, regex, severity)| {
+        SyslogPattern::new(id, display_name, regex, severity).expect("invalid built-in pattern")
+    })
+    .collect()
+});
+
+/// Patterns of errors in the output of a pipeline, before the tool error patterns and the
+/// built-in syslog patterns which tools also print (e.g. `No space left on device`)
+pub static PIPELINE_OUTPUT_PATTERNS: LazyLock<Vec<SyslogPattern>> = LazyLock::new(|| {
+    [
+        (
+            "nextflow_process_failed",
+            "Nextflow process failed",
+            r"Error executing process >",
+            SyslogSeverity::Error,
+        ),
+        (
+            "snakemake_job_failed",
+            "Snakemake job failed",
+            r"Error in rule \S+:",
+            SyslogSeverity::Error,
+        ),
+        (
+            "command_not_found",
+            "Command not found",
+            r"(?i)command not found",
+            SyslogSeverity::Error,
+        ),
+    ]
+    .into_iter()
+    .map(|(id, display_name, regex, severity)| {
+        SyslogPattern::new(id, display_name, regex, severity).expect("invalid built-in pattern")
+    })
+    .chain(TOOL_ERROR_PATTERNS.iter().cloned())
+    .chain(SYSLOG_PATTERNS.iter().cloned())
+    .collect()
+});
+
+/// Loads patterns from a YAML file listing them under `patterns`, e.g.
+///
+/// ```yaml
+/// patterns:
+///   - id: bowtie2_index_missing
+///     display_name: Bowtie2 index missing
+///     regex: "Could not locate a Bowtie index"
+///     severity: error
+/// ```
+pub fn load_patterns_file(path: &Path) -> Result<Vec<SyslogPattern>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read patterns file {}", path.display()))?;
+    load_from_yaml_array_str::<SyslogPatternDefinition>(&contents, "patterns")
+        .and_then(|definitions| {
+            definitions
+                .into_iter()
+                .map(SyslogPattern::try_from)
+                .collect()
+        })
+        .with_context(|| format!("Invalid patterns file {}", path.display()))
+}
+
+/// Returns the first pattern from `patterns` matching `line`
+pub fn find_match<'a>(patterns: &'a [SyslogPattern], line: &str) -> Option<&'a SyslogPattern> {
+    patterns.iter().find(|pattern| pattern.is_match(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "kernel: Out of memory: Killed process 1234 (java)",
+        "oom_kill",
+        SyslogSeverity::Critical
+    )]
+    #[case(
+        "kernel: nfs: server fileserver not responding, still trying",
+        "nfs_server_not_responding",
+        SyslogSeverity::Error
+    )]
+    #[case(
+        "kernel: nfs: server fileserver OK",
+        "nfs_server_ok",
+        SyslogSeverity::Info
+    )]
+    #[case(
+        "write error: No space left on device",
+        "disk_full",
+        SyslogSeverity::Critical
+    )]
+    fn test_builtin_pattern_severity(
+        #[case] line: &str,
+        #[case] id: &str,
+        #[case] severity: SyslogSeverity,
+    ) {
+        let pattern = find_match(&SYSLOG_PATTERNS, line).unwrap();
+        assert_eq!(pattern.id, id);
+        assert_eq!(pattern.severity, severity);
+    }
+
+    #[rstest]
+    #[case(
+        "[E::hts_open_format] Failed to open file \"sample.bam\" : No such file or directory",
+        "htslib_error"
+    )]
+    #[case(
+        "[E::bgzf_read] Read block operation failed with error 4 after 0 of 4 bytes",
+        "htslib_error"
+    )]
+    #[case(
+        "Exception in thread \"main\" java.lang.OutOfMemoryError: Java heap space",
+        "java_out_of_memory"
+    )]
+    #[case(
+        "Exception in thread \"main\" htsjdk.samtools.SAMFormatException: Error parsing text SAM file",
+        "java_exception"
+    )]
+    #[case(
+        "/bin/bash: line 1: 4242 Segmentation fault      (core dumped) bwa mem ref.fa r1.fq",
+        "segmentation_fault"
+    )]
+    #[case("Bus error (core dumped)", "core_dumped")]
+    #[case("Traceback (most recent call last):", "python_traceback")]
+    #[case(
+        "terminate called after throwing an instance of 'std::bad_alloc'",
+        "memory_allocation_failed"
+    )]
+    #[case(
+        "[W::bam_hdr_read] EOF marker is absent. The input is probably truncated",
+        "truncated_input"
+    )]
+    fn test_tool_error_classification(#[case] line: &str, #[case] id: &str) {
+        let pattern = find_match(&TOOL_ERROR_PATTERNS, line).unwrap();
+        assert_eq!(pattern.id, id);
+        assert_eq!(find_match(&PIPELINE_OUTPUT_PATTERNS, line).unwrap().id, id);
+    }
+
+    #[test]
+    fn test_tool_output_without_errors_is_not_classified() {
+        for line in [
+            "[M::bwa_idx_load_from_disk] read 0 ALT contigs",
+            "Started job at Sat Jun 1 12:00:00 2025",
+            "Processed 1000000 reads",
+        ] {
+            assert!(find_match(&TOOL_ERROR_PATTERNS, line).is_none(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_load_patterns_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("patterns.yml");
+        std::fs::write(
+            &path,
+            "patterns:\n  - id: bowtie2_index_missing\n    display_name: Bowtie2 index missing\n    regex: Could not locate a Bowtie index\n    severity: critical\n  - id: star_genome\n    display_name: STAR genome error\n    regex: EXITING because of FATAL ERROR\n",
+        )
+        .unwrap();
+
+        let patterns = load_patterns_file(&path).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].severity, SyslogSeverity::Critical);
+        assert!(patterns[0].is_match("Could not locate a Bowtie index corresponding to basename"));
+        assert_eq!(patterns[1].severity, SyslogSeverity::default());
+
+        std::fs::write(
+            &path,
+            "patterns:\n  - id: bad\n    display_name: Bad\n    regex: \"(\"\n",
+        )
+        .unwrap();
+        assert!(load_patterns_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_pipeline_output_patterns_include_syslog_patterns() {
+        let pattern = find_match(
+            &PIPELINE_OUTPUT_PATTERNS,
+            "ERROR ~ Error executing process > 'NFCORE_RNASEQ:FASTQC (1)'",
+        )
+        .unwrap();
+        assert_eq!(pattern.id, "nextflow_process_failed");
+        let pattern = find_match(&PIPELINE_OUTPUT_PATTERNS, "No space left on device").unwrap();
+        assert_eq!(pattern.id, "disk_full");
+    }
+
+    #[test]
+    fn test_user_defined_pattern_severity() {
+        let definition: SyslogPatternDefinition = serde_json::from_value(serde_json::json!({
+            "id": "gpu_xid",
+            "display_name": "GPU Xid error",
+            "regex": "NVRM: Xid",
+            "severity": "critical",
+        }))
+        .unwrap();
+        let pattern = SyslogPattern::try_from(definition).unwrap();
+        assert_eq!(pattern.severity, SyslogSeverity::Critical);
+        assert!(pattern.is_match("kernel: NVRM: Xid (PCI:0000:3b:00): 79"));
+    }
+}
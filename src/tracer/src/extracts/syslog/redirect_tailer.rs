@@ -0,0 +1,315 @@
+use crate::constants::OUTPUT_BUFFER_LINES;
+use crate::extracts::syslog::output_buffer::OutputBuffer;
+use crate::extracts::syslog::output_scanner::{OutputMatch, OutputScanner};
+use crate::extracts::syslog::redirects::OutputRedirect;
+use crate::extracts::syslog::{SyslogPattern, PIPELINE_OUTPUT_PATTERNS};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, Metadata};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Most bytes read from a file per poll, so a tool writing a lot of output doesn't stall the
+/// poll; the rest is read by the next polls
+const MAX_READ_BYTES_PER_POLL: u64 = 1024 * 1024;
+/// Longest line kept, longer lines are cut there
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Tails the files tools redirect their output to, matching their lines against the same error
+/// patterns as the output of the pipelines the CLI runs
+pub struct RedirectTailer {
+    patterns: Arc<[SyslogPattern]>,
+    /// Error lines of each file kept per poll, see `OutputBuffer`
+    buffer_lines: usize,
+    files: HashMap<PathBuf, TailedFile>,
+}
+
+impl Default for RedirectTailer {
+    fn default() -> Self {
+        Self::with_patterns(Vec::new())
+    }
+}
+
+struct TailedFile {
+    /// Tool whose output is redirected to the file
+    tool: String,
+    scanner: OutputScanner,
+    buffer: OutputBuffer,
+    /// Where the next read starts
+    position: u64,
+    /// Device and inode of the file read, to notice it was rotated
+    file_id: Option<(u64, u64)>,
+    /// Last line read, until its line ending is written
+    partial_line: Vec<u8>,
+}
+
+impl TailedFile {
+    fn new(
+        tool: String,
+        redirect: &OutputRedirect,
+        patterns: Arc<[SyslogPattern]>,
+        buffer_lines: usize,
+    ) -> Self {
+        // an appended file may hold the output of earlier runs, so only what the tool writes is
+        // read; a truncated one only holds the tool's output
+        let metadata = std::fs::metadata(&redirect.path).ok();
+        let position = match &metadata {
+            Some(metadata) if redirect.append => metadata.len(),
+            _ => 0,
+        };
+        Self {
+            tool,
+            scanner: OutputScanner::new(redirect.stream, patterns),
+            buffer: OutputBuffer::new(buffer_lines),
+            position,
+            file_id: metadata.as_ref().map(file_id),
+            partial_line: Vec::new(),
+        }
+    }
+
+    /// Reads the lines written since the last poll, starting over if the file was truncated or
+    /// replaced by a new one
+    fn read_lines(&mut self, path: &Path) -> std::io::Result<Vec<String>> {
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        let id = file_id(&metadata);
+        if self.file_id != Some(id) {
+            if self.file_id.is_some() {
+                debug!("{} was rotated", path.display());
+            }
+            self.file_id = Some(id);
+            self.restart();
+        } else if metadata.len() < self.position {
+            debug!("{} was truncated", path.display());
+            self.restart();
+        }
+
+        file.seek(SeekFrom::Start(self.position))?;
+        let mut read = Vec::new();
+        file.take(MAX_READ_BYTES_PER_POLL).read_to_end(&mut read)?;
+        self.position += read.len() as u64;
+
+        self.partial_line.extend(read);
+        let complete = match self.partial_line.iter().rposition(|byte| *byte == b'\n') {
+            Some(end) => self.partial_line.drain(..=end).collect::<Vec<_>>(),
+            // a line without end, e.g. a progress bar, is cut rather than kept growing
+            None if self.partial_line.len() > MAX_LINE_BYTES => {
+                std::mem::take(&mut self.partial_line)
+            }
+            None => return Ok(Vec::new()),
+        };
+        Ok(String::from_utf8_lossy(&complete)
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    fn restart(&mut self) {
+        self.position = 0;
+        self.partial_line.clear();
+    }
+}
+
+fn file_id(metadata: &Metadata) -> (u64, u64) {
+    (metadata.dev(), metadata.ino())
+}
+
+impl RedirectTailer {
+    /// Matches lines against `patterns` before the built-in ones
+    pub fn with_patterns(patterns: Vec<SyslogPattern>) -> Self {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .chain(PIPELINE_OUTPUT_PATTERNS.iter().cloned())
+                .collect(),
+            buffer_lines: OUTPUT_BUFFER_LINES,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Keeps at most `buffer_lines` error lines per file and poll, dropping the oldest
+    pub fn with_buffer_lines(mut self, buffer_lines: usize) -> Self {
+        self.buffer_lines = buffer_lines;
+        self
+    }
+
+    /// Starts tailing the files of `redirects`, by the tool redirecting to them, that are not
+    /// tailed yet, and returns the error lines written to the tailed files since the last call.
+    /// Files no longer in `redirects`, e.g. as their tool exited, are read one last time and no
+    /// longer tailed.
+    pub fn sync(&mut self, redirects: Vec<(String, OutputRedirect)>) -> Vec<OutputMatch> {
+        let mut redirected = HashSet::new();
+        for (tool, redirect) in redirects {
+            self.files.entry(redirect.path.clone()).or_insert_with(|| {
+                TailedFile::new(tool, &redirect, self.patterns.clone(), self.buffer_lines)
+            });
+            redirected.insert(redirect.path);
+        }
+
+        let mut matches = Vec::new();
+        for (path, file) in &mut self.files {
+            let lines = match file.read_lines(path) {
+                Ok(lines) => lines,
+                // not created yet, or removed
+                Err(e) => {
+                    debug!("Failed to read {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            for line in lines {
+                if let Some(mut found) = file.scanner.scan(&line) {
+                    found.file = Some(path.clone());
+                    found.tool = Some(file.tool.clone());
+                    file.buffer.push(found);
+                }
+            }
+            matches.extend(file.buffer.drain());
+        }
+
+        self.files.retain(|path, _| redirected.contains(path));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extracts::syslog::output_scanner::OutputStream;
+    use crate::process_identification::types::event::attributes::syslog::SyslogSeverity;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn redirect(path: &Path, append: bool) -> (String, OutputRedirect) {
+        let redirect = OutputRedirect {
+            stream: OutputStream::Stderr,
+            path: path.to_path_buf(),
+            append,
+        };
+        ("STAR".to_string(), redirect)
+    }
+
+    fn append(path: &Path, content: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_error_lines_are_matched_once_complete() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("star.log");
+        let mut tailer = RedirectTailer::default();
+        // not created yet
+        assert!(tailer.sync(vec![redirect(&path, false)]).is_empty());
+
+        append(&path, "loading genome\nbash: STAR: command ");
+        assert!(tailer.sync(vec![redirect(&path, false)]).is_empty());
+
+        append(&path, "not found\n");
+        let matches = tailer.sync(vec![redirect(&path, false)]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, "bash: STAR: command not found");
+        assert_eq!(matches[0].line_number, 2);
+        assert_eq!(matches[0].previous_lines, vec!["loading genome"]);
+        assert_eq!(matches[0].file, Some(path));
+        assert_eq!(matches[0].tool.as_deref(), Some("STAR"));
+    }
+
+    #[test]
+    fn test_configured_patterns_are_matched_first() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bowtie2.log");
+        let pattern = SyslogPattern::new(
+            "bowtie2_index_missing",
+            "Bowtie2 index missing",
+            r"Could not locate a Bowtie index",
+            SyslogSeverity::Critical,
+        )
+        .unwrap();
+        let mut tailer = RedirectTailer::with_patterns(vec![pattern]);
+        assert!(tailer.sync(vec![redirect(&path, false)]).is_empty());
+
+        append(
+            &path,
+            "Could not locate a Bowtie index corresponding to basename \"ref\"\n[E::hts_open] x\n",
+        );
+        let ids: Vec<_> = tailer
+            .sync(vec![redirect(&path, false)])
+            .into_iter()
+            .map(|found| found.pattern_id)
+            .collect();
+        assert_eq!(ids, vec!["bowtie2_index_missing", "htslib_error"]);
+    }
+
+    #[test]
+    fn test_flooded_file_is_truncated() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("loop.log");
+        let mut tailer = RedirectTailer::default().with_buffer_lines(10);
+        assert!(tailer.sync(vec![redirect(&path, false)]).is_empty());
+
+        append(
+            &path,
+            &"[E::bgzf_read] Read block operation failed\n".repeat(1000),
+        );
+        let matches = tailer.sync(vec![redirect(&path, false)]);
+        assert_eq!(matches.len(), 11);
+        assert_eq!(matches[0].line, "output truncated, 990 lines dropped");
+        assert_eq!(matches[0].tool.as_deref(), Some("STAR"));
+        assert_eq!(matches[10].line_number, 1000);
+
+        // a line that never ends is cut
+        append(&path, &"#".repeat(2 * MAX_LINE_BYTES));
+        tailer.sync(vec![redirect(&path, false)]);
+        assert!(tailer.files[&path].partial_line.is_empty());
+    }
+
+    #[test]
+    fn test_appended_file_is_read_from_its_end() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sort.err");
+        append(&path, "bash: samtools: command not found\n");
+
+        let mut tailer = RedirectTailer::default();
+        assert!(tailer.sync(vec![redirect(&path, true)]).is_empty());
+        append(&path, "bash: samtools: command not found\n");
+        assert_eq!(tailer.sync(vec![redirect(&path, true)]).len(), 1);
+    }
+
+    #[test]
+    fn test_truncated_and_rotated_files_are_read_again() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tool.log");
+        let error = "bash: tool: command not found\n";
+        let mut tailer = RedirectTailer::default();
+        append(&path, error);
+        assert_eq!(tailer.sync(vec![redirect(&path, false)]).len(), 1);
+
+        std::fs::write(&path, "").unwrap();
+        append(&path, "x\n");
+        assert!(tailer.sync(vec![redirect(&path, false)]).is_empty());
+
+        // rotated, e.g. by logrotate
+        std::fs::rename(&path, dir.path().join("tool.log.1")).unwrap();
+        append(&path, error);
+        assert_eq!(tailer.sync(vec![redirect(&path, false)]).len(), 1);
+    }
+
+    #[test]
+    fn test_file_is_read_one_last_time_once_no_longer_redirected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tool.log");
+        let mut tailer = RedirectTailer::default();
+        assert!(tailer.sync(vec![redirect(&path, false)]).is_empty());
+
+        append(&path, "bash: tool: command not found\n");
+        assert_eq!(tailer.sync(Vec::new()).len(), 1);
+        append(&path, "bash: tool: command not found\n");
+        assert!(tailer.sync(Vec::new()).is_empty());
+    }
+}
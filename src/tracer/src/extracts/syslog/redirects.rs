@@ -0,0 +1,183 @@
+//! Output redirections (`> tool.log 2>&1`) found on command lines, so the files tools write
+//! their output to can be scanned like the output of the pipelines the CLI runs.
+
+use crate::extracts::syslog::output_scanner::OutputStream;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// `[fd]>file`, `[fd]>>file` and `&>file`, with the file possibly in the next argument
+static REDIRECT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d|&)?(>>?)(.*)$").expect("invalid redirect regex"));
+
+/// Shells whose `-c` script is searched for redirections
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh"];
+
+/// A stream of a process redirected to a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputRedirect {
+    pub stream: OutputStream,
+    pub path: PathBuf,
+    /// Whether the file is appended to (`>>`) rather than truncated (`>`)
+    pub append: bool,
+}
+
+/// The files `argv` redirects stdout or stderr to, including in the script of a shell run with
+/// `-c`. Only the first redirection to each file is kept; `/dev` files and redirections to
+/// another stream (`2>&1`) are left out.
+pub fn parse_redirects(argv: &[String]) -> Vec<OutputRedirect> {
+    let mut redirects = redirects_in(argv);
+    if let Some(script) = shell_script(argv) {
+        let words = shlex::split(script)
+            .unwrap_or_else(|| script.split_whitespace().map(String::from).collect());
+        redirects.extend(redirects_in(&words));
+    }
+
+    let mut seen = Vec::new();
+    redirects.retain(|redirect| {
+        let first = !seen.contains(&redirect.path);
+        seen.push(redirect.path.clone());
+        first
+    });
+    redirects
+}
+
+/// Resolves the relative paths of `redirects` against `cwd`, dropping them if it isn't known
+pub fn resolve_redirects(
+    redirects: Vec<OutputRedirect>,
+    cwd: Option<&Path>,
+) -> Vec<OutputRedirect> {
+    redirects
+        .into_iter()
+        .filter_map(|mut redirect| {
+            if redirect.path.is_relative() {
+                redirect.path = cwd?.join(&redirect.path);
+            }
+            Some(redirect)
+        })
+        .collect()
+}
+
+fn redirects_in(words: &[String]) -> Vec<OutputRedirect> {
+    let mut redirects = Vec::new();
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        let Some(captures) = REDIRECT_REGEX.captures(word) else {
+            continue;
+        };
+        let stream = match captures.get(1).map(|fd| fd.as_str()) {
+            None | Some("1") => OutputStream::Stdout,
+            Some("2") | Some("&") => OutputStream::Stderr,
+            // other file descriptors aren't output streams
+            Some(_) => continue,
+        };
+        let append = &captures[2] == ">>";
+        let target = match &captures[3] {
+            "" => match words.next() {
+                Some(target) => target.as_str(),
+                None => break,
+            },
+            target => target,
+        };
+        // `2>&1` and `>&2` redirect to another stream, and `/dev/null` is not worth reading
+        if target.starts_with('&') || target.starts_with("/dev/") {
+            continue;
+        }
+        redirects.push(OutputRedirect {
+            stream,
+            path: PathBuf::from(target),
+            append,
+        });
+    }
+    redirects
+}
+
+/// The script of `sh -c <script>` and the like
+fn shell_script(argv: &[String]) -> Option<&str> {
+    let shell = Path::new(argv.first()?).file_name()?.to_str()?;
+    if !SHELLS.contains(&shell) {
+        return None;
+    }
+    // `-c` may be combined with other options, e.g. `bash -ec`
+    let c_flag = argv[1..]
+        .iter()
+        .position(|arg| arg.starts_with('-') && !arg.starts_with("--") && arg.contains('c'))?;
+    argv.get(c_flag + 2).map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn redirect(stream: OutputStream, path: &str, append: bool) -> OutputRedirect {
+        OutputRedirect {
+            stream,
+            path: PathBuf::from(path),
+            append,
+        }
+    }
+
+    #[test]
+    fn test_redirects_in_shell_script() {
+        let redirects = parse_redirects(&argv(&[
+            "/bin/bash",
+            "-ec",
+            "STAR --runThreadN 8 > star.log 2>&1 && samtools sort x.bam 2>>/data/sort.err",
+        ]));
+        assert_eq!(
+            redirects,
+            vec![
+                redirect(OutputStream::Stdout, "star.log", false),
+                redirect(OutputStream::Stderr, "/data/sort.err", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redirect_targets_in_the_same_or_next_argument() {
+        let redirects = parse_redirects(&argv(&["tool", "2>", "err.log", "&>>all.log", "1>out"]));
+        assert_eq!(
+            redirects,
+            vec![
+                redirect(OutputStream::Stderr, "err.log", false),
+                redirect(OutputStream::Stderr, "all.log", true),
+                redirect(OutputStream::Stdout, "out", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_and_device_redirects_are_ignored() {
+        let redirects = parse_redirects(&argv(&[
+            "sh",
+            "-c",
+            "tool >/dev/null 2>&1; other >&2; third 3> fd3.log",
+        ]));
+        assert!(redirects.is_empty());
+        // not a shell, so the script is not searched
+        assert!(parse_redirects(&argv(&["python", "-c", "print(1) > x.log"])).is_empty());
+    }
+
+    #[test]
+    fn test_relative_redirects_are_resolved() {
+        let redirects = vec![
+            redirect(OutputStream::Stdout, "star.log", false),
+            redirect(OutputStream::Stderr, "/data/sort.err", true),
+        ];
+        assert_eq!(
+            resolve_redirects(redirects.clone(), Some(Path::new("/work"))),
+            vec![
+                redirect(OutputStream::Stdout, "/work/star.log", false),
+                redirect(OutputStream::Stderr, "/data/sort.err", true),
+            ]
+        );
+        assert_eq!(
+            resolve_redirects(redirects, None),
+            vec![redirect(OutputStream::Stderr, "/data/sort.err", true)]
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use crate::process_identification::types::event::{Event, ProcessStatus};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Number of events buffered per subscriber; a subscriber lagging further behind misses events
+const EVENT_STREAM_CAPACITY: usize = 1024;
+
+/// Fan-out of recorded events to live subscribers (e.g. `tracer watch`).
+///
+/// Publishing never blocks: a slow subscriber drops events and receives a gap marker instead.
+#[derive(Clone)]
+pub struct EventStream {
+    tx: broadcast::Sender<Event>,
+}
+
+impl Default for EventStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventStream {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+        Self { tx }
+    }
+
+    pub fn publish(&self, event: &Event) {
+        // avoid cloning events when nobody is listening
+        if self.tx.receiver_count() > 0 {
+            let _ = self.tx.send(event.clone());
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.tx.subscribe()
+    }
+}
+
+/// A single line of the newline-delimited JSON event stream
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage {
+    Event(Box<Event>),
+    Gap { dropped: u64 },
+}
+
+impl StreamMessage {
+    pub fn to_ndjson(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// Filter as sent by subscribers, e.g. `?status=tool_execution,finished_tool_execution&tool=bwa`
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct EventFilterQuery {
+    /// Comma-separated process statuses, or `all`
+    pub status: Option<String>,
+    pub run_id: Option<String>,
+    /// Substring of the tool name
+    pub tool: Option<String>,
+}
+
+/// Server-side filter applied to the event stream; the default lets every event through
+#[derive(Debug, Default, Clone)]
+pub struct EventFilter {
+    statuses: Option<Vec<ProcessStatus>>,
+    run_id: Option<String>,
+    tool: Option<String>,
+}
+
+impl TryFrom<EventFilterQuery> for EventFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(query: EventFilterQuery) -> Result<Self> {
+        let statuses = match query.status.as_deref().map(str::trim) {
+            None | Some("") | Some("all") => None,
+            Some(statuses) => Some(
+                statuses
+                    .split(',')
+                    .map(|status| {
+                        serde_json::from_value(serde_json::Value::String(status.trim().to_string()))
+                            .map_err(|_| anyhow!("Unknown process status: {}", status))
+                    })
+                    .collect::<Result<Vec<ProcessStatus>>>()?,
+            ),
+        };
+
+        Ok(Self {
+            statuses,
+            run_id: query.run_id,
+            tool: query.tool,
+        })
+    }
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        if let Some(statuses) = &self.statuses {
+            if !statuses.contains(&event.process_status) {
+                return false;
+            }
+        }
+        if let Some(run_id) = &self.run_id {
+            if event.run_id.as_ref() != Some(run_id) {
+                return false;
+            }
+        }
+        if let Some(tool) = &self.tool {
+            let tool_name = event
+                .attributes
+                .as_ref()
+                .and_then(|attributes| attributes.tool_name());
+            if !tool_name.is_some_and(|name| name.contains(tool.as_str())) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Waits for the next message for a subscriber matching `filter`, or `None` once the stream is
+/// closed
+pub async fn next_message(
+    receiver: &mut broadcast::Receiver<Event>,
+    filter: &EventFilter,
+) -> Option<StreamMessage> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) if filter.matches(&event) => {
+                return Some(StreamMessage::Event(Box::new(event)))
+            }
+            Ok(_) => continue,
+            Err(RecvError::Lagged(dropped)) => return Some(StreamMessage::Gap { dropped }),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(body: &str) -> Event {
+        Event::builder().body(body).timestamp(Utc::now()).build()
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_receive_published_events() {
+        let stream = EventStream::new();
+        let mut first = stream.subscribe();
+        let mut second = stream.subscribe();
+
+        stream.publish(&event("hello"));
+
+        for receiver in [&mut first, &mut second] {
+            match next_message(receiver, &EventFilter::default()).await {
+                Some(StreamMessage::Event(event)) => assert_eq!(event.body, "hello"),
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_gets_gap_marker() {
+        let stream = EventStream::new();
+        let mut receiver = stream.subscribe();
+
+        for i in 0..EVENT_STREAM_CAPACITY + 5 {
+            stream.publish(&event(&i.to_string()));
+        }
+
+        match next_message(&mut receiver, &EventFilter::default()).await {
+            Some(StreamMessage::Gap { dropped }) => assert_eq!(dropped, 5),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        match next_message(&mut receiver, &EventFilter::default()).await {
+            Some(StreamMessage::Event(event)) => assert_eq!(event.body, "5"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    fn filter(status: Option<&str>, run_id: Option<&str>, tool: Option<&str>) -> EventFilter {
+        EventFilter::try_from(EventFilterQuery {
+            status: status.map(String::from),
+            run_id: run_id.map(String::from),
+            tool: tool.map(String::from),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_status_filter_excludes_non_matching_events() {
+        let filter = filter(Some("tool_execution,finished_tool_execution"), None, None);
+
+        let mut tool_event = event("tool");
+        tool_event.process_status = ProcessStatus::ToolExecution;
+        let mut metric_event = event("metric");
+        metric_event.process_status = ProcessStatus::MetricEvent;
+
+        assert!(filter.matches(&tool_event));
+        assert!(!filter.matches(&metric_event));
+    }
+
+    #[test]
+    fn test_all_filter_matches_everything() {
+        let filter = filter(Some("all"), None, None);
+        assert!(filter.matches(&event("anything")));
+        assert!(EventFilter::default().matches(&event("anything")));
+    }
+
+    #[test]
+    fn test_run_id_and_tool_filters() {
+        let mut event = event("tool");
+        event.run_id = Some("run-1".to_string());
+
+        assert!(filter(None, Some("run-1"), None).matches(&event));
+        assert!(!filter(None, Some("run-2"), None).matches(&event));
+        // events without a tool never match a tool filter
+        assert!(!filter(None, None, Some("bwa")).matches(&event));
+    }
+
+    #[test]
+    fn test_unknown_status_is_rejected() {
+        let query = EventFilterQuery {
+            status: Some("not_a_status".to_string()),
+            ..Default::default()
+        };
+        assert!(EventFilter::try_from(query).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_subscriber_skips_non_matching_events() {
+        let stream = EventStream::new();
+        let mut receiver = stream.subscribe();
+        let filter = filter(Some("tool_execution"), None, None);
+
+        let mut metric_event = event("metric");
+        metric_event.process_status = ProcessStatus::MetricEvent;
+        let mut tool_event = event("tool");
+        tool_event.process_status = ProcessStatus::ToolExecution;
+        stream.publish(&metric_event);
+        stream.publish(&tool_event);
+
+        match next_message(&mut receiver, &filter).await {
+            Some(StreamMessage::Event(event)) => assert_eq!(event.body, "tool"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_message_round_trips_as_ndjson() {
+        let line = StreamMessage::Gap { dropped: 3 }.to_ndjson();
+        assert!(line.ends_with('\n'));
+        match serde_json::from_str(line.trim_end()).unwrap() {
+            StreamMessage::Gap { dropped } => assert_eq!(dropped, 3),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+}
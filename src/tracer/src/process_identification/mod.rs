@@ -1,6 +1,8 @@
 pub mod constants;
+pub mod event_stream;
 pub mod recorder; // todo: this is very ugly, please move me out to tracer client
 pub mod target_pipeline;
 pub mod target_process;
+pub mod trigger_stream;
 pub mod types;
 pub mod utils;
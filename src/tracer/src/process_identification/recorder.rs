@@ -1,17 +1,50 @@
 use crate::daemon::structs::PipelineMetadata;
+use crate::process_identification::event_stream::EventStream;
 use crate::process_identification::types::current_run::RunMetadata;
+use crate::process_identification::types::event::attributes::heartbeat::{Heartbeat, RunState};
+use crate::process_identification::types::event::attributes::run_summary::{
+    RunEndReason, RunSummary, RunSummaryBuilder,
+};
 use crate::process_identification::types::event::attributes::EventAttributes;
+use crate::process_identification::types::event::resource::ResourceAttributes;
 use crate::process_identification::types::event::{Event, ProcessStatus};
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::Mutex;
+use tracing::warn;
 
 #[derive(Clone)]
 pub struct EventDispatcher {
     pipeline: Arc<Mutex<PipelineMetadata>>,
     run: RunMetadata,
     tx: Sender<Event>,
+    stream: Option<EventStream>,
+    resource: Option<ResourceAttributes>,
+    summary: Arc<std::sync::Mutex<RunSummaryBuilder>>,
+    activity: Arc<std::sync::Mutex<RunActivity>>,
+}
+
+/// When events were last recorded, for heartbeats
+#[derive(Debug, Default, Clone, Copy)]
+struct RunActivity {
+    /// Last event other than heartbeats
+    last_event_at: Option<DateTime<Utc>>,
+    /// Last event other than metrics and heartbeats
+    last_activity_at: Option<DateTime<Utc>>,
+}
+
+impl RunActivity {
+    fn observe(&mut self, event: &Event) {
+        if matches!(event.attributes, Some(EventAttributes::Heartbeat(_))) {
+            return;
+        }
+        self.last_event_at = Some(event.timestamp);
+        if event.process_status != ProcessStatus::MetricEvent {
+            self.last_activity_at = Some(event.timestamp);
+        }
+    }
 }
 
 impl EventDispatcher {
@@ -20,7 +53,27 @@ impl EventDispatcher {
         run: RunMetadata,
         tx: Sender<Event>,
     ) -> Self {
-        EventDispatcher { pipeline, run, tx }
+        EventDispatcher {
+            pipeline,
+            run,
+            tx,
+            stream: None,
+            resource: None,
+            summary: Default::default(),
+            activity: Default::default(),
+        }
+    }
+
+    /// Also publishes every recorded event to live subscribers of `stream`
+    pub fn with_stream(mut self, stream: EventStream) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Attaches `resource` to every recorded event
+    pub fn with_resource(mut self, resource: ResourceAttributes) -> Self {
+        self.resource = Some(resource);
+        self
     }
 
     pub fn trace_id(&self) -> Option<String> {
@@ -39,6 +92,108 @@ impl EventDispatcher {
             .await
     }
 
+    /// Summary of the run built from every event recorded so far
+    pub async fn run_summary(&self, ended_at: DateTime<Utc>) -> RunSummary {
+        let pipeline_name = self.pipeline.lock().await.name.clone();
+        self.summary
+            .lock()
+            .expect("run summary lock poisoned")
+            .build(&pipeline_name, &self.run, ended_at, Instant::now())
+    }
+
+    /// Records the end of the run along with its summary
+    pub async fn log_run_summary(&self, summary: RunSummary) -> anyhow::Result<()> {
+        self.log_event(
+            &self.run,
+            ProcessStatus::FinishedRun,
+            match summary.end_reason {
+                RunEndReason::Completed => {
+                    format!("[CLI] Finished pipeline run {}", self.run.name)
+                }
+                reason => format!(
+                    "[CLI] Finished pipeline run {} ({})",
+                    self.run.name,
+                    reason.as_str()
+                ),
+            },
+            None,
+            Some(summary.ended_at),
+        )
+        .await?;
+        self.log_event(
+            &self.run,
+            ProcessStatus::RunStatusMessage,
+            format!(
+                "[CLI] Run {} finished after {}s: {} tool executions, {} failed, {} errors detected",
+                summary.run_name,
+                summary.duration_sec,
+                summary.tools.iter().map(|tool| tool.executions).sum::<u64>(),
+                summary.failed_tool_executions,
+                summary.errors.iter().map(|error| error.occurrences).sum::<u64>(),
+            ),
+            Some(EventAttributes::RunSummary(Box::new(summary))),
+            None,
+        )
+        .await
+    }
+
+    /// Records a heartbeat carrying the current state of the run, unless other events were
+    /// recorded within `quiet_period`. Returns whether a heartbeat was recorded.
+    pub async fn log_heartbeat(
+        &self,
+        running_tools: Vec<String>,
+        quiet_period: chrono::Duration,
+    ) -> anyhow::Result<bool> {
+        let now = Utc::now();
+        let activity = *self.activity.lock().expect("run activity lock poisoned");
+        if activity
+            .last_event_at
+            .is_some_and(|last_event_at| now - last_event_at < quiet_period)
+        {
+            return Ok(false);
+        }
+
+        let state = if running_tools.is_empty() {
+            RunState::Idle
+        } else {
+            RunState::Running
+        };
+        self.log_event(
+            &self.run,
+            ProcessStatus::RunStatusMessage,
+            format!("[CLI] Heartbeat: run {} is alive", self.run.name),
+            Some(EventAttributes::Heartbeat(Heartbeat {
+                state,
+                running_tools,
+                last_activity_at: activity.last_activity_at,
+            })),
+            Some(now),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Warns if no rules to match tools are loaded, i.e. `rule_count` is 0, e.g. as the rules
+    /// files are empty and the built-in rules are disabled, so the run only records system metrics
+    pub async fn log_rule_count(&self, rule_count: usize) -> anyhow::Result<()> {
+        if rule_count > 0 {
+            return Ok(());
+        }
+        warn!(
+            "No tool-matching rules are loaded: no tools will be recorded, only system metrics. \
+            Check the rules files and TRACER_BUILTIN_RULES."
+        );
+        self.log_event(
+            &self.run,
+            ProcessStatus::RunStatusMessage,
+            "[CLI] No tool-matching rules are loaded, only system metrics will be recorded"
+                .to_string(),
+            None,
+            None,
+        )
+        .await
+    }
+
     pub async fn log_new_run(&self, trace_id: &str) -> anyhow::Result<()> {
         self.log_event(
             &self.run,
@@ -61,6 +216,9 @@ impl EventDispatcher {
         timestamp: Option<DateTime<Utc>>,
     ) -> anyhow::Result<()> {
         let pipeline = &self.pipeline.lock().await;
+        let severity = attributes
+            .as_ref()
+            .and_then(|attributes| attributes.severity());
         let event = Event::builder()
             .body(body)
             .timestamp(timestamp.unwrap_or_else(Utc::now))
@@ -70,10 +228,26 @@ impl EventDispatcher {
             .run_id(Some(run.id.clone()))
             .span_id(Some(run.id.clone()))
             .tags(Some(pipeline.tags.clone()))
+            .resource(self.resource.clone())
             .attributes(attributes)
+            .severity_text(severity.map(|(text, _)| text.to_string()))
+            .severity_number(severity.map(|(_, number)| number))
             .trace_id(run.trace_id.clone())
             .build();
 
+        self.summary
+            .lock()
+            .expect("run summary lock poisoned")
+            .observe(&event);
+        self.activity
+            .lock()
+            .expect("run activity lock poisoned")
+            .observe(&event);
+
+        if let Some(stream) = &self.stream {
+            stream.publish(&event);
+        }
+
         self.tx.send(event).await?;
         Ok(())
     }
@@ -82,6 +256,10 @@ impl EventDispatcher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process_identification::target_process::target_manager::TargetManager;
+    use crate::process_identification::types::event::attributes::run_summary::{
+        RunSummary, RunSummaryBuilder,
+    };
     use crate::process_identification::types::event::attributes::EventAttributes;
     use chrono::TimeZone;
     use tokio::sync::mpsc;
@@ -209,6 +387,84 @@ mod tests {
         assert_eq!(event.trace_id, Some(trace_id));
     }
 
+    #[tokio::test]
+    async fn test_heartbeat_suppressed_after_recent_events() {
+        let (pipeline, run) = create_test_pipeline();
+        let (tx, mut rx) = mpsc::channel(10);
+        let recorder = EventDispatcher::new(pipeline, run, tx);
+        let quiet_period = chrono::Duration::seconds(60);
+
+        // nothing recorded yet
+        assert!(recorder.log_heartbeat(vec![], quiet_period).await.unwrap());
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.process_status, ProcessStatus::RunStatusMessage);
+        match event.attributes {
+            Some(EventAttributes::Heartbeat(heartbeat)) => {
+                assert_eq!(heartbeat.state, RunState::Idle);
+                assert_eq!(heartbeat.last_activity_at, None);
+            }
+            _ => panic!("Expected Heartbeat attributes"),
+        }
+
+        // heartbeats themselves don't suppress the next heartbeat
+        assert!(recorder
+            .log_heartbeat(vec!["STAR".to_string()], quiet_period)
+            .await
+            .unwrap());
+        rx.recv().await.unwrap();
+
+        let tool_started_at = Utc::now();
+        recorder
+            .log_with_metadata(
+                ProcessStatus::ToolExecution,
+                "tool".to_string(),
+                None,
+                Some(tool_started_at),
+            )
+            .await
+            .unwrap();
+        rx.recv().await.unwrap();
+        assert!(!recorder
+            .log_heartbeat(vec!["STAR".to_string()], quiet_period)
+            .await
+            .unwrap());
+
+        assert!(recorder
+            .log_heartbeat(vec!["STAR".to_string()], chrono::Duration::zero())
+            .await
+            .unwrap());
+        match rx.recv().await.unwrap().attributes {
+            Some(EventAttributes::Heartbeat(heartbeat)) => {
+                assert_eq!(heartbeat.state, RunState::Running);
+                assert_eq!(
+                    heartbeat.last_activity_at.map(|at| at.timestamp()),
+                    Some(tool_started_at.timestamp())
+                );
+            }
+            _ => panic!("Expected Heartbeat attributes"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_targets_message() {
+        let (pipeline, run) = create_test_pipeline();
+        let (tx, mut rx) = mpsc::channel(10);
+        let recorder = EventDispatcher::new(pipeline, run, tx);
+
+        let builtin = TargetManager::default();
+        recorder.log_rule_count(builtin.rule_count()).await.unwrap();
+        assert!(rx.try_recv().is_err());
+
+        let without_rules = TargetManager::default().without_builtin_targets();
+        recorder
+            .log_rule_count(without_rules.rule_count())
+            .await
+            .unwrap();
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.process_status, ProcessStatus::RunStatusMessage);
+        assert!(event.body.contains("No tool-matching rules are loaded"));
+    }
+
     // Helper function to create a test pipeline
     fn create_test_pipeline() -> (Arc<Mutex<PipelineMetadata>>, RunMetadata) {
         let trace_id = "trace-id-xyz".to_string();
@@ -217,6 +473,7 @@ mod tests {
             name: "test_run".to_string(),
             id: "test-id-123".to_string(),
             start_time: Utc::now(),
+            started: Instant::now(),
             cost_summary: None,
             trace_id: Some(trace_id.clone()),
         };
@@ -228,6 +485,10 @@ mod tests {
             is_dev: true,
             start_time: Default::default(),
             opentelemetry_status: None,
+            ebpf_events_lost: 0,
+            active_rules: None,
+            local_only: false,
+            config: None,
         }));
         (pipeline, run)
     }
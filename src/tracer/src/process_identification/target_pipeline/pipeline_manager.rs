@@ -4,19 +4,22 @@ use crate::process_identification::target_pipeline::parser::pipeline::{
 use crate::process_identification::target_pipeline::parser::yaml_rules_parser::load_pipelines_from_yamls;
 use crate::process_identification::target_process::target::Target;
 use crate::process_identification::target_process::target_match::MatchType;
+use crate::utils::append_file::AppendFile;
 use crate::utils::workdir::TRACER_WORK_DIR;
 use crate::utils::yaml::YamlFile;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::sync::LazyLock;
 use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 use tracing::{error, trace};
 
 pub const TASK_SCORE_THRESHOLD: f64 = 0.9;
 
+static STEP_MATCHES_LOG: LazyLock<AppendFile> =
+    LazyLock::new(|| AppendFile::new(&TRACER_WORK_DIR.step_matches_file));
+
 /// A task that is matched to a set of processes that have been started.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskMatch {
@@ -234,18 +237,11 @@ impl TargetPipelineManager {
         task_pid: usize,
         best_match: Option<&TaskMatch>,
     ) {
-        if let Err(e) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&TRACER_WORK_DIR.step_matches_file)
-            .and_then(|mut file| {
-                let log_line = format!("{} | {} | {} | {:?}\n", rule, pid, task_pid, best_match);
-                file.write_all(log_line.as_bytes())?;
-                let log_line = format!("Candidate matches: {:?}\n", &self.candidate_matches);
-                file.write_all(log_line.as_bytes())?;
-                Ok(())
-            })
-        {
+        let log_line = format!(
+            "{} | {} | {} | {:?}\nCandidate matches: {:?}\n",
+            rule, pid, task_pid, best_match, &self.candidate_matches
+        );
+        if let Err(e) = STEP_MATCHES_LOG.append(log_line.as_bytes()) {
             error!("Failed to write task match log: {}", e);
         }
     }
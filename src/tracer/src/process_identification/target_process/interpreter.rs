@@ -0,0 +1,151 @@
+//! Recognition of tools run through an interpreter, e.g. `perl /opt/conda/bin/fastqc`,
+//! `python -m multiqc`, `Rscript deseq2.R` or `java -jar picard.jar`, so they are matched and
+//! named after the tool rather than the interpreter.
+
+use std::path::Path;
+
+/// A tool run by an interpreter: its name and its command line, starting with the tool itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpretedTool {
+    pub name: String,
+    pub argv: Vec<String>,
+}
+
+/// Returns the tool run by `argv`, if `argv` runs a script, module or jar through python, perl,
+/// Rscript or java. Inline code (`-c`, `-e`) is not a tool and yields `None`.
+pub fn interpreted_tool(argv: &[String]) -> Option<InterpretedTool> {
+    let interpreter = file_name(argv.first()?);
+    let args = &argv[1..];
+    if interpreter.starts_with("python") || interpreter.starts_with("pypy") {
+        python_tool(args)
+    } else if interpreter == "perl" {
+        script_tool(args, &["-I", "-M", "-m"], &["-e", "-E"])
+    } else if interpreter == "Rscript" {
+        script_tool(args, &[], &["-e"])
+    } else if interpreter == "java" {
+        java_tool(args)
+    } else {
+        None
+    }
+}
+
+fn python_tool(args: &[String]) -> Option<InterpretedTool> {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        match arg.as_str() {
+            "-m" => {
+                let module = args.get(i + 1)?;
+                let mut argv = vec![module.clone()];
+                argv.extend_from_slice(&args[i + 2..]);
+                return Some(InterpretedTool {
+                    name: module.clone(),
+                    argv,
+                });
+            }
+            "-c" | "-" => return None,
+            "-W" | "-X" | "-Q" => i += 2,
+            arg if arg.starts_with('-') => i += 1,
+            _ => return Some(tool_from_script(&args[i..])),
+        }
+    }
+    None
+}
+
+/// Finds the script among `args`, skipping options (`options_with_value` take the next argument
+/// unless attached, e.g. `-Ilib`). Returns `None` if one of `inline_code` is given instead.
+fn script_tool(
+    args: &[String],
+    options_with_value: &[&str],
+    inline_code: &[&str],
+) -> Option<InterpretedTool> {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        if inline_code.contains(&arg.as_str()) {
+            return None;
+        } else if options_with_value.contains(&arg.as_str()) {
+            i += 2;
+        } else if arg.starts_with('-') {
+            i += 1;
+        } else {
+            return Some(tool_from_script(&args[i..]));
+        }
+    }
+    None
+}
+
+fn java_tool(args: &[String]) -> Option<InterpretedTool> {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        match arg.as_str() {
+            "-jar" => return args.get(i + 1).map(|_| tool_from_script(&args[i + 1..])),
+            "-cp" | "-classpath" | "--class-path" | "-p" | "--module-path" => i += 2,
+            arg if arg.starts_with('-') => i += 1,
+            class => {
+                // e.g. uk.ac.babraham.FastQC.FastQCApplication -> FastQCApplication
+                let name = class.rsplit('.').next().unwrap_or(class);
+                return Some(InterpretedTool {
+                    name: name.to_string(),
+                    argv: args[i..].to_vec(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// The tool is named after its script, without directory or extension
+fn tool_from_script(args: &[String]) -> InterpretedTool {
+    let script = file_name(&args[0]);
+    let name = [".py", ".pl", ".R", ".r", ".jar"]
+        .iter()
+        .find_map(|extension| script.strip_suffix(extension))
+        .filter(|name| !name.is_empty())
+        .unwrap_or(script);
+    InterpretedTool {
+        name: name.to_string(),
+        argv: args.to_vec(),
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[rstest]
+    #[case::perl_script(&["perl", "/opt/conda/bin/fastqc", "--threads", "2"], "fastqc", "/opt/conda/bin/fastqc")]
+    #[case::perl_options(&["/usr/bin/perl", "-w", "-I", "lib", "trim_galore.pl", "x.fq"], "trim_galore", "trim_galore.pl")]
+    #[case::python_script(&["python3", "-u", "bin/check_samplesheet.py", "in.csv"], "check_samplesheet", "bin/check_samplesheet.py")]
+    #[case::python_module(&["python3.11", "-m", "multiqc", "--force", "."], "multiqc", "multiqc")]
+    #[case::python_warning_option(&["python", "-W", "ignore", "-m", "cutadapt"], "cutadapt", "cutadapt")]
+    #[case::rscript(&["Rscript", "--vanilla", "deseq2_qc.r", "--count_file", "x"], "deseq2_qc", "deseq2_qc.r")]
+    #[case::java_jar(&["java", "-Xmx4g", "-jar", "/opt/picard.jar", "MarkDuplicates"], "picard", "/opt/picard.jar")]
+    #[case::java_class(&["java", "-cp", "/opt/fastqc", "uk.ac.babraham.FastQC.FastQCApplication"], "FastQCApplication", "uk.ac.babraham.FastQC.FastQCApplication")]
+    fn test_interpreted_tool(#[case] args: &[&str], #[case] name: &str, #[case] first_arg: &str) {
+        let tool = interpreted_tool(&argv(args)).unwrap();
+        assert_eq!(tool.name, name);
+        assert_eq!(tool.argv[0], first_arg);
+    }
+
+    #[rstest]
+    #[case::python_inline(&["python3", "-c", "print(1)"])]
+    #[case::perl_inline(&["perl", "-e", "print 1"])]
+    #[case::rscript_inline(&["Rscript", "-e", "1 + 1"])]
+    #[case::java_without_jar(&["java", "-jar"])]
+    #[case::interpreter_only(&["python3"])]
+    #[case::not_an_interpreter(&["STAR", "--runThreadN", "4"])]
+    fn test_not_an_interpreted_tool(#[case] args: &[&str]) {
+        assert_eq!(interpreted_tool(&argv(args)), None);
+    }
+}
@@ -0,0 +1,138 @@
+use lru::LruCache;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+/// Everything about a process the target rules look at, apart from its ancestors
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct MatchKey {
+    comm: String,
+    command_string: String,
+    argv: Vec<String>,
+    container_image: Option<String>,
+}
+
+impl From<&ProcessStartTrigger> for MatchKey {
+    fn from(process: &ProcessStartTrigger) -> Self {
+        Self {
+            comm: process.comm.clone(),
+            command_string: process.command_string.clone(),
+            argv: process.argv.clone(),
+            container_image: process.container_image.clone(),
+        }
+    }
+}
+
+/// Bounded, least-recently-used cache of the target matched by a command.
+///
+/// High-fork workloads start the same command over and over (e.g. `samtools view` in a loop), and
+/// matching it against every rule each time shows up in the process watcher's hot path. Clones
+/// share the same entries.
+#[derive(Clone)]
+pub struct MatchCache {
+    entries: Option<Arc<Mutex<LruCache<MatchKey, Option<String>>>>>,
+}
+
+impl MatchCache {
+    /// A cache of up to `capacity` commands; a capacity of 0 disables caching
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity)
+                .map(|capacity| Arc::new(Mutex::new(LruCache::new(capacity)))),
+        }
+    }
+
+    /// Returns the cached match of `process`, or computes and caches it with `get_match`
+    pub fn get_or_insert_with(
+        &self,
+        process: &ProcessStartTrigger,
+        get_match: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        let Some(entries) = &self.entries else {
+            return get_match();
+        };
+        let key = MatchKey::from(process);
+        if let Some(cached) = entries.lock().expect("match cache lock poisoned").get(&key) {
+            return cached.clone();
+        }
+        let matched = get_match();
+        entries
+            .lock()
+            .expect("match cache lock poisoned")
+            .put(key, matched.clone());
+        matched
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.as_ref().map_or(0, |entries| {
+            entries.lock().expect("match cache lock poisoned").len()
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for MatchCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MatchCache")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(argv: &[&str]) -> ProcessStartTrigger {
+        ProcessStartTrigger::from_name_and_args(1, 0, argv[0], argv)
+    }
+
+    #[test]
+    fn test_repeated_command_is_matched_once() {
+        let cache = MatchCache::new(2);
+        let mut calls = 0;
+        for _ in 0..3 {
+            let matched = cache.get_or_insert_with(&process(&["samtools", "view"]), || {
+                calls += 1;
+                Some("samtools view".to_string())
+            });
+            assert_eq!(matched.as_deref(), Some("samtools view"));
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let cache = MatchCache::new(2);
+        for command in ["a", "b", "c"] {
+            cache.get_or_insert_with(&process(&[command]), || None);
+        }
+        assert_eq!(cache.len(), 2);
+
+        // "a" was evicted as the least recently used command
+        let mut recomputed = false;
+        cache.get_or_insert_with(&process(&["a"]), || {
+            recomputed = true;
+            None
+        });
+        assert!(recomputed);
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_caching() {
+        let cache = MatchCache::new(0);
+        let mut calls = 0;
+        for _ in 0..2 {
+            cache.get_or_insert_with(&process(&["bwa", "mem"]), || {
+                calls += 1;
+                None
+            });
+        }
+        assert_eq!(calls, 2);
+        assert!(cache.is_empty());
+    }
+}
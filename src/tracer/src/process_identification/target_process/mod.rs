@@ -1,5 +1,8 @@
+pub mod interpreter;
+pub mod match_cache;
 pub mod parser;
 pub mod target;
 pub mod target_manager;
 pub mod target_match;
 mod target_set;
+pub mod wrapper;
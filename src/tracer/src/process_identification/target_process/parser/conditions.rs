@@ -26,12 +26,17 @@ pub enum SimpleCondition {
     ArgsNotContain { args_not_contain: String },
     /// Matches a command of the form `command <arg>`, where `arg` is the given string.
     FirstArgIs { first_arg_is: String },
+    /// Matches if the command has the option `flag` with the given value, as `<flag> <value>` or
+    /// `<flag>=<value>`.
+    ArgEquals { flag: String, value: String },
     /// Matches if the entire command string contains the given substring.
     CommandContains { command_contains: String },
     /// Matches if the command string does not contain the given substring.
     CommandNotContains { command_not_contains: String },
     /// Matches entire command string against a regex.
     CommandMatchesRegex { command_matches_regex: String },
+    /// Matches if the image of the container the process runs in contains the given substring.
+    ContainerImageContains { container_image_contains: String },
     /// Matches a command of the form `command <subcommand>`, where `subcommand` is one of the
     /// given subcommands.
     SubcommandIsOneOf { subcommands: Vec<String> },
@@ -84,6 +89,9 @@ impl TryFrom<Condition> for MatchType {
             Condition::Simple(SimpleCondition::FirstArgIs { first_arg_is }) => {
                 MatchType::FirstArgIs(first_arg_is.clone())
             }
+            Condition::Simple(SimpleCondition::ArgEquals { flag, value }) => {
+                MatchType::ArgEquals { flag, value }
+            }
             Condition::Simple(SimpleCondition::CommandContains { command_contains }) => {
                 MatchType::CommandContains(command_contains.clone())
             }
@@ -93,6 +101,9 @@ impl TryFrom<Condition> for MatchType {
             Condition::Simple(SimpleCondition::CommandMatchesRegex {
                 command_matches_regex,
             }) => MatchType::CommandMatchesRegex(CachedRegex::new(command_matches_regex)?),
+            Condition::Simple(SimpleCondition::ContainerImageContains {
+                container_image_contains,
+            }) => MatchType::ContainerImageContains(container_image_contains),
             Condition::Simple(SimpleCondition::SubcommandIsOneOf { subcommands }) => {
                 MatchType::SubcommandIsOneOf(subcommands.into())
             }
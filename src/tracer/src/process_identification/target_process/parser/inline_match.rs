@@ -0,0 +1,122 @@
+//! Parser for the target rules passed inline on the command line with `tracer init --match`.
+//!
+//! Each rule has the form `<kind>=<value>`:
+//! - `name=<process name>`: the process name is exactly the given value
+//! - `cmd-contains=<text>`: the command string contains the given text
+//! - `path-glob=<glob>`: the executable path (the first argument of the command) matches the
+//!   given glob, where `*` and `?` do not cross `/` and `**` matches any number of directories
+
+use crate::process_identification::target_process::target::Target;
+use crate::process_identification::target_process::target_match::{CachedRegex, MatchType};
+use anyhow::{bail, Result};
+
+/// Parses a single `--match` rule into a target. The target is displayed with the process
+/// name for `name=` rules, and with the rule itself otherwise.
+pub fn parse_inline_match(rule: &str) -> Result<Target> {
+    let Some((kind, value)) = rule.split_once('=') else {
+        bail!(
+            "invalid match rule '{}': expected <kind>=<value>, e.g. name=STAR",
+            rule
+        );
+    };
+    if value.is_empty() {
+        bail!("invalid match rule '{}': empty value", rule);
+    }
+
+    let (match_type, display_name) = match kind.trim() {
+        "name" => (
+            MatchType::ProcessNameIs(value.to_string()),
+            value.to_string(),
+        ),
+        "cmd-contains" => (
+            MatchType::CommandContains(value.to_string()),
+            rule.to_string(),
+        ),
+        "path-glob" => (
+            MatchType::ExecutablePathMatches(CachedRegex::new(glob_to_regex(value))?),
+            rule.to_string(),
+        ),
+        other => bail!(
+            "invalid match rule '{}': unknown kind '{}' (expected name, cmd-contains or path-glob)",
+            rule,
+            other
+        ),
+    };
+
+    Ok(Target::with_display_name(match_type, display_name))
+}
+
+/// Parses all `--match` rules, failing on the first invalid one
+pub fn parse_inline_matches(rules: &[String]) -> Result<Vec<Target>> {
+    rules.iter().map(|rule| parse_inline_match(rule)).collect()
+}
+
+/// Translates a path glob into an anchored regex
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // `**/` also matches no directory at all
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+    fn make_process(comm: &str, argv: &[&str]) -> ProcessStartTrigger {
+        ProcessStartTrigger::from_name_and_args(0, 0, comm, argv)
+    }
+
+    #[test]
+    fn test_parse_inline_matches() {
+        let star = parse_inline_match("name=STAR").unwrap();
+        let genome = parse_inline_match("cmd-contains=--genomeGenerate").unwrap();
+        let conda = parse_inline_match("path-glob=/opt/conda/**/bin/*").unwrap();
+
+        let process = make_process(
+            "STAR",
+            &[
+                "/opt/conda/envs/rnaseq/bin/STAR",
+                "--runMode",
+                "--genomeGenerate",
+            ],
+        );
+        assert_eq!(star.get_match(&process).as_deref(), Some("STAR"));
+        assert_eq!(
+            genome.get_match(&process).as_deref(),
+            Some("cmd-contains=--genomeGenerate")
+        );
+        assert!(conda.matches(&process));
+        assert!(conda.matches(&make_process("python", &["/opt/conda/bin/python"])));
+        assert!(!conda.matches(&make_process("python", &["/usr/bin/python"])));
+        assert!(!conda.matches(&make_process("x", &["/opt/conda/bin/sub/x"])));
+    }
+
+    #[test]
+    fn test_parse_inline_match_errors() {
+        assert!(parse_inline_match("STAR").is_err());
+        assert!(parse_inline_match("name=").is_err());
+        assert!(parse_inline_match("exe=STAR")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown kind 'exe'"));
+    }
+}
@@ -1,3 +1,4 @@
 pub(crate) mod conditions;
+pub mod inline_match;
 mod rule;
 pub mod yaml_rules_parser;
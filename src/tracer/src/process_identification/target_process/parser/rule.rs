@@ -4,4 +4,8 @@ use crate::process_identification::target_process::parser::conditions::Condition
 pub struct Rule {
     pub display_name: String,
     pub condition: Condition,
+    /// Condition one of the ancestors of a matching process must also meet
+    pub force_ancestor_to_match: Option<Condition>,
+    /// Conditions that discard a process matching `condition`
+    pub filter_out: Option<Vec<Condition>>,
 }
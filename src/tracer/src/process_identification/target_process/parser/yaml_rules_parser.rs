@@ -3,10 +3,45 @@ use crate::process_identification::target_process::parser::conditions::{
 };
 use crate::process_identification::target_process::parser::rule::Rule;
 use crate::process_identification::target_process::target::Target;
+use crate::process_identification::target_process::target_match::MatchType;
 use crate::utils::yaml::{Yaml, YamlExt, YamlFile};
 use anyhow::{anyhow, bail, Result};
 use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use tracing::error;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+use yaml_rust2::YamlLoader;
+
+/// Error in a user-provided rules file, pointing at the offending line when known
+#[derive(Debug, Clone, PartialEq)]
+pub struct RulesError {
+    pub source: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Display name (or position, if it has none) of the offending rule
+    pub rule: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        if let Some(rule) = &self.rule {
+            write!(f, ": rule {}", rule)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for RulesError {}
 
 pub fn load_targets_from_yaml(yaml_files: &[YamlFile]) -> HashSet<Target> {
     let mut result = HashSet::new();
@@ -21,15 +56,283 @@ pub fn load_targets_from_yaml(yaml_files: &[YamlFile]) -> HashSet<Target> {
     result
 }
 
+/// Reads and parses a user-provided rules file
+pub fn load_rules_file(path: &Path) -> Result<Vec<Target>, RulesError> {
+    let source = path.display().to_string();
+    let content = std::fs::read_to_string(path).map_err(|e| RulesError {
+        source: source.clone(),
+        line: None,
+        column: None,
+        rule: None,
+        message: format!("cannot read file: {}", e),
+    })?;
+    parse_rules(&source, &content)
+}
+
+/// Rules of several rules files merged together, see `merge_rules`
+#[derive(Debug, Default)]
+pub struct MergedRules {
+    pub targets: Vec<Target>,
+    /// Display names of the rules a later file redefined
+    pub overridden: Vec<String>,
+}
+
+/// Splits a list of rules files and directories, separated like `PATH` (`:` on Unix), so the
+/// paths may contain commas
+pub fn split_rules_paths(paths: &str) -> Vec<PathBuf> {
+    std::env::split_paths(paths)
+        .filter(|path| !path.as_os_str().is_empty())
+        .collect()
+}
+
+/// Reads and parses the rules files of `paths` and merges them in order with `merge_rules`. A
+/// directory stands for the `.yml` and `.yaml` files it holds, in name order, so a shared base
+/// file can be overridden by pipeline-specific ones named after it.
+pub fn load_rules_files(paths: &[PathBuf]) -> Result<MergedRules, RulesError> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            files.extend(rules_files_in(path)?);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let mut parsed = Vec::with_capacity(files.len());
+    for file in files {
+        let targets = load_rules_file(&file)?;
+        parsed.push((file.display().to_string(), targets));
+    }
+    merge_rules(parsed)
+}
+
+fn rules_files_in(dir: &Path) -> Result<Vec<PathBuf>, RulesError> {
+    let error = |message| RulesError {
+        source: dir.display().to_string(),
+        line: None,
+        column: None,
+        rule: None,
+        message,
+    };
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| error(format!("cannot read directory: {}", e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == "yml" || extension == "yaml")
+        })
+        .collect();
+    if files.is_empty() {
+        return Err(error("no .yml or .yaml rules files".into()));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Merges the rules of `(source, rules)` files, in order. A rule replaces the rule of an earlier
+/// file with the same display name, taking its precedence, and other rules are added after the
+/// earlier ones. Two rules of a file with the same display name, or two rules with the same
+/// condition under different names, conflict: which one a process is reported as would depend on
+/// their order.
+pub fn merge_rules(files: Vec<(String, Vec<Target>)>) -> Result<MergedRules, RulesError> {
+    let conflict = |source: &str, target: &Target, message: String| RulesError {
+        source: source.to_string(),
+        line: None,
+        column: None,
+        rule: Some(format!("'{}'", target.display_name())),
+        message,
+    };
+
+    // each merged rule along with the file it comes from
+    let mut merged: Vec<(String, Target)> = Vec::new();
+    let mut overridden = Vec::new();
+    for (source, targets) in files {
+        let mut names = HashSet::new();
+        for target in targets {
+            if !names.insert(target.display_name().to_string()) {
+                return Err(conflict(&source, &target, "defined more than once".into()));
+            }
+            if let Some((other_source, other)) = merged.iter().find(|(_, other)| {
+                other.display_name() != target.display_name() && other.has_same_condition(&target)
+            }) {
+                return Err(conflict(
+                    &source,
+                    &target,
+                    format!(
+                        "same condition as rule '{}' of {}",
+                        other.display_name(),
+                        other_source
+                    ),
+                ));
+            }
+
+            match merged
+                .iter_mut()
+                .find(|(_, other)| other.display_name() == target.display_name())
+            {
+                Some(earlier) => {
+                    overridden.push(target.display_name().to_string());
+                    *earlier = (source.clone(), target);
+                }
+                None => merged.push((source.clone(), target)),
+            }
+        }
+    }
+
+    Ok(MergedRules {
+        targets: merged.into_iter().map(|(_, target)| target).collect(),
+        overridden,
+    })
+}
+
+/// Parses the `rules` of a rules file, failing on the first invalid rule rather than skipping it.
+/// `source` names the file in error messages.
+pub fn parse_rules(source: &str, yaml_str: &str) -> Result<Vec<Target>, RulesError> {
+    let error =
+        |line: Option<usize>, column: Option<usize>, rule: Option<String>, message| RulesError {
+            source: source.to_string(),
+            line,
+            column,
+            rule,
+            message,
+        };
+
+    let docs = YamlLoader::load_from_str(yaml_str).map_err(|e| {
+        error(
+            Some(e.marker().line()),
+            Some(e.marker().col() + 1),
+            None,
+            e.info().to_string(),
+        )
+    })?;
+
+    let rules = docs
+        .into_iter()
+        .next()
+        .ok_or_else(|| error(None, None, None, "file is empty".into()))?
+        .into_hash()
+        .ok_or_else(|| {
+            error(
+                None,
+                None,
+                None,
+                "expected a mapping at the top level".into(),
+            )
+        })?
+        .remove(&Yaml::String("rules".into()))
+        .ok_or_else(|| error(None, None, None, "missing top-level key 'rules'".into()))?
+        .into_vec()
+        .ok_or_else(|| error(None, None, None, "expected 'rules' to be a list".into()))?;
+
+    if rules.is_empty() {
+        return Err(error(None, None, None, "no rules defined".into()));
+    }
+
+    let mut targets = Vec::with_capacity(rules.len());
+    for (index, rule) in rules.into_iter().enumerate() {
+        let name = rule
+            .optional_string("display_name")
+            .ok()
+            .flatten()
+            .map(|name| format!("'{}'", name))
+            .unwrap_or_else(|| format!("#{}", index + 1));
+
+        match Target::try_from(rule) {
+            Ok(target) => targets.push(target),
+            Err(e) => {
+                let line = rule_lines(yaml_str).get(index).copied();
+                return Err(error(line, None, Some(name), format!("{:#}", e)));
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+/// Returns the line each item of the top-level `rules` list starts at
+fn rule_lines(yaml_str: &str) -> Vec<usize> {
+    let mut receiver = RuleLines::default();
+    let _ = Parser::new_from_str(yaml_str).load(&mut receiver, false);
+    receiver.lines
+}
+
+enum Frame {
+    Mapping { key: Option<String>, is_key: bool },
+    Sequence,
+}
+
+#[derive(Default)]
+struct RuleLines {
+    stack: Vec<Frame>,
+    lines: Vec<usize>,
+}
+
+impl RuleLines {
+    /// Tracks keys of the enclosing mapping, since mapping entries alternate keys and values
+    fn on_node(&mut self, scalar: Option<&str>) {
+        if let Some(Frame::Mapping { key, is_key }) = self.stack.last_mut() {
+            if *is_key {
+                *key = scalar.map(str::to_string);
+            }
+            *is_key = !*is_key;
+        }
+    }
+
+    fn in_top_level_rules(&self) -> bool {
+        matches!(
+            self.stack.as_slice(),
+            [Frame::Mapping { key: Some(key), .. }, Frame::Sequence] if key == "rules"
+        )
+    }
+}
+
+impl MarkedEventReceiver for RuleLines {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => self.on_node(Some(&value)),
+            Event::Alias(..) => self.on_node(None),
+            Event::MappingStart(..) => {
+                if self.in_top_level_rules() {
+                    self.lines.push(mark.line());
+                }
+                self.on_node(None);
+                self.stack.push(Frame::Mapping {
+                    key: None,
+                    is_key: true,
+                });
+            }
+            Event::SequenceStart(..) => {
+                self.on_node(None);
+                self.stack.push(Frame::Sequence);
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
 impl TryFrom<Yaml> for Target {
     type Error = anyhow::Error;
 
     fn try_from(yaml: Yaml) -> Result<Self> {
         let rule: Rule = yaml.try_into()?;
-        Ok(Target::with_display_name(
-            rule.condition.try_into()?,
-            rule.display_name,
-        ))
+        let mut target = Target::with_display_name(rule.condition.try_into()?, rule.display_name);
+        if let Some(condition) = rule.force_ancestor_to_match {
+            target = target.with_forced_ancestor(condition.try_into()?);
+        }
+        if let Some(conditions) = rule.filter_out {
+            target = target.with_filter_out(
+                conditions
+                    .into_iter()
+                    .map(MatchType::try_from)
+                    .collect::<Result<Vec<_>>>()?,
+            );
+        }
+        Ok(target)
     }
 }
 
@@ -38,10 +341,27 @@ impl TryFrom<Yaml> for Rule {
 
     fn try_from(yaml: Yaml) -> Result<Self> {
         let display_name = yaml.required_string("display_name")?;
-        let condition = yaml.required("condition")?.try_into()?;
+        let condition: Condition = yaml.required("condition")?.try_into()?;
+        // either `true` to require an ancestor matching the rule itself, or a condition
+        let force_ancestor_to_match = match yaml.optional("force_ancestor_to_match") {
+            None | Some(Yaml::Boolean(false)) => None,
+            Some(Yaml::Boolean(true)) => Some(condition.clone()),
+            Some(ancestor_condition) => Some(ancestor_condition.try_into()?),
+        };
+        let filter_out = yaml
+            .optional_vec("filter_out")?
+            .map(|conditions| {
+                conditions
+                    .iter()
+                    .map(Condition::try_from)
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
         Ok(Rule {
             display_name,
             condition,
+            force_ancestor_to_match,
+            filter_out,
         })
     }
 }
@@ -56,9 +376,11 @@ impl TryFrom<&Yaml> for Condition {
             "min_args",
             "args_not_contain",
             "first_arg_is",
+            "arg_equals",
             "command_contains",
             "command_not_contains",
             "command_matches_regex",
+            "container_image_contains",
             "subcommand_is_one_of",
             "java",
         ];
@@ -83,6 +405,10 @@ impl TryFrom<&Yaml> for Condition {
                     "first_arg_is" => Ok(Condition::Simple(SimpleCondition::FirstArgIs {
                         first_arg_is: val.to_string()?,
                     })),
+                    "arg_equals" => Ok(Condition::Simple(SimpleCondition::ArgEquals {
+                        flag: val.required_string("flag")?,
+                        value: val.required_string("value")?,
+                    })),
                     "command_contains" => Ok(Condition::Simple(SimpleCondition::CommandContains {
                         command_contains: val.to_string()?,
                     })),
@@ -96,6 +422,11 @@ impl TryFrom<&Yaml> for Condition {
                             command_matches_regex: val.to_string()?,
                         }))
                     }
+                    "container_image_contains" => {
+                        Ok(Condition::Simple(SimpleCondition::ContainerImageContains {
+                            container_image_contains: val.to_string()?,
+                        }))
+                    }
                     "subcommand_is_one_of" => {
                         let subcommands = val
                             .as_vec()
@@ -153,3 +484,207 @@ impl TryFrom<&Yaml> for Condition {
         bail!("Invalid step: {:?}", yaml);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(yaml: &str) -> RulesError {
+        parse_rules("rules.yml", yaml).unwrap_err()
+    }
+
+    #[test]
+    fn test_rules_paths_may_contain_commas() {
+        let paths = std::env::join_paths(["/data/rules,v2.yml", "", "/etc/tracer/rules"]).unwrap();
+        assert_eq!(
+            split_rules_paths(paths.to_str().unwrap()),
+            vec![
+                PathBuf::from("/data/rules,v2.yml"),
+                PathBuf::from("/etc/tracer/rules")
+            ]
+        );
+        assert!(split_rules_paths("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_valid_rules() {
+        let targets = parse_rules(
+            "rules.yml",
+            r#"
+rules:
+  - display_name: "STAR"
+    condition:
+      process_name_is: "STAR"
+  - display_name: "bwa mem"
+    condition:
+      and:
+        - process_name_is: "bwa"
+        - first_arg_is: "mem"
+"#,
+        )
+        .unwrap();
+        assert_eq!(targets.len(), 2);
+    }
+
+    #[test]
+    fn test_syntax_error_reports_line_and_column() {
+        let error = parse_error("rules:\n  - display_name: \"STAR\n    condition: {\n");
+        assert!(error.line.is_some());
+        assert!(error.column.is_some());
+        assert!(error.to_string().starts_with("rules.yml:"));
+    }
+
+    #[test]
+    fn test_invalid_rule_reports_its_line_and_name() {
+        let error = parse_error(
+            r#"rules:
+  - display_name: "STAR"
+    condition:
+      process_name_is: "STAR"
+  - display_name: "broken"
+    condition:
+      unknown_condition: "x"
+"#,
+        );
+        assert_eq!(error.line, Some(5));
+        assert_eq!(error.rule.as_deref(), Some("'broken'"));
+    }
+
+    #[test]
+    fn test_rule_without_display_name_is_reported_by_position() {
+        let error = parse_error(
+            r#"rules:
+  - condition:
+      process_name_is: "STAR"
+"#,
+        );
+        assert_eq!(error.rule.as_deref(), Some("#1"));
+        assert!(error.message.contains("display_name"));
+    }
+
+    #[test]
+    fn test_structural_errors() {
+        assert_eq!(parse_error("").message, "file is empty");
+        assert_eq!(
+            parse_error("- a\n- b\n").message,
+            "expected a mapping at the top level"
+        );
+        assert_eq!(
+            parse_error("targets: []\n").message,
+            "missing top-level key 'rules'"
+        );
+        assert_eq!(
+            parse_error("rules: 3\n").message,
+            "expected 'rules' to be a list"
+        );
+        assert_eq!(parse_error("rules: []\n").message, "no rules defined");
+    }
+
+    #[test]
+    fn test_invalid_min_args_type() {
+        let error = parse_error(
+            r#"rules:
+  - display_name: "x"
+    condition:
+      min_args: "two"
+"#,
+        );
+        assert_eq!(error.line, Some(2));
+        assert!(error.message.contains("Expected a number"));
+    }
+
+    fn rules(source: &str, yaml: &str) -> (String, Vec<Target>) {
+        (source.to_string(), parse_rules(source, yaml).unwrap())
+    }
+
+    const BASE: &str = r#"rules:
+  - display_name: "STAR"
+    condition:
+      process_name_is: "STAR"
+  - display_name: "bwa"
+    condition:
+      process_name_is: "bwa"
+"#;
+
+    #[test]
+    fn test_later_files_override_and_add_rules() {
+        let merged = merge_rules(vec![
+            rules("base.yml", BASE),
+            rules(
+                "pipeline.yml",
+                r#"rules:
+  - display_name: "salmon"
+    condition:
+      process_name_is: "salmon"
+  - display_name: "STAR"
+    condition:
+      and:
+        - process_name_is: "STAR"
+        - min_args: 2
+"#,
+            ),
+        ])
+        .unwrap();
+
+        let names: Vec<_> = merged.targets.iter().map(Target::display_name).collect();
+        assert_eq!(names, vec!["STAR", "bwa", "salmon"]);
+        assert_eq!(merged.overridden, vec!["STAR"]);
+        // the override replaced the base rule
+        assert_ne!(merged.targets[0], parse_rules("x", BASE).unwrap()[0]);
+    }
+
+    #[test]
+    fn test_merge_order_decides_which_override_wins() {
+        let override_yaml =
+            "rules:\n  - display_name: \"bwa\"\n    condition:\n      process_name_is: \"bwa-mem2\"\n";
+        let merged = merge_rules(vec![
+            rules("override.yml", override_yaml),
+            rules("base.yml", BASE),
+        ])
+        .unwrap();
+        let names: Vec<_> = merged.targets.iter().map(Target::display_name).collect();
+        assert_eq!(names, vec!["bwa", "STAR"]);
+        assert_eq!(merged.targets[0], parse_rules("x", BASE).unwrap()[1]);
+    }
+
+    #[test]
+    fn test_conflicting_rules_are_reported() {
+        let error = merge_rules(vec![
+            rules("base.yml", BASE),
+            rules(
+                "pipeline.yml",
+                "rules:\n  - display_name: \"aligner\"\n    condition:\n      process_name_is: \"bwa\"\n",
+            ),
+        ])
+        .unwrap_err();
+        assert_eq!(error.source, "pipeline.yml");
+        assert_eq!(error.rule.as_deref(), Some("'aligner'"));
+        assert_eq!(error.message, "same condition as rule 'bwa' of base.yml");
+
+        let duplicated = format!("{}{}", BASE, &BASE["rules:\n".len()..]);
+        let error = merge_rules(vec![rules("base.yml", &duplicated)]).unwrap_err();
+        assert_eq!(error.rule.as_deref(), Some("'STAR'"));
+        assert_eq!(error.message, "defined more than once");
+    }
+
+    #[test]
+    fn test_directory_files_are_merged_in_name_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("00-base.yml"), BASE).unwrap();
+        std::fs::write(
+            dir.path().join("10-rnaseq.yaml"),
+            "rules:\n  - display_name: \"STAR\"\n    condition:\n      process_name_contains: \"STAR\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "not rules").unwrap();
+
+        let merged =
+            load_rules_files(&split_rules_paths(&format!("{}, ", dir.path().display()))).unwrap();
+        assert_eq!(merged.targets.len(), 2);
+        assert_eq!(merged.overridden, vec!["STAR"]);
+
+        let empty = tempfile::TempDir::new().unwrap();
+        let error = load_rules_files(&[empty.path().to_path_buf()]).unwrap_err();
+        assert_eq!(error.message, "no .yml or .yaml rules files");
+    }
+}
@@ -7,6 +7,12 @@ use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 pub struct Target {
     match_type: MatchType,
     display_name: String,
+    /// If set, a process only matches if one of its ancestors matches this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    force_ancestor_to_match: Option<MatchType>,
+    /// A process matching any of these is discarded even if it matches `match_type`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filter_out: Option<Vec<MatchType>>,
 }
 
 impl Target {
@@ -14,6 +20,8 @@ impl Target {
         Self {
             match_type,
             display_name: "unknown".to_string(),
+            force_ancestor_to_match: None,
+            filter_out: None,
         }
     }
 
@@ -21,9 +29,23 @@ impl Target {
         Self {
             match_type,
             display_name,
+            force_ancestor_to_match: None,
+            filter_out: None,
         }
     }
 
+    /// Only matches processes that have an ancestor matching `ancestor_match_type`
+    pub fn with_forced_ancestor(mut self, ancestor_match_type: MatchType) -> Self {
+        self.force_ancestor_to_match = Some(ancestor_match_type);
+        self
+    }
+
+    /// Discards processes that match any of `filter_out`, e.g. `--version` probes of a tool
+    pub fn with_filter_out(mut self, filter_out: Vec<MatchType>) -> Self {
+        self.filter_out = Some(filter_out);
+        self
+    }
+
     pub fn match_type_mut(&mut self) -> &mut MatchType {
         &mut self.match_type
     }
@@ -32,13 +54,49 @@ impl Target {
         &self.display_name
     }
 
+    /// Whether `other` matches the same processes, whatever its display name
+    pub fn has_same_condition(&self, other: &Target) -> bool {
+        self.match_type == other.match_type
+            && self.force_ancestor_to_match == other.force_ancestor_to_match
+            && self.filter_out == other.filter_out
+    }
+
+    pub fn should_force_ancestor_to_match(&self) -> bool {
+        self.force_ancestor_to_match.is_some()
+    }
+
+    /// Whether matching needs more than `match_type`, so the target cannot be looked up by
+    /// process name alone
+    pub fn has_extra_constraints(&self) -> bool {
+        self.force_ancestor_to_match.is_some() || self.filter_out.is_some()
+    }
+
     pub fn matches(&self, process: &ProcessStartTrigger) -> bool {
-        self.match_type.matches(process)
+        self.matches_in_tree(process, &[])
+    }
+
+    /// Like `matches`, with `ancestors` the known ancestors of `process`, parent first
+    pub fn matches_in_tree(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> bool {
+        self.get_match_in_tree(process, ancestors).is_some()
     }
 
     pub fn get_match(&self, process: &ProcessStartTrigger) -> Option<String> {
+        self.get_match_in_tree(process, &[])
+    }
+
+    /// Like `get_match`, with `ancestors` the known ancestors of `process`, parent first
+    pub fn get_match_in_tree(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> Option<String> {
         self.match_type
             .get_match(process)
+            .filter(|_| !self.is_filtered_out(process) && self.ancestor_matches(ancestors))
             .map(|process_match| match process_match {
                 ProcessMatch::Simple => self.display_name().to_string(),
                 ProcessMatch::Subcommand(sub_command) => {
@@ -46,4 +104,21 @@ impl Target {
                 }
             })
     }
+
+    fn is_filtered_out(&self, process: &ProcessStartTrigger) -> bool {
+        self.filter_out.as_ref().is_some_and(|filter_out| {
+            filter_out
+                .iter()
+                .any(|match_type| match_type.matches(process))
+        })
+    }
+
+    fn ancestor_matches(&self, ancestors: &[&ProcessStartTrigger]) -> bool {
+        match &self.force_ancestor_to_match {
+            Some(ancestor_match_type) => ancestors
+                .iter()
+                .any(|ancestor| ancestor_match_type.matches(ancestor)),
+            None => true,
+        }
+    }
 }
@@ -1,12 +1,26 @@
+use crate::process_identification::target_process::match_cache::MatchCache;
 use crate::process_identification::target_process::parser::yaml_rules_parser::load_targets_from_yaml;
+use crate::process_identification::target_process::target::Target;
 use crate::process_identification::target_process::target_set::TargetSet;
+use crate::process_identification::target_process::wrapper::{
+    default_wrapper_patterns, unwrap_command, WrapperPattern,
+};
 use crate::utils::yaml::YamlFile;
 use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 
+/// Number of distinct commands whose match is cached
+const MATCH_CACHE_SIZE: usize = 4096;
+
 #[derive(Debug, Clone)]
 pub struct TargetManager {
     exclude: TargetSet,
+    /// User-provided target sets, matched in order before the built-in ones
+    custom: Vec<TargetSet>,
     targets: TargetSet,
+    /// Launchers (e.g. `srun`) whose wrapped command is matched instead of the launcher itself
+    wrappers: Vec<WrapperPattern>,
+    /// Matches of processes without ancestors to check, emptied whenever the rules change
+    cache: MatchCache,
 }
 
 impl TargetManager {
@@ -15,18 +29,103 @@ impl TargetManager {
         let exclude = load_targets_from_yaml(exclude_files);
         Self {
             targets: targets.into(),
+            custom: Vec::new(),
             exclude: exclude.into(),
+            wrappers: default_wrapper_patterns(),
+            cache: MatchCache::new(MATCH_CACHE_SIZE),
         }
     }
 
-    /// Match a process against all targets and return the first matching target name
+    /// Adds user-provided targets, which take precedence over the built-in rules and over
+    /// targets from later calls (but not from earlier ones)
+    pub fn with_custom_targets(mut self, targets: impl IntoIterator<Item = Target>) -> Self {
+        self.custom.push(targets.into());
+        self.cache = MatchCache::new(MATCH_CACHE_SIZE);
+        self
+    }
+
+    /// Drops the built-in rules, leaving only the user-provided targets and the exclude rules
+    pub fn without_builtin_targets(mut self) -> Self {
+        self.targets = TargetSet::new(Vec::new());
+        self.cache = MatchCache::new(MATCH_CACHE_SIZE);
+        self
+    }
+
+    /// Replaces the wrapper patterns, see `wrapper::default_wrapper_patterns` for the defaults
+    pub fn with_wrappers(mut self, wrappers: Vec<WrapperPattern>) -> Self {
+        self.wrappers = wrappers;
+        self.cache = MatchCache::new(MATCH_CACHE_SIZE);
+        self
+    }
+
+    /// Replaces the match cache with one of up to `size` commands; 0 disables caching
+    pub fn with_match_cache_size(mut self, size: usize) -> Self {
+        self.cache = MatchCache::new(size);
+        self
+    }
+
+    /// Match a process against all targets and return the first matching target name.
+    ///
+    /// A process running a known wrapper (e.g. `srun STAR ...`) or an interpreter (e.g.
+    /// `perl /opt/conda/bin/fastqc`) is matched by the tool it runs first, falling back to the
+    /// command itself.
     pub fn get_target_match(&self, process: &ProcessStartTrigger) -> Option<String> {
+        self.get_target_match_in_tree(process, &[])
+    }
+
+    /// Like `get_target_match`, with `ancestors` the known ancestors of `process`, parent first,
+    /// which rules with `force_ancestor_to_match` are checked against
+    pub fn get_target_match_in_tree(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> Option<String> {
+        // with ancestors, the match also depends on the process tree
+        if ancestors.is_empty() {
+            self.cache
+                .get_or_insert_with(process, || self.match_command(process, ancestors))
+        } else {
+            self.match_command(process, ancestors)
+        }
+    }
+
+    fn match_command(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> Option<String> {
+        unwrap_command(&self.wrappers, process)
+            .and_then(|inner| self.match_process(&inner, ancestors))
+            .or_else(|| self.match_process(process, ancestors))
+    }
+
+    /// Number of rules processes are matched against, user-provided and built-in, leaving out
+    /// the exclude rules. With none, no tool is ever recorded.
+    pub fn rule_count(&self) -> usize {
+        self.custom.iter().map(TargetSet::len).sum::<usize>() + self.targets.len()
+    }
+
+    /// Whether matching needs the ancestors of processes, see `get_target_match_in_tree`
+    pub fn has_forced_ancestors(&self) -> bool {
+        self.exclude.has_forced_ancestors()
+            || self.custom.iter().any(TargetSet::has_forced_ancestors)
+            || self.targets.has_forced_ancestors()
+    }
+
+    fn match_process(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> Option<String> {
         // exclude rules take precedence over rules
         // if one of the exclude rules matches, return None, because we want to exclude the process
-        if self.exclude.matches(process) {
+        if self.exclude.matches_in_tree(process, ancestors) {
             None
         } else {
-            self.targets.get_match(process)
+            self.custom
+                .iter()
+                .find_map(|targets| targets.get_match_in_tree(process, ancestors))
+                .or_else(|| self.targets.get_match_in_tree(process, ancestors))
         }
     }
 }
@@ -46,6 +145,8 @@ impl Default for TargetManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process_identification::target_process::parser::yaml_rules_parser::parse_rules;
+    use rstest::rstest;
     use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 
     fn make_process(comm: &str, argv: &[&str]) -> ProcessStartTrigger {
@@ -167,4 +268,256 @@ mod tests {
         let matched = manager.get_target_match(&process);
         assert_eq!(matched.as_deref(), Some("FastQC"));
     }
+
+    #[test]
+    fn test_container_image_contains() {
+        const RULES: &str = r#"
+rules:
+  - rule_name: STAR container
+    display_name: STAR
+    condition:
+      container_image_contains: biocontainers/star
+"#;
+        let manager = TargetManager::new(&[YamlFile::from_embedded_str(RULES)], &[]);
+
+        let mut process = make_process("STAR", &["/usr/local/bin/STAR", "--runThreadN", "4"]);
+        // processes running on the host never match
+        assert_eq!(manager.get_target_match(&process), None);
+
+        process.container_image =
+            Some("quay.io/biocontainers/star:2.7.10b--h9ee0642_0".to_string());
+        assert_eq!(manager.get_target_match(&process).as_deref(), Some("STAR"));
+
+        process.container_image = Some("quay.io/biocontainers/bwa:0.7.17".to_string());
+        assert_eq!(manager.get_target_match(&process), None);
+    }
+
+    #[test]
+    fn test_custom_targets_take_precedence() {
+        const CUSTOM_RULES: &str = r#"
+rules:
+  - display_name: custom samtools
+    condition:
+      process_name_is: samtools
+"#;
+        let custom = parse_rules("custom.yml", CUSTOM_RULES).unwrap();
+        let rule_files = [YamlFile::from_src_path(
+            "src/process_identification/target_process/yml_rules/tracer.rules.yml",
+        )];
+        let manager = TargetManager::new(&rule_files, &[]).with_custom_targets(custom);
+
+        let process = make_process("samtools", &["samtools", "sort", "file.bam"]);
+        assert_eq!(
+            manager.get_target_match(&process).as_deref(),
+            Some("custom samtools")
+        );
+
+        // built-in rules still apply to other processes
+        let process = make_process("cat", &["cat", "input1/index.1.fastq.gz"]);
+        assert_eq!(
+            manager.get_target_match(&process).as_deref(),
+            Some("cat FASTQ")
+        );
+    }
+
+    #[test]
+    fn test_wrapped_tools_match_inner_tool() {
+        const RULES: &str = r#"
+rules:
+  - display_name: STAR
+    condition:
+      process_name_is: STAR
+"#;
+        let manager = TargetManager::new(&[YamlFile::from_embedded_str(RULES)], &[]);
+
+        let process = make_process("srun", &["srun", "-n", "4", "STAR", "--runThreadN", "4"]);
+        assert_eq!(manager.get_target_match(&process).as_deref(), Some("STAR"));
+
+        let process = make_process("bsub", &["bsub", "-q", "long", "STAR", "--runThreadN", "4"]);
+        assert_eq!(manager.get_target_match(&process).as_deref(), Some("STAR"));
+
+        let manager = manager.with_wrappers(Vec::new());
+        assert_eq!(manager.get_target_match(&process), None);
+    }
+
+    #[test]
+    fn test_force_ancestor_to_match() {
+        const RULES: &str = r#"
+rules:
+  - display_name: nextflow python
+    condition:
+      process_name_is: python
+    force_ancestor_to_match:
+      command_contains: nextflow.cli.Launcher
+  - display_name: nested snakemake
+    condition:
+      process_name_is: snakemake
+    force_ancestor_to_match: true
+"#;
+        let manager = TargetManager::new(&[YamlFile::from_embedded_str(RULES)], &[]);
+        assert!(manager.has_forced_ancestors());
+
+        let nextflow = make_process(
+            "java",
+            &[
+                "java",
+                "-cp",
+                "nextflow.jar",
+                "nextflow.cli.Launcher",
+                "run",
+            ],
+        );
+        let bash = make_process("bash", &["bash", ".command.sh"]);
+        let python = make_process("python", &["python", "script.py"]);
+
+        assert_eq!(
+            manager
+                .get_target_match_in_tree(&python, &[&bash, &nextflow])
+                .as_deref(),
+            Some("nextflow python")
+        );
+        // launched outside of Nextflow
+        assert_eq!(manager.get_target_match_in_tree(&python, &[&bash]), None);
+        assert_eq!(manager.get_target_match(&python), None);
+
+        // `true` requires an ancestor matching the rule itself
+        let snakemake = make_process("snakemake", &["snakemake", "--cores", "4"]);
+        assert_eq!(manager.get_target_match_in_tree(&snakemake, &[&bash]), None);
+        assert_eq!(
+            manager
+                .get_target_match_in_tree(&snakemake, &[&bash, &snakemake])
+                .as_deref(),
+            Some("nested snakemake")
+        );
+    }
+
+    #[test]
+    fn test_filter_out() {
+        const RULES: &str = r#"
+rules:
+  - display_name: samtools {subcommand}
+    condition:
+      and:
+        - process_name_is: samtools
+        - subcommand_is_one_of: [sort, index]
+    filter_out:
+      - command_contains: --version
+  - display_name: bwa
+    condition:
+      process_name_is: bwa
+    filter_out:
+      - command_contains: --version
+      - min_args: 3
+"#;
+        let manager = TargetManager::new(&[YamlFile::from_embedded_str(RULES)], &[]);
+
+        let process = make_process("samtools", &["samtools", "sort", "file.bam"]);
+        assert_eq!(
+            manager.get_target_match(&process).as_deref(),
+            Some("samtools sort")
+        );
+        let process = make_process("samtools", &["samtools", "sort", "--version"]);
+        assert_eq!(manager.get_target_match(&process), None);
+
+        let process = make_process("bwa", &["bwa", "index"]);
+        assert_eq!(manager.get_target_match(&process).as_deref(), Some("bwa"));
+        let process = make_process("bwa", &["bwa", "--version"]);
+        assert_eq!(manager.get_target_match(&process), None);
+        let process = make_process("bwa", &["bwa", "mem", "ref.fa", "reads.fq"]);
+        assert_eq!(manager.get_target_match(&process), None);
+    }
+
+    #[test]
+    fn test_arg_equals() {
+        const RULES: &str = r#"
+rules:
+  - display_name: STAR align
+    condition:
+      and:
+        - process_name_is: STAR
+        - arg_equals:
+            flag: --runMode
+            value: alignReads
+"#;
+        let manager = TargetManager::new(&[YamlFile::from_embedded_str(RULES)], &[]);
+
+        for argv in [
+            &["STAR", "--runThreadN", "4", "--runMode", "alignReads"][..],
+            &["STAR", "--runMode=alignReads", "--runThreadN", "4"][..],
+        ] {
+            let process = make_process("STAR", argv);
+            assert_eq!(
+                manager.get_target_match(&process).as_deref(),
+                Some("STAR align")
+            );
+        }
+
+        for argv in [
+            // other value
+            &["STAR", "--runMode", "genomeGenerate"][..],
+            &["STAR", "--runMode=genomeGenerate"][..],
+            // missing flag, or flag without a value
+            &["STAR", "--runThreadN", "4"][..],
+            &["STAR", "--runMode"][..],
+            // only a prefix of the flag
+            &["STAR", "--runModeX=alignReads"][..],
+        ] {
+            let process = make_process("STAR", argv);
+            assert_eq!(manager.get_target_match(&process), None);
+        }
+    }
+
+    #[rstest]
+    #[case::star(&["STAR", "--runThreadN", "8", "--readFilesIn", "R1.fq.gz"], "STAR align")]
+    #[case::star_index(&["STAR", "--runMode", "genomeGenerate", "--genomeDir", "idx"], "STAR index")]
+    #[case::bwa(&["bwa", "mem", "-t", "8", "ref.fa", "R1.fq"], "bwa mem")]
+    #[case::bwa_mem2(&["bwa-mem2", "mem", "-t", "8", "ref.fa", "R1.fq"], "bwa-mem2 mem")]
+    #[case::samtools(&["samtools", "sort", "-@", "4", "-o", "x.bam", "in.bam"], "samtools sort")]
+    #[case::bcftools(&["bcftools", "call", "-mv", "-Oz", "calls.bcf"], "bcftools call")]
+    #[case::gatk(&["gatk", "HaplotypeCaller", "-R", "ref.fa", "-I", "x.bam"], "GATK")]
+    #[case::salmon(&["salmon", "quant", "-i", "idx", "-l", "A"], "salmon")]
+    #[case::kallisto(&["kallisto", "quant", "-i", "idx", "-o", "out"], "kallisto quant")]
+    #[case::multiqc(&["multiqc", "-f", "."], "multiqc")]
+    #[case::fastqc(
+        &["java", "-Xmx250m", "uk.ac.babraham.FastQC.FastQCApplication", "R1.fq.gz"],
+        "FastQC"
+    )]
+    // the embedded pack also unwraps launchers
+    #[case::srun(&["srun", "-n", "1", "STAR", "--readFilesIn", "R1.fq.gz"], "STAR align")]
+    fn test_builtin_rule_pack(#[case] argv: &[&str], #[case] display_name: &str) {
+        let manager = TargetManager::default();
+        let process = make_process(argv[0], argv);
+        assert_eq!(
+            manager.get_target_match(&process).as_deref(),
+            Some(display_name)
+        );
+    }
+
+    #[test]
+    fn test_builtin_rules_can_be_disabled() {
+        let custom = parse_rules(
+            "custom.yml",
+            "rules:\n  - display_name: aligner\n    condition:\n      process_name_is: minimap2\n",
+        )
+        .unwrap();
+        let manager = TargetManager::default()
+            .with_custom_targets(custom)
+            .without_builtin_targets();
+
+        assert_eq!(manager.rule_count(), 1);
+        assert_eq!(
+            TargetManager::default()
+                .without_builtin_targets()
+                .rule_count(),
+            0
+        );
+
+        let process = make_process("samtools", &["samtools", "sort", "file.bam"]);
+        assert_eq!(manager.get_target_match(&process), None);
+        let process = make_process("minimap2", &["minimap2", "-ax", "sr", "ref.fa"]);
+        assert_eq!(
+            manager.get_target_match(&process).as_deref(),
+            Some("aligner")
+        );
+    }
 }
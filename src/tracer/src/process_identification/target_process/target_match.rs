@@ -39,9 +39,20 @@ pub enum MatchType {
     ArgsContain(String),
     ArgsNotContain(String),
     FirstArgIs(String),
+    /// Matches if the command has the option `flag` with the given value, either as
+    /// `<flag> <value>` or `<flag>=<value>`
+    ArgEquals {
+        flag: String,
+        value: String,
+    },
     CommandContains(String),
     CommandNotContains(String),
     CommandMatchesRegex(CachedRegex),
+    /// Matches the executable path (the first argument of the command) against a regex
+    ExecutablePathMatches(CachedRegex),
+    /// Matches if the process runs inside a container whose image contains the given substring.
+    /// Never matches processes running on the host.
+    ContainerImageContains(String),
     SubcommandIsOneOf(Subcommands),
     Java {
         jar: Option<String>,
@@ -78,6 +89,11 @@ impl MatchType {
             MatchType::FirstArgIs(arg) if process.argv.get(1) == Some(arg) => {
                 Some(ProcessMatch::Simple)
             }
+            MatchType::ArgEquals { flag, value }
+                if option_values(&process.argv, flag).any(|arg_value| arg_value == value) =>
+            {
+                Some(ProcessMatch::Simple)
+            }
             MatchType::CommandContains(content) if process.command_string.contains(content) => {
                 Some(ProcessMatch::Simple)
             }
@@ -87,6 +103,22 @@ impl MatchType {
             MatchType::CommandMatchesRegex(regex) if regex.is_match(&process.command_string) => {
                 Some(ProcessMatch::Simple)
             }
+            MatchType::ExecutablePathMatches(regex)
+                if process
+                    .argv
+                    .first()
+                    .is_some_and(|path| regex.is_match(path)) =>
+            {
+                Some(ProcessMatch::Simple)
+            }
+            MatchType::ContainerImageContains(content)
+                if process
+                    .container_image
+                    .as_ref()
+                    .is_some_and(|image| image.contains(content)) =>
+            {
+                Some(ProcessMatch::Simple)
+            }
             MatchType::SubcommandIsOneOf(subcommands) => {
                 // to find the subcommand, we find the first argument that doesn't start with '-'
                 // (as options are usually done with -)
@@ -124,6 +156,18 @@ impl MatchType {
     }
 }
 
+/// Returns the values given to the option `flag` in `argv`, as `<flag> <value>` or
+/// `<flag>=<value>`
+fn option_values<'a>(argv: &'a [String], flag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    argv.iter().enumerate().skip(1).filter_map(move |(i, arg)| {
+        if arg == flag {
+            argv.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix(flag)?.strip_prefix('=')
+        }
+    })
+}
+
 fn match_java<'a>(
     process: &'a ProcessStartTrigger,
     jar: Option<&String>,
@@ -7,13 +7,21 @@ use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 pub struct TargetSet {
     process_name_is: HashMap<String, Target>,
     other: HashSet<Target>,
+    /// Targets the set was built from, as `or` conditions are split across the lookups
+    len: usize,
 }
 
 impl TargetSet {
     pub fn new<I: IntoIterator<Item = Target>>(targets: I) -> Self {
-        let (process_name_is, other) = targets.into_iter().fold(
+        let mut len = 0;
+        let (process_name_is, other) = targets.into_iter().inspect(|_| len += 1).fold(
             (HashMap::new(), HashSet::new()),
             |(mut process_name_is, mut other), mut target| {
+                // the lookup by process name cannot check ancestors or `filter_out`
+                if target.has_extra_constraints() {
+                    other.insert(target);
+                    return (process_name_is, other);
+                }
                 match target.match_type_mut() {
                     MatchType::Or(match_types) => {
                         for i in (0..match_types.len()).rev() {
@@ -41,24 +49,57 @@ impl TargetSet {
         Self {
             process_name_is,
             other,
+            len,
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
     pub fn matches(&self, process: &ProcessStartTrigger) -> bool {
+        self.matches_in_tree(process, &[])
+    }
+
+    /// Like `matches`, with `ancestors` the known ancestors of `process`, parent first
+    pub fn matches_in_tree(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> bool {
         self.process_name_is.contains_key(&process.comm)
-            || self.other.iter().any(|target| target.matches(process))
+            || self
+                .other
+                .iter()
+                .any(|target| target.matches_in_tree(process, ancestors))
     }
 
     pub fn get_match(&self, process: &ProcessStartTrigger) -> Option<String> {
+        self.get_match_in_tree(process, &[])
+    }
+
+    /// Like `get_match`, with `ancestors` the known ancestors of `process`, parent first
+    pub fn get_match_in_tree(
+        &self,
+        process: &ProcessStartTrigger,
+        ancestors: &[&ProcessStartTrigger],
+    ) -> Option<String> {
         self.process_name_is
             .get(&process.comm)
             .map(|target| target.display_name().to_string())
             .or_else(|| {
                 self.other
                     .iter()
-                    .find_map(|target| target.get_match(process))
+                    .find_map(|target| target.get_match_in_tree(process, ancestors))
             })
     }
+
+    /// Whether any target only matches under a matching ancestor
+    pub fn has_forced_ancestors(&self) -> bool {
+        self.other
+            .iter()
+            .any(|target| target.should_force_ancestor_to_match())
+    }
 }
 
 impl<I: IntoIterator<Item = Target>> From<I> for TargetSet {
@@ -0,0 +1,247 @@
+//! Unwrapping of launcher commands (e.g. `srun`, `bsub`, `conda run`) and interpreters to the
+//! tool they run, so `srun -n 4 STAR --runThreadN 4` is matched like `STAR --runThreadN 4`.
+
+use crate::process_identification::target_process::interpreter::interpreted_tool;
+use crate::utils::yaml::{load_from_yaml_array_str, Yaml, YamlExt};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
+
+/// Maximum number of nested wrappers to unwrap, e.g. `srun conda run -n env STAR`
+const MAX_WRAPPER_DEPTH: usize = 4;
+
+/// A launcher binary whose real command follows its own options.
+///
+/// The real command starts at the first argument after `subcommand` (if any) that is not an
+/// option. Options listed in `options_with_value` consume the following argument as their value;
+/// other options (and `--option=value` forms) are a single argument. A `--` argument ends the
+/// options.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrapperPattern {
+    pub binary: String,
+    #[serde(default)]
+    pub subcommand: Option<String>,
+    #[serde(default)]
+    pub options_with_value: Vec<String>,
+}
+
+impl WrapperPattern {
+    pub fn new(binary: &str, subcommand: Option<&str>, options_with_value: &[&str]) -> Self {
+        Self {
+            binary: binary.to_string(),
+            subcommand: subcommand.map(str::to_string),
+            options_with_value: options_with_value.iter().map(|o| o.to_string()).collect(),
+        }
+    }
+
+    /// Returns the arguments of the wrapped command, if `argv` runs this wrapper
+    fn wrapped_args<'a>(&self, argv: &'a [String]) -> Option<&'a [String]> {
+        let binary = Path::new(argv.first()?).file_name()?.to_str()?;
+        if binary != self.binary {
+            return None;
+        }
+
+        let mut i = 1;
+        if let Some(subcommand) = &self.subcommand {
+            // options may come before the subcommand, e.g. `conda --no-plugins run`
+            while argv.get(i)?.starts_with('-') {
+                i += 1;
+            }
+            if argv.get(i)? != subcommand {
+                return None;
+            }
+            i += 1;
+        }
+
+        while let Some(arg) = argv.get(i) {
+            if arg == "--" {
+                i += 1;
+                break;
+            } else if self.options_with_value.contains(arg) {
+                i += 2;
+            } else if arg.starts_with('-') {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+
+        argv.get(i..).filter(|args| !args.is_empty())
+    }
+}
+
+impl TryFrom<Yaml> for WrapperPattern {
+    type Error = anyhow::Error;
+
+    fn try_from(yaml: Yaml) -> Result<Self> {
+        let options_with_value = match yaml.optional_vec("options_with_value")? {
+            Some(options) => options
+                .iter()
+                .map(|option| option.to_string())
+                .collect::<Result<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            binary: yaml.required_string("binary")?,
+            subcommand: yaml.optional_string("subcommand")?,
+            options_with_value,
+        })
+    }
+}
+
+/// Loads the wrapper patterns listed under `wrappers` in the YAML file at `path`, e.g.
+///
+/// ```yaml
+/// wrappers:
+///   - binary: qsub
+///     options_with_value: ["-q", "-N"]
+///   - binary: pixi
+///     subcommand: run
+/// ```
+pub fn load_wrapper_patterns_file(path: &Path) -> Result<Vec<WrapperPattern>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read wrapper patterns file {}", path.display()))?;
+    load_from_yaml_array_str(&contents, "wrappers")
+        .with_context(|| format!("Invalid wrapper patterns file {}", path.display()))
+}
+
+/// Wrapper patterns used unless configured otherwise
+pub fn default_wrapper_patterns() -> Vec<WrapperPattern> {
+    const CONDA_RUN_OPTIONS: &[&str] = &["-n", "--name", "-p", "--prefix", "--cwd"];
+    vec![
+        WrapperPattern::new(
+            "srun",
+            None,
+            &[
+                "-n",
+                "-N",
+                "-c",
+                "-p",
+                "-t",
+                "-J",
+                "-A",
+                "-w",
+                "-o",
+                "-e",
+                "--ntasks",
+                "--nodes",
+                "--cpus-per-task",
+                "--partition",
+                "--time",
+                "--job-name",
+                "--account",
+                "--mem",
+            ],
+        ),
+        WrapperPattern::new(
+            "bsub",
+            None,
+            &[
+                "-n", "-q", "-J", "-o", "-e", "-R", "-W", "-M", "-P", "-G", "-m", "-cwd",
+            ],
+        ),
+        WrapperPattern::new("conda", Some("run"), CONDA_RUN_OPTIONS),
+        WrapperPattern::new("mamba", Some("run"), CONDA_RUN_OPTIONS),
+        WrapperPattern::new("micromamba", Some("run"), CONDA_RUN_OPTIONS),
+    ]
+}
+
+/// Returns the command run by `process` through one or more wrappers and/or an interpreter (see
+/// `interpreter::interpreted_tool`), or `None` if `process` runs neither. The unwrapped trigger
+/// is named after the tool and keeps the pid, start time and container of the original process.
+pub fn unwrap_command(
+    patterns: &[WrapperPattern],
+    process: &ProcessStartTrigger,
+) -> Option<ProcessStartTrigger> {
+    let mut argv: &[String] = &process.argv;
+    for _ in 0..MAX_WRAPPER_DEPTH {
+        match patterns
+            .iter()
+            .find_map(|pattern| pattern.wrapped_args(argv))
+        {
+            Some(wrapped) => argv = wrapped,
+            None => break,
+        }
+    }
+
+    let (name, argv) = match interpreted_tool(argv) {
+        Some(tool) => (tool.name, tool.argv),
+        None if argv.len() < process.argv.len() => {
+            let name = Path::new(&argv[0])
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(&argv[0]);
+            (name.to_string(), argv.to_vec())
+        }
+        None => return None,
+    };
+
+    let mut inner =
+        ProcessStartTrigger::from_name_and_args(process.pid, process.ppid, &name, &argv);
+    inner.started_at = process.started_at;
+    inner.container_id = process.container_id.clone();
+    inner.container_name = process.container_name.clone();
+    inner.container_image = process.container_image.clone();
+    Some(inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::srun(&["srun", "-n", "4", "--mem=8G", "STAR", "--runThreadN", "4"], "STAR")]
+    #[case::bsub(&["/usr/bin/bsub", "-q", "long", "-n", "8", "-R", "span[hosts=1]", "bwa", "mem"], "bwa")]
+    #[case::conda(&["conda", "run", "-n", "rnaseq", "salmon", "quant"], "salmon")]
+    #[case::nested(&["srun", "-c", "2", "conda", "run", "-p", "/envs/qc", "/opt/bin/fastqc", "x.fq"], "fastqc")]
+    #[case::double_dash(&["srun", "--", "samtools", "sort"], "samtools")]
+    #[case::interpreter(&["perl", "/opt/conda/bin/fastqc", "x.fq"], "fastqc")]
+    #[case::wrapped_interpreter(&["conda", "run", "-n", "qc", "python", "-m", "multiqc", "."], "multiqc")]
+    fn test_unwraps_to_inner_tool(#[case] argv: &[&str], #[case] expected: &str) {
+        let process = ProcessStartTrigger::from_name_and_args(7, 1, argv[0], argv);
+
+        let inner = unwrap_command(&default_wrapper_patterns(), &process).unwrap();
+
+        assert_eq!(inner.comm, expected);
+        assert_eq!(inner.pid, 7);
+        assert_eq!(inner.started_at, process.started_at);
+    }
+
+    #[rstest]
+    #[case::not_a_wrapper(&["STAR", "--runThreadN", "4"])]
+    #[case::no_command(&["srun", "-n", "4"])]
+    #[case::other_subcommand(&["conda", "install", "samtools"])]
+    fn test_does_not_unwrap(#[case] argv: &[&str]) {
+        let process = ProcessStartTrigger::from_name_and_args(7, 1, argv[0], argv);
+        assert_eq!(unwrap_command(&default_wrapper_patterns(), &process), None);
+    }
+
+    #[test]
+    fn test_load_wrapper_patterns_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("wrappers.yml");
+        std::fs::write(
+            &path,
+            "wrappers:\n  - binary: qsub\n    options_with_value: [\"-q\", \"-N\"]\n  - binary: pixi\n    subcommand: run\n",
+        )
+        .unwrap();
+
+        let patterns = load_wrapper_patterns_file(&path).unwrap();
+        assert_eq!(
+            patterns,
+            vec![
+                WrapperPattern::new("qsub", None, &["-q", "-N"]),
+                WrapperPattern::new("pixi", Some("run"), &[]),
+            ]
+        );
+
+        let argv = ["qsub", "-q", "long", "bwa", "mem"];
+        let process = ProcessStartTrigger::from_name_and_args(7, 1, argv[0], &argv);
+        assert_eq!(unwrap_command(&patterns, &process).unwrap().comm, "bwa");
+
+        std::fs::write(&path, "wrappers:\n  - subcommand: run\n").unwrap();
+        assert!(load_wrapper_patterns_file(&path).is_err());
+    }
+}
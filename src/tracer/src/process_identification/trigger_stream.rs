@@ -0,0 +1,214 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracer_ebpf::ebpf_trigger::Trigger;
+
+/// Number of triggers buffered per subscriber; triggers arrive in bursts, so this is larger than
+/// the event stream's buffer
+const TRIGGER_STREAM_CAPACITY: usize = 4096;
+
+/// Fan-out of the raw process triggers, as decoded from eBPF (or procfs polling) and before any
+/// target matching, to live subscribers (e.g. `tracer debug-events`).
+///
+/// Publishing never blocks: a slow subscriber drops triggers and receives a gap marker instead.
+#[derive(Clone)]
+pub struct TriggerStream {
+    tx: broadcast::Sender<RawTrigger>,
+}
+
+impl Default for TriggerStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TriggerStream {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(TRIGGER_STREAM_CAPACITY);
+        Self { tx }
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    pub fn publish(&self, triggers: &[Trigger]) {
+        // avoid converting triggers when nobody is listening
+        if !self.has_subscribers() {
+            return;
+        }
+        for trigger in triggers {
+            let _ = self.tx.send(RawTrigger::from(trigger));
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RawTrigger> {
+        self.tx.subscribe()
+    }
+}
+
+/// A trigger as reported by the kernel, without anything the client derives from it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RawTrigger {
+    ProcessStart {
+        pid: usize,
+        ppid: usize,
+        comm: String,
+        /// The executed file, i.e. the first element of `argv`
+        file_name: Option<String>,
+        argv: Vec<String>,
+        started_at: DateTime<Utc>,
+    },
+    ProcessEnd {
+        pid: usize,
+        exit_code: Option<i64>,
+        exit_reason: Option<String>,
+        finished_at: DateTime<Utc>,
+    },
+    OutOfMemory {
+        pid: usize,
+        comm: String,
+        timestamp: DateTime<Utc>,
+    },
+    FileOpen {
+        pid: u32,
+        file_name: String,
+        size_bytes: i128,
+        timestamp: DateTime<Utc>,
+    },
+    ExecFailed {
+        pid: usize,
+        ppid: usize,
+        file_name: String,
+        errno: i32,
+        error: String,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl From<&Trigger> for RawTrigger {
+    fn from(trigger: &Trigger) -> Self {
+        match trigger {
+            Trigger::ProcessStart(start) => RawTrigger::ProcessStart {
+                pid: start.pid,
+                ppid: start.ppid,
+                comm: start.comm.clone(),
+                file_name: start.argv.first().cloned(),
+                argv: start.argv.clone(),
+                started_at: start.started_at,
+            },
+            Trigger::ProcessEnd(end) => RawTrigger::ProcessEnd {
+                pid: end.pid,
+                exit_code: end.exit_reason.as_ref().map(|reason| reason.code),
+                exit_reason: end.exit_reason.as_ref().map(|reason| reason.reason.clone()),
+                finished_at: end.finished_at,
+            },
+            Trigger::OutOfMemory(oom) => RawTrigger::OutOfMemory {
+                pid: oom.pid,
+                comm: oom.comm.clone(),
+                timestamp: oom.timestamp,
+            },
+            Trigger::FileOpen(open) => RawTrigger::FileOpen {
+                pid: open.pid,
+                file_name: open.file_full_path.clone(),
+                size_bytes: open.size_bytes,
+                timestamp: open.timestamp,
+            },
+            Trigger::ExecFailed(failure) => RawTrigger::ExecFailed {
+                pid: failure.pid,
+                ppid: failure.ppid,
+                file_name: failure.file_name.clone(),
+                errno: failure.errno,
+                error: failure.error.clone(),
+                timestamp: failure.timestamp,
+            },
+        }
+    }
+}
+
+/// A single line of the newline-delimited JSON trigger stream
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TriggerStreamMessage {
+    Trigger(RawTrigger),
+    Gap { dropped: u64 },
+}
+
+impl TriggerStreamMessage {
+    pub fn to_ndjson(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// Waits for the next message for a subscriber, or `None` once the stream is closed
+pub async fn next_trigger_message(
+    receiver: &mut broadcast::Receiver<RawTrigger>,
+) -> Option<TriggerStreamMessage> {
+    match receiver.recv().await {
+        Ok(trigger) => Some(TriggerStreamMessage::Trigger(trigger)),
+        Err(RecvError::Lagged(dropped)) => Some(TriggerStreamMessage::Gap { dropped }),
+        Err(RecvError::Closed) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracer_ebpf::ebpf_trigger::{ExitReason, ProcessEndTrigger, ProcessStartTrigger};
+
+    #[tokio::test]
+    async fn test_subscribers_receive_raw_triggers() {
+        let stream = TriggerStream::new();
+        let mut receiver = stream.subscribe();
+
+        stream.publish(&[
+            Trigger::ProcessStart(ProcessStartTrigger::from_name_and_args(
+                10,
+                1,
+                "bwa",
+                &["/usr/bin/bwa", "mem", "ref.fa"],
+            )),
+            Trigger::ProcessEnd(ProcessEndTrigger {
+                pid: 10,
+                finished_at: Utc::now(),
+                exit_reason: Some(ExitReason::success()),
+            }),
+        ]);
+
+        match next_trigger_message(&mut receiver).await {
+            Some(TriggerStreamMessage::Trigger(RawTrigger::ProcessStart {
+                pid,
+                ppid,
+                comm,
+                file_name,
+                argv,
+                ..
+            })) => {
+                assert_eq!((pid, ppid), (10, 1));
+                assert_eq!(comm, "bwa");
+                assert_eq!(file_name.as_deref(), Some("/usr/bin/bwa"));
+                assert_eq!(argv, vec!["/usr/bin/bwa", "mem", "ref.fa"]);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        match next_trigger_message(&mut receiver).await {
+            Some(TriggerStreamMessage::Trigger(RawTrigger::ProcessEnd {
+                pid, exit_code, ..
+            })) => {
+                assert_eq!(pid, 10);
+                assert_eq!(exit_code, Some(0));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_message_serializes_as_tagged_line() {
+        let message = TriggerStreamMessage::Gap { dropped: 3 };
+        assert_eq!(message.to_ndjson(), "{\"type\":\"gap\",\"dropped\":3}\n");
+    }
+}
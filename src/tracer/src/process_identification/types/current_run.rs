@@ -2,12 +2,16 @@ use crate::{
     cloud_providers::aws::types::pricing::InstancePricingContext, utils::env::TRACE_ID_ENV_VAR,
 };
 use chrono::{DateTime, Utc};
+use std::time::Instant;
 
 #[derive(Clone)]
 pub struct RunMetadata {
     pub name: String,
     pub id: String,
     pub start_time: DateTime<Utc>,
+    /// Monotonic reading of `start_time`, which the run duration is measured from so that a
+    /// step of the system clock during the run doesn't affect it
+    pub started: Instant,
     pub trace_id: Option<String>,
     pub cost_summary: Option<PipelineCostSummary>,
 }
@@ -18,6 +22,7 @@ impl RunMetadata {
             name,
             id,
             start_time: Utc::now(),
+            started: Instant::now(),
             trace_id: std::env::var(TRACE_ID_ENV_VAR).ok(),
             cost_summary,
         }
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The local clock the event timestamps are taken from is off from the real time, see
+/// `extracts::clock_skew`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockSkew {
+    /// How far ahead of the reference clock the local clock is, negative when behind
+    pub clock_skew_ms: i64,
+    /// Skew above which the clock is reported
+    pub threshold_ms: u64,
+    /// Clock the local clock was compared against, e.g. the URL whose `Date` header was read
+    pub reference: String,
+    pub reference_time: DateTime<Utc>,
+}
@@ -0,0 +1,21 @@
+use crate::utils::telemetry::ErrorCategory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A matched tool that was never started because its `execve` failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecFailure {
+    /// Target the attempted command matched
+    pub tool_name: String,
+    /// The file that was to be executed
+    pub command: String,
+    pub errno: i32,
+    /// Description of `errno`, e.g. "No such file or directory"
+    pub error: String,
+    /// Category of `errno`, as reported to Sentry
+    #[serde(default)]
+    pub error_category: ErrorCategory,
+    pub pid: usize,
+    pub ppid: usize,
+    pub attempted_at: DateTime<Utc>,
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Periodic liveness signal of a run, emitted while no other events are
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub state: RunState,
+    /// Tools running when the heartbeat was emitted
+    pub running_tools: Vec<String>,
+    /// Time of the last event other than metrics and heartbeats, if any
+    pub last_activity_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunState {
+    /// At least one tool is running
+    Running,
+    /// No tool is running
+    Idle,
+}
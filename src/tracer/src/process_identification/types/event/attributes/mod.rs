@@ -1,13 +1,23 @@
 use crate::extracts::python_monitor::function_monitor::function_monitor_manager::PythonFunctionCall;
 use crate::process_identification::target_pipeline::pipeline_manager::TaskMatch;
+use clock_skew::ClockSkew;
 use container::ContainerProperties;
+use exec_failure::ExecFailure;
+use heartbeat::Heartbeat;
 use process::{CompletedProcess, ProcessProperties};
-use syslog::SyslogProperties;
+use run_summary::RunSummary;
+use storage_stall::StorageStall;
+use syslog::{SyslogProperties, SyslogSeverity};
 use system_metrics::{SystemMetric, SystemProperties};
 use tracer_ebpf::ebpf_trigger::FileOpenTrigger;
 
+pub mod clock_skew;
 pub mod container;
+pub mod exec_failure;
+pub mod heartbeat;
 pub mod process;
+pub mod run_summary;
+pub mod storage_stall;
 pub mod syslog;
 pub mod system_metrics;
 
@@ -22,6 +32,51 @@ pub enum EventAttributes {
     FileOpened(FileOpenTrigger),
     ContainerEvents(ContainerProperties),
     TaskMatch(TaskMatch),
-    NewRun { trace_id: String },
+    NewRun {
+        trace_id: String,
+    },
     PythonFunction(PythonFunctionCall),
+    #[serde(rename = "summary")]
+    RunSummary(Box<RunSummary>),
+    Heartbeat(Heartbeat),
+    ExecFailure(ExecFailure),
+    StorageStall(StorageStall),
+    ClockSkew(ClockSkew),
+}
+
+impl EventAttributes {
+    /// Severity text and number to set on the event carrying these attributes, if any
+    pub fn severity(&self) -> Option<(&'static str, u8)> {
+        match self {
+            EventAttributes::Syslog(properties) => Some((
+                properties.severity.as_text(),
+                properties.severity.as_number(),
+            )),
+            // a tool that failed is an error, so failed steps stand out without the alerts
+            EventAttributes::CompletedProcess(process) => {
+                process.exit_reason.as_ref().map(|exit_reason| {
+                    let severity = if exit_reason.is_failure() {
+                        SyslogSeverity::Error
+                    } else {
+                        SyslogSeverity::Info
+                    };
+                    (severity.as_text(), severity.as_number())
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Name of the tool these attributes describe, if any
+    pub fn tool_name(&self) -> Option<&str> {
+        match self {
+            EventAttributes::Process(ProcessProperties::Full(properties)) => {
+                Some(&properties.tool_name)
+            }
+            EventAttributes::CompletedProcess(process) => Some(&process.tool_name),
+            EventAttributes::ExecFailure(failure) => Some(&failure.tool_name),
+            EventAttributes::StorageStall(stall) => Some(&stall.tool_name),
+            _ => None,
+        }
+    }
 }
@@ -24,6 +24,9 @@ pub struct FullProcessProperties {
     pub process_cpu_utilization: f32,
     pub process_memory_usage: u64,
     pub process_memory_virtual: u64,
+    /// Bytes of the tool's memory swapped out, if known (Linux only)
+    #[serde(default)]
+    pub swap_used: Option<u64>,
     pub process_run_time: u64,
     pub process_disk_usage_read_last_interval: u64,
     pub process_disk_usage_write_last_interval: u64,
@@ -36,6 +39,38 @@ pub struct FullProcessProperties {
     pub trace_id: Option<String>,
     pub container_event: Option<ContainerEvent>,
     pub tool_id: String, // the tool_id is useful to uniquely identify a tool
+    /// Ancestors of the tool as observed by the process watcher, parent first. Only set when
+    /// `Config::process_tree_in_events` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_tree: Option<Vec<ProcessTreeNode>>,
+    /// Set on `ToolMetricEvent`s summarizing the samples of an aggregation window, see
+    /// `Config::tool_metrics_window_ms`; the utilization and memory usage above are then the
+    /// window averages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric_window: Option<MetricWindowSummary>,
+    /// Installed version of the tool, from the packages of the run's environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_version: Option<String>,
+}
+
+/// CPU and memory statistics of the samples of a tool taken during an aggregation window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MetricWindowSummary {
+    pub samples: u32,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub cpu_utilization_min: f32,
+    pub cpu_utilization_avg: f32,
+    pub cpu_utilization_max: f32,
+    pub memory_usage_min: u64,
+    pub memory_usage_avg: u64,
+    pub memory_usage_max: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProcessTreeNode {
+    pub pid: usize,
+    pub comm: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -49,6 +84,20 @@ pub struct CompletedProcess {
     pub tool_name: String,
     pub tool_pid: String,
     pub duration_sec: u64,
+    /// Duration of the tool, from process start to exit, measured with the monotonic clock
+    /// when known so that a step of the system clock doesn't affect it
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Set when the tool was still running when monitoring stopped (e.g. on daemon shutdown), in
+    /// which case `ended_at` and the durations are measured up to that point
+    #[serde(default)]
+    pub incomplete: bool,
+    /// Peak number of threads of the tool process, as sampled on each metrics poll
+    #[serde(default)]
+    pub peak_thread_count: u64,
+    /// Peak number of direct child processes the tool had running at the same time
+    #[serde(default)]
+    pub peak_child_count: u64,
     pub exit_reason: Option<ExitReason>,
     pub started_at: DateTime<Utc>,
     pub ended_at: DateTime<Utc>,
@@ -0,0 +1,364 @@
+use crate::process_identification::types::current_run::RunMetadata;
+use crate::process_identification::types::event::attributes::syslog::SyslogSeverity;
+use crate::process_identification::types::event::attributes::EventAttributes;
+use crate::process_identification::types::event::Event;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+/// Summary of a whole run, emitted when the run ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub run_name: String,
+    pub pipeline_name: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// Measured with the monotonic clock, so it may differ from `ended_at - started_at` if the
+    /// system clock was stepped during the run
+    pub duration_sec: u64,
+    pub instance_type: Option<String>,
+    pub estimated_cost: Option<f64>,
+    /// Tools executed during the run, longest total duration first
+    pub tools: Vec<ToolSummary>,
+    pub peak_cpu_utilization: f32,
+    pub peak_memory_used: u64,
+    pub peak_memory_utilization: f64,
+    /// Errors detected in the logs, most frequent first
+    pub errors: Vec<ErrorSummary>,
+    /// Number of tool executions that exited with a non-zero exit code
+    pub failed_tool_executions: u64,
+    #[serde(default)]
+    pub end_reason: RunEndReason,
+}
+
+/// Why a run ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunEndReason {
+    /// The run was stopped, or the daemon exited
+    #[default]
+    Completed,
+    /// The pipeline ran longer than its timeout and was stopped
+    TimedOut,
+    /// The pipeline was interrupted, e.g. with Ctrl-C
+    Cancelled,
+}
+
+impl RunEndReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunEndReason::Completed => "completed",
+            RunEndReason::TimedOut => "timed_out",
+            RunEndReason::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolSummary {
+    pub tool_name: String,
+    pub executions: u64,
+    pub failed: u64,
+    /// Executions still running when the run ended
+    pub incomplete: u64,
+    pub total_duration_ms: u64,
+    pub max_duration_ms: u64,
+    pub peak_thread_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorSummary {
+    pub error_display_name: String,
+    pub severity: SyslogSeverity,
+    pub occurrences: u64,
+}
+
+/// Accumulates the events of a run into a `RunSummary`
+#[derive(Debug, Default)]
+pub struct RunSummaryBuilder {
+    tools: BTreeMap<String, ToolSummary>,
+    errors: BTreeMap<String, ErrorSummary>,
+    peak_cpu_utilization: f32,
+    peak_memory_used: u64,
+    peak_memory_utilization: f64,
+}
+
+impl RunSummaryBuilder {
+    pub fn observe(&mut self, event: &Event) {
+        match &event.attributes {
+            Some(EventAttributes::CompletedProcess(process)) => {
+                let tool = self
+                    .tools
+                    .entry(process.tool_name.clone())
+                    .or_insert_with(|| ToolSummary {
+                        tool_name: process.tool_name.clone(),
+                        ..Default::default()
+                    });
+                tool.executions += 1;
+                tool.total_duration_ms += process.duration_ms;
+                tool.max_duration_ms = tool.max_duration_ms.max(process.duration_ms);
+                tool.peak_thread_count = tool.peak_thread_count.max(process.peak_thread_count);
+                if process.incomplete {
+                    tool.incomplete += 1;
+                }
+                if process
+                    .exit_reason
+                    .as_ref()
+                    .is_some_and(|reason| reason.is_failure())
+                {
+                    tool.failed += 1;
+                }
+            }
+            Some(EventAttributes::SystemMetric(metric)) => {
+                self.peak_cpu_utilization =
+                    self.peak_cpu_utilization.max(metric.system_cpu_utilization);
+                self.peak_memory_used = self.peak_memory_used.max(metric.system_memory_used);
+                self.peak_memory_utilization = self
+                    .peak_memory_utilization
+                    .max(metric.system_memory_utilization);
+            }
+            Some(EventAttributes::Syslog(syslog))
+                if matches!(
+                    syslog.severity,
+                    SyslogSeverity::Error | SyslogSeverity::Critical
+                ) =>
+            {
+                self.errors
+                    .entry(syslog.error_display_name.clone())
+                    .or_insert_with(|| ErrorSummary {
+                        error_display_name: syslog.error_display_name.clone(),
+                        severity: syslog.severity,
+                        occurrences: 0,
+                    })
+                    .occurrences += 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// Summary of the run ending at `ended_at` on the wall clock and at `ended` on the monotonic
+    /// clock, which the duration is measured with
+    pub fn build(
+        &self,
+        pipeline_name: &str,
+        run: &RunMetadata,
+        ended_at: DateTime<Utc>,
+        ended: Instant,
+    ) -> RunSummary {
+        let mut tools: Vec<ToolSummary> = self.tools.values().cloned().collect();
+        tools.sort_by(|a, b| b.total_duration_ms.cmp(&a.total_duration_ms));
+        let mut errors: Vec<ErrorSummary> = self.errors.values().cloned().collect();
+        errors.sort_by(|a, b| b.occurrences.cmp(&a.occurrences));
+
+        let duration_sec = ended.saturating_duration_since(run.started).as_secs();
+
+        RunSummary {
+            run_id: run.id.clone(),
+            run_name: run.name.clone(),
+            pipeline_name: pipeline_name.to_string(),
+            started_at: run.start_time,
+            ended_at,
+            duration_sec,
+            instance_type: run
+                .cost_summary
+                .as_ref()
+                .map(|cost| cost.instance_type.clone()),
+            estimated_cost: run
+                .cost_summary
+                .as_ref()
+                .map(|cost| cost.per_minute * duration_sec as f64 / 60.0),
+            failed_tool_executions: tools.iter().map(|tool| tool.failed).sum(),
+            tools,
+            peak_cpu_utilization: self.peak_cpu_utilization,
+            peak_memory_used: self.peak_memory_used,
+            peak_memory_utilization: self.peak_memory_utilization,
+            errors,
+            end_reason: RunEndReason::Completed,
+        }
+    }
+}
+
+/// Writes `summary` as pretty-printed JSON, replacing the summary of any previous run
+pub fn write_run_summary(path: &Path, summary: &RunSummary) -> Result<()> {
+    let json = serde_json::to_vec_pretty(summary)?;
+    std::fs::write(path, json)
+        .with_context(|| format!("Failed to write run summary to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_identification::types::current_run::PipelineCostSummary;
+    use crate::process_identification::types::event::attributes::process::CompletedProcess;
+    use crate::process_identification::types::event::attributes::syslog::SyslogProperties;
+    use crate::process_identification::types::event::attributes::system_metrics::SystemMetric;
+    use chrono::{TimeDelta, TimeZone};
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tracer_ebpf::ebpf_trigger::ExitReason;
+
+    fn run() -> RunMetadata {
+        RunMetadata {
+            name: "test_run".to_string(),
+            id: "run-123".to_string(),
+            start_time: Utc.with_ymd_and_hms(2025, 4, 30, 12, 0, 0).unwrap(),
+            started: std::time::Instant::now(),
+            trace_id: None,
+            cost_summary: Some(PipelineCostSummary {
+                instance_type: "m5.large".to_string(),
+                hourly: 6.0,
+                per_minute: 0.1,
+                estimated_total: 0.0,
+                source: "test".to_string(),
+            }),
+        }
+    }
+
+    fn event(attributes: EventAttributes) -> Event {
+        Event::builder().attributes(Some(attributes)).build()
+    }
+
+    fn completed(tool_name: &str, duration_ms: u64, exit_code: Option<i64>) -> Event {
+        let started_at = run().start_time;
+        event(EventAttributes::CompletedProcess(CompletedProcess {
+            tool_id: format!("{tool_name}-{duration_ms}"),
+            tool_name: tool_name.to_string(),
+            tool_pid: "42".to_string(),
+            duration_sec: duration_ms / 1000,
+            duration_ms,
+            incomplete: exit_code.is_none(),
+            peak_thread_count: 4,
+            peak_child_count: 0,
+            exit_reason: exit_code.map(|code| ExitReason::from(code << 8)),
+            started_at,
+            ended_at: started_at + TimeDelta::milliseconds(duration_ms as i64),
+            process_run_time: duration_ms,
+            tool_args: String::new(),
+        }))
+    }
+
+    fn metric(cpu: f32, memory_used: u64) -> SystemMetric {
+        SystemMetric {
+            events_name: "global_system_metrics".to_string(),
+            system_memory_total: 1000,
+            system_memory_used: memory_used,
+            system_memory_available: 1000 - memory_used,
+            system_memory_utilization: memory_used as f64 / 10.0,
+            system_memory_cgroup_scoped: false,
+            system_memory_swap_total: 0,
+            system_memory_swap_used: 0,
+            system_cpu_utilization: cpu,
+            system_cpu_quota: None,
+            system_cpu_quota_utilization: None,
+            system_disk_total_space: 0,
+            system_disk_used_space: 0,
+            system_disk_io: HashMap::new(),
+            system_gpu_utilization: None,
+            system_gpu_memory_used: None,
+            system_gpu_memory_total: None,
+            system_gpu_memory_utilization: None,
+            system_gpu_stats: HashMap::new(),
+            ebpf_events_lost: 0,
+        }
+    }
+
+    fn syslog(error_display_name: &str, severity: SyslogSeverity) -> Event {
+        event(EventAttributes::Syslog(SyslogProperties {
+            system_metrics: metric(0.0, 0),
+            error_display_name: error_display_name.to_string(),
+            error_id: error_display_name.to_string(),
+            error_line: String::new(),
+            file_line_number: 0,
+            file_previous_logs: vec![],
+            severity,
+            error_category: Default::default(),
+            tool: None,
+        }))
+    }
+
+    #[test]
+    fn test_summary_of_synthetic_run() {
+        let mut builder = RunSummaryBuilder::default();
+        for event in [
+            completed("fastqc", 2_000, Some(0)),
+            completed("STAR", 60_000, Some(0)),
+            completed("fastqc", 3_000, Some(1)),
+            completed("STAR", 5_000, None),
+            event(EventAttributes::SystemMetric(metric(85.5, 700))),
+            event(EventAttributes::SystemMetric(metric(40.0, 900))),
+            syslog("Out of memory", SyslogSeverity::Critical),
+            syslog("Missing input file", SyslogSeverity::Error),
+            syslog("Missing input file", SyslogSeverity::Error),
+            syslog("Deprecated option", SyslogSeverity::Warn),
+        ] {
+            builder.observe(&event);
+        }
+
+        let run = run();
+        let ended_at = run.start_time + TimeDelta::minutes(10);
+        let ended = run.started + Duration::from_secs(600);
+        let summary = builder.build("rnaseq", &run, ended_at, ended);
+
+        assert_eq!(summary.run_id, "run-123");
+        assert_eq!(summary.pipeline_name, "rnaseq");
+        assert_eq!(summary.duration_sec, 600);
+        assert_eq!(summary.instance_type.as_deref(), Some("m5.large"));
+        assert_eq!(summary.estimated_cost, Some(1.0));
+
+        let names: Vec<&str> = summary.tools.iter().map(|t| t.tool_name.as_str()).collect();
+        assert_eq!(names, ["STAR", "fastqc"]);
+        let star = &summary.tools[0];
+        assert_eq!(star.executions, 2);
+        assert_eq!(star.incomplete, 1);
+        assert_eq!(star.total_duration_ms, 65_000);
+        assert_eq!(star.max_duration_ms, 60_000);
+        assert_eq!(summary.tools[1].failed, 1);
+        assert_eq!(summary.failed_tool_executions, 1);
+
+        assert_eq!(summary.peak_cpu_utilization, 85.5);
+        assert_eq!(summary.peak_memory_used, 900);
+
+        let errors: Vec<(&str, u64)> = summary
+            .errors
+            .iter()
+            .map(|e| (e.error_display_name.as_str(), e.occurrences))
+            .collect();
+        assert_eq!(errors, [("Missing input file", 2), ("Out of memory", 1)]);
+    }
+
+    #[test]
+    fn test_clock_jump_does_not_affect_run_duration() {
+        let run = run();
+        // the system clock was stepped back an hour during a 10 minute run
+        let ended_at = run.start_time - TimeDelta::hours(1);
+        let ended = run.started + Duration::from_secs(600);
+        let summary = RunSummaryBuilder::default().build("rnaseq", &run, ended_at, ended);
+
+        assert_eq!(summary.duration_sec, 600);
+        assert_eq!(summary.estimated_cost, Some(1.0));
+        assert_eq!(summary.started_at, run.start_time);
+        assert_eq!(summary.ended_at, ended_at);
+    }
+
+    #[test]
+    fn test_summary_round_trips_as_attribute() {
+        let run = run();
+        let summary =
+            RunSummaryBuilder::default().build("rnaseq", &run, run.start_time, run.started);
+        let attributes = EventAttributes::RunSummary(Box::new(summary.clone()));
+
+        let json = serde_json::to_value(&attributes).unwrap();
+        assert_eq!(json["summary"]["run_id"], "run-123");
+        assert_eq!(json["summary"]["end_reason"], "completed");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run_summary.json");
+        write_run_summary(&path, &summary).unwrap();
+        let written: RunSummary = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, summary);
+    }
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A tool stuck in uninterruptible sleep (D state) while a network file system is mounted,
+/// which usually means it waits on an unresponsive file server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageStall {
+    pub tool_name: String,
+    pub pid: usize,
+    /// When the tool was first seen in uninterruptible sleep
+    pub stalled_since: DateTime<Utc>,
+    pub stalled_for_secs: u64,
+    /// The network mount the tool most likely waits on: the one holding its working directory,
+    /// else the first one mounted
+    pub mount_point: String,
+    /// File system type of the mount, e.g. `nfs4` or `lustre`
+    pub fs_type: String,
+}
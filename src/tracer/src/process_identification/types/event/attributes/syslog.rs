@@ -1,4 +1,37 @@
 use super::system_metrics::SystemMetric;
+use crate::utils::telemetry::ErrorCategory;
+
+/// Severity of a matched syslog line, mapped onto the event's OpenTelemetry severity fields
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogSeverity {
+    Info,
+    #[default]
+    Warn,
+    Error,
+    Critical,
+}
+
+impl SyslogSeverity {
+    pub fn as_text(&self) -> &'static str {
+        match self {
+            SyslogSeverity::Info => "INFO",
+            SyslogSeverity::Warn => "WARN",
+            SyslogSeverity::Error => "ERROR",
+            SyslogSeverity::Critical => "CRITICAL",
+        }
+    }
+
+    /// OpenTelemetry severity number (INFO=9, WARN=13, ERROR=17, FATAL=21)
+    pub fn as_number(&self) -> u8 {
+        match self {
+            SyslogSeverity::Info => 9,
+            SyslogSeverity::Warn => 13,
+            SyslogSeverity::Error => 17,
+            SyslogSeverity::Critical => 21,
+        }
+    }
+}
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SyslogProperties {
@@ -8,4 +41,12 @@ pub struct SyslogProperties {
     pub error_line: String,
     pub file_line_number: u64,
     pub file_previous_logs: Vec<String>,
+    #[serde(default)]
+    pub severity: SyslogSeverity,
+    /// Category of the error, as reported to Sentry
+    #[serde(default)]
+    pub error_category: ErrorCategory,
+    /// Tool that printed the line, if it was read from the tool's output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
 }
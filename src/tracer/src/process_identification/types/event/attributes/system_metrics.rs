@@ -1,7 +1,8 @@
 use crate::cloud_providers::aws::{
     aws_metadata::AwsInstanceMetaData, types::pricing::InstancePricingContext,
 };
-use std::collections::HashMap;
+use crate::process_identification::types::lockfiles::LockedEnvironment;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct DiskStatistic {
@@ -9,6 +10,11 @@ pub struct DiskStatistic {
     pub disk_used_space: u64,
     pub disk_available_space: u64,
     pub disk_utilization: f64,
+    /// File system type, e.g. `ext4`, `xfs`, `nfs4` or `lustre`
+    #[serde(default)]
+    pub fs_type: String,
+    #[serde(default)]
+    pub mount_point: String,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -30,9 +36,19 @@ pub struct SystemMetric {
     pub system_memory_used: u64,
     pub system_memory_available: u64,
     pub system_memory_utilization: f64,
+    /// Set when the memory fields describe the memory limit of the cgroup (e.g. container) the
+    /// tracer runs in rather than the host memory
+    #[serde(default)]
+    pub system_memory_cgroup_scoped: bool,
     pub system_memory_swap_total: u64,
     pub system_memory_swap_used: u64,
     pub system_cpu_utilization: f32,
+    /// CPU quota of the cgroup the tracer runs in, in CPUs, if any
+    #[serde(default)]
+    pub system_cpu_quota: Option<f64>,
+    /// CPU usage of the cgroup since the previous metric, in percent of `system_cpu_quota`
+    #[serde(default)]
+    pub system_cpu_quota_utilization: Option<f32>,
     pub system_disk_total_space: u64,
     pub system_disk_used_space: u64,
     pub system_disk_io: HashMap<String, DiskStatistic>,
@@ -41,6 +57,9 @@ pub struct SystemMetric {
     pub system_gpu_memory_total: Option<u64>,
     pub system_gpu_memory_utilization: Option<f64>,
     pub system_gpu_stats: HashMap<String, GpuStatistic>,
+    /// Events the kernel dropped so far because the eBPF ring buffer was full
+    #[serde(default)]
+    pub ebpf_events_lost: u64,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -49,7 +68,11 @@ pub struct SystemProperties {
     pub os_version: Option<String>,
     pub kernel_version: Option<String>,
     pub arch: String,
+    /// CPUs the workload can use: the host cores, or fewer if limited by a cgroup CPU quota
     pub num_cpus: usize,
+    /// CPU quota of the cgroup the tracer runs in, in CPUs, if any
+    #[serde(default)]
+    pub cpu_quota: Option<f64>,
     pub hostname: Option<String>,
     pub total_memory: u64,
     pub total_swap: u64,
@@ -66,4 +89,32 @@ pub struct SystemProperties {
     pub system_gpu_memory_used: Option<u64>,
     pub system_gpu_memory_total: Option<u64>,
     pub system_gpu_memory_utilization: Option<f64>,
+    /// Software environment of the run, captured once at start
+    #[serde(default)]
+    pub environment_fingerprint: Option<EnvironmentFingerprint>,
+}
+
+/// What a run's software environment consisted of, so the run can be reproduced: the
+/// conda/pixi environment and its packages, the images of the running containers and
+/// allowlisted environment variables. OS and kernel are in `SystemProperties`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EnvironmentFingerprint {
+    pub conda_environment: Option<String>,
+    pub pixi_environment: Option<String>,
+    pub pixi_manifest: Option<String>,
+    /// Package versions by name, from the pixi lockfile or the conda environment; version
+    /// constraints of the pixi manifest if there is no lockfile
+    pub packages: BTreeMap<String, String>,
+    /// Environments of the lockfiles and environment files found in the pipeline directory
+    #[serde(default)]
+    pub lockfile_environments: Vec<LockedEnvironment>,
+    pub container_images: Vec<ContainerImage>,
+    pub environment_variables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ContainerImage {
+    pub image: String,
+    /// Repository digest (`repo@sha256:...`), else the local image id
+    pub digest: Option<String>,
 }
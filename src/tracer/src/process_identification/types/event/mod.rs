@@ -1,9 +1,11 @@
 pub mod attributes;
+pub mod resource;
 
 use super::event::attributes::EventAttributes;
 use super::pipeline_tags::PipelineTags;
 use chrono::serde::ts_seconds;
 use chrono::{DateTime, Utc};
+use resource::ResourceAttributes;
 use serde::{Deserialize, Serialize};
 
 use typed_builder::TypedBuilder;
@@ -108,6 +110,9 @@ pub struct Event {
     pub run_id: Option<String>,
     pub attributes: Option<EventAttributes>,
     pub tags: Option<PipelineTags>,
+    /// Host the event was recorded on, exported as the event's resource attributes
+    #[serde(default)]
+    pub resource: Option<ResourceAttributes>,
 
     pub severity_text: Option<String>,
     pub severity_number: Option<u8>,
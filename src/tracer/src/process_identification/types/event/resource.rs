@@ -0,0 +1,84 @@
+use crate::process_identification::types::event::attributes::system_metrics::SystemProperties;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Host the events are recorded on, captured once at startup and attached to every event so tool
+/// executions can be sliced by hardware. Cloud fields are `None` outside of AWS.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceAttributes {
+    pub instance_type: Option<String>,
+    pub region: Option<String>,
+    pub availability_zone: Option<String>,
+    pub arch: String,
+}
+
+impl From<&SystemProperties> for ResourceAttributes {
+    fn from(properties: &SystemProperties) -> Self {
+        let aws = properties.aws_metadata.as_ref();
+        Self {
+            instance_type: aws.map(|metadata| metadata.instance_type.clone()),
+            region: aws.map(|metadata| metadata.region.clone()),
+            availability_zone: aws.map(|metadata| metadata.availability_zone.clone()),
+            arch: properties.arch.clone(),
+        }
+    }
+}
+
+impl ResourceAttributes {
+    /// Flat `resource_attributes` of an exported event, named after the OpenTelemetry resource
+    /// conventions. Missing values are kept as nulls so every row has the same keys.
+    pub fn to_flat_map(&self) -> Map<String, Value> {
+        let cloud_provider = self.region.as_ref().map(|_| "aws");
+        let value = json!({
+            "cloud.provider": cloud_provider,
+            "cloud.region": self.region,
+            "cloud.availability_zone": self.availability_zone,
+            "host.type": self.instance_type,
+            "host.arch": self.arch,
+        });
+        match value {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_cloud_resource_has_null_cloud_fields() {
+        let resource = ResourceAttributes {
+            arch: "x86_64".to_string(),
+            ..Default::default()
+        };
+
+        let flat = resource.to_flat_map();
+        assert_eq!(flat["host.arch"], "x86_64");
+        for key in [
+            "cloud.provider",
+            "cloud.region",
+            "cloud.availability_zone",
+            "host.type",
+        ] {
+            assert_eq!(flat[key], Value::Null, "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_aws_resource() {
+        let resource = ResourceAttributes {
+            instance_type: Some("m5.large".to_string()),
+            region: Some("us-east-1".to_string()),
+            availability_zone: Some("us-east-1a".to_string()),
+            arch: "aarch64".to_string(),
+        };
+
+        let flat = resource.to_flat_map();
+        assert_eq!(flat["cloud.provider"], "aws");
+        assert_eq!(flat["cloud.region"], "us-east-1");
+        assert_eq!(flat["cloud.availability_zone"], "us-east-1a");
+        assert_eq!(flat["host.type"], "m5.large");
+    }
+}
@@ -5,6 +5,7 @@ use anyhow::Context;
 
 use crate::constants::environment::ENV_UNKNOWN;
 use crate::process_identification::types::event::attributes::process::ProcessProperties;
+use crate::process_identification::types::event::resource::ResourceAttributes;
 use crate::process_identification::types::event::{attributes::EventAttributes, Event};
 use serde::Serialize;
 use std::convert::TryFrom;
@@ -50,7 +51,11 @@ impl TryFrom<Event> for EventInsert {
 
     fn try_from(event: Event) -> anyhow::Result<Self> {
         let mut attributes = json!({});
-        let mut resource_attributes = json!({});
+        let mut resource_attributes = event
+            .resource
+            .as_ref()
+            .map(ResourceAttributes::to_flat_map)
+            .unwrap_or_default();
         let mut job_id = None;
         let mut trace_id = None;
         let mut cpu_usage = None;
@@ -72,15 +77,24 @@ impl TryFrom<Event> for EventInsert {
                 EventAttributes::SystemProperties(system_properties) => {
                     ec2_cost_per_hour = system_properties.ec2_cost_per_hour;
 
-                    // Properly flatten and assign to `resource_attributes`
-                    let mut flat = Map::new();
+                    let mut properties = serde_json::to_value(system_properties)
+                        .context("serialize system_properties")?;
+                    // the fingerprint is kept whole, as a single blob
+                    if let Some(fingerprint) = properties
+                        .as_object_mut()
+                        .and_then(|properties| properties.remove("environment_fingerprint"))
+                        .filter(|fingerprint| !fingerprint.is_null())
+                    {
+                        resource_attributes
+                            .insert("environment_fingerprint".to_string(), fingerprint);
+                    }
+
+                    // Properly flatten and add to `resource_attributes`
                     crate::process_identification::utils::flatten_with_prefix(
                         "system_properties",
-                        &serde_json::to_value(system_properties)
-                            .context("serialize system_properties")?,
-                        &mut flat,
+                        &properties,
+                        &mut resource_attributes,
                     );
-                    resource_attributes = Value::Object(flat);
                 }
                 EventAttributes::NewRun { trace_id: t } => {
                     trace_id = Some(t.clone());
@@ -113,7 +127,11 @@ impl TryFrom<Event> for EventInsert {
                 .and_then(|tags| tags.environment.clone())
                 .or_else(|| Some(ENV_UNKNOWN.to_string())),
             pipeline_type: tags.as_ref().and_then(|tags| tags.pipeline_type.clone()),
-            user_id: tags.as_ref().unwrap().user_id.clone().unwrap(),
+            // not logged in, in local-only mode
+            user_id: tags
+                .as_ref()
+                .and_then(|tags| tags.user_id.clone())
+                .unwrap_or_default(),
             organization_id: tags.as_ref().and_then(|tags| tags.organization_id.clone()),
             department: tags.as_ref().map(|tags| tags.department.clone()),
 
@@ -130,7 +148,7 @@ impl TryFrom<Event> for EventInsert {
             process_status: event.process_status.to_string(),
 
             attributes,
-            resource_attributes,
+            resource_attributes: Value::Object(resource_attributes),
             tags: serde_json::to_value(&tags).context("Failed to serialize tags")?,
         })
     }
@@ -0,0 +1,404 @@
+//! Parsers of the lockfiles and environment files of conda-based package managers, which list
+//! the packages, and their versions, a pipeline runs with
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// Lockfiles and environment files looked for in a pipeline directory
+pub const LOCKFILE_NAMES: &[&str] = &[
+    "pixi.lock",
+    "conda-lock.yml",
+    "environment.yml",
+    "environment.yaml",
+];
+
+/// Name of the environment of files that don't name theirs
+const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// An environment defined in a lockfile or environment file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedEnvironment {
+    pub lockfile: String,
+    pub name: String,
+    /// Versions by package name; an environment file may only constrain them (e.g. `>=2.7`)
+    pub packages: BTreeMap<String, String>,
+}
+
+/// The lockfiles and environment files in `directory`
+pub fn find_lockfiles(directory: &Path) -> Vec<PathBuf> {
+    LOCKFILE_NAMES
+        .iter()
+        .map(|name| directory.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// The environments the file at `path` defines, with their packages for the current platform
+pub fn load_lockfile(path: &Path) -> Result<Vec<LockedEnvironment>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    let environments = match file_name {
+        Some("pixi.lock") => parse_pixi_lock(&contents, current_platform()?),
+        Some("conda-lock.yml") => parse_conda_lock(&contents, current_platform()?),
+        _ => parse_environment_file(&contents).map(|environment| vec![environment]),
+    }
+    .with_context(|| format!("Invalid lockfile {}", path.display()))?;
+    Ok(environments
+        .into_iter()
+        .map(|(name, packages)| LockedEnvironment {
+            lockfile: path.display().to_string(),
+            name,
+            packages,
+        })
+        .collect())
+}
+
+/// Name of the current platform in conda lockfiles
+pub fn current_platform() -> Result<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Ok("linux-64"),
+        ("linux", "aarch64") => Ok("linux-aarch64"),
+        ("macos", "x86_64") => Ok("osx-64"),
+        ("macos", "aarch64") => Ok("osx-arm64"),
+        (os, arch) => Err(anyhow!("no conda platform for {} on {}", os, arch)),
+    }
+}
+
+fn load_yaml(contents: &str) -> Result<Yaml> {
+    YamlLoader::load_from_str(contents)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("empty file"))
+}
+
+/// Packages of each environment of a `pixi.lock` on `platform`. The package list records the
+/// versions of most packages; the others are named after their archive.
+pub fn parse_pixi_lock(
+    contents: &str,
+    platform: &str,
+) -> Result<Vec<(String, BTreeMap<String, String>)>> {
+    let doc = load_yaml(contents)?;
+
+    let recorded: HashMap<&str, (&str, &str)> = doc["packages"]
+        .as_vec()
+        .into_iter()
+        .flatten()
+        .filter_map(|package| {
+            Some((
+                package_url(package)?,
+                (package["name"].as_str()?, package["version"].as_str()?),
+            ))
+        })
+        .collect();
+
+    let environments = doc["environments"]
+        .as_hash()
+        .ok_or_else(|| anyhow!("Expected a hash of environments"))?;
+    Ok(environments
+        .iter()
+        .filter_map(|(name, environment)| {
+            let installed = environment["packages"][platform].as_vec()?;
+            let packages = installed
+                .iter()
+                .filter_map(package_url)
+                .filter_map(|url| match recorded.get(url) {
+                    Some((name, version)) => Some((name.to_string(), version.to_string())),
+                    None => name_and_version_from_url(url),
+                })
+                .collect();
+            Some((name.as_str()?.to_string(), packages))
+        })
+        .collect())
+}
+
+fn package_url(package: &Yaml) -> Option<&str> {
+    package["conda"]
+        .as_str()
+        .or_else(|| package["pypi"].as_str())
+}
+
+/// Packages of a `conda-lock.yml` on `platform`; it defines a single environment
+pub fn parse_conda_lock(
+    contents: &str,
+    platform: &str,
+) -> Result<Vec<(String, BTreeMap<String, String>)>> {
+    let doc = load_yaml(contents)?;
+    let packages = doc["package"]
+        .as_vec()
+        .ok_or_else(|| anyhow!("Expected a list of packages"))?
+        .iter()
+        .filter(|package| package["platform"].as_str() == Some(platform))
+        .filter_map(|package| {
+            Some((
+                package["name"].as_str()?.to_string(),
+                scalar(&package["version"])?,
+            ))
+        })
+        .collect();
+    Ok(vec![(DEFAULT_ENVIRONMENT.to_string(), packages)])
+}
+
+/// A string, or a version YAML reads as a number (e.g. an unquoted `1.23`)
+fn scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(value) | Yaml::Real(value) => Some(value.clone()),
+        Yaml::Integer(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// The environment of a conda `environment.yml`, with its conda and pip dependencies. Exact
+/// pins (`samtools=1.20`, `multiqc==1.23`) give the version, other specs their constraint.
+pub fn parse_environment_file(contents: &str) -> Result<(String, BTreeMap<String, String>)> {
+    let doc = load_yaml(contents)?;
+    let name = doc["name"]
+        .as_str()
+        .unwrap_or(DEFAULT_ENVIRONMENT)
+        .to_string();
+    let mut packages = BTreeMap::new();
+    for dependency in doc["dependencies"].as_vec().into_iter().flatten() {
+        if let Some(spec) = dependency.as_str() {
+            let spec = spec.rsplit("::").next().unwrap_or(spec);
+            packages.extend(parse_spec(spec, "="));
+        }
+        for spec in dependency["pip"].as_vec().into_iter().flatten() {
+            packages.extend(spec.as_str().and_then(|spec| parse_spec(spec, "==")));
+        }
+    }
+    Ok((name, packages))
+}
+
+/// Name and version (or constraint) of a dependency spec, `pin` being the operator of exact
+/// versions. A conda build string after the version is left out.
+fn parse_spec(spec: &str, pin: &str) -> Option<(String, String)> {
+    let spec = spec.trim();
+    let split = spec
+        .find(|c: char| "=<>!~[;@ ".contains(c))
+        .unwrap_or(spec.len());
+    let (name, constraint) = spec.split_at(split);
+    if name.is_empty() {
+        return None;
+    }
+    let constraint = constraint.trim();
+    let version = match constraint.strip_prefix(pin) {
+        Some(version) if !version.starts_with(['=', '<', '>']) => {
+            version.split('=').next().unwrap_or(version)
+        }
+        _ if constraint.is_empty() => "*",
+        _ => constraint,
+    };
+    Some((name.to_string(), version.trim().to_string()))
+}
+
+/// Name and version of a conda archive or Python wheel from its URL
+pub fn name_and_version_from_url(url: &str) -> Option<(String, String)> {
+    let file_name = url.rsplit('/').next()?;
+    if let Some(stem) = file_name.strip_suffix(".whl") {
+        let mut parts = stem.splitn(3, '-');
+        return Some((parts.next()?.to_string(), parts.next()?.to_string()));
+    }
+    let stem = file_name
+        .strip_suffix(".conda")
+        .or_else(|| file_name.strip_suffix(".tar.bz2"))?;
+    parse_conda_archive_name(stem)
+}
+
+/// Name and version of a conda package from its `<name>-<version>-<build>` archive name; the
+/// name itself may contain dashes
+pub fn parse_conda_archive_name(stem: &str) -> Option<(String, String)> {
+    let mut parts = stem.rsplitn(3, '-');
+    let _build = parts.next()?;
+    let version = parts.next()?;
+    let name = parts.next()?;
+    Some((name.to_string(), version.to_string()))
+}
+
+/// Versions of the packages installed in the run's environments, to tell which version of a
+/// tool ran
+#[derive(Debug, Clone, Default)]
+pub struct PackageVersions {
+    /// by lowercase package name
+    versions: HashMap<String, String>,
+}
+
+impl PackageVersions {
+    /// Takes the versions of the first environment a package is in; constraints of environment
+    /// files are left out, as they don't tell which version is installed
+    pub fn from_environments<'a>(
+        environments: impl IntoIterator<Item = &'a BTreeMap<String, String>>,
+    ) -> Self {
+        let mut versions = HashMap::new();
+        for packages in environments {
+            for (name, version) in packages {
+                if version.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+                    versions
+                        .entry(name.to_lowercase())
+                        .or_insert_with(|| version.clone());
+                }
+            }
+        }
+        Self { versions }
+    }
+
+    /// The installed version of the package named like `tool`, ignoring case
+    pub fn version_of(&self, tool: &str) -> Option<&str> {
+        self.versions.get(&tool.to_lowercase()).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIXI_LOCK: &str = r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.4-h194c7f8_0_cpython.conda
+      - conda: https://conda.anaconda.org/bioconda/noarch/snakemake-minimal-8.16.0-pyhdfd78af_0.tar.bz2
+      - pypi: https://files.pythonhosted.org/packages/f9/requests-2.32.3-py3-none-any.whl
+      osx-arm64:
+      - conda: https://conda.anaconda.org/conda-forge/osx-arm64/python-3.12.3-h4a7b5fc_0_cpython.conda
+  test:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/pytest-8.2.2-pyhd8ed1ab_0.conda
+packages:
+- conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.4-h194c7f8_0_cpython.conda
+  sha256: 4ba3d6d1b4b2c6e7e36e1dc3a0bc6e7e4a1e6c5b4ae2b84eb35f6c1e5e6bbd73
+- pypi: https://files.pythonhosted.org/packages/f9/requests-2.32.3-py3-none-any.whl
+  name: requests
+  version: 2.32.3
+"#;
+
+    #[test]
+    fn test_parse_pixi_lock_environments() {
+        let environments = parse_pixi_lock(PIXI_LOCK, "linux-64").unwrap();
+        assert_eq!(environments.len(), 2);
+        let (name, packages) = &environments[0];
+        assert_eq!(name, "default");
+        assert_eq!(
+            packages,
+            &BTreeMap::from([
+                ("python".to_string(), "3.12.4".to_string()),
+                ("snakemake-minimal".to_string(), "8.16.0".to_string()),
+                ("requests".to_string(), "2.32.3".to_string()),
+            ])
+        );
+        let (name, packages) = &environments[1];
+        assert_eq!(name, "test");
+        assert_eq!(packages["pytest"], "8.2.2");
+
+        // environments without packages for the platform are left out
+        let environments = parse_pixi_lock(PIXI_LOCK, "osx-arm64").unwrap();
+        assert_eq!(environments.len(), 1);
+        assert_eq!(environments[0].1["python"], "3.12.3");
+    }
+
+    #[test]
+    fn test_parse_conda_lock() {
+        let lock = r#"
+version: 1
+metadata:
+  platforms: [linux-64, osx-arm64]
+package:
+- name: samtools
+  version: '1.20'
+  manager: conda
+  platform: linux-64
+- name: samtools
+  version: '1.19'
+  manager: conda
+  platform: osx-arm64
+- name: multiqc
+  version: 1.23
+  manager: pip
+  platform: linux-64
+"#;
+        let environments = parse_conda_lock(lock, "linux-64").unwrap();
+        assert_eq!(environments.len(), 1);
+        let (name, packages) = &environments[0];
+        assert_eq!(name, "default");
+        assert_eq!(packages["samtools"], "1.20");
+        assert_eq!(packages["multiqc"], "1.23");
+    }
+
+    #[test]
+    fn test_parse_environment_file() {
+        let environment = r#"
+name: rnaseq
+channels:
+  - conda-forge
+  - bioconda
+dependencies:
+  - python=3.12
+  - samtools=1.20=h50ea8bc_0
+  - bioconda::star>=2.7.11
+  - fastqc
+  - pip
+  - pip:
+    - multiqc==1.23
+    - pysam>=0.22
+"#;
+        let (name, packages) = parse_environment_file(environment).unwrap();
+        assert_eq!(name, "rnaseq");
+        assert_eq!(packages["python"], "3.12");
+        assert_eq!(packages["samtools"], "1.20");
+        assert_eq!(packages["star"], ">=2.7.11");
+        assert_eq!(packages["fastqc"], "*");
+        assert_eq!(packages["multiqc"], "1.23");
+        assert_eq!(packages["pysam"], ">=0.22");
+    }
+
+    #[test]
+    fn test_find_and_load_lockfiles() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(
+            directory.path().join("environment.yml"),
+            "dependencies:\n  - samtools=1.20\n",
+        )
+        .unwrap();
+        std::fs::write(directory.path().join("README.md"), "").unwrap();
+
+        let lockfiles = find_lockfiles(directory.path());
+        assert_eq!(lockfiles, vec![directory.path().join("environment.yml")]);
+        let environments = load_lockfile(&lockfiles[0]).unwrap();
+        assert_eq!(environments[0].name, "default");
+        assert_eq!(environments[0].packages["samtools"], "1.20");
+    }
+
+    #[test]
+    fn test_parse_conda_archive_name() {
+        assert_eq!(
+            parse_conda_archive_name("r-base-4.3.3-hf0d99cb_3"),
+            Some(("r-base".to_string(), "4.3.3".to_string()))
+        );
+        assert_eq!(parse_conda_archive_name("history"), None);
+    }
+
+    #[test]
+    fn test_package_versions_match_tools() {
+        let active = BTreeMap::from([("star".to_string(), "2.7.11b".to_string())]);
+        let other = BTreeMap::from([
+            ("star".to_string(), "2.7.10a".to_string()),
+            ("fastqc".to_string(), ">=0.12".to_string()),
+            ("samtools".to_string(), "1.20".to_string()),
+        ]);
+        let versions = PackageVersions::from_environments([&active, &other]);
+        assert_eq!(versions.version_of("STAR"), Some("2.7.11b"));
+        assert_eq!(versions.version_of("samtools"), Some("1.20"));
+        // constraints aren't installed versions
+        assert_eq!(versions.version_of("fastqc"), None);
+        assert_eq!(versions.version_of("bwa"), None);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod current_run;
 pub mod event;
 pub mod extracts;
+pub mod lockfiles;
 pub mod pipeline_tags;
+pub mod tag_schema;
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
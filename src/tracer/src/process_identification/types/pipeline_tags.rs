@@ -1,3 +1,4 @@
+use crate::cloud_providers::aws::batch::AwsBatchJob;
 use crate::utils::env::{self, USER_ID_ENV_VAR};
 use crate::utils::input_validation::StringValueParser;
 use clap::Args;
@@ -60,6 +61,11 @@ pub struct PipelineTags {
 
     #[clap(long, default_value = "")]
     pub user_full_name: String,
+
+    /// AWS Batch job the run executes in, detected at run initialization
+    #[clap(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aws_batch: Option<AwsBatchJob>,
 }
 
 impl Default for PipelineTags {
@@ -77,6 +83,7 @@ impl Default for PipelineTags {
             email: None,
             organization_slug: "".into(),
             user_full_name: "".into(),
+            aws_batch: None,
         }
     }
 }
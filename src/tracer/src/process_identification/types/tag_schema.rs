@@ -0,0 +1,288 @@
+use super::pipeline_tags::PipelineTags;
+use crate::utils::yaml::{Yaml, YamlExt};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+use yaml_rust2::YamlLoader;
+
+/// Tag fields a schema can constrain
+const FIELDS: &[&str] = &["environment", "pipeline_type", "department", "team"];
+
+/// What happens to tag values the schema doesn't allow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSchemaPolicy {
+    /// Values are replaced by the allowed value they refer to; any other value is an error
+    Strict,
+    /// Values are replaced by the allowed value they refer to; other values are kept with a
+    /// warning
+    #[default]
+    Normalize,
+    /// Values are kept as they are, with a warning if they aren't allowed values
+    Warn,
+}
+
+impl FromStr for TagSchemaPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "strict" => Ok(Self::Strict),
+            "normalize" => Ok(Self::Normalize),
+            "warn" => Ok(Self::Warn),
+            _ => bail!(
+                "invalid tag schema policy '{}': expected 'strict', 'normalize' or 'warn'",
+                value
+            ),
+        }
+    }
+}
+
+/// An allowed value of a tag field and the aliases that refer to it
+#[derive(Debug, Clone, PartialEq)]
+struct AllowedValue {
+    value: String,
+    aliases: Vec<String>,
+}
+
+/// Allowed values of pipeline tags, loaded from a YAML file listing them per field, e.g.
+///
+/// ```yaml
+/// environment:
+///   - local
+///   - production: [prod, prd]
+/// pipeline_type:
+///   - RNA-seq
+/// ```
+///
+/// Values and aliases match ignoring case and separators. Fields that aren't listed accept
+/// any value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagSchema {
+    fields: Vec<(&'static str, Vec<AllowedValue>)>,
+}
+
+impl TagSchema {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tag schema {}", path.display()))?;
+        Self::from_yaml_str(&contents)
+            .with_context(|| format!("Invalid tag schema {}", path.display()))
+    }
+
+    pub fn from_yaml_str(yaml_str: &str) -> Result<Self> {
+        let docs = YamlLoader::load_from_str(yaml_str)?;
+        let Some(doc) = docs.into_iter().next() else {
+            return Ok(Self::default());
+        };
+        let hash = doc
+            .into_hash()
+            .ok_or(anyhow!("Expected top-level element to be a hash"))?;
+
+        let mut fields = Vec::new();
+        for (key, values) in hash {
+            let key = key.to_string()?;
+            let field = FIELDS
+                .iter()
+                .find(|field| **field == key)
+                .ok_or_else(|| anyhow!("Unknown tag field {}", key))?;
+            let values = values
+                .into_vec()
+                .ok_or_else(|| anyhow!("Expected a list of values for {}", key))?
+                .into_iter()
+                .map(parse_allowed_value)
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("Invalid values for {}", key))?;
+            fields.push((*field, values));
+        }
+        Ok(Self { fields })
+    }
+
+    /// Checks the constrained fields of `tags` against the schema, rewriting them according to
+    /// `policy`. Returns a warning for each value that isn't allowed and wasn't rejected.
+    pub fn apply(&self, tags: &mut PipelineTags, policy: TagSchemaPolicy) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+
+        for (field, allowed) in &self.fields {
+            let value = match *field {
+                "environment" => tags.environment.as_mut(),
+                "pipeline_type" => tags.pipeline_type.as_mut(),
+                "department" => Some(&mut tags.department),
+                "team" => Some(&mut tags.team),
+                _ => None,
+            };
+            let Some(value) = value else {
+                continue;
+            };
+
+            match (resolve(allowed, value), policy) {
+                (Some(canonical), _) if canonical == value.as_str() => {}
+                (Some(canonical), TagSchemaPolicy::Warn) => {
+                    warnings.push(format!("{} '{}' should be '{}'", field, value, canonical))
+                }
+                (Some(canonical), _) => *value = canonical.to_string(),
+                (None, TagSchemaPolicy::Strict) => errors.push(format!(
+                    "{} '{}' is not one of: {}",
+                    field,
+                    value,
+                    list_values(allowed)
+                )),
+                (None, _) => warnings.push(format!(
+                    "{} '{}' is not one of: {}",
+                    field,
+                    value,
+                    list_values(allowed)
+                )),
+            }
+        }
+
+        if !errors.is_empty() {
+            bail!("Tags don't match the tag schema: {}", errors.join("; "));
+        }
+        Ok(warnings)
+    }
+}
+
+fn parse_allowed_value(yaml: Yaml) -> Result<AllowedValue> {
+    if let Some(hash) = yaml.as_hash() {
+        let mut entries = hash.iter();
+        let (Some((value, aliases)), None) = (entries.next(), entries.next()) else {
+            bail!("Expected a single value with its aliases");
+        };
+        let aliases = aliases
+            .as_vec()
+            .ok_or_else(|| anyhow!("Expected a list of aliases"))?
+            .iter()
+            .map(|alias| alias.to_string())
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(AllowedValue {
+            value: value.to_string()?,
+            aliases,
+        });
+    }
+    Ok(AllowedValue {
+        value: yaml.to_string()?,
+        aliases: Vec::new(),
+    })
+}
+
+/// The allowed value `value` refers to, ignoring case and separators
+fn resolve<'a>(allowed: &'a [AllowedValue], value: &str) -> Option<&'a str> {
+    fn normalize(value: &str) -> String {
+        value
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+    let value = normalize(value);
+    allowed
+        .iter()
+        .find(|allowed| {
+            normalize(&allowed.value) == value
+                || allowed
+                    .aliases
+                    .iter()
+                    .any(|alias| normalize(alias) == value)
+        })
+        .map(|allowed| allowed.value.as_str())
+}
+
+fn list_values(allowed: &[AllowedValue]) -> String {
+    allowed
+        .iter()
+        .map(|allowed| allowed.value.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+environment:
+  - local
+  - staging: [stage]
+  - production: [prod, prd]
+pipeline_type:
+  - RNA-seq
+  - WGS
+"#;
+
+    fn tags(environment: &str, pipeline_type: &str) -> PipelineTags {
+        PipelineTags {
+            environment: Some(environment.to_string()),
+            pipeline_type: Some(pipeline_type.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aliases_and_spellings_are_normalized() {
+        let schema = TagSchema::from_yaml_str(SCHEMA).unwrap();
+        let mut tags = tags("PRD", "rna seq");
+        let warnings = schema.apply(&mut tags, TagSchemaPolicy::Normalize).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(tags.environment.as_deref(), Some("production"));
+        assert_eq!(tags.pipeline_type.as_deref(), Some("RNA-seq"));
+        // unconstrained fields are left alone
+        assert_eq!(tags.team, "dev");
+    }
+
+    #[test]
+    fn test_strict_policy_rejects_unknown_values() {
+        let schema = TagSchema::from_yaml_str(SCHEMA).unwrap();
+        let mut tags = tags("qa", "WGS");
+        let error = schema
+            .apply(&mut tags, TagSchemaPolicy::Strict)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Tags don't match the tag schema: environment 'qa' is not one of: local, staging, production"
+        );
+
+        // values the schema can resolve are still normalized
+        let mut tags = self::tags("stage", "wgs");
+        schema.apply(&mut tags, TagSchemaPolicy::Strict).unwrap();
+        assert_eq!(tags.environment.as_deref(), Some("staging"));
+        assert_eq!(tags.pipeline_type.as_deref(), Some("WGS"));
+    }
+
+    #[test]
+    fn test_normalize_policy_keeps_unknown_values() {
+        let schema = TagSchema::from_yaml_str(SCHEMA).unwrap();
+        let mut tags = tags("qa", "prod");
+        let warnings = schema.apply(&mut tags, TagSchemaPolicy::Normalize).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(tags.environment.as_deref(), Some("qa"));
+        assert_eq!(tags.pipeline_type.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn test_warn_policy_changes_nothing() {
+        let schema = TagSchema::from_yaml_str(SCHEMA).unwrap();
+        let mut tags = tags("prod", "WGS");
+        let warnings = schema.apply(&mut tags, TagSchemaPolicy::Warn).unwrap();
+        assert_eq!(warnings, vec!["environment 'prod' should be 'production'"]);
+        assert_eq!(tags.environment.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn test_invalid_schemas_are_rejected() {
+        assert!(TagSchema::from_yaml_str("owner:\n  - me\n").is_err());
+        assert!(TagSchema::from_yaml_str("environment: local\n").is_err());
+        assert_eq!(TagSchema::from_yaml_str("").unwrap(), TagSchema::default());
+    }
+
+    #[test]
+    fn test_policy_from_str() {
+        assert_eq!(
+            "strict".parse::<TagSchemaPolicy>().unwrap(),
+            TagSchemaPolicy::Strict
+        );
+        assert!("lenient".parse::<TagSchemaPolicy>().is_err());
+    }
+}
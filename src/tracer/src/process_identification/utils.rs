@@ -1,10 +1,10 @@
 use crate::process_identification::types::event::attributes::process::ProcessProperties;
 use crate::process_identification::types::event::{attributes::EventAttributes, Event};
+use crate::utils::append_file::AppendFile;
 use crate::utils::workdir::TRACER_WORK_DIR;
 use anyhow::{Context, Result};
 use serde_json::{Map, Value};
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::sync::LazyLock;
 use tracer_ebpf::ebpf_trigger::ProcessStartTrigger;
 use tracing::{error, info};
 
@@ -33,6 +33,11 @@ pub fn flatten_event_attributes(event: &Event) -> Result<Value> {
             ("new_run", serde_json::json!({ "trace_id": trace_id }))
         }
         EventAttributes::PythonFunction(p) => ("python_function", serde_json::to_value(p)?),
+        EventAttributes::RunSummary(p) => ("summary", serde_json::to_value(p)?),
+        EventAttributes::Heartbeat(p) => ("heartbeat", serde_json::to_value(p)?),
+        EventAttributes::ExecFailure(p) => ("exec_failure", serde_json::to_value(p)?),
+        EventAttributes::StorageStall(p) => ("storage_stall", serde_json::to_value(p)?),
+        EventAttributes::ClockSkew(p) => ("clock_skew", serde_json::to_value(p)?),
     };
 
     flatten_with_prefix(prefix, &json, &mut map);
@@ -63,6 +68,9 @@ pub fn flatten_with_prefix(prefix: &str, val: &Value, out: &mut Map<String, Valu
     }
 }
 
+static PROCESS_MATCHES_LOG: LazyLock<AppendFile> =
+    LazyLock::new(|| AppendFile::new(&TRACER_WORK_DIR.process_matches_file));
+
 pub fn log_matched_process(trigger: &ProcessStartTrigger, matched_rule: &str, is_matched: bool) {
     let matched_string = if is_matched { "MATCHED" } else { "NOT MATCHED" };
 
@@ -73,12 +81,7 @@ pub fn log_matched_process(trigger: &ProcessStartTrigger, matched_rule: &str, is
 
     info!(log_line);
 
-    if let Err(e) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&TRACER_WORK_DIR.process_matches_file)
-        .and_then(|mut file| file.write_all(log_line.as_bytes()))
-    {
+    if let Err(e) = PROCESS_MATCHES_LOG.append(log_line.as_bytes()) {
         error!("Failed to write match log: {}", e);
     }
 }
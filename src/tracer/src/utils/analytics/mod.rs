@@ -2,7 +2,7 @@ pub mod types;
 
 use crate::constants::TRACER_ANALYTICS_ENDPOINT;
 use crate::utils::analytics::types::{AnalyticsEventType, AnalyticsPayload};
-use reqwest::Client;
+use crate::utils::http;
 use std::collections::HashMap;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tokio_retry::Retry;
@@ -22,7 +22,7 @@ pub async fn send_event(
     event: AnalyticsEventType,
     metadata: Option<HashMap<String, String>>,
 ) -> anyhow::Result<()> {
-    let client = Client::new();
+    let client = http::client();
     let retry_strategy = ExponentialBackoff::from_millis(500).map(jitter).take(3);
 
     // Ensure the environment is set in metadata
@@ -0,0 +1,127 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// A log file opened once in append mode and reused across writes.
+///
+/// Opening and closing the file on every line costs two syscalls per write on hot paths such as
+/// process matching. The handle is reopened only if the file has been removed or replaced (e.g.
+/// by `tracer cleanup` or log rotation), which is detected by comparing inodes. Every write is a
+/// single unbuffered `write_all` on an `O_APPEND` handle, so lines are never lost in a buffer on
+/// crash.
+pub struct AppendFile {
+    path: PathBuf,
+    handle: Mutex<Option<OpenHandle>>,
+    opens: AtomicUsize,
+}
+
+struct OpenHandle {
+    file: File,
+    dev: u64,
+    ino: u64,
+}
+
+impl OpenHandle {
+    fn is_current(&self, path: &Path) -> bool {
+        std::fs::metadata(path)
+            .map(|metadata| metadata.dev() == self.dev && metadata.ino() == self.ino)
+            .unwrap_or(false)
+    }
+}
+
+impl AppendFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            handle: Mutex::new(None),
+            opens: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn append(&self, data: &[u8]) -> io::Result<()> {
+        let mut guard = self
+            .handle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let handle = match guard.take() {
+            Some(open) if open.is_current(&self.path) => guard.insert(open),
+            _ => guard.insert(self.open()?),
+        };
+
+        handle.file.write_all(data)
+    }
+
+    /// Number of times the file has been opened, for diagnostics
+    pub fn open_count(&self) -> usize {
+        self.opens.load(Ordering::Relaxed)
+    }
+
+    fn open(&self) -> io::Result<OpenHandle> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let metadata = file.metadata()?;
+        self.opens.fetch_add(1, Ordering::Relaxed);
+        Ok(OpenHandle {
+            file,
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_reuses_handle() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("matches.txt");
+        let log = AppendFile::new(&path);
+
+        for i in 0..1000 {
+            log.append(format!("line {}\n", i).as_bytes()).unwrap();
+        }
+
+        // a single open instead of one open/close pair per line
+        assert_eq!(log.open_count(), 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 1000);
+        assert_eq!(content.lines().last(), Some("line 999"));
+    }
+
+    #[test]
+    fn test_append_reopens_removed_or_rotated_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("matches.txt");
+        let log = AppendFile::new(&path);
+
+        log.append(b"first\n").unwrap();
+        std::fs::remove_file(&path).unwrap();
+        log.append(b"second\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second\n");
+
+        std::fs::rename(&path, temp_dir.path().join("matches.txt.1")).unwrap();
+        log.append(b"third\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(log.open_count(), 3);
+    }
+
+    #[test]
+    fn test_append_preserves_existing_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("matches.txt");
+        std::fs::write(&path, "existing\n").unwrap();
+
+        AppendFile::new(&path).append(b"new\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "existing\nnew\n");
+    }
+}
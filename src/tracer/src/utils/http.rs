@@ -0,0 +1,153 @@
+//! Construction of the HTTP clients used to reach Tracer services.
+//!
+//! Deployments behind an internal CA point `ca_bundle` (or `TRACER_CA_BUNDLE`) to a PEM bundle.
+//! Its certificates are trusted in addition to the built-in roots by every client built here.
+
+use anyhow::{bail, Context, Result};
+use reqwest::{Certificate, Client, ClientBuilder};
+use std::path::Path;
+use std::sync::OnceLock;
+
+static EXTRA_ROOT_CERTIFICATES: OnceLock<Vec<Certificate>> = OnceLock::new();
+
+/// Loads the certificates of a PEM bundle, failing if it contains none
+pub fn load_ca_bundle(path: &Path) -> Result<Vec<Certificate>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+    let certificates = Certificate::from_pem_bundle(&pem)
+        .with_context(|| format!("Invalid CA bundle {}", path.display()))?;
+    if certificates.is_empty() {
+        bail!("CA bundle {} contains no certificates", path.display());
+    }
+    Ok(certificates)
+}
+
+/// Trusts the certificates of the given bundle in all clients built afterwards. Must be called
+/// before any client is built; later calls are ignored.
+pub fn install_ca_bundle(path: Option<&str>) -> Result<()> {
+    let certificates = match path {
+        Some(path) => load_ca_bundle(Path::new(path))?,
+        None => Vec::new(),
+    };
+    let _ = EXTRA_ROOT_CERTIFICATES.set(certificates);
+    Ok(())
+}
+
+/// Returns a client builder trusting the installed CA bundle, if any
+pub fn client_builder() -> ClientBuilder {
+    with_root_certificates(
+        Client::builder(),
+        EXTRA_ROOT_CERTIFICATES
+            .get()
+            .map(Vec::as_slice)
+            .unwrap_or(&[]),
+    )
+}
+
+/// Returns a client trusting the installed CA bundle, if any
+pub fn client() -> Client {
+    client_builder()
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+fn with_root_certificates(builder: ClientBuilder, certificates: &[Certificate]) -> ClientBuilder {
+    certificates
+        .iter()
+        .cloned()
+        .fold(builder, |builder, certificate| {
+            builder.add_root_certificate(certificate)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    use rustls::ServerConfig;
+    use std::sync::Arc;
+    use tempfile::NamedTempFile;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    const TEST_CA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/test-ca.pem");
+    /// Certificate for 127.0.0.1 signed by `TEST_CA`, and its key
+    const TEST_SERVER_CERT: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/test-server.pem");
+    const TEST_SERVER_KEY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/assets/test-server-key.pem"
+    );
+
+    /// Serves "ok" over HTTPS with the test server certificate, returning the URL
+    async fn serve_tls() -> String {
+        let certificates = CertificateDer::pem_file_iter(TEST_SERVER_CERT)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = PrivateKeyDer::from_pem_file(TEST_SERVER_KEY).unwrap();
+        let config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(certificates, key)
+                .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                // the handshake fails for clients that don't trust the test CA
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    continue;
+                };
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+                    )
+                    .await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        format!("https://{}/", address)
+    }
+
+    #[tokio::test]
+    async fn test_custom_root_is_loaded_into_client() {
+        let url = serve_tls().await;
+        let certificates = load_ca_bundle(Path::new(TEST_CA)).unwrap();
+        assert_eq!(certificates.len(), 1);
+
+        let client = with_root_certificates(Client::builder(), &certificates)
+            .build()
+            .unwrap();
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        // the built-in roots alone don't trust the server
+        let error = Client::builder()
+            .build()
+            .unwrap()
+            .get(&url)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", error).contains("UnknownIssuer"));
+    }
+
+    #[test]
+    fn test_invalid_ca_bundle_is_rejected() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "not a certificate").unwrap();
+
+        let error = load_ca_bundle(file.path()).unwrap_err();
+        assert!(error.to_string().contains("no certificates"));
+        assert!(load_ca_bundle(Path::new("/nonexistent/ca.pem")).is_err());
+    }
+}
@@ -1,4 +1,3 @@
-use crate::constants::JWT_TOKEN_FILE_PATH;
 use crate::utils::jwt_utils::claims::Claims;
 use crate::utils::jwt_utils::clerk::ClerkJwtVerifier;
 use tracing::error;
@@ -34,17 +33,3 @@ pub async fn is_jwt_valid(token: &str, platform: &str) -> (bool, Option<Claims>)
         }
     }
 }
-
-/// reads the file token.txt and returns the claims if the token is valid
-pub async fn get_token_claims_from_file(platform: &str) -> Option<Claims> {
-    // read the token.txt file
-    let token = std::fs::read_to_string(JWT_TOKEN_FILE_PATH).ok()?;
-
-    let is_token_valid_with_claims = is_jwt_valid(token.as_str(), platform).await;
-
-    if is_token_valid_with_claims.0 {
-        Some(is_token_valid_with_claims.1.unwrap())
-    } else {
-        None
-    }
-}
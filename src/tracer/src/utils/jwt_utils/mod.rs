@@ -1,3 +1,4 @@
 pub mod claims;
 mod clerk;
 pub mod jwt;
+pub mod token;
@@ -3,13 +3,16 @@ pub use version::Version;
 mod sentry;
 pub use sentry::Sentry;
 pub mod analytics;
+pub mod append_file;
 pub mod browser;
 pub mod cli;
 pub mod command;
 pub mod env;
 pub mod file_system;
+pub mod http;
 pub mod input_validation;
 pub mod jwt_utils;
+pub mod run_logs;
 pub mod spawn;
 pub mod string_validation;
 pub mod system_info;
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+/// Name of the directory (relative to the daemon's working directory) holding per-run logs
+pub const RUN_LOGS_DIR: &str = "tracer-run-logs";
+
+/// Summary of a run logs cleanup pass
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CleanupReport {
+    pub removed_dirs: usize,
+    pub reclaimed_bytes: u64,
+}
+
+struct RunLogDir {
+    path: PathBuf,
+    size_bytes: u64,
+    last_modified: SystemTime,
+}
+
+/// Returns the log directory for the given run id inside `base_dir`
+pub fn run_log_dir(base_dir: &Path, run_id: &str) -> PathBuf {
+    base_dir.join(format!("run-{}", run_id))
+}
+
+/// Removes run log directories under `base_dir` that have not been modified for longer than
+/// `ttl`, then removes the oldest remaining directories until the total size is within
+/// `max_total_bytes`. The directory of the active run is never removed, since it is still being
+/// written to.
+pub fn cleanup_run_logs(
+    base_dir: &Path,
+    ttl: Duration,
+    max_total_bytes: u64,
+    active_run_id: Option<&str>,
+) -> Result<CleanupReport> {
+    let mut report = CleanupReport::default();
+
+    if !base_dir.exists() {
+        return Ok(report);
+    }
+
+    let active_dir = active_run_id.map(|run_id| run_log_dir(base_dir, run_id));
+
+    let mut dirs: Vec<RunLogDir> = fs::read_dir(base_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter(|path| Some(path) != active_dir.as_ref())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("run-"))
+        })
+        .map(|path| {
+            let (size_bytes, last_modified) = dir_usage(&path);
+            RunLogDir {
+                path,
+                size_bytes,
+                last_modified,
+            }
+        })
+        .collect();
+
+    // oldest first
+    dirs.sort_by_key(|dir| dir.last_modified);
+
+    let now = SystemTime::now();
+    let (expired, mut retained): (Vec<_>, Vec<_>) = dirs.into_iter().partition(|dir| {
+        now.duration_since(dir.last_modified)
+            .map(|age| age > ttl)
+            .unwrap_or(false)
+    });
+
+    for dir in expired {
+        remove_dir(dir, &mut report);
+    }
+
+    let active_size = active_dir
+        .as_deref()
+        .filter(|dir| dir.exists())
+        .map(|dir| dir_usage(dir).0)
+        .unwrap_or(0);
+    let mut total_bytes = active_size + retained.iter().map(|dir| dir.size_bytes).sum::<u64>();
+
+    while total_bytes > max_total_bytes && !retained.is_empty() {
+        let dir = retained.remove(0);
+        total_bytes = total_bytes.saturating_sub(dir.size_bytes);
+        remove_dir(dir, &mut report);
+    }
+
+    Ok(report)
+}
+
+fn remove_dir(dir: RunLogDir, report: &mut CleanupReport) {
+    match fs::remove_dir_all(&dir.path) {
+        Ok(_) => {
+            debug!("Removed run log directory {}", dir.path.display());
+            report.removed_dirs += 1;
+            report.reclaimed_bytes += dir.size_bytes;
+        }
+        Err(e) => {
+            warn!(
+                "Failed to remove run log directory {}: {}",
+                dir.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Returns the total size of the files in `path` and the most recent modification time of any
+/// file inside it (or of the directory itself, if it is empty)
+fn dir_usage(path: &Path) -> (u64, SystemTime) {
+    let mut size_bytes = 0;
+    let mut last_modified = None;
+
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let entry_path = entry.path();
+            let (entry_size, entry_modified) = if entry_path.is_dir() {
+                dir_usage(&entry_path)
+            } else {
+                match entry.metadata() {
+                    Ok(metadata) => (
+                        metadata.len(),
+                        metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    ),
+                    Err(_) => continue,
+                }
+            };
+            size_bytes += entry_size;
+            last_modified = last_modified.max(Some(entry_modified));
+        }
+    }
+
+    let last_modified = last_modified.unwrap_or_else(|| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    });
+
+    (size_bytes, last_modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_run_dir(base: &Path, run_id: &str, size: usize, age: Duration) {
+        let dir = run_log_dir(base, run_id);
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join(format!("tracer-run-{}.log", run_id));
+        fs::write(&file_path, vec![b'x'; size]).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&file_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_removes_expired_run_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_run_dir(base, "old", 10, Duration::from_secs(3600));
+        create_run_dir(base, "new", 10, Duration::ZERO);
+
+        let report = cleanup_run_logs(base, Duration::from_secs(60), u64::MAX, None).unwrap();
+
+        assert_eq!(report.removed_dirs, 1);
+        assert_eq!(report.reclaimed_bytes, 10);
+        assert!(!run_log_dir(base, "old").exists());
+        assert!(run_log_dir(base, "new").exists());
+    }
+
+    #[test]
+    fn test_cleanup_enforces_size_cap_oldest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_run_dir(base, "a", 100, Duration::from_secs(30));
+        create_run_dir(base, "b", 100, Duration::from_secs(20));
+        create_run_dir(base, "c", 100, Duration::from_secs(10));
+
+        let report = cleanup_run_logs(base, Duration::from_secs(3600), 150, None).unwrap();
+
+        assert_eq!(report.removed_dirs, 2);
+        assert_eq!(report.reclaimed_bytes, 200);
+        assert!(!run_log_dir(base, "a").exists());
+        assert!(!run_log_dir(base, "b").exists());
+        assert!(run_log_dir(base, "c").exists());
+    }
+
+    #[test]
+    fn test_cleanup_never_removes_active_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_run_dir(base, "active", 100, Duration::from_secs(3600));
+
+        let report = cleanup_run_logs(base, Duration::from_secs(60), 0, Some("active")).unwrap();
+
+        assert_eq!(report, CleanupReport::default());
+        assert!(run_log_dir(base, "active").exists());
+    }
+}
@@ -1,28 +1,50 @@
+use crate::config::Config;
 use crate::constants::SENTRY_DSN;
 use crate::utils::system_info::{get_kernel_version, get_platform_information};
+use crate::utils::telemetry::Scrubber;
 use sentry::protocol::Context;
 use sentry::ClientOptions;
 use serde_json::Value;
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Set once `setup` was told to disable Sentry, turning every other call into a no-op
+static DISABLED: AtomicBool = AtomicBool::new(false);
 
 pub struct Sentry;
 
 // COPIED: tracer-installer/src/sentry.rs
 impl Sentry {
-    /// Initializes Sentry if a DSN is provided in the config.
+    /// Initializes Sentry if a DSN is provided in the config and it isn't disabled. Events are
+    /// scrubbed of personal information and secrets before being sent.
     /// Returns a guard to keep Sentry active for the program's lifetime.
-    pub fn setup() -> Option<sentry::ClientInitGuard> {
+    pub fn setup(config: &Config) -> Option<sentry::ClientInitGuard> {
         if cfg!(test) {
             return None;
         }
+        Self::init(
+            config.disable_sentry,
+            Scrubber::new(&config.sentry_scrub_patterns),
+        )
+    }
+
+    fn init(disabled: bool, scrubber: Scrubber) -> Option<sentry::ClientInitGuard> {
+        if disabled {
+            // no client is created, so nothing can be sent
+            DISABLED.store(true, Ordering::Relaxed);
+            tracing::info!("Sentry error reporting is disabled");
+            return None;
+        }
 
         let sentry = sentry::init((
             SENTRY_DSN,
             ClientOptions {
                 release: sentry::release_name!(),
-                // Enables capturing user IPs and sensitive headers when using HTTP server integrations.
+                // Don't capture user IPs and sensitive headers in HTTP server integrations.
                 // See: https://docs.sentry.io/platforms/rust/data-management/data-collected/
-                send_default_pii: true,
+                send_default_pii: false,
+                before_send: Some(Arc::new(move |event| Some(scrubber.scrub_event(event)))),
                 ..Default::default()
             },
         ));
@@ -36,9 +58,13 @@ impl Sentry {
         Some(sentry)
     }
 
+    fn is_disabled() -> bool {
+        cfg!(test) || DISABLED.load(Ordering::Relaxed)
+    }
+
     /// Adds a tag (key-value pair) to the Sentry event for short, string-based metadata.
     pub fn add_tag(key: &str, value: &str) {
-        if cfg!(test) {
+        if Self::is_disabled() {
             return;
         }
         sentry::configure_scope(|scope| {
@@ -50,7 +76,7 @@ impl Sentry {
     /// Requirements:
     ///   - The value must not be nested.
     pub fn add_context(key: &str, value: Value) {
-        if cfg!(test) {
+        if Self::is_disabled() {
             return;
         }
         // Only accept flat JSON objects
@@ -70,7 +96,7 @@ impl Sentry {
     /// Adds extra data (arbitrary JSON) to the Sentry event.
     /// Suitable for long or complex JSON values.
     pub fn add_extra(key: &str, value: Value) {
-        if cfg!(test) {
+        if Self::is_disabled() {
             return;
         }
         sentry::configure_scope(|scope| {
@@ -80,9 +106,25 @@ impl Sentry {
 
     /// Captures a message event in Sentry with the specified level.
     pub fn capture_message(message: &str, level: sentry::Level) {
-        if cfg!(test) {
+        if Self::is_disabled() {
             return;
         }
         sentry::capture_message(message, level);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_sentry_has_no_client() {
+        assert!(Sentry::init(true, Scrubber::new(&[])).is_none());
+        assert!(Sentry::is_disabled());
+        assert!(sentry::Hub::current().client().is_none());
+
+        Sentry::add_tag("type", "client");
+        Sentry::capture_message("not sent", sentry::Level::Error);
+        assert!(sentry::Hub::current().client().is_none());
+    }
+}
@@ -1,19 +1,25 @@
+use crate::client::exporters::event_forward::EventForwardError;
 use crate::constants::environment::*;
 use crate::utils::env::*;
+use serde::{Deserialize, Serialize};
 
-/// Error categories for telemetry reporting
-#[derive(Debug, Clone, Copy)]
+/// Error categories for telemetry reporting, also attached to error events so that they group
+/// like the errors reported to Sentry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ErrorCategory {
     NetworkFailure,
     SerializationFailure,
     Non2xxResponse,
     JsonParseFailure,
     DatabaseError,
+    #[serde(rename = "filesystem_error")]
     FileSystemError,
     ConfigurationError,
     AuthenticationError,
     ValidationError,
     TimeoutError,
+    #[default]
     UnknownError,
 }
 
@@ -33,6 +39,118 @@ impl ErrorCategory {
             ErrorCategory::UnknownError => "unknown_error",
         }
     }
+
+    /// Classifies an error by the first error of its chain with a known type, falling back to
+    /// its message
+    pub fn of_error(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(Self::of_typed_error)
+            .unwrap_or_else(|| Self::from_message(&error.to_string()))
+    }
+
+    fn of_typed_error(error: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        if let Some(error) = error.downcast_ref::<EventForwardError>() {
+            return Some(error.error_category());
+        }
+        if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+            return Some(if error.is_timeout() {
+                ErrorCategory::TimeoutError
+            } else if error.is_status() {
+                ErrorCategory::Non2xxResponse
+            } else if error.is_decode() {
+                ErrorCategory::JsonParseFailure
+            } else {
+                ErrorCategory::NetworkFailure
+            });
+        }
+        if let Some(error) = error.downcast_ref::<serde_json::Error>() {
+            return Some(if error.is_io() {
+                ErrorCategory::SerializationFailure
+            } else {
+                ErrorCategory::JsonParseFailure
+            });
+        }
+        if error.is::<rusqlite::Error>() {
+            return Some(ErrorCategory::DatabaseError);
+        }
+        if error.is::<toml::de::Error>() {
+            return Some(ErrorCategory::ConfigurationError);
+        }
+        if let Some(error) = error.downcast_ref::<std::io::Error>() {
+            return Some(match error.kind() {
+                std::io::ErrorKind::TimedOut => ErrorCategory::TimeoutError,
+                std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::NotConnected
+                | std::io::ErrorKind::AddrNotAvailable => ErrorCategory::NetworkFailure,
+                _ => ErrorCategory::FileSystemError,
+            });
+        }
+        None
+    }
+
+    /// Classifies the `errno` of a failed system call
+    pub fn from_errno(errno: i32) -> Self {
+        Self::of_typed_error(&std::io::Error::from_raw_os_error(errno))
+            .unwrap_or(ErrorCategory::UnknownError)
+    }
+
+    /// Classifies an error from its message alone, e.g. a line a tool logged
+    pub fn from_message(message: &str) -> Self {
+        const KEYWORDS: &[(ErrorCategory, &[&str])] = &[
+            (ErrorCategory::TimeoutError, &["timed out", "timeout"]),
+            (
+                ErrorCategory::AuthenticationError,
+                &["unauthorized", "authentication", "invalid credentials"],
+            ),
+            (
+                ErrorCategory::Non2xxResponse,
+                &[
+                    "http error",
+                    "status code",
+                    "bad gateway",
+                    "service unavailable",
+                ],
+            ),
+            (
+                ErrorCategory::NetworkFailure,
+                &[
+                    "connection refused",
+                    "connection reset",
+                    "could not resolve",
+                    "name resolution",
+                    "network is unreachable",
+                    "no route to host",
+                ],
+            ),
+            (ErrorCategory::JsonParseFailure, &["json"]),
+            (
+                ErrorCategory::DatabaseError,
+                &["database", "sqlite", "postgres"],
+            ),
+            (
+                ErrorCategory::FileSystemError,
+                &[
+                    "no such file",
+                    "permission denied",
+                    "no space left",
+                    "disk quota",
+                    "read-only file system",
+                ],
+            ),
+            (ErrorCategory::ConfigurationError, &["config"]),
+            (ErrorCategory::ValidationError, &["invalid", "validation"]),
+        ];
+
+        let message = message.to_lowercase();
+        KEYWORDS
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|keyword| message.contains(keyword)))
+            .map(|(category, _)| *category)
+            .unwrap_or(ErrorCategory::UnknownError)
+    }
 }
 
 /// Detect the current execution environment
@@ -87,3 +205,85 @@ pub fn detect_environment() -> String {
     // Default to local development
     ENV_LOCAL.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_typed_errors_are_classified() {
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(
+            ErrorCategory::of_error(&anyhow::Error::from(json).context("Invalid response")),
+            ErrorCategory::JsonParseFailure
+        );
+
+        let forward = EventForwardError::server_error(502, "Bad Gateway".to_string());
+        assert_eq!(
+            ErrorCategory::of_error(&anyhow::Error::from(forward)),
+            ErrorCategory::Non2xxResponse
+        );
+
+        let config = toml::from_str::<toml::Value>("key = ").unwrap_err();
+        assert_eq!(
+            ErrorCategory::of_error(&anyhow::Error::from(config)),
+            ErrorCategory::ConfigurationError
+        );
+
+        let io = std::fs::read("/nonexistent/tracer.toml")
+            .context("Failed to read config")
+            .unwrap_err();
+        assert_eq!(ErrorCategory::of_error(&io), ErrorCategory::FileSystemError);
+    }
+
+    #[test]
+    fn test_untyped_errors_are_classified_by_message() {
+        assert_eq!(
+            ErrorCategory::of_error(&anyhow::anyhow!("Request timed out after 30s")),
+            ErrorCategory::TimeoutError
+        );
+        assert_eq!(
+            ErrorCategory::from_message("curl: (7) Failed to connect: Connection refused"),
+            ErrorCategory::NetworkFailure
+        );
+        assert_eq!(
+            ErrorCategory::from_message("samtools: No space left on device"),
+            ErrorCategory::FileSystemError
+        );
+        assert_eq!(
+            ErrorCategory::from_message("Segmentation fault (core dumped)"),
+            ErrorCategory::UnknownError
+        );
+    }
+
+    #[test]
+    fn test_errno_is_classified() {
+        use nix::errno::Errno;
+        assert_eq!(
+            ErrorCategory::from_errno(Errno::ENOENT as i32),
+            ErrorCategory::FileSystemError
+        );
+        assert_eq!(
+            ErrorCategory::from_errno(Errno::ETIMEDOUT as i32),
+            ErrorCategory::TimeoutError
+        );
+        assert_eq!(
+            ErrorCategory::from_errno(Errno::ECONNREFUSED as i32),
+            ErrorCategory::NetworkFailure
+        );
+    }
+
+    #[test]
+    fn test_serialized_like_sentry_error_type() {
+        for category in [
+            ErrorCategory::FileSystemError,
+            ErrorCategory::Non2xxResponse,
+        ] {
+            assert_eq!(
+                serde_json::to_value(category).unwrap(),
+                serde_json::json!(category.as_str())
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use super::context::TelemetryContext;
 use super::environment::ErrorCategory;
+use super::rate_limiter::{ReportDecision, REPORT_RATE_LIMITER};
 use crate::utils::Sentry;
+use std::time::Instant;
 
 /// Error reporter with rich context for Sentry
 pub struct ErrorReporter {
@@ -23,6 +25,13 @@ impl ErrorReporter {
         self
     }
 
+    /// Add context for any error, categorized like the error events of a run
+    pub fn error(self, error: &anyhow::Error) -> Self {
+        self.add("error_type", ErrorCategory::of_error(error).as_str())
+            .add("error_message", error.to_string())
+            .add("error_debug", format!("{:?}", error))
+    }
+
     /// Add network error context
     pub fn network_error<E>(self, endpoint: &str, error: &E) -> Self
     where
@@ -116,9 +125,36 @@ impl ErrorReporter {
                 .unwrap_or("unknown")
         );
 
-        let json_context = self.context.to_json();
+        let decision = REPORT_RATE_LIMITER
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .check(&key, Instant::now());
+        let suppressed = match decision {
+            ReportDecision::Report { suppressed } => suppressed,
+            ReportDecision::Suppress => return,
+        };
 
-        Sentry::add_extra(&key, json_context);
+        let mut context = self.context;
+        if suppressed > 0 {
+            context = context.add("suppressed_reports", suppressed);
+        }
+
+        Sentry::add_extra(&key, context.to_json());
         Sentry::capture_message(message, sentry::Level::Error);
     }
+
+    /// Reports how many errors were collapsed by the rate limiter since their last report, as
+    /// these counts would otherwise be lost on exit
+    pub fn report_suppressed() {
+        let suppressed = REPORT_RATE_LIMITER
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take_suppressed();
+        for (key, count) in suppressed {
+            Sentry::capture_message(
+                &format!("{} identical '{}' errors were not reported", count, key),
+                sentry::Level::Warning,
+            );
+        }
+    }
 }
@@ -6,11 +6,15 @@
 mod context;
 mod environment;
 mod error_reporter;
+mod rate_limiter;
+mod scrubber;
 
 // Public exports
 pub use context::TelemetryContext;
 pub use environment::{detect_environment, ErrorCategory};
 pub use error_reporter::ErrorReporter;
+pub use rate_limiter::SENTRY_RATE_LIMIT_WINDOW_ENV_VAR;
+pub use scrubber::Scrubber;
 
 // Presets module with convenience functions
 pub mod presets {
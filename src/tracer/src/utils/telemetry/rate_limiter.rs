@@ -0,0 +1,164 @@
+use crate::utils::env::get_env_var;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Seconds during which repeated reports of the same error are collapsed; 0 reports every one
+pub const SENTRY_RATE_LIMIT_WINDOW_ENV_VAR: &str = "TRACER_SENTRY_RATE_LIMIT_WINDOW_SECS";
+const DEFAULT_WINDOW: Duration = Duration::from_secs(300);
+
+/// Limiter shared by all error reporters of the process
+pub(super) static REPORT_RATE_LIMITER: LazyLock<Mutex<ReportRateLimiter>> = LazyLock::new(|| {
+    let window = get_env_var(SENTRY_RATE_LIMIT_WINDOW_ENV_VAR)
+        .and_then(|secs| secs.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_WINDOW);
+    Mutex::new(ReportRateLimiter::new(window))
+});
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReportDecision {
+    /// Report the error, with the number of identical reports dropped since the last one
+    Report { suppressed: u64 },
+    /// An identical error was already reported in the current window
+    Suppress,
+}
+
+struct Window {
+    started_at: Instant,
+    suppressed: u64,
+}
+
+/// Lets through the first report of each key per window and counts the others, so an error
+/// repeated on every poll doesn't use up the Sentry quota
+pub struct ReportRateLimiter {
+    window: Duration,
+    windows: HashMap<String, Window>,
+}
+
+impl ReportRateLimiter {
+    /// A zero `window` disables the limiter
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn check(&mut self, key: &str, now: Instant) -> ReportDecision {
+        if self.window.is_zero() {
+            return ReportDecision::Report { suppressed: 0 };
+        }
+        match self.windows.get_mut(key) {
+            Some(window) if now.duration_since(window.started_at) < self.window => {
+                window.suppressed += 1;
+                ReportDecision::Suppress
+            }
+            Some(window) => {
+                let suppressed = window.suppressed;
+                *window = Window {
+                    started_at: now,
+                    suppressed: 0,
+                };
+                ReportDecision::Report { suppressed }
+            }
+            None => {
+                self.windows.insert(
+                    key.to_string(),
+                    Window {
+                        started_at: now,
+                        suppressed: 0,
+                    },
+                );
+                ReportDecision::Report { suppressed: 0 }
+            }
+        }
+    }
+
+    /// Counts of the reports suppressed in the current windows, to be reported before exiting
+    pub fn take_suppressed(&mut self) -> Vec<(String, u64)> {
+        self.windows
+            .iter_mut()
+            .filter(|(_, window)| window.suppressed > 0)
+            .map(|(key, window)| (key.clone(), std::mem::take(&mut window.suppressed)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_identical_reports_are_collapsed() {
+        let mut limiter = ReportRateLimiter::new(WINDOW);
+        let start = Instant::now();
+        let key = "pricing_network_failure";
+
+        let decisions: Vec<_> = (0..10)
+            .map(|i| limiter.check(key, start + Duration::from_secs(i)))
+            .collect();
+        assert_eq!(decisions[0], ReportDecision::Report { suppressed: 0 });
+        assert!(decisions[1..]
+            .iter()
+            .all(|decision| *decision == ReportDecision::Suppress));
+
+        // the first report of the next window carries the count of the previous one
+        assert_eq!(
+            limiter.check(key, start + WINDOW),
+            ReportDecision::Report { suppressed: 9 }
+        );
+        assert_eq!(
+            limiter.check(key, start + WINDOW * 3),
+            ReportDecision::Report { suppressed: 0 }
+        );
+    }
+
+    #[test]
+    fn test_keys_are_limited_separately() {
+        let mut limiter = ReportRateLimiter::new(WINDOW);
+        let now = Instant::now();
+        assert_eq!(
+            limiter.check("pricing_network_failure", now),
+            ReportDecision::Report { suppressed: 0 }
+        );
+        assert_eq!(
+            limiter.check("pricing_non_2xx_response", now),
+            ReportDecision::Report { suppressed: 0 }
+        );
+        assert_eq!(
+            limiter.check("pricing_network_failure", now),
+            ReportDecision::Suppress
+        );
+    }
+
+    #[test]
+    fn test_zero_window_disables_limiting() {
+        let mut limiter = ReportRateLimiter::new(Duration::ZERO);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(
+                limiter.check("pricing_network_failure", now),
+                ReportDecision::Report { suppressed: 0 }
+            );
+        }
+    }
+
+    #[test]
+    fn test_take_suppressed() {
+        let mut limiter = ReportRateLimiter::new(WINDOW);
+        let now = Instant::now();
+        for _ in 0..4 {
+            limiter.check("pricing_network_failure", now);
+        }
+        limiter.check("daemon_client_network_failure", now);
+
+        assert_eq!(
+            limiter.take_suppressed(),
+            vec![("pricing_network_failure".to_string(), 3)]
+        );
+        assert!(limiter.take_suppressed().is_empty());
+    }
+}
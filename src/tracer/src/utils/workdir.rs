@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
 const PID_FILE: &str = "tracerd.pid";
+const LOCK_FILE: &str = "tracerd.lock";
 const STDOUT_FILE: &str = "tracerd.out";
 const STDERR_FILE: &str = "tracerd.err";
 const LOG_FILE: &str = "daemon.log";
@@ -18,6 +19,12 @@ const OTEL_CONFIG_FILE: &str = "otel-config.yaml";
 const OTEL_PID_FILE: &str = "otelcol.pid";
 const OTEL_STDOUT_FILE: &str = "otelcol.out";
 const OTEL_STDERR_FILE: &str = "otelcol.err";
+const PRICING_CACHE_FILE: &str = "pricing_cache.json";
+const RUN_SUMMARY_FILE: &str = "run_summary.json";
+const RUN_NAMES_FILE: &str = "run_names.txt";
+const CONFIG_AUDIT_FILE: &str = "config_audit.ndjson";
+const LOCAL_EVENTS_FILE: &str = "events.ndjson";
+const DAEMON_CONFIG_FILE: &str = "daemon_config.json";
 
 pub static TRACER_WORK_DIR: LazyLock<TracerWorkDir> = LazyLock::new(|| {
     // Use /tmp/tracer as the working directory for demo runs
@@ -25,6 +32,7 @@ pub static TRACER_WORK_DIR: LazyLock<TracerWorkDir> = LazyLock::new(|| {
     let path = base_dir.join("tracer");
     TracerWorkDir {
         pid_file: path.join(PID_FILE),
+        lock_file: path.join(LOCK_FILE),
         stdout_file: path.join(STDOUT_FILE),
         stderr_file: path.join(STDERR_FILE),
         log_file: path.join(LOG_FILE),
@@ -35,6 +43,12 @@ pub static TRACER_WORK_DIR: LazyLock<TracerWorkDir> = LazyLock::new(|| {
         otel_pid_file: path.join(OTEL_PID_FILE),
         otel_stdout_file: path.join(OTEL_STDOUT_FILE),
         otel_stderr_file: path.join(OTEL_STDERR_FILE),
+        pricing_cache_file: path.join(PRICING_CACHE_FILE),
+        run_summary_file: path.join(RUN_SUMMARY_FILE),
+        run_names_file: path.join(RUN_NAMES_FILE),
+        config_audit_file: path.join(CONFIG_AUDIT_FILE),
+        local_events_file: path.join(LOCAL_EVENTS_FILE),
+        daemon_config_file: path.join(DAEMON_CONFIG_FILE),
         path,
         // Avoid canonicalizing /tmp on macOS which resolves to /private/tmp
         canonical_path: Ok(base_dir.join("tracer")),
@@ -45,6 +59,8 @@ pub struct TracerWorkDir {
     pub path: PathBuf,
     pub canonical_path: io::Result<PathBuf>,
     pub pid_file: PathBuf,
+    /// Not removed by `cleanup_run`, since deleting it would let a second daemon lock a new file
+    pub lock_file: PathBuf,
     pub stdout_file: PathBuf,
     pub stderr_file: PathBuf,
     pub log_file: PathBuf,
@@ -55,6 +71,20 @@ pub struct TracerWorkDir {
     pub otel_pid_file: PathBuf,
     pub otel_stdout_file: PathBuf,
     pub otel_stderr_file: PathBuf,
+    /// Kept across runs, so pricing looked up once is reused by later daemons
+    pub pricing_cache_file: PathBuf,
+    /// Summary of the last finished run, kept after the daemon stops so it can be shared
+    pub run_summary_file: PathBuf,
+    /// Recently generated run names, kept across runs to avoid giving two runs the same name
+    pub run_names_file: PathBuf,
+    /// Append-only record of configuration changes, kept across runs, see `config::audit`
+    pub config_audit_file: PathBuf,
+    /// Events of the runs recorded without a token, kept across runs so they can be looked at
+    /// or uploaded later
+    pub local_events_file: PathBuf,
+    /// Effective configuration of the last daemon started, kept across runs so the next
+    /// `tracer init` audits its changes against it
+    pub daemon_config_file: PathBuf,
 }
 
 impl TracerWorkDir {
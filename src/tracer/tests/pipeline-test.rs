@@ -5,10 +5,12 @@ use pretty_assertions_sorted::assert_eq;
 use rstest::*;
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::{self, Sender};
 use tokio::sync::{Mutex, RwLock};
 use tracer::daemon::structs::PipelineMetadata;
+use tracer::extracts::containers::display_name::ContainerDisplayName;
 use tracer::extracts::containers::DockerWatcher;
 use tracer::extracts::files::file_manager::manager::FileManager;
 use tracer::extracts::process::process_manager::recorder::EventRecorder;
@@ -38,6 +40,9 @@ fn create_process_watcher(
         event_dispatcher,
         Arc::new(docker_watcher),
         file_manager,
+        ContainerDisplayName::default(),
+        false,
+        Duration::ZERO,
     ))
 }
 
@@ -126,6 +131,7 @@ fn pipeline() -> PipelineMetadata {
         is_dev: true,
         start_time: Default::default(),
         opentelemetry_status: None,
+        ebpf_events_lost: 0,
     }
 }
 